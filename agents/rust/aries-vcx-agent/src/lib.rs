@@ -9,7 +9,6 @@ extern crate uuid;
 
 mod agent;
 mod error;
-mod http_client;
 mod services;
 mod storage;
 
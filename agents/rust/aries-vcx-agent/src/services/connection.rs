@@ -1,7 +1,6 @@
 use std::sync::{Arc, Mutex};
 
 use crate::error::*;
-use crate::http_client::HttpClient;
 use crate::storage::object_cache::ObjectCache;
 use crate::storage::Storage;
 use aries_vcx::core::profile::profile::Profile;
@@ -11,6 +10,7 @@ use aries_vcx::messages::msg_fields::protocols::connection::response::Response;
 use aries_vcx::messages::msg_fields::protocols::notification::ack::Ack;
 use aries_vcx::protocols::connection::pairwise_info::PairwiseInfo;
 use aries_vcx::protocols::connection::{Connection, GenericConnection, State, ThinState};
+use aries_vcx::transport::http::HttpTransport;
 use url::Url;
 
 pub type ServiceEndpoint = Url;
@@ -60,7 +60,7 @@ impl ServiceConnections {
                 &self.profile.inject_wallet(),
                 self.service_endpoint.clone(),
                 vec![],
-                &HttpClient,
+                &HttpTransport::default(),
             )
             .await?;
 
@@ -91,7 +91,7 @@ impl ServiceConnections {
                 request,
                 self.service_endpoint.clone(),
                 vec![],
-                &HttpClient,
+                &HttpTransport::default(),
             )
             .await?;
 
@@ -103,7 +103,7 @@ impl ServiceConnections {
     pub async fn send_response(&self, thread_id: &str) -> AgentResult<()> {
         let inviter: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
         let inviter = inviter
-            .send_response(&self.profile.inject_wallet(), &HttpClient)
+            .send_response(&self.profile.inject_wallet(), &HttpTransport::default())
             .await?;
 
         self.connections.insert(thread_id, inviter.into())?;
@@ -114,7 +114,7 @@ impl ServiceConnections {
     pub async fn accept_response(&self, thread_id: &str, response: Response) -> AgentResult<()> {
         let invitee: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
         let invitee = invitee
-            .handle_response(&self.profile.inject_wallet(), response, &HttpClient)
+            .handle_response(&self.profile.inject_wallet(), response, &HttpTransport::default())
             .await?;
 
         self.connections.insert(thread_id, invitee.into())?;
@@ -124,7 +124,9 @@ impl ServiceConnections {
 
     pub async fn send_ack(&self, thread_id: &str) -> AgentResult<()> {
         let invitee: Connection<_, _> = self.connections.get(thread_id)?.try_into()?;
-        let invitee = invitee.send_ack(&self.profile.inject_wallet(), &HttpClient).await?;
+        let invitee = invitee
+            .send_ack(&self.profile.inject_wallet(), &HttpTransport::default())
+            .await?;
 
         self.connections.insert(thread_id, invitee.into())?;
 
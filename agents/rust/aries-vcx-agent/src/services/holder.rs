@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use crate::error::*;
-use crate::http_client::HttpClient;
 use crate::services::connection::ServiceConnections;
 use crate::storage::object_cache::ObjectCache;
 use crate::storage::Storage;
@@ -13,6 +12,7 @@ use aries_vcx::messages::msg_fields::protocols::cred_issuance::propose_credentia
 use aries_vcx::messages::AriesMessage;
 use aries_vcx::protocols::issuance::holder::state_machine::HolderState;
 use aries_vcx::protocols::SendClosure;
+use aries_vcx::transport::http::HttpTransport;
 
 #[derive(Clone)]
 struct HolderWrapper {
@@ -63,7 +63,7 @@ impl ServiceCredentialsHolder {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         let mut holder = Holder::create("")?;
@@ -96,7 +96,7 @@ impl ServiceCredentialsHolder {
         let pw_did = connection.pairwise_info().pw_did.to_string();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         holder
@@ -118,7 +118,7 @@ impl ServiceCredentialsHolder {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         holder
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use crate::error::*;
-use crate::http_client::HttpClient;
 use crate::services::connection::ServiceConnections;
 use crate::storage::object_cache::ObjectCache;
 use crate::storage::Storage;
@@ -14,6 +13,7 @@ use aries_vcx::messages::msg_fields::protocols::cred_issuance::request_credentia
 use aries_vcx::messages::AriesMessage;
 use aries_vcx::protocols::issuance::issuer::state_machine::IssuerState;
 use aries_vcx::protocols::SendClosure;
+use aries_vcx::transport::http::HttpTransport;
 
 #[derive(Clone)]
 struct IssuerWrapper {
@@ -81,7 +81,7 @@ impl ServiceCredentialsIssuer {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         issuer.send_credential_offer(send_closure).await?;
@@ -121,7 +121,7 @@ impl ServiceCredentialsIssuer {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         issuer
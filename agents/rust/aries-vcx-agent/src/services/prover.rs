@@ -2,7 +2,6 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::*;
-use crate::http_client::HttpClient;
 use crate::storage::object_cache::ObjectCache;
 use crate::storage::Storage;
 use aries_vcx::core::profile::profile::Profile;
@@ -14,6 +13,7 @@ use aries_vcx::messages::msg_fields::protocols::present_proof::request::RequestP
 use aries_vcx::messages::AriesMessage;
 use aries_vcx::protocols::proof_presentation::prover::state_machine::ProverState;
 use aries_vcx::protocols::SendClosure;
+use aries_vcx::transport::http::HttpTransport;
 use serde_json::Value;
 
 use super::connection::ServiceConnections;
@@ -95,7 +95,7 @@ impl ServiceProver {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         prover.send_proposal(proposal, send_closure).await?;
@@ -129,7 +129,7 @@ impl ServiceProver {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         prover.send_presentation(send_closure).await?;
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use crate::error::*;
-use crate::http_client::HttpClient;
 use crate::storage::object_cache::ObjectCache;
 use crate::storage::Storage;
 use aries_vcx::common::proofs::proof_request::PresentationRequestData;
@@ -13,6 +12,7 @@ use aries_vcx::messages::AriesMessage;
 use aries_vcx::protocols::proof_presentation::verifier::state_machine::VerifierState;
 use aries_vcx::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
 use aries_vcx::protocols::SendClosure;
+use aries_vcx::transport::http::HttpTransport;
 
 use super::connection::ServiceConnections;
 
@@ -62,7 +62,7 @@ impl ServiceVerifier {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         verifier.send_presentation_request(send_closure).await?;
@@ -86,7 +86,7 @@ impl ServiceVerifier {
         let wallet = self.profile.inject_wallet();
 
         let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpClient).await })
+            Box::pin(async move { connection.send_message(&wallet, &msg, &HttpTransport::default()).await })
         });
 
         verifier
@@ -0,0 +1,477 @@
+//! Conversions between the legacy [`AriesDidDoc`], used by the connection protocol handlers,
+//! and the W3C [`DidDocument`], as returned by the DID resolvers.
+//!
+//! Legacy documents only know Ed25519 keys and services listing recipient and routing keys,
+//! so converting a [`DidDocument`] drops its other verification methods and services.
+
+use std::collections::HashMap;
+
+use did_doc::schema::{
+    did_doc::DidDocument,
+    types::{public_key::KeyType, uri::Uri},
+    verification_method::VerificationMethod,
+};
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use diddoc_legacy::aries::service::AriesService;
+use diddoc_legacy::w3c::model::{Authentication, Ed25519PublicKey, CONTEXT, KEY_AUTHENTICATION_TYPE, KEY_TYPE};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::common::keys::{did_key_to_verkey, verkey_to_did_key};
+use crate::errors::error::prelude::*;
+use crate::utils::didcomm_v2::keys::resolve_verification_method;
+
+/// Service types carrying DIDComm v1 recipient and routing keys, preferred over other services.
+const DIDCOMM_V1_SERVICE_TYPES: [&str; 2] = ["did-communication", "IndyAgent"];
+const DIDCOMM_V2_SERVICE_TYPE: &str = "DIDCommMessaging";
+
+const SOV_DID_PREFIX: &str = "did:sov:";
+
+/// Converts a legacy DID document into a [`DidDocument`].
+///
+/// Unqualified ids are qualified as `did:sov` DIDs. Public keys become `Ed25519VerificationKey2018`
+/// verification methods which the recipient keys of the services reference, while routing keys
+/// and recipient keys not found in the document become `did:key`s.
+pub fn aries_did_doc_to_did_doc<E>(ddo: &AriesDidDoc) -> VcxResult<DidDocument<E>>
+where
+    E: Default + DeserializeOwned,
+{
+    let did = if ddo.id.starts_with("did:") {
+        ddo.id.clone()
+    } else {
+        format!("{SOV_DID_PREFIX}{}", ddo.id)
+    };
+
+    // Legacy key ids may be plain fragments, DID URLs or even the key itself, so keys are
+    // looked up by all of these.
+    let mut key_ids = HashMap::new();
+    let mut verification_methods = Vec::new();
+    for (index, key) in ddo.public_key.iter().enumerate() {
+        let fragment = match key.id.rsplit('#').next() {
+            Some(fragment) if !fragment.is_empty() && fragment != key.public_key_base_58 => fragment.to_owned(),
+            _ => format!("key-{}", index + 1),
+        };
+        let id = format!("{did}#{fragment}");
+
+        key_ids.insert(key.id.clone(), id.clone());
+        key_ids.entry(fragment).or_insert_with(|| id.clone());
+        key_ids
+            .entry(key.public_key_base_58.clone())
+            .or_insert_with(|| id.clone());
+
+        verification_methods.push(json!({
+            "id": id,
+            "type": KEY_TYPE,
+            "controller": did,
+            "publicKeyBase58": key.public_key_base_58,
+        }));
+    }
+
+    let find_key_id = |key: &str| {
+        key_ids
+            .get(key)
+            .or_else(|| key.rsplit('#').next().and_then(|fragment| key_ids.get(fragment)))
+            .cloned()
+    };
+
+    // Without authentication entries, legacy documents treat all their keys as authentication keys.
+    let authentication = if ddo.authentication.is_empty() {
+        verification_methods.iter().map(|method| method["id"].clone()).collect()
+    } else {
+        ddo.authentication
+            .iter()
+            .map(|authentication| {
+                find_key_id(&authentication.public_key).map(Value::from).ok_or_else(|| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidDid,
+                        format!(
+                            "Authentication key {} not found in DID document {}",
+                            authentication.public_key, ddo.id
+                        ),
+                    )
+                })
+            })
+            .collect::<VcxResult<Vec<_>>>()?
+    };
+
+    let services = ddo
+        .service
+        .iter()
+        .enumerate()
+        .map(|(index, service)| {
+            let id = match Uri::new(&service.id) {
+                Ok(_) => service.id.clone(),
+                Err(_) => format!("{did}#service-{index}"),
+            };
+            let recipient_keys = service
+                .recipient_keys
+                .iter()
+                .map(|key| match find_key_id(key) {
+                    Some(id) => Ok(id),
+                    None => key_to_did_key(key),
+                })
+                .collect::<VcxResult<Vec<_>>>()?;
+            let routing_keys = service
+                .routing_keys
+                .iter()
+                .map(|key| key_to_did_key(key))
+                .collect::<VcxResult<Vec<_>>>()?;
+
+            Ok(json!({
+                "id": id,
+                "type": service.type_,
+                "priority": service.priority,
+                "recipientKeys": recipient_keys,
+                "routingKeys": routing_keys,
+                "serviceEndpoint": service.service_endpoint.as_str(),
+            }))
+        })
+        .collect::<VcxResult<Vec<_>>>()?;
+
+    let did_doc = json!({
+        "id": did,
+        "verificationMethod": verification_methods,
+        "authentication": authentication,
+        "service": services,
+    });
+
+    serde_json::from_value(did_doc).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Cannot convert legacy DID document {}: {}", ddo.id, err),
+        )
+    })
+}
+
+/// Converts a [`DidDocument`] into a legacy DID document.
+///
+/// `did:sov` DIDs are unqualified, as the connection protocol expects. Only Ed25519 keys are kept,
+/// and the services are ordered by type and priority, so the first one is the best suited
+/// DIDComm v1 service. Recipient keys are resolved to base58 verkeys, falling back to the
+/// authentication keys for services which don't list any.
+pub fn did_doc_to_aries_did_doc<E>(did_doc: &DidDocument<E>) -> VcxResult<AriesDidDoc>
+where
+    E: Serialize,
+{
+    let did = did_doc.id().to_string();
+    let id = did.strip_prefix(SOV_DID_PREFIX).unwrap_or(&did).to_owned();
+
+    let mut public_key = Vec::new();
+    let mut authentication = Vec::new();
+    for method in did_doc.verification_method() {
+        if let Some(verkey) = ed25519_verkey(method)? {
+            public_key.push(Ed25519PublicKey {
+                id: method.id().to_string(),
+                type_: KEY_TYPE.to_owned(),
+                controller: method.controller().to_string(),
+                public_key_base_58: verkey,
+            });
+        }
+    }
+    for method in did_doc.authentication() {
+        let method = resolve_verification_method(did_doc, method)?;
+        let Some(verkey) = ed25519_verkey(method)? else {
+            continue;
+        };
+        // Authentication methods may be embedded rather than referenced.
+        if !public_key.iter().any(|key| key.id == method.id().to_string()) {
+            public_key.push(Ed25519PublicKey {
+                id: method.id().to_string(),
+                type_: KEY_TYPE.to_owned(),
+                controller: method.controller().to_string(),
+                public_key_base_58: verkey,
+            });
+        }
+        authentication.push(Authentication {
+            type_: KEY_AUTHENTICATION_TYPE.to_owned(),
+            public_key: method.id().to_string(),
+        });
+    }
+
+    let authentication_keys: Vec<String> = authentication
+        .iter()
+        .filter_map(|authentication| public_key.iter().find(|key| key.id == authentication.public_key))
+        .map(|key| key.public_key_base_58.clone())
+        .collect();
+
+    let mut services = Vec::new();
+    for service in did_doc.service() {
+        let service = serde_json::to_value(service)?;
+        let service_types: Vec<&str> = match &service["type"] {
+            Value::String(service_type) => vec![service_type.as_str()],
+            Value::Array(service_types) => service_types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let service_type = service_types
+            .iter()
+            .find(|service_type| DIDCOMM_V1_SERVICE_TYPES.contains(service_type))
+            .or_else(|| service_types.first());
+        let is_didcomm = service_types.iter().any(|service_type| {
+            DIDCOMM_V1_SERVICE_TYPES.contains(service_type) || *service_type == DIDCOMM_V2_SERVICE_TYPE
+        });
+        if !is_didcomm && service.get("recipientKeys").is_none() {
+            continue;
+        }
+
+        let mut recipient_keys = string_list(&service["recipientKeys"])
+            .map(|key| key_verkey(&did, &public_key, key, "Recipient"))
+            .collect::<VcxResult<Vec<_>>>()?;
+        if recipient_keys.is_empty() {
+            recipient_keys = authentication_keys.clone();
+        }
+        let routing_keys = string_list(&service["routingKeys"])
+            .map(|key| key_verkey(&did, &public_key, key, "Routing"))
+            .collect::<VcxResult<Vec<_>>>()?;
+
+        let service_endpoint = service["serviceEndpoint"].as_str().unwrap_or_default();
+        let service_endpoint = service_endpoint.parse().map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidUrl,
+                format!("Invalid service endpoint {}: {}", service_endpoint, err),
+            )
+        })?;
+
+        services.push(AriesService {
+            id: service["id"].as_str().unwrap_or_default().to_owned(),
+            type_: service_type
+                .map(|service_type| service_type.to_string())
+                .unwrap_or_default(),
+            priority: service["priority"].as_u64().unwrap_or_default() as u32,
+            recipient_keys,
+            routing_keys,
+            service_endpoint,
+        });
+    }
+    services.sort_by_key(|service| {
+        (
+            !DIDCOMM_V1_SERVICE_TYPES.contains(&service.type_.as_str()),
+            service.priority,
+        )
+    });
+
+    // Recipient keys must be found among the public keys of legacy documents.
+    for verkey in services.iter().flat_map(|service| service.recipient_keys.iter()) {
+        if public_key.iter().any(|key| &key.public_key_base_58 == verkey) {
+            continue;
+        }
+        let did_key = verkey_to_did_key(verkey)?;
+        let key_id = format!("{did_key}#{}", did_key.trim_start_matches("did:key:"));
+        authentication.push(Authentication {
+            type_: KEY_AUTHENTICATION_TYPE.to_owned(),
+            public_key: key_id.clone(),
+        });
+        public_key.push(Ed25519PublicKey {
+            id: key_id,
+            type_: KEY_TYPE.to_owned(),
+            controller: did_key,
+            public_key_base_58: verkey.clone(),
+        });
+    }
+
+    Ok(AriesDidDoc {
+        context: CONTEXT.to_owned(),
+        id,
+        public_key,
+        authentication,
+        service: services,
+    })
+}
+
+/// Converts a base58 verkey into a `did:key`, leaving DIDs and DID URLs unchanged.
+fn key_to_did_key(key: &str) -> VcxResult<String> {
+    match key.starts_with("did:") {
+        true => Ok(key.to_owned()),
+        false => verkey_to_did_key(key),
+    }
+}
+
+/// Resolves a recipient or routing key, which is either a `did:key`, the base58 verkey itself
+/// or a reference to one of the Ed25519 keys of the document identified by `did`.
+///
+/// References must match the id of the key in full, relative references being resolved against
+/// `did` only. Keys of other DIDs can't be resolved here and are rejected.
+fn key_verkey(did: &str, public_key: &[Ed25519PublicKey], key: &str, role: &str) -> VcxResult<String> {
+    if key.starts_with("did:key:") {
+        return did_key_to_verkey(key);
+    }
+    if !key.starts_with("did:") && !key.starts_with('#') {
+        return Ok(key.to_owned());
+    }
+
+    let key_id = absolute_key_id(did, key);
+    public_key
+        .iter()
+        .find(|public_key| absolute_key_id(did, &public_key.id) == key_id)
+        .map(|public_key| public_key.public_key_base_58.clone())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidDid,
+                format!("{} key {} is not an Ed25519 key of the DID document {}", role, key, did),
+            )
+        })
+}
+
+fn absolute_key_id(did: &str, key_id: &str) -> String {
+    match key_id.starts_with('#') {
+        true => format!("{did}{key_id}"),
+        false => key_id.to_owned(),
+    }
+}
+
+/// Extracts the base58 encoded key of an Ed25519 verification method,
+/// or `None` if the method holds another type of key.
+fn ed25519_verkey(method: &VerificationMethod) -> VcxResult<Option<String>> {
+    match method.public_key() {
+        Ok(public_key) if public_key.key_type() == KeyType::Ed25519 => Ok(Some(public_key.to_base58())),
+        Ok(_) => Ok(None),
+        // Methods of other types may hold keys which are not supported at all.
+        Err(_)
+            if KeyType::from_verification_method_type(method.verification_method_type()) != Some(KeyType::Ed25519) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Cannot decode key of {}: {}", method.id(), err),
+        )),
+    }
+}
+
+fn string_list(value: &Value) -> impl Iterator<Item = &str> {
+    value.as_array().into_iter().flatten().filter_map(Value::as_str)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use diddoc_legacy::aries::diddoc::test_utils::*;
+
+    use super::*;
+
+    type ExtraFields = HashMap<String, Value>;
+
+    #[test]
+    fn test_aries_did_doc_roundtrip() {
+        for ddo in [
+            _did_doc_vcx_legacy(),
+            _did_doc_inlined_recipient_keys(),
+            _did_doc_recipient_keys_by_value(),
+            _did_doc_empty_routing(),
+        ] {
+            let did_doc: DidDocument<ExtraFields> = aries_did_doc_to_did_doc(&ddo).unwrap();
+            assert_eq!(did_doc.id().to_string(), format!("did:sov:{}", _did()));
+            assert_eq!(did_doc.service().len(), 1);
+
+            let converted = did_doc_to_aries_did_doc(&did_doc).unwrap();
+            converted.validate().unwrap();
+            assert_eq!(converted.id, ddo.id);
+            assert_eq!(converted.get_service().unwrap(), ddo.get_service().unwrap());
+            assert_eq!(converted.get_endpoint(), ddo.get_endpoint());
+        }
+    }
+
+    #[test]
+    fn test_recipient_keys_reference_verification_methods() {
+        let did_doc: DidDocument<ExtraFields> = aries_did_doc_to_did_doc(&_did_doc_vcx_legacy()).unwrap();
+        let service = serde_json::to_value(&did_doc.service()[0]).unwrap();
+
+        assert_eq!(service["recipientKeys"], json!([format!("did:sov:{}#1", _did())]));
+        assert_eq!(service["routingKeys"][0], json!(verkey_to_did_key(&_key_2()).unwrap()));
+        assert_eq!(did_doc.authentication().len(), 1);
+    }
+
+    #[test]
+    fn test_did_doc_to_aries_did_doc() {
+        let did = "did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc";
+        let did_doc: DidDocument<ExtraFields> = serde_json::from_value(json!({
+            "id": did,
+            "verificationMethod": [
+                {
+                    "id": format!("{did}#key-agreement"),
+                    "type": "X25519KeyAgreementKey2019",
+                    "controller": did,
+                    "publicKeyBase58": "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr"
+                }
+            ],
+            "service": [
+                {
+                    "id": format!("{did}#didcomm"),
+                    "type": "DIDCommMessaging",
+                    "priority": 1,
+                    "serviceEndpoint": "https://example.org/didcomm"
+                },
+                {
+                    "id": format!("{did}#did-communication"),
+                    "type": "did-communication",
+                    "priority": 0,
+                    "recipientKeys": [_key_1_did_key()],
+                    "routingKeys": [_key_2_did_key()],
+                    "serviceEndpoint": "https://example.org/didcomm-v1"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let ddo = did_doc_to_aries_did_doc(&did_doc).unwrap();
+        ddo.validate().unwrap();
+
+        assert_eq!(ddo.id, did);
+        assert_eq!(ddo.service.len(), 2);
+        assert_eq!(ddo.service[0].type_, "did-communication");
+        assert_eq!(ddo.recipient_keys().unwrap(), vec![_key_1()]);
+        assert_eq!(ddo.routing_keys(), vec![_key_2()]);
+        assert_eq!(ddo.get_endpoint().unwrap().as_str(), "https://example.org/didcomm-v1");
+    }
+
+    fn did_doc_with_keys(did: &str, recipient_keys: Value, routing_keys: Value) -> DidDocument<ExtraFields> {
+        serde_json::from_value(json!({
+            "id": did,
+            "verificationMethod": [
+                {
+                    "id": format!("{did}#key-1"),
+                    "type": "Ed25519VerificationKey2018",
+                    "controller": did,
+                    "publicKeyBase58": _key_1()
+                },
+                {
+                    "id": format!("{did}#key-2"),
+                    "type": "Ed25519VerificationKey2018",
+                    "controller": did,
+                    "publicKeyBase58": _key_2()
+                }
+            ],
+            "service": [
+                {
+                    "id": format!("{did}#did-communication"),
+                    "type": "did-communication",
+                    "recipientKeys": recipient_keys,
+                    "routingKeys": routing_keys,
+                    "serviceEndpoint": "https://example.org/didcomm"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_did_doc_to_aries_did_doc_resolves_key_references() {
+        let did = "did:example:alice";
+        let did_doc = did_doc_with_keys(did, json!(["#key-1"]), json!([format!("{did}#key-2")]));
+
+        let ddo = did_doc_to_aries_did_doc(&did_doc).unwrap();
+
+        assert_eq!(ddo.recipient_keys().unwrap(), vec![_key_1()]);
+        assert_eq!(ddo.routing_keys(), vec![_key_2()]);
+    }
+
+    #[test]
+    fn test_did_doc_to_aries_did_doc_rejects_keys_of_other_dids() {
+        let did = "did:example:alice";
+
+        let did_doc = did_doc_with_keys(did, json!(["did:example:mallory#key-1"]), json!([]));
+        did_doc_to_aries_did_doc(&did_doc).unwrap_err();
+
+        let did_doc = did_doc_with_keys(did, json!(["#key-1"]), json!(["did:example:mediator#key-1"]));
+        did_doc_to_aries_did_doc(&did_doc).unwrap_err();
+    }
+}
@@ -79,9 +79,78 @@ pub async fn get_verkey_from_ledger(indy_ledger: &Arc<dyn IndyLedgerRead>, did:
         .to_string())
 }
 
+/// Multicodec prefix of an Ed25519 public key, used when expressing a verkey as a `did:key`.
+const ED25519_MULTICODEC_PREFIX: [u8; 2] = [0xed, 0x01];
+
+/// Converts a base58 encoded Ed25519 verkey into its `did:key` representation.
+pub fn verkey_to_did_key(verkey: &str) -> VcxResult<String> {
+    let key_bytes = bs58::decode(verkey).into_vec().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Cannot decode verkey {}: {:?}", verkey, err),
+        )
+    })?;
+
+    let mut multicodec_key = ED25519_MULTICODEC_PREFIX.to_vec();
+    multicodec_key.extend(&key_bytes);
+
+    Ok(format!("did:key:z{}", bs58::encode(multicodec_key).into_string()))
+}
+
+/// Converts an Ed25519 `did:key` into a base58 encoded verkey.
+/// Keys which are not `did:key`s are returned unchanged.
+pub fn did_key_to_verkey(key: &str) -> VcxResult<String> {
+    let Some(multibase_key) = key.strip_prefix("did:key:") else {
+        return Ok(key.to_owned());
+    };
+
+    // A key reference, such as `did:key:z6Mk...#z6Mk...`, points to the key itself.
+    let multibase_key = multibase_key.split('#').next().unwrap_or_default();
+
+    let decoded = multibase_key
+        .strip_prefix('z')
+        .map(|key| bs58::decode(key).into_vec())
+        .transpose()
+        .map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidVerkey,
+                format!("Cannot decode did:key {}: {:?}", key, err),
+            )
+        })?;
+
+    match decoded {
+        Some(bytes) if bytes.starts_with(&ED25519_MULTICODEC_PREFIX) => {
+            Ok(bs58::encode(&bytes[ED25519_MULTICODEC_PREFIX.len()..]).into_string())
+        }
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!(
+                "Only base58btc encoded Ed25519 did:key values are supported, found: {}",
+                key
+            ),
+        )),
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
+    use super::*;
+
+    #[test]
+    fn test_verkey_did_key_roundtrip() {
+        let verkey = "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K";
+        let did_key = verkey_to_did_key(verkey).unwrap();
+
+        assert_eq!(did_key, "did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th");
+        assert_eq!(did_key_to_verkey(&did_key).unwrap(), verkey);
+        assert_eq!(
+            did_key_to_verkey(&format!("{did_key}#z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th")).unwrap(),
+            verkey
+        );
+        assert_eq!(did_key_to_verkey(verkey).unwrap(), verkey);
+    }
+
     #[tokio::test]
     #[ignore]
     #[cfg(all(
@@ -1,5 +1,6 @@
 pub mod anoncreds;
 pub mod credentials;
+pub mod did_doc_conversion;
 pub mod keys;
 pub mod ledger;
 pub mod primitives;
@@ -2,10 +2,16 @@ use std::sync::Arc;
 
 use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use base64;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::decorators::attachment::{
+    Attachment, AttachmentData, AttachmentJws, AttachmentJwsHeader, AttachmentType,
+};
+use messages::misc::MimeType;
 use messages::msg_fields::protocols::connection::response::{ConnectionSignature, ResponseContent};
 use messages::msg_fields::protocols::connection::ConnectionData;
 use time;
 
+use crate::common::keys::verkey_to_did_key;
 use crate::errors::error::prelude::*;
 use crate::global::settings;
 
@@ -78,6 +84,131 @@ pub async fn decode_signed_connection_response(
     Ok(connection)
 }
 
+/// Creates a `did_doc~attach` attachment as used by the DID Exchange protocol.
+///
+/// The [`AriesDidDoc`] is base64 encoded and signed with `key`. The signature is stored in the
+/// attachment's `jws` field as a JWS with detached payload, the payload being the base64url encoded
+/// DID document and the signer's `did:key` being given in both the protected and unprotected header.
+pub async fn sign_did_doc_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    key: &str,
+    did_doc: &AriesDidDoc,
+) -> VcxResult<Attachment> {
+    let did_doc = json!(did_doc).to_string();
+    let did_key = verkey_to_did_key(key)?;
+    let key_bytes = bs58::decode(key)
+        .into_vec()
+        .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidVerkey, err.to_string()))?;
+    let jwk_x = base64::encode_config(&key_bytes, base64::URL_SAFE_NO_PAD);
+
+    let protected = json!({
+        "alg": "EdDSA",
+        "kid": did_key,
+        "jwk": {
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": jwk_x,
+            "kid": did_key
+        }
+    })
+    .to_string();
+
+    let protected = base64::encode_config(&protected, base64::URL_SAFE_NO_PAD);
+    let payload = base64::encode_config(&did_doc, base64::URL_SAFE_NO_PAD);
+    let signing_input = format!("{}.{}", protected, payload);
+
+    let signature = wallet.sign(key, signing_input.as_bytes()).await?;
+    let signature = base64::encode_config(&signature, base64::URL_SAFE_NO_PAD);
+
+    let mut attach_data = AttachmentData::new(AttachmentType::Base64(base64::encode(&did_doc)));
+    attach_data.jws = Some(AttachmentJws::new(
+        AttachmentJwsHeader::new(did_key),
+        protected,
+        signature,
+    ));
+
+    let mut attachment = Attachment::new(attach_data);
+    attachment.mime_type = Some(MimeType::Json);
+
+    Ok(attachment)
+}
+
+fn get_did_doc_attachment_bytes(attachment: &Attachment) -> VcxResult<Vec<u8>> {
+    let AttachmentType::Base64(encoded) = &attachment.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("DID document attachment is not base64 encoded: {:?}", attachment),
+        ));
+    };
+
+    base64::decode(encoded).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot decode DID document attachment: {:?}", err),
+        )
+    })
+}
+
+/// Decodes the [`AriesDidDoc`] from a `did_doc~attach` attachment, without checking any signature.
+pub fn decode_did_doc_attachment(attachment: &Attachment) -> VcxResult<AriesDidDoc> {
+    let bytes = get_did_doc_attachment_bytes(attachment)?;
+
+    serde_json::from_slice(&bytes)
+        .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, err.to_string()))
+}
+
+/// Decodes the [`AriesDidDoc`] from a `did_doc~attach` attachment created through
+/// [`sign_did_doc_attachment`], verifying that it was signed by `their_vk`.
+pub async fn decode_signed_did_doc_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    attachment: &Attachment,
+    their_vk: &str,
+) -> VcxResult<AriesDidDoc> {
+    let jws = attachment.data.jws.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, "DID document attachment is not signed")
+    })?;
+
+    let header = base64::decode_config(&jws.protected, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot decode DID document attachment JWS header: {:?}", err),
+        )
+    })?;
+    let header: serde_json::Value = serde_json::from_slice(&header)
+        .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, err.to_string()))?;
+
+    // The signer may be declared in either header, the protected one taking precedence
+    let kid = header["kid"].as_str().or(jws.header.kid.as_deref());
+    let did_key = verkey_to_did_key(their_vk)?;
+    if kid != Some(did_key.as_str()) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "Signer declared in DID document attachment is not matching the expected signer",
+        ));
+    }
+
+    let signature = base64::decode_config(&jws.signature, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Cannot decode DID document attachment signature: {:?}", err),
+        )
+    })?;
+
+    let bytes = get_did_doc_attachment_bytes(attachment)?;
+    let payload = base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD);
+    let signing_input = format!("{}.{}", jws.protected, payload);
+
+    if !wallet.verify(their_vk, signing_input.as_bytes(), &signature).await? {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "DID document attachment signature is invalid for the expected signer",
+        ));
+    }
+
+    serde_json::from_slice(&bytes)
+        .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, err.to_string()))
+}
+
 pub async fn unpack_message_to_string(wallet: &Arc<dyn BaseWallet>, msg: &[u8]) -> VcxResult<String> {
     if settings::indy_mocks_enabled() {
         return Ok(String::new());
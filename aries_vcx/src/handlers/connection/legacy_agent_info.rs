@@ -1,7 +1,12 @@
+use crate::handlers::discovery::policy::DisclosurePolicy;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegacyAgentInfo {
     pub pw_did: String,
     pub pw_vk: String,
     pub agent_did: String,
     pub agent_vk: String,
+    /// Only persisted when it differs from the default policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disclosure_policy: Option<DisclosurePolicy>,
 }
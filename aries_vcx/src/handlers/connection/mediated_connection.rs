@@ -13,9 +13,13 @@ use messages::msg_fields::protocols::connection::invitation::Invitation;
 use messages::msg_fields::protocols::connection::request::Request;
 use messages::msg_fields::protocols::connection::Connection;
 use messages::msg_fields::protocols::discover_features::disclose::Disclose;
+use messages::msg_fields::protocols::discover_features::v2::disclosures::Disclosures;
+use messages::msg_fields::protocols::discover_features::v2::{DiscoverFeaturesV2, FeatureQuery};
 use messages::msg_fields::protocols::discover_features::{DiscoverFeatures, ProtocolDescriptor};
 use messages::msg_fields::protocols::out_of_band::OutOfBand;
 use messages::msg_fields::protocols::trust_ping::TrustPing;
+use messages::msg_types::traits::ProtocolName;
+use messages::msg_types::Protocol;
 use messages::AriesMessage;
 use serde::de::{Error, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -32,7 +36,11 @@ use crate::core::profile::profile::Profile;
 use crate::errors::error::prelude::*;
 use crate::handlers::connection::cloud_agent::CloudAgentInfo;
 use crate::handlers::connection::legacy_agent_info::LegacyAgentInfo;
-use crate::handlers::discovery::{respond_discovery_query, send_discovery_query};
+use crate::handlers::discovery::policy::DisclosurePolicy;
+use crate::handlers::discovery::{
+    ensure_protocol_supported, negotiate_protocol, respond_discovery_queries, respond_discovery_query,
+    send_discovery_queries, send_discovery_query,
+};
 use crate::handlers::trust_ping::TrustPingSender;
 use crate::handlers::util::AnyInvitation;
 use crate::protocols::mediated_connection::invitee::state_machine::{
@@ -53,6 +61,7 @@ pub struct MediatedConnection {
     connection_sm: SmConnection,
     cloud_agent_info: Option<CloudAgentInfo>,
     autohop_enabled: bool,
+    disclosure_policy: DisclosurePolicy,
 }
 
 #[derive(Clone, PartialEq)]
@@ -110,6 +119,7 @@ impl MediatedConnection {
             cloud_agent_info,
             connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id, pairwise_info)),
             autohop_enabled,
+            disclosure_policy: DisclosurePolicy::default(),
         })
     }
 
@@ -132,6 +142,7 @@ impl MediatedConnection {
             cloud_agent_info,
             connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id, pairwise_info, did_doc)),
             autohop_enabled,
+            disclosure_policy: DisclosurePolicy::default(),
         };
         connection.process_invite(invitation)?;
         Ok(connection)
@@ -152,6 +163,7 @@ impl MediatedConnection {
             cloud_agent_info: None,
             connection_sm: SmConnection::Inviter(SmConnectionInviter::new(&request.id, pairwise_info)),
             autohop_enabled: true,
+            disclosure_policy: DisclosurePolicy::default(),
         };
         connection.process_request(wallet, agency_client, request).await?;
         Ok(connection)
@@ -175,6 +187,7 @@ impl MediatedConnection {
                     state,
                 )),
                 autohop_enabled,
+                disclosure_policy: DisclosurePolicy::default(),
             },
             SmConnectionState::Invitee(state) => Self {
                 cloud_agent_info,
@@ -185,6 +198,7 @@ impl MediatedConnection {
                     state,
                 )),
                 autohop_enabled,
+                disclosure_policy: DisclosurePolicy::default(),
             },
         }
     }
@@ -258,6 +272,24 @@ impl MediatedConnection {
         }
     }
 
+    /// Picks the highest version of the protocol `T` supported by both parties, if the
+    /// counterparty disclosed its protocols.
+    pub fn negotiate_protocol<T>(&self) -> Option<T>
+    where
+        T: ProtocolName + TryFrom<Protocol>,
+    {
+        negotiate_protocol(&self.get_remote_protocols()?)
+    }
+
+    /// Sets which features are disclosed when the counterparty queries them.
+    pub fn set_disclosure_policy(&mut self, disclosure_policy: DisclosurePolicy) {
+        self.disclosure_policy = disclosure_policy;
+    }
+
+    pub fn disclosure_policy(&self) -> &DisclosurePolicy {
+        &self.disclosure_policy
+    }
+
     pub fn get_remote_protocols(&self) -> Option<Vec<ProtocolDescriptor>> {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => sm_inviter.get_remote_protocols(),
@@ -428,7 +460,9 @@ impl MediatedConnection {
                 | AriesMessage::OutOfBand(OutOfBand::HandshakeReuse(_))
                 | AriesMessage::OutOfBand(OutOfBand::HandshakeReuseAccepted(_))
                 | AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(_))
-                | AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(_)) => return Some((uid, message)),
+                | AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(_))
+                | AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Queries(_))
+                | AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Disclosures(_)) => return Some((uid, message)),
                 _ => {}
             }
         }
@@ -471,7 +505,7 @@ impl MediatedConnection {
                 send_message(Arc::clone(wallet), pw_vk.to_string(), did_doc.clone(), msg.into()).await?;
             }
             AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(query)) => {
-                let supported_protocols = query.content.lookup();
+                let supported_protocols = self.disclosure_policy.disclose_protocols(&query.content.query);
 
                 info!(
                     "Answering discovery protocol query, @id: {}, with supported protocols: {:?}",
@@ -486,6 +520,21 @@ impl MediatedConnection {
 
                 self.connection_sm = self.handle_disclose(disclose).await?;
             }
+            AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Queries(queries)) => {
+                let disclosures = self.disclosure_policy.disclose_features(&queries.content.queries);
+
+                info!(
+                    "Answering discovery protocol queries, @id: {}, with disclosures: {:?}",
+                    &queries.id, &disclosures
+                );
+
+                respond_discovery_queries(wallet, queries, &did_doc, pw_vk, disclosures).await?;
+            }
+            AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Disclosures(disclosures)) => {
+                info!("Handling disclosures message, @id: {}", disclosures.id);
+
+                self.connection_sm = self.handle_disclosures(disclosures).await?;
+            }
             _ => {
                 // todo: implement to_string for A2AMessage, printing only type of the message, not entire payload
                 // todo: attempt to print @id / thread_id of the message
@@ -594,6 +643,7 @@ impl MediatedConnection {
                     cloud_agent_info: new_cloud_agent_info.or(self.cloud_agent_info.clone()),
                     connection_sm: SmConnection::Inviter(sm_inviter),
                     autohop_enabled: self.autohop_enabled,
+                    disclosure_policy: self.disclosure_policy.clone(),
                 };
 
                 Ok((connection, can_autohop))
@@ -646,6 +696,7 @@ impl MediatedConnection {
                     connection_sm: SmConnection::Invitee(sm_invitee),
                     cloud_agent_info: self.cloud_agent_info.clone(),
                     autohop_enabled: self.autohop_enabled,
+                    disclosure_policy: self.disclosure_policy.clone(),
                 };
                 Ok((connection, can_autohop))
             }
@@ -657,6 +708,17 @@ impl MediatedConnection {
         }
     }
 
+    async fn handle_disclosures(&self, disclosures: Disclosures) -> VcxResult<SmConnection> {
+        match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => Ok(SmConnection::Inviter(
+                sm_inviter.clone().handle_disclosures(disclosures)?,
+            )),
+            SmConnection::Invitee(sm_invitee) => Ok(SmConnection::Invitee(
+                sm_invitee.clone().handle_disclosures(disclosures)?,
+            )),
+        }
+    }
+
     async fn handle_disclose(&self, disclose: Disclose) -> VcxResult<SmConnection> {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -790,8 +852,16 @@ impl MediatedConnection {
             "Cannot send message: Remote Connection information is not set",
         ))?;
         let sender_vk = self.pairwise_info().pw_vk.clone();
+        // Messages built by the protocol handlers are checked against the protocols the
+        // counterparty disclosed, as handlers only speak a single version of their protocol.
+        let remote_protocols = self.get_remote_protocols();
         Ok(Box::new(move |message: AriesMessage| {
-            Box::pin(send_message(wallet, sender_vk.clone(), did_doc.clone(), message))
+            Box::pin(async move {
+                if let Some(remote_protocols) = remote_protocols {
+                    ensure_protocol_supported(&remote_protocols, &message)?;
+                }
+                send_message(wallet, sender_vk, did_doc, message).await
+            })
         }))
     }
 
@@ -898,6 +968,20 @@ impl MediatedConnection {
         Ok(())
     }
 
+    pub async fn send_discovery_queries(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        queries: Vec<FeatureQuery>,
+    ) -> VcxResult<()> {
+        trace!("MediatedConnection::send_discovery_queries >>> queries: {:?}", queries);
+        let did_doc = self.their_did_doc().ok_or(AriesVcxError::from_msg(
+            AriesVcxErrorKind::NotReady,
+            "Can't send discovery queries to the counterparty, because their did doc is not available".to_string(),
+        ))?;
+        send_discovery_queries(wallet, queries, &did_doc, &self.pairwise_info().pw_vk).await?;
+        Ok(())
+    }
+
     pub async fn get_connection_info(&self, agency_client: &AgencyClient) -> VcxResult<String> {
         trace!("MediatedConnection::get_connection_info >>>");
 
@@ -1013,6 +1097,8 @@ impl Serialize for MediatedConnection {
     where
         S: Serializer,
     {
+        let disclosure_policy =
+            (self.disclosure_policy != DisclosurePolicy::default()).then(|| self.disclosure_policy.clone());
         let (state, pairwise_info, cloud_agent_info, source_id, thread_id) = self.to_owned().into();
         let CloudAgentInfo { agent_did, agent_vk } = cloud_agent_info.unwrap_or_default();
         let data = LegacyAgentInfo {
@@ -1020,6 +1106,7 @@ impl Serialize for MediatedConnection {
             pw_vk: pairwise_info.pw_vk,
             agent_did,
             agent_vk,
+            disclosure_policy,
         };
         let object = SerializableObjectWithState::V1 {
             data,
@@ -1068,7 +1155,12 @@ impl<'de> Visitor<'de> for ConnectionVisitor {
                     agent_did: data.agent_did,
                     agent_vk: data.agent_vk,
                 };
-                Ok((state, pairwise_info, Some(cloud_agent_info), source_id, thread_id).into())
+                let mut connection: MediatedConnection =
+                    (state, pairwise_info, Some(cloud_agent_info), source_id, thread_id).into();
+                if let Some(disclosure_policy) = data.disclosure_policy {
+                    connection.set_disclosure_policy(disclosure_policy);
+                }
+                Ok(connection)
             }
         }
     }
@@ -1109,6 +1201,50 @@ impl From<(SmConnectionState, PairwiseInfo, Option<CloudAgentInfo>, String, Stri
     }
 }
 
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use messages::msg_types::Role;
+
+    use super::*;
+
+    fn _connection() -> MediatedConnection {
+        let pairwise_info = PairwiseInfo {
+            pw_did: "2ZHFFhzA2XtTD6hJqzL7ux".to_owned(),
+            pw_vk: "rCw3x5h1jS6gPo7rRrt3EYbXXe5nNjnGbdf1jAwUxuj".to_owned(),
+        };
+        MediatedConnection {
+            connection_sm: SmConnection::Inviter(SmConnectionInviter::new("source_id", pairwise_info)),
+            cloud_agent_info: Some(CloudAgentInfo::default()),
+            autohop_enabled: true,
+            disclosure_policy: DisclosurePolicy::default(),
+        }
+    }
+
+    #[test]
+    fn test_serialization_keeps_disclosure_policy() {
+        let mut connection = _connection();
+        let disclosure_policy = DisclosurePolicy::default()
+            .allow_protocols(vec!["https://didcomm.org/issue-credential/*".to_owned()])
+            .allow_roles(vec![Role::Issuer]);
+        connection.set_disclosure_policy(disclosure_policy.clone());
+
+        let serialized = serde_json::to_string(&connection).unwrap();
+        let deserialized: MediatedConnection = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.disclosure_policy(), &disclosure_policy);
+    }
+
+    #[test]
+    fn test_serialization_omits_default_disclosure_policy() {
+        let serialized = serde_json::to_value(_connection()).unwrap();
+        assert!(serialized["data"].get("disclosure_policy").is_none());
+
+        let deserialized: MediatedConnection = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.disclosure_policy(), &DisclosurePolicy::default());
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 
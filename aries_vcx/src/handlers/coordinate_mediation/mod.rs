@@ -0,0 +1,402 @@
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::msg_fields::protocols::coordinate_mediation::{
+    keylist::Keylist,
+    keylist_query::KeylistQueryPaginate,
+    keylist_update::{KeylistUpdateAction, KeylistUpdateItem},
+    keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult},
+    mediate_deny::MediateDeny,
+    mediate_grant::MediateGrant,
+    CoordinateMediation,
+};
+use messages::AriesMessage;
+use url::Url;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::protocols::coordinate_mediation::{build_keylist_query, build_keylist_update, build_mediate_request};
+use crate::protocols::SendClosure;
+
+use super::util::matches_thread_id;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MediationState {
+    Initial,
+    Requested,
+    Granted,
+    Denied,
+}
+
+/// Client (recipient) side of the [coordinate mediation protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0211-route-coordination/README.md).
+///
+/// Keeps track of the mediator's endpoint and routing keys, as well as of the recipient keys
+/// registered with the mediator. Messages are expected to be sent over an already
+/// established connection with the mediator, through the provided [`SendClosure`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediationClient {
+    state: MediationState,
+    thread_id: Option<String>,
+    endpoint: Option<Url>,
+    routing_keys: Vec<String>,
+    recipient_keys: Vec<String>,
+    pending_thread_ids: Vec<String>,
+}
+
+impl Default for MediationClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediationClient {
+    pub fn new() -> Self {
+        Self {
+            state: MediationState::Initial,
+            thread_id: None,
+            endpoint: None,
+            routing_keys: Vec::new(),
+            recipient_keys: Vec::new(),
+            pending_thread_ids: Vec::new(),
+        }
+    }
+
+    pub fn get_state(&self) -> MediationState {
+        self.state
+    }
+
+    pub fn get_thread_id(&self) -> Option<&str> {
+        self.thread_id.as_deref()
+    }
+
+    pub fn is_granted(&self) -> bool {
+        self.state == MediationState::Granted
+    }
+
+    /// Returns the mediator's endpoint, available once mediation was granted.
+    pub fn endpoint(&self) -> Option<&Url> {
+        self.endpoint.as_ref()
+    }
+
+    /// Returns the routing keys granted by the mediator.
+    pub fn routing_keys(&self) -> &[String] {
+        &self.routing_keys
+    }
+
+    /// Returns the recipient keys known to be registered with the mediator.
+    pub fn recipient_keys(&self) -> &[String] {
+        &self.recipient_keys
+    }
+
+    /// Sets the mediator's endpoint and routing keys as the service endpoint and routing keys
+    /// of the provided [`AriesDidDoc`].
+    ///
+    /// Once the DidDoc is shared with a counterparty, their messages get wrapped into forward
+    /// messages for the mediator's routing keys through
+    /// [`crate::utils::encryption_envelope::EncryptionEnvelope`].
+    pub fn apply_routing(&self, did_doc: &mut AriesDidDoc) -> VcxResult<()> {
+        let endpoint = self.granted_endpoint()?;
+
+        did_doc.set_service_endpoint(endpoint.clone());
+        did_doc.set_routing_keys(self.routing_keys.clone());
+
+        Ok(())
+    }
+
+    pub async fn send_mediate_request(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        if !matches!(self.state, MediationState::Initial | MediationState::Denied) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Cannot request mediation in state {:?}", self.state),
+            ));
+        }
+
+        let request = build_mediate_request();
+        let thread_id = request.id.clone();
+
+        send_message(request.into()).await?;
+
+        self.thread_id = Some(thread_id);
+        self.state = MediationState::Requested;
+        Ok(())
+    }
+
+    pub fn handle_mediate_grant(&mut self, grant: MediateGrant) -> VcxResult<()> {
+        self.verify_request_thread(&grant)?;
+
+        self.endpoint = Some(grant.content.endpoint);
+        self.routing_keys = grant.content.routing_keys;
+        self.state = MediationState::Granted;
+        Ok(())
+    }
+
+    pub fn handle_mediate_deny(&mut self, deny: MediateDeny) -> VcxResult<()> {
+        self.verify_request_thread(&deny)?;
+
+        self.endpoint = None;
+        self.routing_keys = Vec::new();
+        self.state = MediationState::Denied;
+        Ok(())
+    }
+
+    /// Sends a keylist update to the mediator.
+    /// The recipient keys are updated once the mediator's response is processed.
+    pub async fn send_keylist_update(
+        &mut self,
+        updates: Vec<KeylistUpdateItem>,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        self.granted_endpoint()?;
+
+        let update = build_keylist_update(updates);
+        let thread_id = update.id.clone();
+
+        send_message(update.into()).await?;
+
+        self.pending_thread_ids.push(thread_id);
+        Ok(())
+    }
+
+    pub async fn add_recipient_key(&mut self, recipient_key: String, send_message: SendClosure) -> VcxResult<()> {
+        let update = KeylistUpdateItem::new(recipient_key, KeylistUpdateAction::Add);
+        self.send_keylist_update(vec![update], send_message).await
+    }
+
+    pub async fn remove_recipient_key(&mut self, recipient_key: String, send_message: SendClosure) -> VcxResult<()> {
+        let update = KeylistUpdateItem::new(recipient_key, KeylistUpdateAction::Remove);
+        self.send_keylist_update(vec![update], send_message).await
+    }
+
+    pub fn handle_keylist_update_response(&mut self, response: KeylistUpdateResponse) -> VcxResult<()> {
+        self.take_pending_thread(&response.decorators.thread.thid)?;
+
+        for item in response.content.updated {
+            match (item.action, item.result) {
+                (KeylistUpdateAction::Add, KeylistUpdateResult::Success | KeylistUpdateResult::NoChange) => {
+                    if !self.recipient_keys.contains(&item.recipient_key) {
+                        self.recipient_keys.push(item.recipient_key);
+                    }
+                }
+                (KeylistUpdateAction::Remove, KeylistUpdateResult::Success | KeylistUpdateResult::NoChange) => {
+                    self.recipient_keys.retain(|key| key != &item.recipient_key);
+                }
+                (action, result) => {
+                    warn!(
+                        "Mediator failed to {:?} recipient key {}: {:?}",
+                        action, item.recipient_key, result
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send_keylist_query(
+        &mut self,
+        paginate: Option<KeylistQueryPaginate>,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        self.granted_endpoint()?;
+
+        let query = build_keylist_query(paginate);
+        let thread_id = query.id.clone();
+
+        send_message(query.into()).await?;
+
+        self.pending_thread_ids.push(thread_id);
+        Ok(())
+    }
+
+    /// Processes the [`Keylist`] sent by the mediator as a response to a keylist query.
+    /// The first page of a (possibly paginated) keylist replaces the known recipient keys.
+    pub fn handle_keylist(&mut self, keylist: Keylist) -> VcxResult<()> {
+        self.take_pending_thread(&keylist.decorators.thread.thid)?;
+
+        let is_first_page = keylist.content.pagination.as_ref().map_or(true, |p| p.offset == 0);
+        if is_first_page {
+            self.recipient_keys.clear();
+        }
+
+        for item in keylist.content.keys {
+            if !self.recipient_keys.contains(&item.recipient_key) {
+                self.recipient_keys.push(item.recipient_key);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_message(&mut self, message: AriesMessage) -> VcxResult<()> {
+        match message {
+            AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(grant)) => {
+                self.handle_mediate_grant(grant)
+            }
+            AriesMessage::CoordinateMediation(CoordinateMediation::MediateDeny(deny)) => self.handle_mediate_deny(deny),
+            AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(response)) => {
+                self.handle_keylist_update_response(response)
+            }
+            AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(keylist)) => self.handle_keylist(keylist),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!("Message is not supported by the mediation client: {:?}", message),
+            )),
+        }
+    }
+
+    fn granted_endpoint(&self) -> VcxResult<&Url> {
+        match (&self.state, &self.endpoint) {
+            (MediationState::Granted, Some(endpoint)) => Ok(endpoint),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                format!("Mediation has not been granted, current state: {:?}", self.state),
+            )),
+        }
+    }
+
+    fn verify_request_thread<T>(&self, msg: &T) -> VcxResult<()>
+    where
+        T: MediationResponse,
+    {
+        match (&self.state, self.thread_id.as_deref()) {
+            (MediationState::Requested, Some(thread_id)) if msg.matches_thread_id(thread_id) => Ok(()),
+            (MediationState::Requested, _) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                "Thread ID mismatch",
+            )),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Message was not expected in state {:?}", self.state),
+            )),
+        }
+    }
+
+    fn take_pending_thread(&mut self, thread_id: &str) -> VcxResult<()> {
+        let position = self
+            .pending_thread_ids
+            .iter()
+            .position(|id| id == thread_id)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("No pending keylist update or query found for thread ID {}", thread_id),
+                )
+            })?;
+
+        self.pending_thread_ids.remove(position);
+        Ok(())
+    }
+}
+
+/// Helper trait for checking the thread of the mediator's answers to a mediation request.
+trait MediationResponse {
+    fn matches_thread_id(&self, thread_id: &str) -> bool;
+}
+
+impl MediationResponse for MediateGrant {
+    fn matches_thread_id(&self, thread_id: &str) -> bool {
+        matches_thread_id!(self, thread_id)
+    }
+}
+
+impl MediationResponse for MediateDeny {
+    fn matches_thread_id(&self, thread_id: &str) -> bool {
+        matches_thread_id!(self, thread_id)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use messages::decorators::thread::Thread;
+    use messages::msg_fields::protocols::coordinate_mediation::keylist_update_response::{
+        KeylistUpdateResponseContent, KeylistUpdateResponseDecorators, KeylistUpdateResponseItem,
+    };
+    use messages::msg_fields::protocols::coordinate_mediation::mediate_deny::{
+        MediateDenyContent, MediateDenyDecorators,
+    };
+    use messages::msg_fields::protocols::coordinate_mediation::mediate_grant::{
+        MediateGrantContent, MediateGrantDecorators,
+    };
+
+    use super::*;
+
+    fn _send_message() -> SendClosure {
+        Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) }))
+    }
+
+    fn _grant(thread_id: &str) -> MediateGrant {
+        let content = MediateGrantContent::new(
+            "https://mediator.dummy/endpoint".parse().unwrap(),
+            vec!["did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th".to_owned()],
+        );
+        let decorators = MediateGrantDecorators::new(Thread::new(thread_id.to_owned()));
+        MediateGrant::with_decorators("grant_id".to_owned(), content, decorators)
+    }
+
+    async fn _granted_client() -> MediationClient {
+        let mut client = MediationClient::new();
+        client.send_mediate_request(_send_message()).await.unwrap();
+        let thread_id = client.get_thread_id().unwrap().to_owned();
+        client.handle_mediate_grant(_grant(&thread_id)).unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_mediation_granted() {
+        let client = _granted_client().await;
+
+        assert_eq!(client.get_state(), MediationState::Granted);
+        assert_eq!(client.endpoint().unwrap().as_str(), "https://mediator.dummy/endpoint");
+        assert_eq!(client.routing_keys().len(), 1);
+
+        let mut did_doc = AriesDidDoc::default();
+        client.apply_routing(&mut did_doc).unwrap();
+        assert_eq!(did_doc.routing_keys(), client.routing_keys().to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_mediation_denied() {
+        let mut client = MediationClient::new();
+        client.send_mediate_request(_send_message()).await.unwrap();
+        let thread_id = client.get_thread_id().unwrap().to_owned();
+
+        let decorators = MediateDenyDecorators::new(Thread::new(thread_id));
+        let deny = MediateDeny::with_decorators("deny_id".to_owned(), MediateDenyContent::default(), decorators);
+        client.handle_mediate_deny(deny).unwrap();
+
+        assert_eq!(client.get_state(), MediationState::Denied);
+        client
+            .add_recipient_key("test_key".to_owned(), _send_message())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_should_fail_on_thread_id_mismatch() {
+        let mut client = MediationClient::new();
+        client.send_mediate_request(_send_message()).await.unwrap();
+
+        client.handle_mediate_grant(_grant("other_thread_id")).unwrap_err();
+        assert_eq!(client.get_state(), MediationState::Requested);
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_response_updates_recipient_keys() {
+        let mut client = _granted_client().await;
+        client
+            .add_recipient_key("test_key".to_owned(), _send_message())
+            .await
+            .unwrap();
+        let thread_id = client.pending_thread_ids[0].clone();
+
+        let content = KeylistUpdateResponseContent::new(vec![KeylistUpdateResponseItem::new(
+            "test_key".to_owned(),
+            KeylistUpdateAction::Add,
+            KeylistUpdateResult::Success,
+        )]);
+        let decorators = KeylistUpdateResponseDecorators::new(Thread::new(thread_id));
+        let response = KeylistUpdateResponse::with_decorators("response_id".to_owned(), content, decorators);
+
+        client.handle_message(response.into()).unwrap();
+        assert_eq!(client.recipient_keys(), ["test_key".to_owned()]);
+        assert!(client.pending_thread_ids.is_empty());
+    }
+}
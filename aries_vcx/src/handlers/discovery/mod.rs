@@ -1,6 +1,8 @@
+pub mod policy;
+
 use std::sync::Arc;
 
-use crate::errors::error::VcxResult;
+use crate::errors::error::prelude::*;
 use aries_vcx_core::wallet::base_wallet::BaseWallet;
 use chrono::Utc;
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
@@ -8,7 +10,17 @@ use messages::decorators::thread::Thread;
 use messages::decorators::timing::Timing;
 use messages::msg_fields::protocols::discover_features::disclose::{Disclose, DiscloseContent, DiscloseDecorators};
 use messages::msg_fields::protocols::discover_features::query::{Query, QueryContent, QueryDecorators};
+use messages::msg_fields::protocols::discover_features::v2::disclosures::{
+    Disclosures, DisclosuresContent, DisclosuresDecorators,
+};
+use messages::msg_fields::protocols::discover_features::v2::queries::{Queries, QueriesContent, QueriesDecorators};
+use messages::msg_fields::protocols::discover_features::v2::{FeatureDisclosure, FeatureQuery};
 use messages::msg_fields::protocols::discover_features::ProtocolDescriptor;
+use messages::msg_types::registry::get_supported_version;
+use messages::msg_types::traits::ProtocolName;
+use messages::msg_types::Protocol;
+use messages::AriesMessage;
+use shared_vcx::maybe_known::MaybeKnown;
 use uuid::Uuid;
 
 use crate::utils::send_message;
@@ -39,9 +51,11 @@ pub async fn respond_discovery_query(
     query: Query,
     did_doc: &AriesDidDoc,
     pw_vk: &str,
-    _supported_protocols: Vec<ProtocolDescriptor>,
+    supported_protocols: Vec<ProtocolDescriptor>,
 ) -> VcxResult<()> {
-    let content = DiscloseContent::default();
+    let content = DiscloseContent {
+        protocols: supported_protocols,
+    };
 
     let mut decorators = DiscloseDecorators::new(Thread::new(query.id));
     let mut timing = Timing::default();
@@ -52,3 +66,209 @@ pub async fn respond_discovery_query(
 
     send_message(Arc::clone(wallet), pw_vk.to_string(), did_doc.clone(), disclose.into()).await
 }
+
+pub async fn send_discovery_queries(
+    wallet: &Arc<dyn BaseWallet>,
+    queries: Vec<FeatureQuery>,
+    did_doc: &AriesDidDoc,
+    pw_vk: &str,
+) -> VcxResult<()> {
+    let content = QueriesContent::new(queries);
+
+    let mut decorators = QueriesDecorators::default();
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    let queries = Queries::with_decorators(Uuid::new_v4().to_string(), content, decorators);
+
+    send_message(Arc::clone(wallet), pw_vk.to_string(), did_doc.clone(), queries.into()).await
+}
+
+pub async fn respond_discovery_queries(
+    wallet: &Arc<dyn BaseWallet>,
+    queries: Queries,
+    did_doc: &AriesDidDoc,
+    pw_vk: &str,
+    disclosures: Vec<FeatureDisclosure>,
+) -> VcxResult<()> {
+    let content = DisclosuresContent::new(disclosures);
+
+    let mut decorators = DisclosuresDecorators::default();
+    decorators.thread = Some(Thread::new(queries.id));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    let disclosures = Disclosures::with_decorators(Uuid::new_v4().to_string(), content, decorators);
+
+    send_message(
+        Arc::clone(wallet),
+        pw_vk.to_string(),
+        did_doc.clone(),
+        disclosures.into(),
+    )
+    .await
+}
+
+/// Picks the highest version of the protocol `T` (e.g. [`messages::msg_types::cred_issuance::CredentialIssuanceType`])
+/// that both we, as per the [`messages::msg_types::registry::PROTOCOL_REGISTRY`], and the peer, as
+/// per the protocols it disclosed, support.
+///
+/// Peers disclosing a newer minor version of a major version we support are assumed to support
+/// ours as well, as minor versions are backwards compatible.
+pub fn negotiate_protocol<T>(remote_protocols: &[ProtocolDescriptor]) -> Option<T>
+where
+    T: ProtocolName + TryFrom<Protocol>,
+{
+    negotiate_version(remote_protocols, T::PROTOCOL, None).and_then(|protocol| T::try_from(protocol).ok())
+}
+
+/// Checks that the peer supports the protocol version in which an outgoing message was built,
+/// which is the case if negotiating its protocol among the versions of the same major version
+/// succeeds.
+///
+/// Peers which disclosed no version of the protocol at all are assumed to support it, as they
+/// may only have been queried for other protocols.
+pub fn ensure_protocol_supported(remote_protocols: &[ProtocolDescriptor], message: &AriesMessage) -> VcxResult<()> {
+    let Some(protocol) = message_protocol(message) else {
+        return Ok(());
+    };
+    let (name, major, minor) = protocol.as_parts();
+    if !remote_protocols
+        .iter()
+        .any(|descriptor| discloses_protocol(descriptor, name))
+        || negotiate_version(remote_protocols, name, Some(major)).is_some()
+    {
+        return Ok(());
+    }
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::ActionNotSupported,
+        format!(
+            "Counterparty does not support version {}.{} of the {} protocol",
+            major, minor, name
+        ),
+    ))
+}
+
+/// Highest version of the protocol `name`, restricted to the major version `major` if given,
+/// supported by both parties.
+fn negotiate_version(remote_protocols: &[ProtocolDescriptor], name: &str, major: Option<u8>) -> Option<Protocol> {
+    remote_protocols
+        .iter()
+        .filter_map(|descriptor| match descriptor.pid {
+            MaybeKnown::Known(protocol) => Some(protocol),
+            MaybeKnown::Unknown(_) => None,
+        })
+        .filter(|protocol| {
+            let (protocol_name, protocol_major, minor) = protocol.as_parts();
+            protocol_name == name
+                && major.map_or(true, |major| major == protocol_major)
+                && get_supported_version(protocol_name, protocol_major, minor) == Some(minor)
+        })
+        .max_by_key(|protocol| {
+            let (_, major, minor) = protocol.as_parts();
+            (major, minor)
+        })
+}
+
+fn discloses_protocol(descriptor: &ProtocolDescriptor, name: &str) -> bool {
+    match &descriptor.pid {
+        MaybeKnown::Known(protocol) => protocol.as_parts().0 == name,
+        // Versions we don't know, e.g. https://didcomm.org/issue-credential/3.0
+        MaybeKnown::Unknown(pid) => pid
+            .rsplit_once('/')
+            .map_or(false, |(uri, _)| uri.ends_with(&format!("/{}", name))),
+    }
+}
+
+/// The protocol of a message, as found in its `@type`.
+fn message_protocol(message: &AriesMessage) -> Option<Protocol> {
+    let message = serde_json::to_value(message).ok()?;
+    let (protocol, _) = message["@type"].as_str()?.rsplit_once('/')?;
+    protocol.parse().ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use messages::msg_types::cred_issuance::{
+        CredentialIssuanceType, CredentialIssuanceTypeV1, CredentialIssuanceTypeV2,
+    };
+    use messages::msg_types::present_proof::{PresentProofType, PresentProofTypeV1};
+
+    use super::*;
+
+    fn descriptor(pid: &str) -> ProtocolDescriptor {
+        ProtocolDescriptor::new(serde_json::from_value(serde_json::Value::String(pid.to_owned())).unwrap())
+    }
+
+    #[test]
+    fn test_negotiate_highest_mutual_version() {
+        let remote_protocols = vec![
+            descriptor("https://didcomm.org/issue-credential/1.0"),
+            descriptor("https://didcomm.org/issue-credential/2.4"),
+            descriptor("https://didcomm.org/issue-credential/3.0"),
+            descriptor("https://didcomm.org/present-proof/1.0"),
+        ];
+
+        assert_eq!(
+            negotiate_protocol::<CredentialIssuanceType>(&remote_protocols),
+            Some(CredentialIssuanceType::V2(CredentialIssuanceTypeV2::new_v2_0()))
+        );
+        assert_eq!(
+            negotiate_protocol::<PresentProofType>(&remote_protocols),
+            Some(PresentProofType::V1(PresentProofTypeV1::new_v1_0()))
+        );
+
+        let remote_protocols = vec![descriptor("https://didcomm.org/issue-credential/1.0")];
+        assert_eq!(
+            negotiate_protocol::<CredentialIssuanceType>(&remote_protocols),
+            Some(CredentialIssuanceType::V1(CredentialIssuanceTypeV1::new_v1_0()))
+        );
+    }
+
+    #[test]
+    fn test_negotiate_no_mutual_version() {
+        let remote_protocols = vec![
+            descriptor("https://didcomm.org/issue-credential/3.0"),
+            descriptor("https://didcomm.org/basicmessage/1.0"),
+        ];
+
+        assert_eq!(negotiate_protocol::<CredentialIssuanceType>(&remote_protocols), None);
+        assert_eq!(negotiate_protocol::<PresentProofType>(&[]), None);
+    }
+
+    fn basic_message() -> AriesMessage {
+        use messages::msg_fields::protocols::basic_message::{
+            BasicMessage, BasicMessageContent, BasicMessageDecorators,
+        };
+
+        BasicMessage::with_decorators(
+            "message_id".to_owned(),
+            BasicMessageContent::new("Hello".to_owned(), Utc::now()),
+            BasicMessageDecorators::default(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_ensure_protocol_supported() {
+        let message = basic_message();
+
+        // Newer minor versions of the same major version are backwards compatible.
+        for pid in [
+            "https://didcomm.org/basicmessage/1.0",
+            "https://didcomm.org/basicmessage/1.3",
+        ] {
+            ensure_protocol_supported(&[descriptor(pid)], &message).unwrap();
+        }
+        // Peers which were not queried for the protocol are given the benefit of the doubt.
+        ensure_protocol_supported(&[descriptor("https://didcomm.org/trust_ping/1.0")], &message).unwrap();
+        ensure_protocol_supported(&[], &message).unwrap();
+
+        let err =
+            ensure_protocol_supported(&[descriptor("https://didcomm.org/basicmessage/2.0")], &message).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+    }
+}
@@ -0,0 +1,226 @@
+use messages::msg_fields::protocols::discover_features::v2::{FeatureDisclosure, FeatureQuery, FeatureType};
+use messages::msg_fields::protocols::discover_features::{matches_query, ProtocolDescriptor};
+use messages::msg_types::registry::{RegistryEntry, PROTOCOL_REGISTRY};
+use messages::msg_types::Role;
+use shared_vcx::maybe_known::MaybeKnown;
+
+/// Decides which of our features are disclosed to peers querying them.
+///
+/// By default, every protocol of the [`PROTOCOL_REGISTRY`] is disclosed along with all its roles,
+/// while no goal codes are.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisclosurePolicy {
+    protocols: Vec<String>,
+    roles: Option<Vec<Role>>,
+    goal_codes: Vec<String>,
+}
+
+impl Default for DisclosurePolicy {
+    fn default() -> Self {
+        Self {
+            protocols: vec!["*".to_owned()],
+            roles: None,
+            goal_codes: Vec::new(),
+        }
+    }
+}
+
+impl DisclosurePolicy {
+    /// Only discloses protocols matching one of the given patterns, in which `*` matches any
+    /// sequence of characters (e.g. `https://didcomm.org/issue-credential/*`).
+    pub fn allow_protocols(mut self, patterns: Vec<String>) -> Self {
+        self.protocols = patterns;
+        self
+    }
+
+    /// Only discloses the given roles, omitting protocols in which we play none of them.
+    pub fn allow_roles(mut self, roles: Vec<Role>) -> Self {
+        self.roles = Some(roles);
+        self
+    }
+
+    pub fn add_goal_code(mut self, goal_code: String) -> Self {
+        self.goal_codes.push(goal_code);
+        self
+    }
+
+    /// Answers a discover features 1.0 query.
+    pub fn disclose_protocols(&self, query: &str) -> Vec<ProtocolDescriptor> {
+        self.matching_protocols(query)
+            .into_iter()
+            .map(|(entry, roles)| {
+                let mut descriptor = ProtocolDescriptor::new(MaybeKnown::Known(entry.protocol));
+                descriptor.roles = Some(roles);
+                descriptor
+            })
+            .collect()
+    }
+
+    pub fn disclose_goal_codes(&self, query: &str) -> Vec<String> {
+        self.goal_codes
+            .iter()
+            .filter(|goal_code| matches_query(query, goal_code))
+            .cloned()
+            .collect()
+    }
+
+    /// Answers the queries of a discover features 2.0 `queries` message. Features matched by
+    /// several queries are disclosed once, feature types we don't know are ignored.
+    pub fn disclose_features(&self, queries: &[FeatureQuery]) -> Vec<FeatureDisclosure> {
+        let mut disclosures: Vec<FeatureDisclosure> = Vec::new();
+
+        for query in queries {
+            let matched = match &query.feature_type {
+                MaybeKnown::Known(FeatureType::Protocol) => self
+                    .matching_protocols(&query.match_)
+                    .into_iter()
+                    .map(|(entry, roles)| {
+                        let mut disclosure = FeatureDisclosure::new(FeatureType::Protocol, entry.str_pid.clone());
+                        disclosure.roles = Some(roles);
+                        disclosure
+                    })
+                    .collect(),
+                MaybeKnown::Known(FeatureType::GoalCode) => self
+                    .disclose_goal_codes(&query.match_)
+                    .into_iter()
+                    .map(|goal_code| FeatureDisclosure::new(FeatureType::GoalCode, goal_code))
+                    .collect(),
+                MaybeKnown::Unknown(_) => Vec::new(),
+            };
+
+            for disclosure in matched {
+                if !disclosures
+                    .iter()
+                    .any(|d| d.feature_type == disclosure.feature_type && d.id == disclosure.id)
+                {
+                    disclosures.push(disclosure);
+                }
+            }
+        }
+
+        disclosures
+    }
+
+    /// Registry entries matching both the query and the allow-list, sorted by protocol URI, along
+    /// with the roles we disclose for them.
+    fn matching_protocols(&self, query: &str) -> Vec<(&'static RegistryEntry, Vec<MaybeKnown<Role>>)> {
+        let mut protocols: Vec<(&'static RegistryEntry, Vec<MaybeKnown<Role>>)> = PROTOCOL_REGISTRY
+            .values()
+            .flatten()
+            .filter(|entry| matches_query(query, &entry.str_pid))
+            .filter(|entry| {
+                self.protocols
+                    .iter()
+                    .any(|pattern| matches_query(pattern, &entry.str_pid))
+            })
+            .filter_map(|entry| {
+                let roles: Vec<MaybeKnown<Role>> = match &self.roles {
+                    None => entry.roles.clone(),
+                    Some(allowed) => entry
+                        .roles
+                        .iter()
+                        .filter(|role| matches!(role, MaybeKnown::Known(role) if allowed.contains(role)))
+                        .cloned()
+                        .collect(),
+                };
+                (self.roles.is_none() || !roles.is_empty()).then_some((entry, roles))
+            })
+            .collect();
+
+        protocols.sort_by(|(a, _), (b, _)| a.str_pid.cmp(&b.str_pid));
+        protocols
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CRED_ISSUANCE_V1: &str = "https://didcomm.org/issue-credential/1.0";
+    const CRED_ISSUANCE_V2: &str = "https://didcomm.org/issue-credential/2.0";
+
+    fn disclosed_ids(disclosures: &[FeatureDisclosure]) -> Vec<&str> {
+        disclosures.iter().map(|d| d.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_policy_discloses_all_protocols() {
+        let policy = DisclosurePolicy::default();
+
+        let all: usize = PROTOCOL_REGISTRY.values().map(Vec::len).sum();
+        assert_eq!(policy.disclose_protocols("*").len(), all);
+        assert_eq!(
+            policy
+                .disclose_protocols("https://didcomm.org/issue-credential/*")
+                .len(),
+            2
+        );
+        assert!(policy.disclose_goal_codes("*").is_empty());
+    }
+
+    #[test]
+    fn test_policy_restricts_protocols_and_roles() {
+        let policy = DisclosurePolicy::default()
+            .allow_protocols(vec![
+                "https://didcomm.org/issue-credential/*".to_owned(),
+                "https://didcomm.org/trust_ping/*".to_owned(),
+            ])
+            .allow_roles(vec![Role::Issuer]);
+
+        // Trust ping is allowed, but we play none of its roles.
+        let protocols = policy.disclose_protocols("*");
+        assert_eq!(protocols.len(), 2);
+        for protocol in protocols {
+            assert_eq!(protocol.roles, Some(vec![MaybeKnown::Known(Role::Issuer)]));
+        }
+        assert!(policy
+            .disclose_protocols("https://didcomm.org/connections/*")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_disclose_features() {
+        let policy = DisclosurePolicy::default()
+            .allow_protocols(vec!["https://didcomm.org/issue-credential/*".to_owned()])
+            .add_goal_code("aries.vc.issue".to_owned())
+            .add_goal_code("aries.vc.verify".to_owned());
+
+        let queries = vec![
+            FeatureQuery::new(
+                FeatureType::Protocol,
+                "https://didcomm.org/issue-credential/2.*".to_owned(),
+            ),
+            FeatureQuery::new(FeatureType::Protocol, "*".to_owned()),
+            FeatureQuery::new(FeatureType::GoalCode, "aries.vc.iss*".to_owned()),
+            FeatureQuery {
+                feature_type: MaybeKnown::Unknown("gov-fw".to_owned()),
+                match_: "*".to_owned(),
+            },
+        ];
+
+        assert_eq!(
+            disclosed_ids(&policy.disclose_features(&queries)),
+            vec![CRED_ISSUANCE_V2, CRED_ISSUANCE_V1, "aries.vc.issue"]
+        );
+    }
+
+    #[test]
+    fn test_policy_serialization() {
+        let policy = DisclosurePolicy::default()
+            .allow_protocols(vec!["https://didcomm.org/issue-credential/*".to_owned()])
+            .allow_roles(vec![Role::Issuer])
+            .add_goal_code("aries.vc.issue".to_owned());
+
+        let serialized = serde_json::to_string(&policy).unwrap();
+        assert_eq!(serde_json::from_str::<DisclosurePolicy>(&serialized).unwrap(), policy);
+
+        // Missing fields keep their default values.
+        let policy: DisclosurePolicy = serde_json::from_str(r#"{"goal_codes":["aries.vc.issue"]}"#).unwrap();
+        assert_eq!(
+            policy,
+            DisclosurePolicy::default().add_goal_code("aries.vc.issue".to_owned())
+        );
+    }
+}
@@ -0,0 +1,460 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use messages::msg_fields::protocols::{
+    connection::Connection,
+    cred_issuance::{v2::CredentialIssuanceV2, CredentialIssuance},
+    did_exchange::DidExchange,
+    notification::Notification,
+    present_proof::{v2::PresentProofV2, PresentProof},
+};
+use messages::msg_types::protocols::{
+    basic_message::BasicMessageType, connection::ConnectionType, coordinate_mediation::CoordinateMediationType,
+    cred_issuance::CredentialIssuanceType, did_exchange::DidExchangeType, discover_features::DiscoverFeaturesType,
+    notification::NotificationType, out_of_band::OutOfBandType, pickup::PickupType, present_proof::PresentProofType,
+    report_problem::ReportProblemType, revocation::RevocationType, routing::RoutingType, trust_ping::TrustPingType,
+};
+use messages::msg_types::traits::ProtocolName;
+use messages::AriesMessage;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::protocols::common::build_problem_report_msg;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+use crate::utils::thread::{message_thread, MessageThread};
+
+/// Problem report code sent back for messages no handler accepted.
+pub const UNSUPPORTED_MESSAGE_CODE: &str = "unsupported-message";
+/// Problem report code sent back for messages whose handler failed.
+pub const PROCESSING_ERROR_CODE: &str = "processing-error";
+
+/// Message received from a peer, along with what the [`Dispatcher`] found out about it.
+#[derive(Clone, Debug)]
+pub struct InboundMessage {
+    pub message: AriesMessage,
+    pub sender_vk: Option<String>,
+    /// Id of the connection the sender verkey belongs to, as resolved by the
+    /// [`ConnectionResolver`].
+    pub connection_id: Option<String>,
+    pub thread: MessageThread,
+}
+
+/// Gives the [`Dispatcher`] access to the connections of the consumer, which it identifies by
+/// their ids.
+#[async_trait]
+pub trait ConnectionResolver: Send + Sync {
+    /// Returns the id of the connection whose remote party uses the verkey, if any.
+    async fn resolve_connection(&self, sender_vk: &str) -> VcxResult<Option<String>>;
+
+    /// Sends the message to the remote party of the connection, used for problem reports.
+    async fn send_message(&self, connection_id: &str, message: AriesMessage) -> VcxResult<()>;
+}
+
+/// Handler of the messages of a protocol, typically looking up the protocol instance (such as an
+/// `Issuer` or a `Verifier`) running on the thread of the message and updating it.
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    /// Whether one of the protocol instances of the handler runs on the thread.
+    async fn has_thread(&self, thread_id: &str) -> VcxResult<bool>;
+
+    async fn handle(&self, message: InboundMessage) -> VcxResult<()>;
+}
+
+type UnknownMessageHook = Arc<dyn Fn(InboundMessage) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
+
+/// Routes inbound messages to the [`MessageHandler`] in charge of them.
+///
+/// A message is handed to the handler running a protocol instance on its `~thread.thid`, or
+/// else on its `~thread.pthid`. Messages belonging to no known thread start a new one and are
+/// handed to the first handler registered for their protocol. Messages no handler accepted go to
+/// the unknown message hook, if any.
+///
+/// Unless disabled, a problem report is sent back on the connection of the sender when a message
+/// is not handled or its handler fails.
+pub struct Dispatcher {
+    wallet: Arc<dyn BaseWallet>,
+    connections: Arc<dyn ConnectionResolver>,
+    handlers: Vec<(&'static str, Arc<dyn MessageHandler>)>,
+    unknown_message_hook: Option<UnknownMessageHook>,
+    send_problem_reports: bool,
+}
+
+impl Dispatcher {
+    pub fn new(wallet: Arc<dyn BaseWallet>, connections: Arc<dyn ConnectionResolver>) -> Self {
+        Self {
+            wallet,
+            connections,
+            handlers: Vec::new(),
+            unknown_message_hook: None,
+            send_problem_reports: true,
+        }
+    }
+
+    /// Registers a handler for the messages of the protocol, of any version, e.g.
+    /// `register::<CredentialIssuanceType>(handler)`.
+    pub fn register<T: ProtocolName>(mut self, handler: Arc<dyn MessageHandler>) -> Self {
+        self.handlers.push((T::PROTOCOL, handler));
+        self
+    }
+
+    /// Sets the hook receiving the messages no handler accepted. Messages the hook successfully
+    /// processed are not reported back as unsupported.
+    pub fn on_unknown_message<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(InboundMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = VcxResult<()>> + Send + 'static,
+    {
+        self.unknown_message_hook = Some(Arc::new(move |message| Box::pin(hook(message))));
+        self
+    }
+
+    pub fn send_problem_reports(mut self, enabled: bool) -> Self {
+        self.send_problem_reports = enabled;
+        self
+    }
+
+    /// Unpacks the envelope and dispatches the message it contains.
+    pub async fn dispatch_envelope(&self, payload: Vec<u8>) -> VcxResult<()> {
+        let (message, sender_vk) = EncryptionEnvelope::anon_unpack(&self.wallet, payload).await?;
+        self.dispatch(message, sender_vk).await
+    }
+
+    pub async fn dispatch(&self, message: AriesMessage, sender_vk: Option<String>) -> VcxResult<()> {
+        let connection_id = match &sender_vk {
+            Some(sender_vk) => self.connections.resolve_connection(sender_vk).await?,
+            None => None,
+        };
+        let thread = message_thread(&message);
+        // Problems are reported on the connection of the sender, if known.
+        let report_to = connection_id
+            .clone()
+            .filter(|_| self.send_problem_reports && !is_problem_report(&message));
+        let message = InboundMessage {
+            message,
+            sender_vk,
+            connection_id,
+            thread: thread.clone(),
+        };
+
+        let (result, code) = match self.find_handler(&message).await? {
+            Some(handler) => (handler.handle(message).await, PROCESSING_ERROR_CODE),
+            None => {
+                let result = match &self.unknown_message_hook {
+                    Some(hook) => hook(message).await,
+                    None => Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::ActionNotSupported,
+                        format!("No handler registered for message {:?}", message.message),
+                    )),
+                };
+                (result, UNSUPPORTED_MESSAGE_CODE)
+            }
+        };
+
+        if let (Err(err), Some(connection_id)) = (&result, report_to) {
+            warn!(
+                "Dispatcher::dispatch >> reporting problem {} on thread {}, err: {}",
+                code, thread.thid, err
+            );
+            let problem_report = build_problem_report_msg(Some(code.to_owned()), &thread.thid);
+            if let Err(err) = self
+                .connections
+                .send_message(&connection_id, problem_report.into())
+                .await
+            {
+                error!("Dispatcher::dispatch >> failed to send problem report, err: {}", err);
+            }
+        }
+
+        result
+    }
+
+    async fn find_handler(&self, message: &InboundMessage) -> VcxResult<Option<&Arc<dyn MessageHandler>>> {
+        // Protocol instances are looked up among all handlers, as acks and problem reports of a
+        // thread may belong to other protocols.
+        for thread_id in std::iter::once(&message.thread.thid).chain(message.thread.pthid.as_ref()) {
+            for (_, handler) in &self.handlers {
+                if handler.has_thread(thread_id).await? {
+                    return Ok(Some(handler));
+                }
+            }
+        }
+
+        let protocol = protocol_name(&message.message);
+        Ok(self
+            .handlers
+            .iter()
+            .find(|(handled, _)| *handled == protocol)
+            .map(|(_, handler)| handler))
+    }
+}
+
+fn protocol_name(message: &AriesMessage) -> &'static str {
+    match message {
+        AriesMessage::Routing(_) => RoutingType::PROTOCOL,
+        AriesMessage::Connection(_) => ConnectionType::PROTOCOL,
+        AriesMessage::Revocation(_) => RevocationType::PROTOCOL,
+        AriesMessage::CredentialIssuance(_) | AriesMessage::CredentialIssuanceV2(_) => CredentialIssuanceType::PROTOCOL,
+        AriesMessage::ReportProblem(_) => ReportProblemType::PROTOCOL,
+        AriesMessage::PresentProof(_) | AriesMessage::PresentProofV2(_) => PresentProofType::PROTOCOL,
+        AriesMessage::TrustPing(_) => TrustPingType::PROTOCOL,
+        AriesMessage::DiscoverFeatures(_) | AriesMessage::DiscoverFeaturesV2(_) => DiscoverFeaturesType::PROTOCOL,
+        AriesMessage::BasicMessage(_) => BasicMessageType::PROTOCOL,
+        AriesMessage::OutOfBand(_) => OutOfBandType::PROTOCOL,
+        AriesMessage::Notification(_) => NotificationType::PROTOCOL,
+        AriesMessage::DidExchange(_) => DidExchangeType::PROTOCOL,
+        AriesMessage::CoordinateMediation(_) => CoordinateMediationType::PROTOCOL,
+        AriesMessage::Pickup(_) => PickupType::PROTOCOL,
+    }
+}
+
+/// Problem reports are never answered with problem reports, lest two agents keep reporting each
+/// other's reports.
+fn is_problem_report(message: &AriesMessage) -> bool {
+    matches!(
+        message,
+        AriesMessage::ReportProblem(_)
+            | AriesMessage::Connection(Connection::ProblemReport(_))
+            | AriesMessage::DidExchange(DidExchange::ProblemReport(_))
+            | AriesMessage::CredentialIssuance(CredentialIssuance::ProblemReport(_))
+            | AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(_))
+            | AriesMessage::Notification(Notification::ProblemReport(_))
+            | AriesMessage::PresentProof(PresentProof::ProblemReport(_))
+            | AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(_))
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::sync::Mutex;
+
+    use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+    use super::*;
+    use crate::utils::constants::VERKEY;
+    use crate::utils::mockdata::profile::mock_wallet::MockWallet;
+
+    const CONNECTION_ID: &str = "connection-1";
+    const THREAD_ID: &str = "thread-1";
+
+    #[derive(Default)]
+    struct TestConnections {
+        sent: Mutex<Vec<AriesMessage>>,
+    }
+
+    #[async_trait]
+    impl ConnectionResolver for TestConnections {
+        async fn resolve_connection(&self, sender_vk: &str) -> VcxResult<Option<String>> {
+            Ok((sender_vk == VERKEY).then(|| CONNECTION_ID.to_owned()))
+        }
+
+        async fn send_message(&self, connection_id: &str, message: AriesMessage) -> VcxResult<()> {
+            assert_eq!(connection_id, CONNECTION_ID);
+            self.sent.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct TestHandler {
+        threads: Vec<String>,
+        fails: bool,
+        handled: Mutex<Vec<InboundMessage>>,
+    }
+
+    impl TestHandler {
+        fn running(thread_id: &str) -> Self {
+            Self {
+                threads: vec![thread_id.to_owned()],
+                ..Default::default()
+            }
+        }
+
+        fn handled(&self) -> usize {
+            self.handled.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait]
+    impl MessageHandler for TestHandler {
+        async fn has_thread(&self, thread_id: &str) -> VcxResult<bool> {
+            Ok(self.threads.iter().any(|thread| thread == thread_id))
+        }
+
+        async fn handle(&self, message: InboundMessage) -> VcxResult<()> {
+            self.handled.lock().unwrap().push(message);
+            if self.fails {
+                return Err(AriesVcxError::from_msg(AriesVcxErrorKind::InvalidState, "failed"));
+            }
+            Ok(())
+        }
+    }
+
+    fn dispatcher(connections: &Arc<TestConnections>) -> Dispatcher {
+        Dispatcher::new(Arc::new(MockWallet), connections.clone())
+    }
+
+    fn ping() -> AriesMessage {
+        serde_json::from_value(json!({
+            "@type": "https://didcomm.org/trust_ping/1.0/ping",
+            "@id": "ping-1",
+        }))
+        .unwrap()
+    }
+
+    fn ack(thread: serde_json::Value) -> AriesMessage {
+        serde_json::from_value(json!({
+            "@type": "https://didcomm.org/notification/1.0/ack",
+            "@id": "ack-1",
+            "status": "OK",
+            "~thread": thread,
+        }))
+        .unwrap()
+    }
+
+    fn sent_problem_reports(connections: &TestConnections) -> Vec<ProblemReport> {
+        connections
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|message| match message {
+                AriesMessage::ReportProblem(problem_report) => problem_report.clone(),
+                other => panic!("expected a problem report, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_message_thread() {
+        let thread = message_thread(&ping());
+        assert_eq!(thread, MessageThread::unthreaded("ping-1".to_owned()));
+        assert!(thread.matches(THREAD_ID));
+
+        let thread = message_thread(&ack(json!({ "thid": THREAD_ID, "pthid": "parent-1" })));
+        assert_eq!(thread.thid, THREAD_ID);
+        assert!(thread.matches("parent-1"));
+        assert!(!thread.matches("ack-1"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_to_thread_owner() {
+        let connections = Arc::new(TestConnections::default());
+        let issuer = Arc::new(TestHandler::default());
+        let verifier = Arc::new(TestHandler::running(THREAD_ID));
+        let dispatcher = dispatcher(&connections)
+            .register::<CredentialIssuanceType>(issuer.clone())
+            .register::<PresentProofType>(verifier.clone());
+
+        // Acks belong to the notification protocol, yet are handled by the owner of the thread.
+        dispatcher
+            .dispatch(ack(json!({ "thid": THREAD_ID })), Some(VERKEY.to_owned()))
+            .await
+            .unwrap();
+        dispatcher
+            .dispatch(ack(json!({ "thid": "child-1", "pthid": THREAD_ID })), None)
+            .await
+            .unwrap();
+
+        assert_eq!(issuer.handled(), 0);
+        assert_eq!(verifier.handled(), 2);
+        let handled = verifier.handled.lock().unwrap();
+        assert_eq!(handled[0].connection_id.as_deref(), Some(CONNECTION_ID));
+        assert_eq!(handled[1].connection_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_new_thread_by_protocol() {
+        let connections = Arc::new(TestConnections::default());
+        let issuer = Arc::new(TestHandler::default());
+        let trust_ping = Arc::new(TestHandler::default());
+        let dispatcher = dispatcher(&connections)
+            .register::<CredentialIssuanceType>(issuer.clone())
+            .register::<TrustPingType>(trust_ping.clone());
+
+        dispatcher.dispatch(ping(), Some(VERKEY.to_owned())).await.unwrap();
+
+        assert_eq!(issuer.handled(), 0);
+        assert_eq!(trust_ping.handled(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_message_hook() {
+        let connections = Arc::new(TestConnections::default());
+        let (sender, receiver) = async_channel::unbounded();
+        let dispatcher = dispatcher(&connections).on_unknown_message(move |message| {
+            let sender = sender.clone();
+            async move {
+                sender.send(message.thread.thid).await.unwrap();
+                Ok(())
+            }
+        });
+
+        dispatcher.dispatch(ping(), Some(VERKEY.to_owned())).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap(), "ping-1");
+        assert!(sent_problem_reports(&connections).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_problem_reports() {
+        let connections = Arc::new(TestConnections::default());
+        let verifier = Arc::new(TestHandler {
+            threads: vec![THREAD_ID.to_owned()],
+            fails: true,
+            ..Default::default()
+        });
+        let dispatcher = dispatcher(&connections).register::<PresentProofType>(verifier.clone());
+
+        let err = dispatcher.dispatch(ping(), Some(VERKEY.to_owned())).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+        assert!(dispatcher
+            .dispatch(ack(json!({ "thid": THREAD_ID })), Some(VERKEY.to_owned()))
+            .await
+            .is_err());
+
+        let problem_reports = sent_problem_reports(&connections);
+        assert_eq!(problem_reports.len(), 2);
+        assert_eq!(problem_reports[0].content.description.code, UNSUPPORTED_MESSAGE_CODE);
+        assert_eq!(problem_reports[0].decorators.thread.as_ref().unwrap().thid, "ping-1");
+        assert_eq!(problem_reports[1].content.description.code, PROCESSING_ERROR_CODE);
+        assert_eq!(problem_reports[1].decorators.thread.as_ref().unwrap().thid, THREAD_ID);
+
+        // Problem reports are not answered, nor are messages of unknown senders.
+        let problem_report: AriesMessage = problem_reports[0].clone().into();
+        assert!(dispatcher
+            .dispatch(problem_report, Some(VERKEY.to_owned()))
+            .await
+            .is_err());
+        assert!(dispatcher.dispatch(ping(), None).await.is_err());
+        assert_eq!(sent_problem_reports(&connections).len(), 2);
+
+        let dispatcher = dispatcher.send_problem_reports(false);
+        assert!(dispatcher.dispatch(ping(), Some(VERKEY.to_owned())).await.is_err());
+        assert_eq!(sent_problem_reports(&connections).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_envelope() {
+        let connections = Arc::new(TestConnections::default());
+        let trust_ping = Arc::new(TestHandler::default());
+        let dispatcher = dispatcher(&connections).register::<TrustPingType>(trust_ping.clone());
+
+        // The mock wallet "unpacks" envelopes by returning them as they are.
+        let envelope = json!({
+            "message": json!(ping()).to_string(),
+            "sender_verkey": VERKEY,
+        });
+        dispatcher
+            .dispatch_envelope(envelope.to_string().into_bytes())
+            .await
+            .unwrap();
+
+        let handled = trust_ping.handled.lock().unwrap();
+        assert_eq!(handled.len(), 1);
+        assert_eq!(handled[0].sender_vk.as_deref(), Some(VERKEY));
+        assert_eq!(handled[0].connection_id.as_deref(), Some(CONNECTION_ID));
+    }
+}
@@ -36,7 +36,7 @@ pub struct IssuerConfig {
     pub tails_file: Option<String>,
 }
 
-fn _build_credential_preview(credential_json: &str) -> VcxResult<CredentialPreview> {
+pub(crate) fn _build_credential_preview(credential_json: &str) -> VcxResult<CredentialPreview> {
     trace!(
         "Issuer::_build_credential_preview >>> credential_json: {:?}",
         secret!(credential_json)
@@ -1,16 +1,25 @@
 use crate::handlers::connection::mediated_connection::ConnectionState as MediatedConnectionState;
 use crate::protocols::issuance::holder::state_machine::HolderState;
 use crate::protocols::issuance::issuer::state_machine::IssuerState;
+use crate::protocols::issuance_v2::holder::state_machine::HolderV2State;
+use crate::protocols::issuance_v2::issuer::state_machine::IssuerV2State;
 use crate::protocols::mediated_connection::invitee::state_machine::InviteeState;
 use crate::protocols::mediated_connection::inviter::state_machine::InviterState;
 use crate::protocols::proof_presentation::prover::state_machine::ProverState;
 use crate::protocols::proof_presentation::verifier::state_machine::VerifierState;
+use crate::protocols::proof_presentation_v2::prover::state_machine::ProverV2State;
+use crate::protocols::proof_presentation_v2::verifier::state_machine::VerifierV2State;
 
 pub mod connection;
+pub mod coordinate_mediation;
 pub mod discovery;
+pub mod dispatcher;
 pub mod issuance;
+pub mod issuance_v2;
 pub mod out_of_band;
+pub mod pickup;
 pub mod proof_presentation;
+pub mod proof_presentation_v2;
 pub mod revocation_notification;
 pub mod trust_ping;
 pub mod util;
@@ -64,6 +73,34 @@ impl From<IssuerState> for u32 {
     }
 }
 
+impl From<HolderV2State> for u32 {
+    fn from(state: HolderV2State) -> u32 {
+        match state {
+            HolderV2State::Initial => 0,
+            HolderV2State::ProposalSent => 1,
+            HolderV2State::OfferReceived => 2,
+            HolderV2State::RequestSent => 3,
+            HolderV2State::Finished => 4,
+            HolderV2State::Failed => 5,
+        }
+    }
+}
+
+impl From<IssuerV2State> for u32 {
+    fn from(state: IssuerV2State) -> u32 {
+        match state {
+            IssuerV2State::Initial => 0,
+            IssuerV2State::ProposalReceived => 1,
+            IssuerV2State::OfferSet => 2,
+            IssuerV2State::OfferSent => 3,
+            IssuerV2State::RequestReceived => 4,
+            IssuerV2State::CredentialSent => 5,
+            IssuerV2State::Finished => 6,
+            IssuerV2State::Failed => 7,
+        }
+    }
+}
+
 impl From<ProverState> for u32 {
     fn from(state: ProverState) -> u32 {
         match state {
@@ -91,3 +128,31 @@ impl From<VerifierState> for u32 {
         }
     }
 }
+
+impl From<ProverV2State> for u32 {
+    fn from(state: ProverV2State) -> u32 {
+        match state {
+            ProverV2State::Initial => 0,
+            ProverV2State::ProposalSent => 1,
+            ProverV2State::RequestReceived => 2,
+            ProverV2State::PresentationPrepared => 3,
+            ProverV2State::PresentationPreparationFailed => 4,
+            ProverV2State::PresentationSent => 5,
+            ProverV2State::Finished => 6,
+            ProverV2State::Failed => 7,
+        }
+    }
+}
+
+impl From<VerifierV2State> for u32 {
+    fn from(state: VerifierV2State) -> u32 {
+        match state {
+            VerifierV2State::Initial => 0,
+            VerifierV2State::ProposalReceived => 1,
+            VerifierV2State::RequestSet => 2,
+            VerifierV2State::RequestSent => 3,
+            VerifierV2State::Finished => 4,
+            VerifierV2State::Failed => 5,
+        }
+    }
+}
@@ -64,7 +64,7 @@ impl OutOfBandSender {
 
     pub fn append_handshake_protocol(mut self, protocol: Protocol) -> VcxResult<Self> {
         let new_protocol = match protocol {
-            Protocol::ConnectionType(_) => MaybeKnown::Known(protocol),
+            Protocol::ConnectionType(_) | Protocol::DidExchangeType(_) => MaybeKnown::Known(protocol),
             _ => {
                 return Err(AriesVcxError::from_msg(
                     AriesVcxErrorKind::ActionNotSupported,
@@ -193,3 +193,39 @@ impl OutOfBandSender {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use messages::msg_types::{
+        connection::ConnectionTypeV1, did_exchange::DidExchangeTypeV1, trust_ping::TrustPingTypeV1,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_append_handshake_protocols() {
+        let connection = Protocol::from(ConnectionTypeV1::new_v1_0());
+        let did_exchange = Protocol::from(DidExchangeTypeV1::new_v1_0());
+
+        let oob = OutOfBandSender::create()
+            .append_handshake_protocol(did_exchange)
+            .unwrap()
+            .append_handshake_protocol(connection)
+            .unwrap();
+
+        assert_eq!(
+            Some(vec![MaybeKnown::Known(did_exchange), MaybeKnown::Known(connection)]),
+            oob.oob.content.handshake_protocols
+        );
+    }
+
+    #[test]
+    fn test_append_handshake_protocol_rejects_non_handshake_protocol() {
+        let err = OutOfBandSender::create()
+            .append_handshake_protocol(Protocol::from(TrustPingTypeV1::new_v1_0()))
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::ActionNotSupported, err.kind());
+    }
+}
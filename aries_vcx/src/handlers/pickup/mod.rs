@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::decorators::attachment::{Attachment, AttachmentType};
+use messages::msg_fields::protocols::pickup::{
+    delivery::Delivery, messages_received::MessagesReceived, status::Status, Pickup,
+};
+use messages::AriesMessage;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::protocols::pickup::{build_delivery_request, build_messages_received, build_status_request};
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+/// A message retrieved from the mediator's queue.
+#[derive(Debug, Clone)]
+pub struct DeliveredMessage {
+    /// ID of the message in the mediator's queue.
+    pub id: String,
+    pub message: AriesMessage,
+    pub sender_verkey: Option<String>,
+}
+
+/// A message retrieved from the mediator's queue which could not be unpacked.
+#[derive(Debug)]
+pub struct UndeliverableMessage {
+    /// ID of the message in the mediator's queue, if the mediator gave one.
+    pub id: Option<String>,
+    pub error: AriesVcxError,
+}
+
+/// The messages retrieved by [`PickupClient::fetch_messages`].
+#[derive(Debug, Default)]
+pub struct FetchedMessages {
+    pub delivered: Vec<DeliveredMessage>,
+    /// Messages which could not be unpacked. They are acknowledged along with the delivered
+    /// messages, for the mediator not to deliver them again, and are left to the caller to keep
+    /// as dead letters.
+    pub undeliverable: Vec<UndeliverableMessage>,
+}
+
+/// Client for retrieving messages queued by a mediator through the
+/// [pickup protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0685-pickup-v2/README.md).
+///
+/// Messages are polled over HTTP, the mediator answering every request in the HTTP response.
+#[derive(Debug, Clone)]
+pub struct PickupClient {
+    mediator_did_doc: AriesDidDoc,
+    sender_verkey: String,
+}
+
+impl PickupClient {
+    /// Creates a new [`PickupClient`] from the mediator's [`AriesDidDoc`] and our verkey
+    /// on the connection with the mediator.
+    pub fn new(mediator_did_doc: AriesDidDoc, sender_verkey: String) -> Self {
+        Self {
+            mediator_did_doc,
+            sender_verkey,
+        }
+    }
+
+    /// Requests the status of the message queue, optionally filtered by `recipient_key`.
+    pub async fn get_status(&self, wallet: &Arc<dyn BaseWallet>, recipient_key: Option<String>) -> VcxResult<Status> {
+        let request = build_status_request(recipient_key);
+
+        match self.send_and_receive(wallet, request.into()).await? {
+            Some(AriesMessage::Pickup(Pickup::Status(status))) => Ok(status),
+            msg => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!(
+                    "Expected a pickup status message from the mediator, received: {:?}",
+                    msg
+                ),
+            )),
+        }
+    }
+
+    /// Retrieves up to `limit` queued messages, optionally filtered by `recipient_key`,
+    /// and acknowledges their receipt to the mediator so they get removed from the queue.
+    ///
+    /// Messages which cannot be unpacked are acknowledged as well, as they would otherwise be
+    /// delivered again on every call, and are returned as [`UndeliverableMessage`]s.
+    pub async fn fetch_messages(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        limit: u32,
+        recipient_key: Option<String>,
+    ) -> VcxResult<FetchedMessages> {
+        let request = build_delivery_request(limit, recipient_key);
+
+        let delivery = match self.send_and_receive(wallet, request.into()).await? {
+            Some(AriesMessage::Pickup(Pickup::Delivery(delivery))) => delivery,
+            // The mediator answers with a status message when there are no queued messages.
+            Some(AriesMessage::Pickup(Pickup::Status(_))) => return Ok(FetchedMessages::default()),
+            msg => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!(
+                        "Expected a pickup delivery message from the mediator, received: {:?}",
+                        msg
+                    ),
+                ))
+            }
+        };
+
+        let messages = unpack_delivery(wallet, delivery).await;
+
+        if let Some(ack) = build_ack(&messages) {
+            self.send_and_receive(wallet, ack.into()).await?;
+        }
+
+        Ok(messages)
+    }
+
+    async fn send_and_receive(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: AriesMessage,
+    ) -> VcxResult<Option<AriesMessage>> {
+        trace!("PickupClient::send_and_receive >>> message: {:?}", message);
+
+        let EncryptionEnvelope(envelope) =
+            EncryptionEnvelope::create(wallet, &message, Some(&self.sender_verkey), &self.mediator_did_doc).await?;
+
+        let endpoint = self
+            .mediator_did_doc
+            .get_endpoint()
+            .ok_or_else(|| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc"))?;
+
+        // TODO: Extract from agency client
+        let response = agency_client::httpclient::post_message(envelope, endpoint).await?;
+
+        if response.is_empty() {
+            return Ok(None);
+        }
+
+        let (message, _) = EncryptionEnvelope::anon_unpack(wallet, response).await?;
+        Ok(Some(message))
+    }
+}
+
+/// Unpacks the messages attached to a [`Delivery`], those without an ID or which cannot be
+/// unpacked being reported as undeliverable.
+async fn unpack_delivery(wallet: &Arc<dyn BaseWallet>, delivery: Delivery) -> FetchedMessages {
+    let mut messages = FetchedMessages::default();
+
+    for attachment in delivery.content.attach {
+        let Some(id) = attachment.id.clone() else {
+            warn!("Delivered message without an ID: {:?}", attachment);
+            messages.undeliverable.push(UndeliverableMessage {
+                id: None,
+                error: AriesVcxError::from_msg(AriesVcxErrorKind::InvalidMessageFormat, "Delivered message has no ID"),
+            });
+            continue;
+        };
+
+        match unpack_attachment(wallet, attachment).await {
+            Ok((message, sender_verkey)) => messages.delivered.push(DeliveredMessage {
+                id,
+                message,
+                sender_verkey,
+            }),
+            Err(err) => {
+                error!("Failed to unpack delivered message {}: {}", id, err);
+                messages.undeliverable.push(UndeliverableMessage {
+                    id: Some(id),
+                    error: err,
+                });
+            }
+        }
+    }
+
+    messages
+}
+
+/// Acknowledges the receipt of the delivered and undeliverable messages, if there are any with an
+/// ID.
+fn build_ack(messages: &FetchedMessages) -> Option<MessagesReceived> {
+    let received_ids: Vec<String> = messages
+        .delivered
+        .iter()
+        .map(|delivered| delivered.id.clone())
+        .chain(
+            messages
+                .undeliverable
+                .iter()
+                .filter_map(|undeliverable| undeliverable.id.clone()),
+        )
+        .collect();
+
+    if received_ids.is_empty() {
+        return None;
+    }
+
+    Some(build_messages_received(received_ids))
+}
+
+async fn unpack_attachment(
+    wallet: &Arc<dyn BaseWallet>,
+    attachment: Attachment,
+) -> VcxResult<(AriesMessage, Option<String>)> {
+    let packed = match attachment.data.content {
+        AttachmentType::Base64(encoded) => base64::decode(&encoded).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Delivered message is not valid base64: {:?}", err),
+            )
+        })?,
+        AttachmentType::Json(value) => serde_json::to_vec(&value)?,
+        AttachmentType::Links(_) => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Delivered messages referenced by links are not supported",
+            ))
+        }
+    };
+
+    EncryptionEnvelope::anon_unpack(wallet, packed).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use aries_vcx_core::wallet::indy_wallet::IndySdkWallet;
+    use messages::decorators::attachment::AttachmentData;
+    use messages::msg_fields::protocols::pickup::delivery::DeliveryContent;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::utils::devsetup::SetupLibraryWallet;
+
+    fn _attachment(id: Option<&str>, content: AttachmentType) -> Attachment {
+        let mut attachment = Attachment::new(AttachmentData::new(content));
+        attachment.id = id.map(ToOwned::to_owned);
+        attachment
+    }
+
+    fn _delivery(attach: Vec<Attachment>) -> Delivery {
+        Delivery::with_decorators(
+            Uuid::new_v4().to_string(),
+            DeliveryContent::new(attach),
+            Default::default(),
+        )
+    }
+
+    async fn _pack(
+        wallet: &Arc<dyn BaseWallet>,
+        sender_vk: &str,
+        recipient_vk: &str,
+        message: &AriesMessage,
+    ) -> String {
+        let packed = wallet
+            .pack_message(
+                Some(sender_vk),
+                &json!([recipient_vk]).to_string(),
+                json!(message).to_string().as_bytes(),
+            )
+            .await
+            .unwrap();
+        base64::encode(&packed)
+    }
+
+    #[tokio::test]
+    async fn test_unpack_delivery_and_ack_received_messages() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, sender_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let (_, recipient_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            let first: AriesMessage = build_status_request(None).into();
+            let second: AriesMessage = build_delivery_request(5, None).into();
+            let delivery = _delivery(vec![
+                _attachment(
+                    Some("msg_1"),
+                    AttachmentType::Base64(_pack(&wallet, &sender_vk, &recipient_vk, &first).await),
+                ),
+                _attachment(
+                    Some("msg_2"),
+                    AttachmentType::Base64(_pack(&wallet, &sender_vk, &recipient_vk, &second).await),
+                ),
+            ]);
+
+            let messages = unpack_delivery(&wallet, delivery).await;
+
+            assert!(messages.undeliverable.is_empty());
+            let delivered = &messages.delivered;
+            assert_eq!(2, delivered.len());
+            assert_eq!("msg_1", delivered[0].id);
+            // Timing decorators are only serialized to the millisecond
+            assert_eq!(json!(first), json!(delivered[0].message));
+            assert_eq!(Some(sender_vk.clone()), delivered[0].sender_verkey);
+            assert_eq!("msg_2", delivered[1].id);
+            assert_eq!(json!(second), json!(delivered[1].message));
+
+            let ack = build_ack(&messages).unwrap();
+            assert_eq!(
+                vec!["msg_1".to_owned(), "msg_2".to_owned()],
+                ack.content.message_id_list
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_unpack_delivery_reports_and_acks_undecodable_messages() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, sender_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let (_, recipient_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            let message: AriesMessage = build_status_request(None).into();
+            let packed = _pack(&wallet, &sender_vk, &recipient_vk, &message).await;
+            let delivery = _delivery(vec![
+                _attachment(None, AttachmentType::Base64(packed.clone())),
+                _attachment(Some("not_base64"), AttachmentType::Base64("%%%".to_owned())),
+                _attachment(
+                    Some("not_packed"),
+                    AttachmentType::Base64(base64::encode("not a packed message")),
+                ),
+                _attachment(Some("links"), AttachmentType::Links(vec![])),
+                _attachment(Some("valid"), AttachmentType::Base64(packed)),
+            ]);
+
+            let messages = unpack_delivery(&wallet, delivery).await;
+
+            assert_eq!(1, messages.delivered.len());
+            assert_eq!("valid", messages.delivered[0].id);
+            let undeliverable: Vec<Option<&str>> = messages
+                .undeliverable
+                .iter()
+                .map(|undeliverable| undeliverable.id.as_deref())
+                .collect();
+            assert_eq!(
+                vec![None, Some("not_base64"), Some("not_packed"), Some("links")],
+                undeliverable
+            );
+
+            let ack = build_ack(&messages).unwrap();
+            assert_eq!(
+                vec!["valid", "not_base64", "not_packed", "links"],
+                ack.content.message_id_list
+            );
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_no_ack_without_delivered_messages() {
+        assert!(build_ack(&FetchedMessages::default()).is_none());
+    }
+}
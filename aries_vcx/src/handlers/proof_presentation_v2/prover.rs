@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use agency_client::agency_client::AgencyClient;
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::AriesMessage;
+
+use crate::errors::error::prelude::*;
+use crate::handlers::connection::mediated_connection::MediatedConnection;
+use crate::handlers::proof_presentation::types::{RetrievedCredentials, SelectedCredentials};
+use crate::protocols::proof_presentation_v2::actions::PresentProofV2Action;
+use crate::protocols::proof_presentation_v2::prover::state_machine::{ProverV2SM, ProverV2State};
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProverV2 {
+    prover_sm: ProverV2SM,
+}
+
+impl ProverV2 {
+    pub fn create(source_id: &str) -> VcxResult<ProverV2> {
+        trace!("ProverV2::create >>> source_id: {}", source_id);
+        Ok(ProverV2 {
+            prover_sm: ProverV2SM::new(source_id.to_string()),
+        })
+    }
+
+    pub fn create_from_request(source_id: &str, presentation_request: RequestPresentationV2) -> VcxResult<ProverV2> {
+        trace!(
+            "ProverV2::create_from_request >>> source_id: {}, presentation_request: {:?}",
+            source_id,
+            presentation_request
+        );
+        Ok(ProverV2 {
+            prover_sm: ProverV2SM::from_request(presentation_request, source_id.to_string()),
+        })
+    }
+
+    pub fn get_state(&self) -> ProverV2State {
+        self.prover_sm.get_state()
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.prover_sm.get_source_id()
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        self.prover_sm.get_thread_id()
+    }
+
+    pub fn presentation_status(&self) -> u32 {
+        self.prover_sm.presentation_status()
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        self.prover_sm.is_terminal_state()
+    }
+
+    pub fn get_presentation_request(&self) -> VcxResult<RequestPresentationV2> {
+        Ok(self.prover_sm.get_presentation_request()?.to_owned())
+    }
+
+    pub fn get_presentation_request_attachment(&self) -> VcxResult<String> {
+        self.prover_sm.get_presentation_request_attachment()
+    }
+
+    pub fn get_presentation_msg(&self) -> VcxResult<PresentationV2> {
+        Ok(self.prover_sm.get_presentation_msg()?.to_owned())
+    }
+
+    pub async fn retrieve_credentials(&self, anoncreds: &Arc<dyn BaseAnonCreds>) -> VcxResult<RetrievedCredentials> {
+        trace!("ProverV2::retrieve_credentials >>>");
+        let presentation_request = self.get_presentation_request_attachment()?;
+        let json_retrieved_credentials = anoncreds
+            .prover_get_credentials_for_proof_req(&presentation_request)
+            .await?;
+
+        Ok(serde_json::from_str(&json_retrieved_credentials)?)
+    }
+
+    pub async fn send_proposal(&mut self, proposal: ProposePresentationV2, send_message: SendClosure) -> VcxResult<()> {
+        trace!("ProverV2::send_proposal >>>");
+        self.prover_sm = self.prover_sm.clone().send_proposal(proposal, send_message).await?;
+        Ok(())
+    }
+
+    pub async fn generate_presentation(
+        &mut self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        credentials: SelectedCredentials,
+        self_attested_attrs: HashMap<String, String>,
+    ) -> VcxResult<()> {
+        trace!(
+            "ProverV2::generate_presentation >>> credentials: {:?}, self_attested_attrs: {:?}",
+            credentials,
+            self_attested_attrs
+        );
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .generate_presentation(ledger, anoncreds, credentials, self_attested_attrs)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn send_presentation(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        trace!("ProverV2::send_presentation >>>");
+        self.prover_sm = self.prover_sm.clone().send_presentation(send_message).await?;
+        Ok(())
+    }
+
+    pub async fn decline_presentation_request(&mut self, reason: &str, send_message: SendClosure) -> VcxResult<()> {
+        trace!("ProverV2::decline_presentation_request >>> reason: {:?}", reason);
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .decline_request(reason.to_string(), send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub fn progressable_by_message(&self) -> bool {
+        self.prover_sm.progressable_by_message()
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        self.prover_sm.find_message_to_handle(messages)
+    }
+
+    pub async fn step(
+        &mut self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        message: PresentProofV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<()> {
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .handle_message(ledger, anoncreds, message, send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_state(
+        &mut self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        wallet: &Arc<dyn BaseWallet>,
+        agency_client: &AgencyClient,
+        connection: &MediatedConnection,
+    ) -> VcxResult<ProverV2State> {
+        trace!("ProverV2::update_state >>>");
+        if !self.progressable_by_message() {
+            return Ok(self.get_state());
+        }
+        let send_message = connection.send_message_closure(Arc::clone(wallet)).await?;
+
+        let messages = connection.get_messages(agency_client).await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step(ledger, anoncreds, msg.into(), Some(send_message)).await?;
+            connection.update_message_status(&uid, agency_client).await?;
+        }
+        Ok(self.get_state())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+    use crate::protocols::proof_presentation_v2::test_utils::*;
+    use crate::utils::constants::PROOF_JSON;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    fn _ledger() -> Arc<dyn AnoncredsLedgerRead> {
+        Arc::new(MockLedger)
+    }
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    async fn _prover_with_presentation_sent() -> ProverV2 {
+        let _mock_builder = MockBuilder::init().set_mock_generate_indy_proof(PROOF_JSON);
+        let mut prover = ProverV2::create_from_request("test_source_id", _presentation_request("thread_id")).unwrap();
+        prover
+            .generate_presentation(
+                &_ledger(),
+                &_anoncreds(),
+                SelectedCredentials::default(),
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        prover.send_presentation(_send_message().unwrap()).await.unwrap();
+        prover
+    }
+
+    #[tokio::test]
+    async fn test_prover_send_presentation() {
+        let prover = _prover_with_presentation_sent().await;
+
+        assert_eq!(ProverV2State::PresentationSent, prover.get_state());
+        assert!(prover.progressable_by_message());
+        assert_eq!(
+            "thread_id",
+            prover.get_presentation_msg().unwrap().decorators.thread.thid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prover_step_ack_finishes_protocol() {
+        let mut prover = _prover_with_presentation_sent().await;
+        let ack: AriesMessage = _presentation_ack("thread_id").into();
+        prover.step(&_ledger(), &_anoncreds(), ack.into(), None).await.unwrap();
+
+        assert_eq!(ProverV2State::Finished, prover.get_state());
+        assert!(prover.is_terminal_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_decline_presentation_request() {
+        let mut prover = ProverV2::create_from_request("test_source_id", _presentation_request("thread_id")).unwrap();
+        prover
+            .decline_presentation_request(&_reason(), _send_message().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(ProverV2State::Failed, prover.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_serialization_roundtrip() {
+        let prover = _prover_with_presentation_sent().await;
+        let serialized = serde_json::to_string(&prover).unwrap();
+        let deserialized: ProverV2 = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(prover.get_state(), deserialized.get_state());
+        assert_eq!(prover.get_thread_id().unwrap(), deserialized.get_thread_id().unwrap());
+        // Timing decorators are only serialized to the millisecond
+        assert_eq!(
+            json!(prover.get_presentation_msg().unwrap()),
+            json!(deserialized.get_presentation_msg().unwrap())
+        );
+    }
+}
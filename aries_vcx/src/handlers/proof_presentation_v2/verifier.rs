@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use agency_client::agency_client::AgencyClient;
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::AriesMessage;
+
+use crate::common::proofs::proof_request::PresentationRequestData;
+use crate::errors::error::prelude::*;
+use crate::handlers::connection::mediated_connection::MediatedConnection;
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+use crate::protocols::proof_presentation_v2::actions::PresentProofV2Action;
+use crate::protocols::proof_presentation_v2::verifier::state_machine::{VerifierV2SM, VerifierV2State};
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifierV2 {
+    verifier_sm: VerifierV2SM,
+}
+
+impl VerifierV2 {
+    pub fn create(source_id: &str) -> VcxResult<Self> {
+        trace!("VerifierV2::create >>> source_id: {:?}", source_id);
+        Ok(Self {
+            verifier_sm: VerifierV2SM::new(source_id),
+        })
+    }
+
+    pub fn create_from_request(
+        source_id: &str,
+        presentation_request: &PresentationRequestData,
+        comment: Option<String>,
+    ) -> VcxResult<Self> {
+        trace!(
+            "VerifierV2::create_from_request >>> source_id: {:?}, presentation_request: {:?}",
+            source_id,
+            presentation_request
+        );
+        let verifier_sm = VerifierV2SM::from_request(source_id, presentation_request, comment)?;
+        Ok(Self { verifier_sm })
+    }
+
+    pub fn create_from_proposal(source_id: &str, presentation_proposal: &ProposePresentationV2) -> VcxResult<Self> {
+        trace!(
+            "VerifierV2::create_from_proposal >>> source_id: {:?}, presentation_proposal: {:?}",
+            source_id,
+            presentation_proposal
+        );
+        Ok(Self {
+            verifier_sm: VerifierV2SM::from_proposal(source_id, presentation_proposal),
+        })
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.verifier_sm.get_source_id()
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        self.verifier_sm.thread_id()
+    }
+
+    pub fn get_state(&self) -> VerifierV2State {
+        self.verifier_sm.get_state()
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        self.verifier_sm.is_terminal_state()
+    }
+
+    pub fn set_request(
+        &mut self,
+        presentation_request_data: PresentationRequestData,
+        comment: Option<String>,
+    ) -> VcxResult<()> {
+        trace!(
+            "VerifierV2::set_request >>> presentation_request_data: {:?}, comment: {:?}",
+            presentation_request_data,
+            comment
+        );
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .set_request(&presentation_request_data, comment)?;
+        Ok(())
+    }
+
+    pub async fn send_presentation_request(&mut self, send_message: SendClosure) -> VcxResult<()> {
+        trace!("VerifierV2::send_presentation_request >>>");
+        self.verifier_sm = self.verifier_sm.clone().send_presentation_request(send_message).await?;
+        Ok(())
+    }
+
+    pub fn mark_presentation_request_msg_sent(&mut self) -> VcxResult<()> {
+        trace!("VerifierV2::mark_presentation_request_msg_sent >>>");
+        self.verifier_sm = self.verifier_sm.clone().mark_presentation_request_msg_sent()?;
+        Ok(())
+    }
+
+    pub async fn decline_presentation_proposal(&mut self, reason: &str, send_message: SendClosure) -> VcxResult<()> {
+        trace!("VerifierV2::decline_presentation_proposal >>> reason: {:?}", reason);
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .decline_proposal(reason.to_string(), send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn verify_presentation(
+        &mut self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        presentation: PresentationV2,
+        send_message: SendClosure,
+    ) -> VcxResult<()> {
+        trace!("VerifierV2::verify_presentation >>>");
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .verify_presentation(ledger, anoncreds, presentation, send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub fn get_presentation_request_msg(&self) -> VcxResult<RequestPresentationV2> {
+        self.verifier_sm.get_presentation_request_msg()
+    }
+
+    pub fn get_presentation_request_attachment(&self) -> VcxResult<String> {
+        self.verifier_sm.get_presentation_request_attachment()
+    }
+
+    pub fn get_presentation_proposal(&self) -> VcxResult<ProposePresentationV2> {
+        self.verifier_sm.get_proposal()
+    }
+
+    pub fn get_presentation_msg(&self) -> VcxResult<PresentationV2> {
+        self.verifier_sm.get_presentation_msg()
+    }
+
+    pub fn get_presentation_attachment(&self) -> VcxResult<String> {
+        self.verifier_sm.get_presentation_attachment()
+    }
+
+    pub fn get_verification_status(&self) -> PresentationVerificationStatus {
+        self.verifier_sm.get_verification_status()
+    }
+
+    pub fn progressable_by_message(&self) -> bool {
+        self.verifier_sm.progressable_by_message()
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        self.verifier_sm.find_message_to_handle(messages)
+    }
+
+    pub async fn step(
+        &mut self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        message: PresentProofV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<()> {
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .handle_message(ledger, anoncreds, message, send_message)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_state(
+        &mut self,
+        wallet: &Arc<dyn BaseWallet>,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        agency_client: &AgencyClient,
+        connection: &MediatedConnection,
+    ) -> VcxResult<VerifierV2State> {
+        trace!("VerifierV2::update_state >>>");
+        if !self.progressable_by_message() {
+            return Ok(self.get_state());
+        }
+        let send_message = connection.send_message_closure(Arc::clone(wallet)).await?;
+
+        let messages = connection.get_messages(agency_client).await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step(ledger, anoncreds, msg.into(), Some(send_message)).await?;
+            connection.update_message_status(&uid, agency_client).await?;
+        }
+        Ok(self.get_state())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use messages::msg_fields::protocols::present_proof::v2::PresentProofV2;
+
+    use super::*;
+    use crate::common::proofs::proof_request::test_utils::_presentation_request_data;
+    use crate::handlers::proof_presentation::types::SelectedCredentials;
+    use crate::handlers::proof_presentation_v2::prover::ProverV2;
+    use crate::protocols::proof_presentation_v2::prover::state_machine::ProverV2State;
+    use crate::protocols::proof_presentation_v2::test_utils::*;
+    use crate::utils::constants::PROOF_JSON;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    fn _ledger() -> Arc<dyn AnoncredsLedgerRead> {
+        Arc::new(MockLedger)
+    }
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    fn _verifier() -> VerifierV2 {
+        VerifierV2::create_from_request("test_source_id", &_presentation_request_data(), None).unwrap()
+    }
+
+    fn _recording_send_message(sender: async_channel::Sender<AriesMessage>) -> SendClosure {
+        Box::new(move |message: AriesMessage| {
+            Box::pin(async move {
+                sender.try_send(message).unwrap();
+                VcxResult::Ok(())
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_presentation() {
+        let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(Ok(true));
+        let mut verifier = _verifier();
+        verifier
+            .send_presentation_request(_send_message().unwrap())
+            .await
+            .unwrap();
+        let thread_id = verifier.get_thread_id().unwrap();
+        verifier
+            .verify_presentation(
+                &_ledger(),
+                &_anoncreds(),
+                _presentation(&thread_id),
+                _send_message().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(VerifierV2State::Finished, verifier.get_state());
+        assert_eq!(PROOF_JSON, verifier.get_presentation_attachment().unwrap());
+        assert_eq!(
+            thread_id,
+            verifier.get_presentation_msg().unwrap().decorators.thread.thid
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_decline_presentation_proposal() {
+        let mut verifier = VerifierV2::create_from_proposal("test_source_id", &_presentation_proposal()).unwrap();
+        verifier
+            .decline_presentation_proposal(&_reason(), _send_message().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(VerifierV2State::Failed, verifier.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_and_verifier_exchange_presentation() {
+        let _mock_builder = MockBuilder::init()
+            .set_mock_generate_indy_proof(PROOF_JSON)
+            .set_mock_result_for_validate_indy_proof(Ok(true));
+        let (sender, receiver) = async_channel::unbounded();
+
+        let mut verifier = _verifier();
+        verifier
+            .send_presentation_request(_recording_send_message(sender.clone()))
+            .await
+            .unwrap();
+        let request = match receiver.try_recv().unwrap() {
+            AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(request)) => request,
+            message => panic!("expected presentation request, got {:?}", message),
+        };
+
+        let mut prover = ProverV2::create_from_request("test_source_id", request).unwrap();
+        prover
+            .generate_presentation(
+                &_ledger(),
+                &_anoncreds(),
+                SelectedCredentials::default(),
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        prover
+            .send_presentation(_recording_send_message(sender.clone()))
+            .await
+            .unwrap();
+
+        let presentation = receiver.try_recv().unwrap();
+        verifier
+            .step(
+                &_ledger(),
+                &_anoncreds(),
+                presentation.into(),
+                Some(_recording_send_message(sender)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(VerifierV2State::Finished, verifier.get_state());
+        assert_eq!(
+            PresentationVerificationStatus::Valid,
+            verifier.get_verification_status()
+        );
+
+        let ack = receiver.try_recv().unwrap();
+        prover.step(&_ledger(), &_anoncreds(), ack.into(), None).await.unwrap();
+        assert_eq!(ProverV2State::Finished, prover.get_state());
+        assert_eq!(verifier.get_thread_id().unwrap(), prover.get_thread_id().unwrap());
+    }
+}
@@ -1,23 +1,16 @@
 use messages::{
     msg_fields::protocols::{
-        connection::{invitation::Invitation, Connection},
-        cred_issuance::CredentialIssuance,
-        discover_features::DiscoverFeatures,
-        notification::Notification,
-        out_of_band::{invitation::Invitation as OobInvitation, OutOfBand},
-        present_proof::{
-            propose::{Predicate, PresentationAttr},
-            PresentProof,
-        },
+        connection::invitation::Invitation,
+        out_of_band::invitation::Invitation as OobInvitation,
+        present_proof::propose::{Predicate, PresentationAttr},
         report_problem::ProblemReport,
-        revocation::Revocation,
-        trust_ping::TrustPing,
     },
     AriesMessage,
 };
 use strum_macros::{AsRefStr, EnumString};
 
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::utils::thread::message_thread;
 
 macro_rules! matches_thread_id {
     ($msg:expr, $id:expr) => {
@@ -77,49 +70,7 @@ pub(crate) use matches_opt_thread_id;
 pub(crate) use matches_thread_id;
 
 pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()> {
-    let is_match = match message {
-        AriesMessage::BasicMessage(msg) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Connection(Connection::Invitation(Invitation::Public(msg))) => msg.id == thread_id,
-        AriesMessage::Connection(Connection::Invitation(Invitation::Pairwise(msg))) => msg.id == thread_id,
-        AriesMessage::Connection(Connection::Invitation(Invitation::PairwiseDID(msg))) => msg.id == thread_id,
-        AriesMessage::Connection(Connection::ProblemReport(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::Connection(Connection::Request(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Connection(Connection::Response(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::CredentialIssuance(CredentialIssuance::Ack(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::CredentialIssuance(CredentialIssuance::IssueCredential(msg)) => {
-            matches_thread_id!(msg, thread_id)
-        }
-        AriesMessage::CredentialIssuance(CredentialIssuance::OfferCredential(msg)) => {
-            matches_opt_thread_id!(msg, thread_id)
-        }
-        AriesMessage::CredentialIssuance(CredentialIssuance::ProposeCredential(msg)) => {
-            matches_opt_thread_id!(msg, thread_id)
-        }
-        AriesMessage::CredentialIssuance(CredentialIssuance::RequestCredential(msg)) => {
-            matches_opt_thread_id!(msg, thread_id)
-        }
-        AriesMessage::CredentialIssuance(CredentialIssuance::ProblemReport(msg)) => {
-            matches_opt_thread_id!(msg, thread_id)
-        }
-        AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(msg)) => msg.id == thread_id,
-        AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::Notification(Notification::Ack(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::Notification(Notification::ProblemReport(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::OutOfBand(OutOfBand::Invitation(msg)) => msg.id == thread_id,
-        AriesMessage::OutOfBand(OutOfBand::HandshakeReuse(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::OutOfBand(OutOfBand::HandshakeReuseAccepted(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::PresentProof(PresentProof::Ack(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::PresentProof(PresentProof::Presentation(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::PresentProof(PresentProof::ProposePresentation(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::PresentProof(PresentProof::RequestPresentation(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::PresentProof(PresentProof::ProblemReport(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::ReportProblem(msg) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Revocation(Revocation::Revoke(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Revocation(Revocation::Ack(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::Routing(msg) => msg.id == thread_id,
-        AriesMessage::TrustPing(TrustPing::Ping(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => matches_thread_id!(msg, thread_id),
-    };
+    let is_match = message_thread(message).matches(thread_id);
 
     if !is_match {
         return Err(AriesVcxError::from_msg(
@@ -1,9 +1,16 @@
+use chrono::Utc;
 use messages::{
-    decorators::thread::Thread,
+    decorators::{thread::Thread, timing::Timing},
     msg_fields::protocols::report_problem::{ProblemReport, ProblemReportContent, ProblemReportDecorators},
 };
 use uuid::Uuid;
 
+pub fn make_timing() -> Timing {
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    timing
+}
+
 pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> ProblemReport {
     let id = Uuid::new_v4().to_string();
     let content = ProblemReportContent::new(comment.unwrap_or_default());
@@ -1,7 +1,9 @@
 use std::clone::Clone;
 
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
-use messages::msg_fields::protocols::discover_features::{disclose::Disclose, ProtocolDescriptor};
+use messages::msg_fields::protocols::discover_features::{
+    disclose::Disclose, v2::disclosures::Disclosures, ProtocolDescriptor,
+};
 
 use crate::protocols::connection::trait_bounds::{BootstrapDidDoc, CompletedState, TheirDidDoc, ThreadId};
 
@@ -55,4 +57,11 @@ impl CompletedState for Completed {
     fn handle_disclose(&mut self, disclose: Disclose) {
         self.protocols = Some(disclose.content.protocols)
     }
+
+    fn handle_disclosures(&mut self, disclosures: Disclosures) {
+        let protocols = disclosures.content.protocols();
+        if !protocols.is_empty() {
+            self.protocols = Some(protocols)
+        }
+    }
 }
@@ -13,8 +13,11 @@ use messages::{
     decorators::{thread::Thread, timing::Timing},
     msg_fields::protocols::{
         connection::problem_report::{ProblemReport, ProblemReportContent, ProblemReportDecorators},
-        discover_features::{disclose::Disclose, query::QueryContent, ProtocolDescriptor},
+        discover_features::{
+            disclose::Disclose, query::QueryContent, v2::disclosures::Disclosures, ProtocolDescriptor,
+        },
     },
+    msg_types::{traits::ProtocolName, Protocol},
     AriesMessage,
 };
 use std::{error::Error, sync::Arc};
@@ -22,6 +25,7 @@ use uuid::Uuid;
 
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::discovery::negotiate_protocol,
     transport::Transport,
     utils::encryption_envelope::EncryptionEnvelope,
 };
@@ -179,6 +183,19 @@ where
     pub fn handle_disclose(&mut self, disclose: Disclose) {
         self.state.handle_disclose(disclose)
     }
+
+    pub fn handle_disclosures(&mut self, disclosures: Disclosures) {
+        self.state.handle_disclosures(disclosures)
+    }
+
+    /// Picks the highest version of the protocol `T` supported by both parties, if the
+    /// counterparty disclosed its protocols.
+    pub fn negotiate_protocol<T>(&self) -> Option<T>
+    where
+        T: ProtocolName + TryFrom<Protocol>,
+    {
+        negotiate_protocol(self.remote_protocols()?)
+    }
 }
 
 pub(crate) async fn wrap_and_send_msg<T>(
@@ -1,5 +1,7 @@
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
-use messages::msg_fields::protocols::discover_features::{disclose::Disclose, ProtocolDescriptor};
+use messages::msg_fields::protocols::discover_features::{
+    disclose::Disclose, v2::disclosures::Disclosures, ProtocolDescriptor,
+};
 
 /// Trait implemented for [`super::Connection`] states that store an [`AriesDidDoc`].
 pub trait TheirDidDoc {
@@ -26,6 +28,10 @@ pub trait CompletedState {
     fn remote_protocols(&self) -> Option<&[ProtocolDescriptor]>;
 
     fn handle_disclose(&mut self, disclose: Disclose);
+
+    /// Stores the protocols disclosed through discover features 2.0. Disclosures of other
+    /// feature types only leave the protocols known so far untouched.
+    fn handle_disclosures(&mut self, disclosures: Disclosures);
 }
 
 /// Marker trait used for implementing [`messages::protocols::connection::problem_report::ProblemReport`]
@@ -0,0 +1,34 @@
+use ::uuid::Uuid;
+use messages::msg_fields::protocols::coordinate_mediation::{
+    keylist_query::{KeylistQuery, KeylistQueryContent, KeylistQueryDecorators, KeylistQueryPaginate},
+    keylist_update::{KeylistUpdate, KeylistUpdateContent, KeylistUpdateDecorators, KeylistUpdateItem},
+    mediate_request::{MediateRequest, MediateRequestContent, MediateRequestDecorators},
+};
+
+use crate::protocols::common::make_timing;
+
+pub fn build_mediate_request() -> MediateRequest {
+    let mut decorators = MediateRequestDecorators::default();
+    decorators.timing = Some(make_timing());
+
+    MediateRequest::with_decorators(Uuid::new_v4().to_string(), MediateRequestContent::default(), decorators)
+}
+
+pub fn build_keylist_update(updates: Vec<KeylistUpdateItem>) -> KeylistUpdate {
+    let content = KeylistUpdateContent::new(updates);
+
+    let mut decorators = KeylistUpdateDecorators::default();
+    decorators.timing = Some(make_timing());
+
+    KeylistUpdate::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+pub fn build_keylist_query(paginate: Option<KeylistQueryPaginate>) -> KeylistQuery {
+    let mut content = KeylistQueryContent::default();
+    content.paginate = paginate;
+
+    let mut decorators = KeylistQueryDecorators::default();
+    decorators.timing = Some(make_timing());
+
+    KeylistQuery::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
@@ -0,0 +1,7 @@
+/// Unit struct illustrating that the DID exchange was initiated by the requester.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Requester;
+
+/// Unit struct illustrating that the DID exchange is being answered by the responder.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Responder;
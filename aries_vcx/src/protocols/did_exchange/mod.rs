@@ -0,0 +1,466 @@
+pub mod initiation_type;
+pub mod requester;
+pub mod responder;
+pub mod states;
+mod trait_bounds;
+
+use std::{error::Error, sync::Arc};
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::did_exchange::problem_report::{
+        ProblemCode, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+    },
+    AriesMessage,
+};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::connection::{pairwise_info::PairwiseInfo, wrap_and_send_msg},
+    transport::Transport,
+};
+
+use self::trait_bounds::{TheirDidDoc, ThreadId};
+
+/// A state machine for progressing through the [DID exchange protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0023-did-exchange/README.md).
+///
+/// Follows the same typestate approach as [`crate::protocols::connection::Connection`].
+/// Once completed, the exchange can be turned into a completed
+/// [`crate::protocols::connection::Connection`] for further messaging.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DidExchange<I, S> {
+    source_id: String,
+    pairwise_info: PairwiseInfo,
+    initiation_type: I,
+    state: S,
+}
+
+impl<I, S> DidExchange<I, S> {
+    pub fn from_parts(source_id: String, pairwise_info: PairwiseInfo, initiation_type: I, state: S) -> Self {
+        Self {
+            source_id,
+            pairwise_info,
+            initiation_type,
+            state,
+        }
+    }
+
+    pub fn into_parts(self) -> (String, PairwiseInfo, I, S) {
+        let Self {
+            source_id,
+            pairwise_info,
+            initiation_type,
+            state,
+        } = self;
+        (source_id, pairwise_info, initiation_type, state)
+    }
+
+    pub fn pairwise_info(&self) -> &PairwiseInfo {
+        &self.pairwise_info
+    }
+
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+}
+
+impl<I, S> DidExchange<I, S>
+where
+    S: ThreadId,
+{
+    pub fn thread_id(&self) -> &str {
+        self.state.thread_id()
+    }
+}
+
+impl<I, S> DidExchange<I, S>
+where
+    S: TheirDidDoc,
+{
+    pub fn their_did_doc(&self) -> &AriesDidDoc {
+        self.state.their_did_doc()
+    }
+
+    pub fn remote_did(&self) -> &str {
+        &self.their_did_doc().id
+    }
+
+    pub fn remote_vk(&self) -> VcxResult<String> {
+        self.their_did_doc()
+            .recipient_keys()?
+            .first()
+            .map(ToOwned::to_owned)
+            .ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Can't resolve recipient key from the counterparty diddoc.",
+            ))
+    }
+
+    pub async fn send_message<T>(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        T: Transport,
+    {
+        let sender_verkey = &self.pairwise_info().pw_vk;
+        let did_doc = self.their_did_doc();
+        wrap_and_send_msg(wallet, message, sender_verkey, did_doc, transport).await
+    }
+}
+
+/// Builds the [`AriesDidDoc`] advertised to the counterparty in the `did_doc~attach` attachment.
+fn build_our_did_doc(pairwise_info: &PairwiseInfo, service_endpoint: Url, routing_keys: Vec<String>) -> AriesDidDoc {
+    let mut did_doc = AriesDidDoc::default();
+    did_doc.set_id(pairwise_info.pw_did.clone());
+    did_doc.set_service_endpoint(service_endpoint);
+    did_doc.set_routing_keys(routing_keys);
+    did_doc.set_recipient_keys(vec![pairwise_info.pw_vk.clone()]);
+    did_doc
+}
+
+fn create_problem_report<E>(err: &E, problem_code: ProblemCode, thread: Thread) -> ProblemReport
+where
+    E: Error,
+{
+    let mut content = ProblemReportContent::default();
+    content.problem_code = Some(problem_code);
+    content.explain = Some(err.to_string());
+
+    let mut decorators = ProblemReportDecorators::new(thread);
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    ProblemReport::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+async fn send_problem_report<E, T>(
+    wallet: &Arc<dyn BaseWallet>,
+    err: &E,
+    problem_code: ProblemCode,
+    thread: Thread,
+    sender_verkey: &str,
+    did_doc: &AriesDidDoc,
+    transport: &T,
+) where
+    E: Error,
+    T: Transport,
+{
+    let problem_report = create_problem_report(err, problem_code, thread);
+    let res = wrap_and_send_msg(wallet, &problem_report.into(), sender_verkey, did_doc, transport).await;
+
+    if let Err(e) = res {
+        trace!("Error encountered when sending ProblemReport: {}", e);
+    } else {
+        info!("Error report sent!");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use aries_vcx_core::{ledger::base_ledger::IndyLedgerRead, wallet::indy_wallet::IndySdkWallet};
+    use async_trait::async_trait;
+    use diddoc_legacy::aries::service::AriesService;
+    use messages::{
+        msg_fields::protocols::{
+            did_exchange::{
+                complete::{Complete, CompleteContent, CompleteDecorators},
+                request::Request,
+                response::Response,
+                DidExchange as DidExchangeMsg,
+            },
+            out_of_band::invitation::{Invitation as OobInvitation, OobService},
+        },
+        msg_types::{did_exchange::DidExchangeTypeV1, Protocol},
+        AriesMessage,
+    };
+
+    use super::*;
+    use crate::{
+        common::{
+            keys::verkey_to_did_key,
+            signing::{decode_did_doc_attachment, decode_signed_did_doc_attachment, sign_did_doc_attachment},
+        },
+        handlers::out_of_band::sender::OutOfBandSender,
+        protocols::did_exchange::{
+            requester::DidExchangeRequester,
+            responder::DidExchangeResponder,
+            states::{request_sent::RequestSent, response_sent::ResponseSent},
+        },
+        utils::{
+            devsetup::SetupLibraryWallet, encryption_envelope::EncryptionEnvelope,
+            mockdata::profile::mock_ledger::MockLedger,
+        },
+    };
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        messages: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send_message(&self, msg: Vec<u8>, _service_endpoint: Url) -> VcxResult<()> {
+            self.messages.lock().unwrap().push(msg);
+            Ok(())
+        }
+    }
+
+    impl RecordingTransport {
+        async fn take_message(&self, wallet: &Arc<dyn BaseWallet>) -> AriesMessage {
+            let payload = self.messages.lock().unwrap().pop().unwrap();
+            EncryptionEnvelope::anon_unpack(wallet, payload).await.unwrap().0
+        }
+
+        fn is_empty(&self) -> bool {
+            self.messages.lock().unwrap().is_empty()
+        }
+    }
+
+    fn _service_endpoint() -> Url {
+        "http://example.org/agent".parse().unwrap()
+    }
+
+    fn _oob_invitation(invitation_key: &str) -> OobInvitation {
+        let service = AriesService::create()
+            .set_service_endpoint(_service_endpoint())
+            .set_recipient_keys(vec![invitation_key.to_owned()]);
+
+        OutOfBandSender::create()
+            .append_service(&OobService::AriesService(service))
+            .append_handshake_protocol(Protocol::from(DidExchangeTypeV1::new_v1_0()))
+            .unwrap()
+            .oob
+    }
+
+    async fn _send_request(
+        wallet: &Arc<dyn BaseWallet>,
+        invitation: OobInvitation,
+        transport: &RecordingTransport,
+    ) -> (DidExchangeRequester<RequestSent>, Request) {
+        let indy_ledger: Arc<dyn IndyLedgerRead> = Arc::new(MockLedger);
+        let requester = DidExchangeRequester::<RequestSent>::send_request(
+            wallet,
+            &indy_ledger,
+            "requester".to_owned(),
+            "Alice".to_owned(),
+            invitation,
+            _service_endpoint(),
+            vec![],
+            transport,
+        )
+        .await
+        .unwrap();
+
+        match transport.take_message(wallet).await {
+            AriesMessage::DidExchange(DidExchangeMsg::Request(request)) => (requester, request),
+            message => panic!("expected DidExchange request, got {:?}", message),
+        }
+    }
+
+    async fn _handle_request(
+        wallet: &Arc<dyn BaseWallet>,
+        request: Request,
+        invitation: &OobInvitation,
+        invitation_key: &str,
+        transport: &RecordingTransport,
+    ) -> VcxResult<DidExchangeResponder<ResponseSent>> {
+        DidExchangeResponder::<ResponseSent>::handle_request(
+            wallet,
+            "responder".to_owned(),
+            request,
+            &invitation.id,
+            invitation_key,
+            _service_endpoint(),
+            vec![],
+            transport,
+        )
+        .await
+    }
+
+    async fn _take_response(wallet: &Arc<dyn BaseWallet>, transport: &RecordingTransport) -> Response {
+        match transport.take_message(wallet).await {
+            AriesMessage::DidExchange(DidExchangeMsg::Response(response)) => response,
+            message => panic!("expected DidExchange response, got {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_round_trip() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let transport = RecordingTransport::default();
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = _oob_invitation(&invitation_key);
+
+            let (requester, request) = _send_request(&wallet, invitation.clone(), &transport).await;
+            assert_eq!("Alice", request.content.label);
+            assert_eq!(requester.pairwise_info().pw_did, request.content.did);
+
+            let responder = _handle_request(&wallet, request, &invitation, &invitation_key, &transport)
+                .await
+                .unwrap();
+            assert_eq!(requester.pairwise_info().pw_did, responder.remote_did());
+
+            let response = _take_response(&wallet, &transport).await;
+            let requester = requester.handle_response(&wallet, response, &transport).await.unwrap();
+            assert_eq!(responder.pairwise_info().pw_did, requester.remote_did());
+
+            let complete = match transport.take_message(&wallet).await {
+                AriesMessage::DidExchange(DidExchangeMsg::Complete(complete)) => complete,
+                message => panic!("expected DidExchange complete, got {:?}", message),
+            };
+            let responder = responder.handle_complete(complete).unwrap();
+
+            assert_eq!(requester.thread_id(), responder.thread_id());
+            assert_eq!(
+                requester.pairwise_info().pw_vk,
+                responder.into_connection().remote_vk().unwrap()
+            );
+            assert!(transport.is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_request_with_bad_signature_is_rejected() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let transport = RecordingTransport::default();
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = _oob_invitation(&invitation_key);
+
+            let (_, mut request) = _send_request(&wallet, invitation.clone(), &transport).await;
+            let attach = request.content.did_doc.as_mut().unwrap();
+            attach.data.jws.as_mut().unwrap().signature = base64::encode_config(&[0u8; 64], base64::URL_SAFE_NO_PAD);
+
+            _handle_request(&wallet, request, &invitation, &invitation_key, &transport)
+                .await
+                .unwrap_err();
+
+            assert_match!(
+                AriesMessage::DidExchange(DidExchangeMsg::ProblemReport(_)),
+                transport.take_message(&wallet).await
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_request_signed_by_other_key_is_rejected() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let transport = RecordingTransport::default();
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = _oob_invitation(&invitation_key);
+
+            let (_, mut request) = _send_request(&wallet, invitation.clone(), &transport).await;
+            let their_did_doc = decode_did_doc_attachment(request.content.did_doc.as_ref().unwrap()).unwrap();
+            let (_, other_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            request.content.did_doc = Some(
+                sign_did_doc_attachment(&wallet, &other_key, &their_did_doc)
+                    .await
+                    .unwrap(),
+            );
+
+            let err = _handle_request(&wallet, request, &invitation, &invitation_key, &transport)
+                .await
+                .unwrap_err();
+
+            assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_doc_attachment_signer_in_either_header() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let did_key = verkey_to_did_key(&key).unwrap();
+            let did_doc = build_our_did_doc(
+                &PairwiseInfo {
+                    pw_did: "did".to_owned(),
+                    pw_vk: key.clone(),
+                },
+                _service_endpoint(),
+                vec![],
+            );
+
+            let mut attach = sign_did_doc_attachment(&wallet, &key, &did_doc).await.unwrap();
+            assert_eq!(Some(&did_key), attach.data.jws.as_ref().unwrap().header.kid.as_ref());
+            decode_signed_did_doc_attachment(&wallet, &attach, &key).await.unwrap();
+
+            // Signer only given in the unprotected header
+            let protected = base64::encode_config(&json!({ "alg": "EdDSA" }).to_string(), base64::URL_SAFE_NO_PAD);
+            let payload = base64::encode_config(&json!(did_doc).to_string(), base64::URL_SAFE_NO_PAD);
+            let signature = wallet
+                .sign(&key, format!("{}.{}", protected, payload).as_bytes())
+                .await
+                .unwrap();
+            let jws = attach.data.jws.as_mut().unwrap();
+            jws.protected = protected;
+            jws.signature = base64::encode_config(&signature, base64::URL_SAFE_NO_PAD);
+            decode_signed_did_doc_attachment(&wallet, &attach, &key).await.unwrap();
+
+            // Signer in neither header
+            attach.data.jws.as_mut().unwrap().header.kid = None;
+            decode_signed_did_doc_attachment(&wallet, &attach, &key)
+                .await
+                .unwrap_err();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_did_exchange_rejects_messages_from_other_thread() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let transport = RecordingTransport::default();
+            let (_, invitation_key) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let invitation = _oob_invitation(&invitation_key);
+
+            let (requester, request) = _send_request(&wallet, invitation.clone(), &transport).await;
+
+            let mut other_request = request.clone();
+            other_request.decorators.thread = Some(Thread::new("other_thread_id".to_owned()));
+            let err = _handle_request(&wallet, other_request, &invitation, &invitation_key, &transport)
+                .await
+                .unwrap_err();
+            assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+
+            let responder = _handle_request(&wallet, request, &invitation, &invitation_key, &transport)
+                .await
+                .unwrap();
+            let mut response = _take_response(&wallet, &transport).await;
+            response.decorators.thread = Thread::new("other_thread_id".to_owned());
+            let err = requester
+                .clone()
+                .handle_response(&wallet, response, &transport)
+                .await
+                .unwrap_err();
+            assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+            assert!(transport.is_empty());
+
+            let complete = Complete::with_decorators(
+                Uuid::new_v4().to_string(),
+                CompleteContent::default(),
+                CompleteDecorators::new(Thread::new("other_thread_id".to_owned())),
+            );
+            let err = responder.handle_complete(complete).unwrap_err();
+            assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+        })
+        .await;
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use aries_vcx_core::{ledger::base_ledger::IndyLedgerRead, wallet::base_wallet::BaseWallet};
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::{
+        did_exchange::{
+            complete::{Complete, CompleteContent, CompleteDecorators},
+            problem_report::ProblemCode,
+            request::{Request, RequestContent, RequestDecorators},
+            response::Response,
+        },
+        out_of_band::invitation::Invitation as OobInvitation,
+    },
+};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    common::{
+        ledger::transactions::into_did_doc,
+        signing::{decode_signed_did_doc_attachment, sign_did_doc_attachment},
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::util::{matches_thread_id, AnyInvitation},
+    protocols::connection::{
+        initiation_type::Invitee, invitee::states::completed::Completed as InviteeCompleted,
+        pairwise_info::PairwiseInfo, Connection,
+    },
+    transport::Transport,
+};
+
+use super::{
+    build_our_did_doc,
+    initiation_type::Requester,
+    send_problem_report,
+    states::{completed::Completed, request_sent::RequestSent},
+    DidExchange,
+};
+
+/// Convenience alias
+pub type DidExchangeRequester<S> = DidExchange<Requester, S>;
+
+impl DidExchangeRequester<RequestSent> {
+    /// Sends a [`Request`] to the sender of an out-of-band [`OobInvitation`]
+    /// and creates a [`DidExchangeRequester<RequestSent>`].
+    ///
+    /// A new [`PairwiseInfo`] is generated for the exchange and its DidDoc
+    /// is attached, signed, to the request. The `label` is the name the requester
+    /// suggests the responder uses for this connection.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if a DidDoc could not be resolved from the [`OobInvitation`]
+    /// or if sending the request fails.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_request<T>(
+        wallet: &Arc<dyn BaseWallet>,
+        indy_ledger: &Arc<dyn IndyLedgerRead>,
+        source_id: String,
+        label: String,
+        invitation: OobInvitation,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+        transport: &T,
+    ) -> VcxResult<Self>
+    where
+        T: Transport,
+    {
+        trace!("DidExchange::send_request >>> invitation: {:?}", &invitation);
+
+        let invitation_id = invitation.id.clone();
+        let invitation_did_doc = into_did_doc(indy_ledger, &AnyInvitation::Oob(invitation)).await?;
+
+        let pairwise_info = PairwiseInfo::create(wallet).await?;
+        let did_doc = build_our_did_doc(&pairwise_info, service_endpoint, routing_keys);
+        let did_doc_attach = sign_did_doc_attachment(wallet, &pairwise_info.pw_vk, &did_doc).await?;
+
+        let id = Uuid::new_v4().to_string();
+
+        let mut content = RequestContent::new(label, pairwise_info.pw_did.clone());
+        content.did_doc = Some(did_doc_attach);
+
+        // The invitation's ID is used as the parent thread ID, while the request ID
+        // is the thread ID, as multiple requesters can use the same invitation.
+        let mut thread = Thread::new(id.clone());
+        thread.pthid = Some(invitation_id.clone());
+
+        let mut decorators = RequestDecorators::default();
+        let mut timing = Timing::default();
+        timing.out_time = Some(Utc::now());
+        decorators.thread = Some(thread);
+        decorators.timing = Some(timing);
+
+        let request = Request::with_decorators(id.clone(), content, decorators);
+
+        let did_exchange = DidExchange {
+            source_id,
+            pairwise_info,
+            initiation_type: Requester,
+            state: RequestSent::new(invitation_did_doc, invitation_id, id),
+        };
+
+        did_exchange.send_message(wallet, &request.into(), transport).await?;
+
+        Ok(did_exchange)
+    }
+
+    /// Processes a [`Response`] from the responder, sends a [`Complete`] message
+    /// and transitions to [`DidExchangeRequester<Completed>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if:
+    ///     * the thread ID of the response does not match the exchange thread ID
+    ///     * the response does not contain a DidDoc attachment
+    ///     * the DidDoc attachment is not signed by the invitation key
+    ///     * sending the complete message fails
+    pub async fn handle_response<T>(
+        self,
+        wallet: &Arc<dyn BaseWallet>,
+        response: Response,
+        transport: &T,
+    ) -> VcxResult<DidExchangeRequester<Completed>>
+    where
+        T: Transport,
+    {
+        if !matches_thread_id!(response, self.state.thread_id.as_str()) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot handle message {:?}: thread id does not match, expected {:?}",
+                    response, self.state.thread_id
+                ),
+            ));
+        };
+
+        let invitation_key = self.remote_vk()?;
+
+        let did_doc = match &response.content.did_doc {
+            Some(attach) => decode_signed_did_doc_attachment(wallet, attach, &invitation_key).await,
+            None => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                "DidExchange response does not contain a DidDoc attachment",
+            )),
+        };
+
+        let did_doc = match did_doc {
+            Ok(did_doc) => did_doc,
+            Err(err) => {
+                error!("Response DidDoc validation failed! Sending ProblemReport...");
+
+                let mut thread = Thread::new(self.state.thread_id.clone());
+                thread.pthid = Some(self.state.invitation_id.clone());
+
+                send_problem_report(
+                    wallet,
+                    &err,
+                    ProblemCode::ResponseNotAccepted,
+                    thread,
+                    &self.pairwise_info.pw_vk,
+                    &self.state.invitation_did_doc,
+                    transport,
+                )
+                .await;
+
+                return Err(err);
+            }
+        };
+
+        let mut thread = Thread::new(self.state.thread_id.clone());
+        thread.pthid = Some(self.state.invitation_id.clone());
+
+        let mut decorators = CompleteDecorators::new(thread);
+        let mut timing = Timing::default();
+        timing.out_time = Some(Utc::now());
+        decorators.timing = Some(timing);
+
+        let complete = Complete::with_decorators(Uuid::new_v4().to_string(), CompleteContent::default(), decorators);
+
+        let did_exchange = DidExchange {
+            source_id: self.source_id,
+            pairwise_info: self.pairwise_info,
+            initiation_type: Requester,
+            state: Completed::new(did_doc, self.state.invitation_did_doc, self.state.thread_id),
+        };
+
+        did_exchange.send_message(wallet, &complete.into(), transport).await?;
+
+        Ok(did_exchange)
+    }
+}
+
+impl DidExchangeRequester<Completed> {
+    /// Converts the completed exchange into a completed [`Connection`],
+    /// which can then be used for regular messaging.
+    pub fn into_connection(self) -> Connection<Invitee, InviteeCompleted> {
+        let state = InviteeCompleted::new(
+            self.state.did_doc,
+            self.state.bootstrap_did_doc,
+            self.state.thread_id,
+            None,
+        );
+
+        Connection::from_parts(self.source_id, self.pairwise_info, Invitee, state)
+    }
+}
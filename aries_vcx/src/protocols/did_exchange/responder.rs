@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::{
+    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    msg_fields::protocols::did_exchange::{
+        complete::Complete,
+        problem_report::ProblemCode,
+        request::Request,
+        response::{Response, ResponseContent, ResponseDecorators},
+    },
+};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    common::signing::{decode_did_doc_attachment, decode_signed_did_doc_attachment, sign_did_doc_attachment},
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::util::{matches_thread_id, verify_thread_id},
+    protocols::connection::{
+        initiation_type::Inviter, inviter::states::completed::Completed as InviterCompleted,
+        pairwise_info::PairwiseInfo, Connection,
+    },
+    transport::Transport,
+};
+
+use super::{
+    build_our_did_doc,
+    initiation_type::Responder,
+    send_problem_report,
+    states::{completed::Completed, response_sent::ResponseSent},
+    DidExchange,
+};
+
+/// Convenience alias
+pub type DidExchangeResponder<S> = DidExchange<Responder, S>;
+
+impl DidExchangeResponder<ResponseSent> {
+    /// Processes a [`Request`] received for an out-of-band invitation, sends a [`Response`]
+    /// and creates a [`DidExchangeResponder<ResponseSent>`].
+    ///
+    /// The `invitation_id` and `invitation_key` are the ID and recipient key of the
+    /// invitation the request answers. A new [`PairwiseInfo`] is generated for the
+    /// exchange and its DidDoc is attached to the response, signed with the `invitation_key`.
+    ///
+    /// # Errors
+    ///
+    /// Will error out if:
+    ///     * the [`Request`]'s parent thread ID does not match the invitation ID
+    ///     * the [`Request`] does not contain a valid DidDoc attachment
+    ///     * the DidDoc attachment is not signed by the recipient key of the attached DidDoc
+    ///     * generating new [`PairwiseInfo`] or sending the response fails
+    #[allow(clippy::too_many_arguments)]
+    pub async fn handle_request<T>(
+        wallet: &Arc<dyn BaseWallet>,
+        source_id: String,
+        request: Request,
+        invitation_id: &str,
+        invitation_key: &str,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+        transport: &T,
+    ) -> VcxResult<Self>
+    where
+        T: Transport,
+    {
+        trace!(
+            "DidExchange::handle_request >>> request: {:?}, service_endpoint: {}, routing_keys: {:?}",
+            request,
+            service_endpoint,
+            routing_keys,
+        );
+
+        // There must be some other way to validate the thread ID other than cloning the entire Request
+        verify_thread_id(invitation_id, &request.clone().into())?;
+
+        let attach = request.content.did_doc.as_ref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                "DidExchange request does not contain a DidDoc attachment, resolving public DIDs is not supported",
+            )
+        })?;
+        let their_did_doc = decode_did_doc_attachment(attach)?;
+
+        let thread_id = request
+            .decorators
+            .thread
+            .as_ref()
+            .map(|t| t.thid.clone())
+            .unwrap_or(request.id.clone());
+
+        let mut thread = Thread::new(thread_id.clone());
+        thread.pthid = Some(invitation_id.to_owned());
+
+        // If the request's DidDoc validation fails, we generate and send a ProblemReport.
+        // We then return early with the provided error.
+        let their_did_doc = match verify_request_did_doc(wallet, attach, &their_did_doc).await {
+            Ok(did_doc) => did_doc,
+            Err(err) => {
+                error!("Request DidDoc validation failed! Sending ProblemReport...");
+
+                send_problem_report(
+                    wallet,
+                    &err,
+                    ProblemCode::RequestNotAccepted,
+                    thread,
+                    invitation_key,
+                    &their_did_doc,
+                    transport,
+                )
+                .await;
+
+                return Err(err);
+            }
+        };
+
+        // Generate new pairwise info that will be used from this point on
+        // and incorporate that into the response.
+        let pairwise_info = PairwiseInfo::create(wallet).await?;
+        let did_doc = build_our_did_doc(&pairwise_info, service_endpoint, routing_keys);
+        let did_doc_attach = sign_did_doc_attachment(wallet, invitation_key, &did_doc).await?;
+
+        let mut content = ResponseContent::new(pairwise_info.pw_did.clone());
+        content.did_doc = Some(did_doc_attach);
+
+        let mut decorators = ResponseDecorators::new(thread);
+        let mut timing = Timing::default();
+        timing.out_time = Some(Utc::now());
+        decorators.timing = Some(timing);
+
+        let response = Response::with_decorators(Uuid::new_v4().to_string(), content, decorators);
+
+        let did_exchange = DidExchange {
+            source_id,
+            pairwise_info,
+            initiation_type: Responder,
+            state: ResponseSent::new(their_did_doc, thread_id),
+        };
+
+        did_exchange.send_message(wallet, &response.into(), transport).await?;
+
+        Ok(did_exchange)
+    }
+
+    /// Processes the requester's [`Complete`] message and transitions to [`DidExchangeResponder<Completed>`].
+    ///
+    /// # Errors
+    ///
+    /// Will error out if the message's thread ID does not match
+    /// the ID of the thread context used in this exchange.
+    pub fn handle_complete(self, complete: Complete) -> VcxResult<DidExchangeResponder<Completed>> {
+        if !matches_thread_id!(complete, self.state.thread_id.as_str()) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!(
+                    "Cannot handle message {:?}: thread id does not match, expected {:?}",
+                    complete, self.state.thread_id
+                ),
+            ));
+        };
+
+        let state = Completed::new(self.state.did_doc.clone(), self.state.did_doc, self.state.thread_id);
+
+        Ok(DidExchange {
+            source_id: self.source_id,
+            pairwise_info: self.pairwise_info,
+            initiation_type: Responder,
+            state,
+        })
+    }
+}
+
+/// Validates the DidDoc attached to a [`Request`] and checks that the attachment was signed by
+/// the requester, which is the owner of the recipient key advertised in that DidDoc.
+async fn verify_request_did_doc(
+    wallet: &Arc<dyn BaseWallet>,
+    attach: &Attachment,
+    their_did_doc: &AriesDidDoc,
+) -> VcxResult<AriesDidDoc> {
+    their_did_doc.validate()?;
+
+    let signer = their_did_doc
+        .recipient_keys()?
+        .first()
+        .cloned()
+        .ok_or(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "DidExchange request DidDoc does not contain a recipient key",
+        ))?;
+
+    decode_signed_did_doc_attachment(wallet, attach, &signer).await
+}
+
+impl DidExchangeResponder<Completed> {
+    /// Converts the completed exchange into a completed [`Connection`],
+    /// which can then be used for regular messaging.
+    pub fn into_connection(self) -> Connection<Inviter, InviterCompleted> {
+        let state = InviterCompleted::new(self.state.did_doc, self.state.thread_id, None);
+
+        Connection::from_parts(self.source_id, self.pairwise_info, Inviter, state)
+    }
+}
@@ -0,0 +1,38 @@
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::did_exchange::trait_bounds::{TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Completed {
+    pub(crate) did_doc: AriesDidDoc,
+    pub(crate) bootstrap_did_doc: AriesDidDoc,
+    pub(crate) thread_id: String,
+}
+
+impl Completed {
+    pub fn new(did_doc: AriesDidDoc, bootstrap_did_doc: AriesDidDoc, thread_id: String) -> Self {
+        Self {
+            did_doc,
+            bootstrap_did_doc,
+            thread_id,
+        }
+    }
+
+    /// Returns the [`AriesDidDoc`] used to bootstrap the exchange.
+    /// For the responder, this is the same as the [`AriesDidDoc`] received in the request.
+    pub fn bootstrap_did_doc(&self) -> &AriesDidDoc {
+        &self.bootstrap_did_doc
+    }
+}
+
+impl TheirDidDoc for Completed {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.did_doc
+    }
+}
+
+impl ThreadId for Completed {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
@@ -0,0 +1,3 @@
+pub mod completed;
+pub mod request_sent;
+pub mod response_sent;
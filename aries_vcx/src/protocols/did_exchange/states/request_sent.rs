@@ -0,0 +1,32 @@
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+
+use crate::protocols::did_exchange::trait_bounds::{TheirDidDoc, ThreadId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestSent {
+    pub(crate) invitation_did_doc: AriesDidDoc,
+    pub(crate) invitation_id: String,
+    pub(crate) thread_id: String,
+}
+
+impl RequestSent {
+    pub fn new(invitation_did_doc: AriesDidDoc, invitation_id: String, thread_id: String) -> Self {
+        Self {
+            invitation_did_doc,
+            invitation_id,
+            thread_id,
+        }
+    }
+}
+
+impl TheirDidDoc for RequestSent {
+    fn their_did_doc(&self) -> &AriesDidDoc {
+        &self.invitation_did_doc
+    }
+}
+
+impl ThreadId for RequestSent {
+    fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+}
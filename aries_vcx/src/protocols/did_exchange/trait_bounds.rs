@@ -0,0 +1,12 @@
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+
+/// Trait implemented for [`super::DidExchange`] states that store an [`AriesDidDoc`].
+pub trait TheirDidDoc {
+    /// Returns the [`AriesDidDoc`] currently being used by a [`super::DidExchange`].
+    fn their_did_doc(&self) -> &AriesDidDoc;
+}
+
+/// Trait implemented for [`super::DidExchange`] states that keep track of a thread ID.
+pub trait ThreadId {
+    fn thread_id(&self) -> &str;
+}
@@ -0,0 +1,83 @@
+//! Helpers for binding attachments to their formats through the `formats` field
+//! of the issue credential 2.0 and present proof 2.0 messages.
+//!
+//! Only the `hlindy` formats are currently produced and consumed by the state machines.
+
+use std::fmt::Debug;
+
+use messages::decorators::attachment::{Attachment, AttachmentType};
+use messages::msg_fields::protocols::common::attachment_format_specifier::AttachmentFormatSpecifier;
+use shared_vcx::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+use crate::errors::error::prelude::*;
+use crate::handlers::util::make_attach_from_str;
+
+/// Builds a base64 encoded attachment with a fresh id, along with the format specifier pointing to it.
+pub fn make_format_attach<F>(attach: &str, format: F) -> (AttachmentFormatSpecifier<F>, Attachment) {
+    let attach_id = Uuid::new_v4().to_string();
+    let attachment = make_attach_from_str!(attach, attach_id.clone());
+    (
+        AttachmentFormatSpecifier::new(attach_id, MaybeKnown::Known(format)),
+        attachment,
+    )
+}
+
+/// Finds the attachment bound to `format` and returns its contents as a string.
+pub fn get_attach_by_format<F>(
+    formats: &[AttachmentFormatSpecifier<F>],
+    attachments: &[Attachment],
+    format: F,
+) -> VcxResult<String>
+where
+    F: PartialEq + Debug,
+{
+    let attach_id = formats
+        .iter()
+        .find_map(|spec| match &spec.format {
+            MaybeKnown::Known(known) if *known == format => Some(spec.attach_id.as_str()),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("No attachment of format {:?} found in formats: {:?}", format, formats),
+            )
+        })?;
+
+    let attachment = attachments
+        .iter()
+        .find(|attach| attach.id.as_deref() == Some(attach_id))
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("No attachment with id {} found", attach_id),
+            )
+        })?;
+
+    attach_to_string(attachment)
+}
+
+fn attach_to_string(attachment: &Attachment) -> VcxResult<String> {
+    match &attachment.data.content {
+        AttachmentType::Base64(encoded_attach) => {
+            let bytes = base64::decode(encoded_attach).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::SerializationError,
+                    format!("Attachment is not base 64 encoded: {:?}, err: {}", attachment, err),
+                )
+            })?;
+            String::from_utf8(bytes).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::SerializationError,
+                    format!("Attachment is not UTF-8 encoded: {:?}, err: {}", attachment, err),
+                )
+            })
+        }
+        AttachmentType::Json(value) => Ok(value.to_string()),
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::SerializationError,
+            format!("Unsupported attachment content: {:?}", attachment),
+        )),
+    }
+}
@@ -0,0 +1,1077 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use chrono::Utc;
+use messages::decorators::thread::Thread;
+use messages::decorators::timing::Timing;
+use messages::msg_fields::protocols::cred_issuance::v2::ack::{AckCredentialV2, AckCredentialV2Content};
+use messages::msg_fields::protocols::cred_issuance::v2::issue_credential::{
+    IssueCredentialAttachmentFormatType, IssueCredentialV2,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::offer_credential::{
+    OfferCredentialAttachmentFormatType, OfferCredentialV2,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::ProposeCredentialV2;
+use messages::msg_fields::protocols::cred_issuance::v2::request_credential::{
+    RequestCredentialAttachmentFormatType, RequestCredentialV2, RequestCredentialV2Content,
+    RequestCredentialV2Decorators,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::CredentialIssuanceV2;
+use messages::msg_fields::protocols::notification::ack::{AckDecorators, AckStatus};
+use messages::msg_fields::protocols::notification::Notification;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+use messages::AriesMessage;
+use uuid::Uuid;
+
+use crate::common::credentials::{get_cred_rev_id, is_cred_revoked};
+use crate::errors::error::prelude::*;
+use crate::handlers::util::{matches_opt_thread_id, matches_thread_id, Status};
+use crate::protocols::common::build_problem_report_msg;
+use crate::protocols::issuance::holder::state_machine::{create_credential_request, parse_cred_def_id_from_cred_offer};
+use crate::protocols::issuance_v2::actions::CredentialIssuanceV2Action;
+use crate::protocols::issuance_v2::formats::{get_attach_by_format, make_format_attach};
+use crate::protocols::issuance_v2::holder::states::finished::FinishedHolderState;
+use crate::protocols::issuance_v2::holder::states::initial::InitialHolderState;
+use crate::protocols::issuance_v2::holder::states::offer_received::OfferReceivedState;
+use crate::protocols::issuance_v2::holder::states::proposal_sent::ProposalSentState;
+use crate::protocols::issuance_v2::holder::states::request_sent::RequestSentState;
+use crate::protocols::issuance_v2::verify_thread_id;
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum HolderV2FullState {
+    Initial(InitialHolderState),
+    ProposalSent(ProposalSentState),
+    OfferReceived(OfferReceivedState),
+    RequestSent(RequestSentState),
+    Finished(FinishedHolderState),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HolderV2State {
+    Initial,
+    ProposalSent,
+    OfferReceived,
+    RequestSent,
+    Finished,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HolderV2SM {
+    state: HolderV2FullState,
+    source_id: String,
+    thread_id: String,
+}
+
+impl fmt::Display for HolderV2FullState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            HolderV2FullState::Initial(_) => f.write_str("Initial"),
+            HolderV2FullState::ProposalSent(_) => f.write_str("ProposalSent"),
+            HolderV2FullState::OfferReceived(_) => f.write_str("OfferReceived"),
+            HolderV2FullState::RequestSent(_) => f.write_str("RequestSent"),
+            HolderV2FullState::Finished(_) => f.write_str("Finished"),
+        }
+    }
+}
+
+fn build_credential_request_msg(credential_request_attach: String, thread_id: &str) -> RequestCredentialV2 {
+    let (format, attach) = make_format_attach(
+        &credential_request_attach,
+        RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0,
+    );
+    let content = RequestCredentialV2Content::new(vec![format], vec![attach]);
+
+    let mut decorators = RequestCredentialV2Decorators::default();
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.thread = Some(Thread::new(thread_id.to_owned()));
+    decorators.timing = Some(timing);
+
+    RequestCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+fn build_credential_ack(thread_id: &str) -> AckCredentialV2 {
+    let content = AckCredentialV2Content::new(AckStatus::Ok);
+    let mut decorators = AckDecorators::new(Thread::new(thread_id.to_owned()));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    AckCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+impl HolderV2SM {
+    pub fn new(source_id: String) -> Self {
+        HolderV2SM {
+            thread_id: Uuid::new_v4().to_string(),
+            state: HolderV2FullState::Initial(InitialHolderState::new()),
+            source_id,
+        }
+    }
+
+    pub fn from_offer(offer: OfferCredentialV2, source_id: String) -> Self {
+        let thread_id = offer
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| offer.id.clone());
+        HolderV2SM {
+            thread_id,
+            state: HolderV2FullState::OfferReceived(OfferReceivedState::new(offer)),
+            source_id,
+        }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn get_state(&self) -> HolderV2State {
+        match self.state {
+            HolderV2FullState::Initial(_) => HolderV2State::Initial,
+            HolderV2FullState::ProposalSent(_) => HolderV2State::ProposalSent,
+            HolderV2FullState::OfferReceived(_) => HolderV2State::OfferReceived,
+            HolderV2FullState::RequestSent(_) => HolderV2State::RequestSent,
+            HolderV2FullState::Finished(ref status) => match status.status {
+                Status::Success => HolderV2State::Finished,
+                _ => HolderV2State::Failed,
+            },
+        }
+    }
+
+    pub fn get_proposal(&self) -> VcxResult<ProposeCredentialV2> {
+        match &self.state {
+            HolderV2FullState::ProposalSent(state) => Ok(state.credential_proposal.clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Proposal not available in this state",
+            )),
+        }
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        trace!(
+            "HolderV2SM::find_message_to_handle >>> messages: {:?}, state: {:?}",
+            messages,
+            self.state
+        );
+        for (uid, message) in messages {
+            match self.state {
+                HolderV2FullState::ProposalSent(_) => match &message {
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::OfferCredential(offer)) => {
+                        if matches_opt_thread_id!(offer, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                HolderV2FullState::OfferReceived(_) => match &message {
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(problem_report) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                HolderV2FullState::RequestSent(_) => match &message {
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::IssueCredential(credential)) => {
+                        if matches_thread_id!(credential, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(problem_report) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::Notification(Notification::ProblemReport(msg)) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            };
+        }
+        None
+    }
+
+    pub async fn handle_message(
+        self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        cim: CredentialIssuanceV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<HolderV2SM> {
+        trace!("HolderV2SM::handle_message >>> cim: {:?}, state: {:?}", cim, self.state);
+        verify_thread_id(&self.thread_id, &cim)?;
+        let holder_sm = match cim {
+            CredentialIssuanceV2Action::CredentialProposalSend(proposal_data) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.send_proposal(proposal_data, send_message).await?
+            }
+            CredentialIssuanceV2Action::CredentialOffer(offer) => self.receive_offer(offer)?,
+            CredentialIssuanceV2Action::CredentialRequestSend(my_pw_did) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.send_request(ledger, anoncreds, my_pw_did, send_message).await?
+            }
+            CredentialIssuanceV2Action::CredentialOfferReject(comment) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.decline_offer(comment, send_message).await?
+            }
+            CredentialIssuanceV2Action::Credential(credential) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.receive_credential(ledger, anoncreds, credential, send_message)
+                    .await?
+            }
+            CredentialIssuanceV2Action::ProblemReport(problem_report) => self.receive_problem_report(problem_report)?,
+            _ => self,
+        };
+        Ok(holder_sm)
+    }
+
+    pub async fn send_proposal(self, proposal_data: ProposeCredentialV2, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            HolderV2FullState::Initial(_) => {
+                let mut proposal = proposal_data;
+                proposal.id = self.thread_id.clone();
+                send_message(proposal.clone().into()).await?;
+                HolderV2FullState::ProposalSent(ProposalSentState::new(proposal))
+            }
+            HolderV2FullState::OfferReceived(_) => {
+                let mut proposal = proposal_data;
+                proposal.decorators.thread = Some(Thread::new(self.thread_id.clone()));
+                send_message(proposal.clone().into()).await?;
+                HolderV2FullState::ProposalSent(ProposalSentState::new(proposal))
+            }
+            s => {
+                warn!("Unable to send credential proposal in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_offer(self, offer: OfferCredentialV2) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &CredentialIssuanceV2Action::CredentialOffer(offer.clone()),
+        )?;
+        let state = match self.state {
+            HolderV2FullState::ProposalSent(_) => HolderV2FullState::OfferReceived(OfferReceivedState::new(offer)),
+            s => {
+                warn!("Unable to receive credential offer in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn send_request<'a>(
+        self,
+        ledger: &'a Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &'a Arc<dyn BaseAnonCreds>,
+        my_pw_did: String,
+        send_message: SendClosure,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            HolderV2FullState::OfferReceived(state_data) => {
+                match _make_credential_request(ledger, anoncreds, &self.thread_id, my_pw_did, &state_data.offer).await {
+                    Ok((cred_request, req_meta, cred_def_json)) => {
+                        send_message(cred_request.into()).await?;
+                        HolderV2FullState::RequestSent((state_data, req_meta, cred_def_json).into())
+                    }
+                    Err(err) => {
+                        let problem_report = build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        error!(
+                            "Failed to create credential request, sending problem report: {:?}",
+                            problem_report
+                        );
+                        send_message(problem_report.clone().into()).await?;
+                        HolderV2FullState::Finished(problem_report.into())
+                    }
+                }
+            }
+            s => {
+                warn!("Unable to send credential request in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn decline_offer(self, comment: Option<String>, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            HolderV2FullState::OfferReceived(_) => {
+                let problem_report = build_problem_report_msg(comment, &self.thread_id);
+                send_message(problem_report.clone().into()).await?;
+                HolderV2FullState::Finished(problem_report.into())
+            }
+            s => {
+                warn!("Unable to decline credential offer in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn receive_credential<'a>(
+        self,
+        ledger: &'a Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &'a Arc<dyn BaseAnonCreds>,
+        credential: IssueCredentialV2,
+        send_message: SendClosure,
+    ) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &CredentialIssuanceV2Action::Credential(credential.clone()),
+        )?;
+        let state = match self.state {
+            HolderV2FullState::RequestSent(state_data) => {
+                match _store_credential(
+                    ledger,
+                    anoncreds,
+                    &credential,
+                    &state_data.req_meta,
+                    &state_data.cred_def_json,
+                )
+                .await
+                {
+                    Ok((cred_id, rev_reg_def_json)) => {
+                        if credential.decorators.please_ack.is_some() {
+                            let ack = build_credential_ack(&self.thread_id);
+                            send_message(ack.into()).await?;
+                        }
+                        HolderV2FullState::Finished((state_data, cred_id, credential, rev_reg_def_json).into())
+                    }
+                    Err(err) => {
+                        let problem_report = build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        error!(
+                            "Failed to process or save received credential, sending problem report: {:?}",
+                            problem_report
+                        );
+                        send_message(problem_report.clone().into()).await?;
+                        HolderV2FullState::Finished(problem_report.into())
+                    }
+                }
+            }
+            s => {
+                warn!("Unable to receive credential in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
+        let state = match self.state {
+            HolderV2FullState::ProposalSent(_)
+            | HolderV2FullState::OfferReceived(_)
+            | HolderV2FullState::RequestSent(_) => HolderV2FullState::Finished(problem_report.into()),
+            s => {
+                warn!("Unable to receive problem report in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn credential_status(&self) -> u32 {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => state.status.code(),
+            _ => Status::Undefined.code(),
+        }
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        matches!(self.state, HolderV2FullState::Finished(_))
+    }
+
+    pub fn get_credential(&self) -> VcxResult<(String, AriesMessage)> {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => {
+                let cred_id = state.cred_id.clone().ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot get credential: Credential Id not found",
+                ))?;
+                let credential = state.credential.clone().ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot get credential: Credential not found",
+                ))?;
+                Ok((cred_id, credential.into()))
+            }
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot get credential: Credential Issuance is not finished yet",
+            )),
+        }
+    }
+
+    pub fn get_attributes(&self) -> VcxResult<String> {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => state.get_attributes(),
+            HolderV2FullState::OfferReceived(ref state) => state.get_attributes(),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot get credential attributes: credential offer or credential must be receieved first",
+            )),
+        }
+    }
+
+    pub fn get_attachment(&self) -> VcxResult<String> {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => state.get_attachment(),
+            HolderV2FullState::OfferReceived(ref state) => state.get_attachment(),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot get credential attachment: credential offer or credential must be receieved first",
+            )),
+        }
+    }
+
+    pub fn get_rev_reg_id(&self) -> VcxResult<String> {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => state.get_rev_reg_id(),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot get rev reg id: credential exchange not finished yet",
+            )),
+        }
+    }
+
+    pub fn get_cred_id(&self) -> VcxResult<String> {
+        match self.state {
+            HolderV2FullState::Finished(ref state) => state.get_cred_id(),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot get credential id: credential exchange not finished yet",
+            )),
+        }
+    }
+
+    pub fn get_offer(&self) -> VcxResult<OfferCredentialV2> {
+        match self.state {
+            HolderV2FullState::OfferReceived(ref state) => Ok(state.offer.clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Credential offer can only be obtained from OfferReceived state",
+            )),
+        }
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        Ok(self.thread_id.clone())
+    }
+
+    pub async fn is_revokable(&self, ledger: &Arc<dyn AnoncredsLedgerRead>) -> VcxResult<bool> {
+        match self.state {
+            HolderV2FullState::OfferReceived(ref state) => state.is_revokable(ledger).await,
+            HolderV2FullState::RequestSent(ref state) => state.is_revokable(),
+            HolderV2FullState::Finished(ref state) => state.is_revokable(),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Revocation information not available in the {} state", self.state),
+            )),
+        }
+    }
+
+    pub async fn is_revoked(
+        &self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+    ) -> VcxResult<bool> {
+        if self.is_revokable(ledger).await? {
+            let rev_reg_id = self.get_rev_reg_id()?;
+            let cred_id = self.get_cred_id()?;
+            let rev_id = get_cred_rev_id(anoncreds, &cred_id).await?;
+            is_cred_revoked(ledger, &rev_reg_id, &rev_id).await
+        } else {
+            Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Unable to check revocation status - this credential is not revokable",
+            ))
+        }
+    }
+
+    pub async fn delete_credential(&self, anoncreds: &Arc<dyn BaseAnonCreds>) -> VcxResult<()> {
+        trace!("HolderV2SM::delete_credential");
+
+        match self.state {
+            HolderV2FullState::Finished(ref state) => {
+                let cred_id = state.get_cred_id()?;
+                anoncreds
+                    .prover_delete_credential(&cred_id)
+                    .await
+                    .map_err(|err| err.into())
+            }
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Cannot delete credential: credential issuance is not finished yet",
+            )),
+        }
+    }
+}
+
+async fn _store_credential(
+    ledger: &Arc<dyn AnoncredsLedgerRead>,
+    anoncreds: &Arc<dyn BaseAnonCreds>,
+    credential: &IssueCredentialV2,
+    req_meta: &str,
+    cred_def_json: &str,
+) -> VcxResult<(String, Option<String>)> {
+    trace!(
+        "HolderV2SM::_store_credential >>> credential: {:?}, req_meta: {}, cred_def_json: {}",
+        credential,
+        req_meta,
+        cred_def_json
+    );
+
+    let credential_json = get_attach_by_format(
+        &credential.content.formats,
+        &credential.content.credentials_attach,
+        IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0,
+    )?;
+
+    let parsed_credential: serde_json::Value = serde_json::from_str(&credential_json).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!("Invalid Credential Json: {}, err: {:?}", credential_json, err),
+        )
+    })?;
+    let rev_reg_def_json = match parsed_credential["rev_reg_id"].as_str() {
+        Some(rev_reg_id) => Some(ledger.get_rev_reg_def_json(rev_reg_id).await?),
+        None => None,
+    };
+
+    let cred_id = anoncreds
+        .prover_store_credential(
+            None,
+            req_meta,
+            &credential_json,
+            cred_def_json,
+            rev_reg_def_json.as_deref(),
+        )
+        .await?;
+    Ok((cred_id, rev_reg_def_json))
+}
+
+async fn _make_credential_request(
+    ledger: &Arc<dyn AnoncredsLedgerRead>,
+    anoncreds: &Arc<dyn BaseAnonCreds>,
+    thread_id: &str,
+    my_pw_did: String,
+    offer: &OfferCredentialV2,
+) -> VcxResult<(RequestCredentialV2, String, String)> {
+    trace!(
+        "HolderV2SM::_make_credential_request >>> my_pw_did: {:?}, offer: {:?}",
+        my_pw_did,
+        offer
+    );
+
+    let cred_offer = get_attach_by_format(
+        &offer.content.formats,
+        &offer.content.offers_attach,
+        OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0,
+    )?;
+
+    trace!("Parsed cred offer attachment: {}", cred_offer);
+    let cred_def_id = parse_cred_def_id_from_cred_offer(&cred_offer)?;
+    let (req, req_meta, _cred_def_id, cred_def_json) =
+        create_credential_request(ledger, anoncreds, &cred_def_id, &my_pw_did, &cred_offer).await?;
+    let credential_request_msg = build_credential_request_msg(req, thread_id);
+    Ok((credential_request_msg, req_meta, cred_def_json))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+    use crate::protocols::issuance_v2::test_utils::*;
+    use crate::utils::constants::REV_REG_ID;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    const THREAD_ID: &str = "test_thread_id";
+
+    fn _ledger() -> Arc<dyn AnoncredsLedgerRead> {
+        Arc::new(MockLedger)
+    }
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    fn _holder_sm() -> HolderV2SM {
+        HolderV2SM::from_offer(_credential_offer(THREAD_ID), "test_source_id".to_owned())
+    }
+
+    impl HolderV2SM {
+        async fn to_request_sent_state(self) -> HolderV2SM {
+            let holder_sm = self
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    CredentialIssuanceV2Action::CredentialRequestSend(_my_pw_did()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(HolderV2FullState::RequestSent(_), holder_sm.state);
+            holder_sm
+        }
+
+        async fn to_finished_state(self) -> HolderV2SM {
+            let holder_sm = self
+                .to_request_sent_state()
+                .await
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    CredentialIssuanceV2Action::Credential(_credential(THREAD_ID)),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(HolderV2FullState::Finished(_), holder_sm.state);
+            holder_sm
+        }
+    }
+
+    #[test]
+    fn test_holder_from_offer() {
+        let holder_sm = _holder_sm();
+
+        assert_match!(HolderV2FullState::OfferReceived(_), holder_sm.state);
+        assert_eq!("test_source_id", holder_sm.get_source_id());
+        assert_eq!(THREAD_ID, holder_sm.get_thread_id().unwrap());
+    }
+
+    #[test]
+    fn test_holder_from_offer_without_thread_uses_offer_id() {
+        let mut offer = _credential_offer(THREAD_ID);
+        offer.decorators.thread = None;
+
+        let holder_sm = HolderV2SM::from_offer(offer.clone(), "test_source_id".to_owned());
+
+        assert_eq!(offer.id, holder_sm.get_thread_id().unwrap());
+    }
+
+    #[test]
+    fn test_holder_build_credential_request_msg() {
+        let msg = build_credential_request_msg("{}".into(), THREAD_ID);
+
+        assert_eq!(THREAD_ID, msg.decorators.thread.unwrap().thid);
+        assert!(msg.decorators.timing.unwrap().out_time.is_some());
+        assert_eq!(
+            "{}",
+            get_attach_by_format(
+                &msg.content.formats,
+                &msg.content.requests_attach,
+                RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0,
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_holder_build_credential_ack() {
+        let msg = build_credential_ack(THREAD_ID);
+
+        assert_eq!(THREAD_ID, msg.decorators.thread.thid);
+        assert!(msg.decorators.timing.unwrap().out_time.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_holder_send_proposal_and_receive_offer() {
+        let holder_sm = HolderV2SM::new("test_source_id".to_owned());
+        assert_match!(HolderV2FullState::Initial(_), holder_sm.state);
+
+        let holder_sm = holder_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialProposalSend(_credential_proposal()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::ProposalSent(_), holder_sm.state);
+        let thread_id = holder_sm.get_thread_id().unwrap();
+        assert_eq!(thread_id, holder_sm.get_proposal().unwrap().id);
+
+        let holder_sm = holder_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialOffer(_credential_offer(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::OfferReceived(_), holder_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_holder_send_request_from_offer_received_state() {
+        let holder_sm = _holder_sm().to_request_sent_state().await;
+
+        assert_eq!(HolderV2State::RequestSent, holder_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_holder_send_request_for_invalid_offer() {
+        let offer = _credential_offer_with_attach(THREAD_ID, r#"{"credential offer": {}}"#);
+
+        let holder_sm = HolderV2SM::from_offer(offer, "test_source_id".to_owned())
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequestSend(_my_pw_did()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(HolderV2FullState::Finished(_), holder_sm.state);
+        assert_eq!(HolderV2State::Failed, holder_sm.get_state());
+        assert_eq!(
+            Status::Failed(_problem_report(THREAD_ID)).code(),
+            holder_sm.credential_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_decline_offer() {
+        let holder_sm = _holder_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialOfferReject(Some("declined".to_owned())),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(HolderV2State::Failed, holder_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_holder_receive_credential_from_request_sent_state() {
+        let holder_sm = _holder_sm().to_finished_state().await;
+
+        assert_eq!(HolderV2State::Finished, holder_sm.get_state());
+        assert_eq!(Status::Success.code(), holder_sm.credential_status());
+        assert_eq!("cred_id", holder_sm.get_cred_id().unwrap());
+        assert_eq!(REV_REG_ID, holder_sm.get_rev_reg_id().unwrap());
+        let (cred_id, credential) = holder_sm.get_credential().unwrap();
+        assert_eq!("cred_id", cred_id);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::IssueCredential(_)),
+            credential
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_receive_invalid_credential_from_request_sent_state() {
+        let credential =
+            _credential_with_format(THREAD_ID, IssueCredentialAttachmentFormatType::AnoncredsCredential1_0);
+
+        let holder_sm = _holder_sm()
+            .to_request_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::Credential(credential),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(HolderV2State::Failed, holder_sm.get_state());
+        assert_eq!(
+            Status::Failed(_problem_report(THREAD_ID)).code(),
+            holder_sm.credential_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_receive_credential_with_mismatched_thread_id() {
+        let err = _holder_sm()
+            .to_request_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::Credential(_credential("other_thread_id")),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_holder_receive_problem_report_from_offer_received_state() {
+        let holder_sm = _holder_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::ProblemReport(_problem_report(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(HolderV2State::Failed, holder_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_holder_receive_problem_report_from_request_sent_state() {
+        let holder_sm = _holder_sm()
+            .to_request_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::ProblemReport(_problem_report(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(HolderV2State::Failed, holder_sm.get_state());
+        assert_eq!(
+            Status::Failed(_problem_report(THREAD_ID)).code(),
+            holder_sm.credential_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_ignores_other_messages_from_offer_received_state() {
+        let holder_sm = _holder_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::Credential(_credential(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::OfferReceived(_), holder_sm.state);
+
+        let holder_sm = holder_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialAck(_credential_ack(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::OfferReceived(_), holder_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_holder_ignores_other_messages_from_request_sent_state() {
+        let holder_sm = _holder_sm()
+            .to_request_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialOffer(_credential_offer(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::RequestSent(_), holder_sm.state);
+
+        let holder_sm = holder_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialAck(_credential_ack(THREAD_ID)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(HolderV2FullState::RequestSent(_), holder_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_holder_ignores_messages_from_finished_state() {
+        let mut holder_sm = _holder_sm().to_finished_state().await;
+
+        for action in [
+            CredentialIssuanceV2Action::CredentialOffer(_credential_offer(THREAD_ID)),
+            CredentialIssuanceV2Action::Credential(_credential(THREAD_ID)),
+            CredentialIssuanceV2Action::CredentialAck(_credential_ack(THREAD_ID)),
+            CredentialIssuanceV2Action::ProblemReport(_problem_report(THREAD_ID)),
+        ] {
+            holder_sm = holder_sm
+                .handle_message(&_ledger(), &_anoncreds(), action, _send_message())
+                .await
+                .unwrap();
+            assert_eq!(HolderV2State::Finished, holder_sm.get_state());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_holder_requires_send_message_callback() {
+        let err = _holder_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequestSend(_my_pw_did()),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_holder_find_message_to_handle_from_offer_received_state() {
+        let holder_sm = _holder_sm();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_offer(THREAD_ID).into()),
+            ("key_2".to_owned(), _credential(THREAD_ID).into()),
+            ("key_3".to_owned(), _problem_report(THREAD_ID).into()),
+        ]);
+        let (uid, message) = holder_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_3", uid);
+        assert_match!(AriesMessage::ReportProblem(_), message);
+
+        let messages = HashMap::from([("key_1".to_owned(), _problem_report("other_thread_id").into())]);
+        assert!(holder_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_holder_find_message_to_handle_from_request_sent_state() {
+        let holder_sm = _holder_sm().to_request_sent_state().await;
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_offer(THREAD_ID).into()),
+            ("key_2".to_owned(), _credential_request(THREAD_ID).into()),
+            ("key_3".to_owned(), _credential(THREAD_ID).into()),
+        ]);
+        let (uid, message) = holder_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_3", uid);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::IssueCredential(_)),
+            message
+        );
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_offer(THREAD_ID).into()),
+            ("key_2".to_owned(), _problem_report(THREAD_ID).into()),
+        ]);
+        let (uid, message) = holder_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(AriesMessage::ReportProblem(_), message);
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report("other_thread_id").into()),
+        ]);
+        assert!(holder_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_holder_find_message_to_handle_from_finished_state() {
+        let holder_sm = _holder_sm().to_finished_state().await;
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_offer(THREAD_ID).into()),
+            ("key_2".to_owned(), _credential(THREAD_ID).into()),
+            ("key_3".to_owned(), _credential_ack(THREAD_ID).into()),
+            ("key_4".to_owned(), _problem_report(THREAD_ID).into()),
+        ]);
+        assert!(holder_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_holder_get_state() {
+        assert_eq!(HolderV2State::Initial, HolderV2SM::new("test".to_owned()).get_state());
+        assert_eq!(HolderV2State::OfferReceived, _holder_sm().get_state());
+        assert_eq!(
+            HolderV2State::RequestSent,
+            _holder_sm().to_request_sent_state().await.get_state()
+        );
+        assert_eq!(
+            HolderV2State::Finished,
+            _holder_sm().to_finished_state().await.get_state()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_getters_before_finished() {
+        let holder_sm = _holder_sm();
+
+        assert!(holder_sm.get_offer().is_ok());
+        assert!(holder_sm.get_attachment().is_ok());
+        assert_eq!(
+            AriesVcxErrorKind::NotReady,
+            holder_sm.get_credential().unwrap_err().kind()
+        );
+        assert_eq!(AriesVcxErrorKind::NotReady, holder_sm.get_cred_id().unwrap_err().kind());
+
+        let holder_sm = holder_sm.to_request_sent_state().await;
+        assert_eq!(
+            AriesVcxErrorKind::NotReady,
+            holder_sm.get_rev_reg_id().unwrap_err().kind()
+        );
+        assert_eq!(
+            AriesVcxErrorKind::InvalidState,
+            holder_sm.get_offer().unwrap_err().kind()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_holder_is_revokable() {
+        assert!(_holder_sm()
+            .to_finished_state()
+            .await
+            .is_revokable(&_ledger())
+            .await
+            .unwrap());
+    }
+}
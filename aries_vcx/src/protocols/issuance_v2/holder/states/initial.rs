@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct InitialHolderState {}
+
+impl InitialHolderState {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
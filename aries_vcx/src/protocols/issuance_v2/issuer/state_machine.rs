@@ -0,0 +1,1156 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use chrono::Utc;
+use messages::decorators::please_ack::PleaseAck;
+use messages::decorators::thread::Thread;
+use messages::decorators::timing::Timing;
+use messages::msg_fields::protocols::cred_issuance::v2::ack::AckCredentialV2;
+use messages::msg_fields::protocols::cred_issuance::v2::issue_credential::{
+    IssueCredentialAttachmentFormatType, IssueCredentialV2, IssueCredentialV2Content, IssueCredentialV2Decorators,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::offer_credential::{
+    OfferCredentialAttachmentFormatType, OfferCredentialV2, OfferCredentialV2Content, OfferCredentialV2Decorators,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::ProposeCredentialV2;
+use messages::msg_fields::protocols::cred_issuance::v2::request_credential::{
+    RequestCredentialAttachmentFormatType, RequestCredentialV2,
+};
+use messages::msg_fields::protocols::cred_issuance::v2::{CredentialIssuanceV2, CredentialPreviewV2};
+use messages::msg_fields::protocols::notification::Notification;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+use messages::AriesMessage;
+use uuid::Uuid;
+
+use crate::common::credentials::encoding::encode_attributes;
+use crate::common::credentials::is_cred_revoked;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::handlers::util::{matches_opt_thread_id, matches_thread_id, OfferInfo, Status};
+use crate::protocols::common::build_problem_report_msg;
+use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
+use crate::protocols::issuance_v2::actions::CredentialIssuanceV2Action;
+use crate::protocols::issuance_v2::formats::{get_attach_by_format, make_format_attach};
+use crate::protocols::issuance_v2::issuer::states::credential_sent::CredentialSentState;
+use crate::protocols::issuance_v2::issuer::states::finished::FinishedState;
+use crate::protocols::issuance_v2::issuer::states::initial::InitialIssuerState;
+use crate::protocols::issuance_v2::issuer::states::offer_sent::OfferSentState;
+use crate::protocols::issuance_v2::issuer::states::offer_set::OfferSetState;
+use crate::protocols::issuance_v2::issuer::states::proposal_received::ProposalReceivedState;
+use crate::protocols::issuance_v2::issuer::states::requested_received::RequestReceivedState;
+use crate::protocols::issuance_v2::verify_thread_id;
+use crate::protocols::SendClosure;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum IssuerV2FullState {
+    Initial(InitialIssuerState),
+    OfferSet(OfferSetState),
+    ProposalReceived(ProposalReceivedState),
+    OfferSent(OfferSentState),
+    RequestReceived(RequestReceivedState),
+    CredentialSent(CredentialSentState),
+    Finished(FinishedState),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum IssuerV2State {
+    Initial,
+    OfferSet,
+    ProposalReceived,
+    OfferSent,
+    RequestReceived,
+    CredentialSent,
+    Finished,
+    Failed,
+}
+
+impl Display for IssuerV2FullState {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
+        match *self {
+            IssuerV2FullState::Initial(_) => f.write_str("Initial"),
+            IssuerV2FullState::OfferSet(_) => f.write_str("OfferSet"),
+            IssuerV2FullState::ProposalReceived(_) => f.write_str("ProposalReceived"),
+            IssuerV2FullState::OfferSent(_) => f.write_str("OfferSent"),
+            IssuerV2FullState::RequestReceived(_) => f.write_str("RequestReceived"),
+            IssuerV2FullState::CredentialSent(_) => f.write_str("CredentialSent"),
+            IssuerV2FullState::Finished(_) => f.write_str("Finished"),
+        }
+    }
+}
+
+impl Default for IssuerV2FullState {
+    fn default() -> Self {
+        Self::Initial(InitialIssuerState::default())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IssuerV2SM {
+    source_id: String,
+    thread_id: String,
+    state: IssuerV2FullState,
+}
+
+fn build_credential_message(libindy_credential: String, thread_id: &str) -> IssueCredentialV2 {
+    let (format, attach) = make_format_attach(
+        &libindy_credential,
+        IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0,
+    );
+    let content = IssueCredentialV2Content::new(vec![format], vec![attach]);
+
+    let mut decorators = IssueCredentialV2Decorators::new(Thread::new(thread_id.to_owned()));
+    decorators.please_ack = Some(PleaseAck::new(vec![]));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    IssueCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+fn build_credential_offer(
+    thread_id: &str,
+    credential_offer: &str,
+    credential_preview: CredentialPreviewV2,
+    comment: Option<String>,
+) -> OfferCredentialV2 {
+    let (format, attach) = make_format_attach(
+        credential_offer,
+        OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0,
+    );
+    let mut content = OfferCredentialV2Content::new(credential_preview, vec![format], vec![attach]);
+    content.comment = comment;
+
+    let mut decorators = OfferCredentialV2Decorators::default();
+    decorators.thread = Some(Thread::new(thread_id.to_owned()));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    OfferCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+impl IssuerV2SM {
+    pub fn new(source_id: &str) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            thread_id: Uuid::new_v4().to_string(),
+            state: IssuerV2FullState::Initial(InitialIssuerState {}),
+        }
+    }
+
+    pub fn from_proposal(source_id: &str, credential_proposal: &ProposeCredentialV2) -> Self {
+        Self {
+            thread_id: credential_proposal.id.clone(),
+            source_id: source_id.to_string(),
+            state: IssuerV2FullState::ProposalReceived(ProposalReceivedState::new(credential_proposal.clone())),
+        }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn step(source_id: String, thread_id: String, state: IssuerV2FullState) -> Self {
+        Self {
+            source_id,
+            thread_id,
+            state,
+        }
+    }
+
+    pub fn get_revocation_info(&self) -> Option<RevocationInfoV1> {
+        match &self.state {
+            IssuerV2FullState::CredentialSent(state) => state.revocation_info_v1.clone(),
+            IssuerV2FullState::Finished(state) => state.revocation_info_v1.clone(),
+            _ => None,
+        }
+    }
+
+    pub fn get_rev_id(&self) -> VcxResult<String> {
+        self.get_revocation_info()
+            .ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "No revocation info found - is this credential revokable?",
+            ))?
+            .cred_rev_id
+            .ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Revocation info does not contain rev id",
+            ))
+    }
+
+    pub fn get_rev_reg_id(&self) -> VcxResult<String> {
+        let rev_registry = match &self.state {
+            IssuerV2FullState::Initial(_) | IssuerV2FullState::ProposalReceived(_) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    format!("No revocation info available in the {} state", self.state),
+                ));
+            }
+            IssuerV2FullState::OfferSet(state) => state.rev_reg_id.clone(),
+            IssuerV2FullState::OfferSent(state) => state.rev_reg_id.clone(),
+            IssuerV2FullState::RequestReceived(state) => state.rev_reg_id.clone(),
+            IssuerV2FullState::CredentialSent(_) | IssuerV2FullState::Finished(_) => {
+                self.get_revocation_info()
+                    .ok_or(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidState,
+                        "No revocation info found - is this credential revokable?",
+                    ))?
+                    .rev_reg_id
+            }
+        };
+        rev_registry.ok_or(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            "No revocation registry id found on revocation info - is this credential revokable?",
+        ))
+    }
+
+    pub fn is_revokable(&self) -> bool {
+        self.get_revocation_info()
+            .map(|rev_info| rev_info.cred_rev_id.is_some())
+            .unwrap_or(false)
+    }
+
+    pub async fn is_revoked(&self, ledger: &Arc<dyn AnoncredsLedgerRead>) -> VcxResult<bool> {
+        if self.is_revokable() {
+            let rev_reg_id = self.get_rev_reg_id()?;
+            let rev_id = self.get_rev_id()?;
+            is_cred_revoked(ledger, &rev_reg_id, &rev_id).await
+        } else {
+            Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Unable to check revocation status - this credential is not revokable",
+            ))
+        }
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        trace!(
+            "IssuerV2SM::find_message_to_handle >>> messages: {:?}, state: {:?}",
+            messages,
+            self.state
+        );
+
+        for (uid, message) in messages {
+            match self.state {
+                IssuerV2FullState::Initial(_) => {
+                    if let AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProposeCredential(_)) = &message {
+                        return Some((uid, message));
+                    }
+                }
+                IssuerV2FullState::OfferSent(_) => match &message {
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::RequestCredential(msg)) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProposeCredential(msg)) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(msg)) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(msg) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                IssuerV2FullState::CredentialSent(_) => match &message {
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::Ack(msg)) => {
+                        if matches_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::Notification(Notification::Ack(msg)) => {
+                        if matches_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(msg)) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(msg) => {
+                        if matches_opt_thread_id!(msg, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            };
+        }
+
+        None
+    }
+
+    pub fn get_state(&self) -> IssuerV2State {
+        match self.state {
+            IssuerV2FullState::Initial(_) => IssuerV2State::Initial,
+            IssuerV2FullState::ProposalReceived(_) => IssuerV2State::ProposalReceived,
+            IssuerV2FullState::OfferSet(_) => IssuerV2State::OfferSet,
+            IssuerV2FullState::OfferSent(_) => IssuerV2State::OfferSent,
+            IssuerV2FullState::RequestReceived(_) => IssuerV2State::RequestReceived,
+            IssuerV2FullState::CredentialSent(_) => IssuerV2State::CredentialSent,
+            IssuerV2FullState::Finished(ref status) => match status.status {
+                Status::Success => IssuerV2State::Finished,
+                _ => IssuerV2State::Failed,
+            },
+        }
+    }
+
+    pub fn get_proposal(&self) -> VcxResult<ProposeCredentialV2> {
+        match &self.state {
+            IssuerV2FullState::ProposalReceived(state) => Ok(state.credential_proposal.clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Proposal is only available in ProposalReceived state",
+            )),
+        }
+    }
+
+    pub fn build_credential_offer_msg(
+        self,
+        credential_offer: &str,
+        credential_preview: CredentialPreviewV2,
+        comment: Option<String>,
+        offer_info: &OfferInfo,
+    ) -> VcxResult<Self> {
+        let Self {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        let state = match state {
+            IssuerV2FullState::Initial(_) | IssuerV2FullState::OfferSet(_) | IssuerV2FullState::ProposalReceived(_) => {
+                let cred_offer_msg = build_credential_offer(&thread_id, credential_offer, credential_preview, comment);
+                IssuerV2FullState::OfferSet(OfferSetState::new(
+                    cred_offer_msg,
+                    &offer_info.credential_json,
+                    &offer_info.cred_def_id,
+                    offer_info.rev_reg_id.clone(),
+                    offer_info.tails_file.clone(),
+                ))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    format!("Can not set_offer in current state {}.", state),
+                ));
+            }
+        };
+        Ok(Self::step(source_id, thread_id, state))
+    }
+
+    pub fn get_credential_offer_msg(&self) -> VcxResult<OfferCredentialV2> {
+        match &self.state {
+            IssuerV2FullState::OfferSet(state) => Ok(state.offer.clone()),
+            IssuerV2FullState::OfferSent(state) => Ok(state.offer.clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Can not get_credential_offer in current state {}.", self.state),
+            )),
+        }
+    }
+
+    pub fn mark_credential_offer_msg_sent(self) -> VcxResult<Self> {
+        let Self {
+            state,
+            source_id,
+            thread_id,
+        } = self;
+        let state = match state {
+            IssuerV2FullState::OfferSet(state) => IssuerV2FullState::OfferSent(state.into()),
+            IssuerV2FullState::OfferSent(state) => IssuerV2FullState::OfferSent(state),
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    format!("Can not mark_as_offer_sent in current state {}.", state),
+                ))
+            }
+        };
+        Ok(Self::step(source_id, thread_id, state))
+    }
+
+    pub fn receive_proposal(self, proposal: ProposeCredentialV2) -> VcxResult<Self> {
+        let (state, thread_id) = match self.state {
+            IssuerV2FullState::Initial(_) => {
+                let thread_id = proposal.id.to_string();
+                let state = IssuerV2FullState::ProposalReceived(ProposalReceivedState::new(proposal));
+                (state, thread_id)
+            }
+            IssuerV2FullState::OfferSent(_) => {
+                verify_thread_id(
+                    &self.thread_id,
+                    &CredentialIssuanceV2Action::CredentialProposal(proposal.clone()),
+                )?;
+                let state = IssuerV2FullState::ProposalReceived(ProposalReceivedState::new(proposal));
+                (state, self.thread_id.clone())
+            }
+            s => {
+                warn!("Unable to receive credential proposal in state {}", s);
+                (s, self.thread_id.clone())
+            }
+        };
+        Ok(Self {
+            state,
+            thread_id,
+            ..self
+        })
+    }
+
+    pub async fn send_credential_offer(self, send_message: SendClosure) -> VcxResult<Self> {
+        Ok(match self.state {
+            IssuerV2FullState::OfferSet(ref state_data) => {
+                let cred_offer_msg = state_data.offer.clone().into();
+                send_message(cred_offer_msg).await?;
+                self.mark_credential_offer_msg_sent()?
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(AriesVcxErrorKind::NotReady, "Invalid action"));
+            }
+        })
+    }
+
+    pub fn receive_request(self, request: RequestCredentialV2) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &CredentialIssuanceV2Action::CredentialRequest(request.clone()),
+        )?;
+        let state = match self.state {
+            IssuerV2FullState::OfferSent(state_data) => {
+                IssuerV2FullState::RequestReceived((state_data, request).into())
+            }
+            s => {
+                warn!("Unable to receive credential request in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn send_credential(
+        self,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        send_message: SendClosure,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            IssuerV2FullState::RequestReceived(state_data) => {
+                match _create_credential(
+                    anoncreds,
+                    &state_data.request,
+                    &state_data.rev_reg_id,
+                    &state_data.tails_file,
+                    &state_data.offer,
+                    &state_data.cred_data,
+                    &self.thread_id,
+                )
+                .await
+                {
+                    Ok((credential_msg, cred_rev_id)) => {
+                        send_message(credential_msg.into()).await?;
+                        IssuerV2FullState::CredentialSent((state_data, cred_rev_id).into())
+                    }
+                    Err(err) => {
+                        let problem_report = build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        error!(
+                            "Failed to create credential, sending problem report {:?}",
+                            problem_report
+                        );
+                        send_message(problem_report.clone().into()).await?;
+                        IssuerV2FullState::Finished((state_data, problem_report).into())
+                    }
+                }
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(AriesVcxErrorKind::NotReady, "Invalid action"));
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_ack(self, ack: AckCredentialV2) -> VcxResult<Self> {
+        verify_thread_id(&self.thread_id, &CredentialIssuanceV2Action::CredentialAck(ack))?;
+        let state = match self.state {
+            IssuerV2FullState::CredentialSent(state_data) => IssuerV2FullState::Finished(state_data.into()),
+            s => {
+                warn!("Unable to receive credential ack in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &CredentialIssuanceV2Action::ProblemReport(problem_report.clone()),
+        )?;
+        let state = match self.state {
+            IssuerV2FullState::OfferSent(state_data) => {
+                IssuerV2FullState::Finished((state_data, problem_report).into())
+            }
+            IssuerV2FullState::CredentialSent(state_data) => {
+                IssuerV2FullState::Finished((state_data, problem_report).into())
+            }
+            s => {
+                warn!("Unable to receive problem report in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn handle_message(
+        self,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        cim: CredentialIssuanceV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<Self> {
+        trace!("IssuerV2SM::handle_message >>> cim: {:?}, state: {:?}", cim, self.state);
+        let issuer_sm = match cim {
+            CredentialIssuanceV2Action::CredentialProposal(proposal) => self.receive_proposal(proposal)?,
+            CredentialIssuanceV2Action::CredentialRequest(request) => self.receive_request(request)?,
+            CredentialIssuanceV2Action::CredentialSend() => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.send_credential(anoncreds, send_message).await?
+            }
+            CredentialIssuanceV2Action::CredentialAck(ack) => self.receive_ack(ack)?,
+            CredentialIssuanceV2Action::ProblemReport(problem_report) => self.receive_problem_report(problem_report)?,
+            _ => self,
+        };
+        Ok(issuer_sm)
+    }
+
+    pub fn credential_status(&self) -> u32 {
+        trace!("IssuerV2SM::credential_status >>>");
+
+        match self.state {
+            IssuerV2FullState::Finished(ref state) => state.status.code(),
+            _ => Status::Undefined.code(),
+        }
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        matches!(self.state, IssuerV2FullState::Finished(_))
+    }
+
+    pub fn thread_id(&self) -> VcxResult<String> {
+        Ok(self.thread_id.clone())
+    }
+}
+
+async fn _create_credential(
+    anoncreds: &Arc<dyn BaseAnonCreds>,
+    request: &RequestCredentialV2,
+    rev_reg_id: &Option<String>,
+    tails_file: &Option<String>,
+    offer: &OfferCredentialV2,
+    cred_data: &str,
+    thread_id: &str,
+) -> VcxResult<(IssueCredentialV2, Option<String>)> {
+    let offer = get_attach_by_format(
+        &offer.content.formats,
+        &offer.content.offers_attach,
+        OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0,
+    )?;
+
+    trace!("IssuerV2SM::_create_credential >>> request: {:?}, rev_reg_id: {:?}, tails_file: {:?}, offer: {}, cred_data: {}, thread_id: {}", request, rev_reg_id, tails_file, offer, cred_data, thread_id);
+    if !matches_opt_thread_id!(request, thread_id) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "Cannot handle credential request: thread id does not match",
+        ));
+    };
+
+    let request = get_attach_by_format(
+        &request.content.formats,
+        &request.content.requests_attach,
+        RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0,
+    )?;
+
+    let cred_data = encode_attributes(cred_data)?;
+    let (libindy_credential, cred_rev_id, _) = anoncreds
+        .issuer_create_credential(&offer, &request, &cred_data, rev_reg_id.clone(), tails_file.clone())
+        .await?;
+    let credential = build_credential_message(libindy_credential, thread_id);
+    Ok((credential, cred_rev_id))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+    use crate::protocols::issuance_v2::holder::state_machine::{HolderV2SM, HolderV2State};
+    use crate::protocols::issuance_v2::test_utils::*;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    fn _issuer_sm() -> IssuerV2SM {
+        IssuerV2SM::new("test_source_id")
+    }
+
+    fn _recording_send_message(sender: async_channel::Sender<AriesMessage>) -> Option<SendClosure> {
+        Some(Box::new(move |message: AriesMessage| {
+            Box::pin(async move {
+                sender.try_send(message).unwrap();
+                VcxResult::Ok(())
+            })
+        }))
+    }
+
+    impl IssuerV2SM {
+        fn to_offer_sent_state(self) -> IssuerV2SM {
+            let issuer_sm = self
+                .build_credential_offer_msg(&_libindy_cred_offer(), _credential_preview(), None, &_offer_info())
+                .unwrap()
+                .mark_credential_offer_msg_sent()
+                .unwrap();
+            assert_match!(IssuerV2FullState::OfferSent(_), issuer_sm.state);
+            issuer_sm
+        }
+
+        async fn to_request_received_state(self) -> IssuerV2SM {
+            let issuer_sm = self.to_offer_sent_state();
+            let thread_id = issuer_sm.thread_id().unwrap();
+            let issuer_sm = issuer_sm
+                .handle_message(
+                    &_anoncreds(),
+                    CredentialIssuanceV2Action::CredentialRequest(_credential_request(&thread_id)),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerV2FullState::RequestReceived(_), issuer_sm.state);
+            issuer_sm
+        }
+
+        async fn to_credential_sent_state(self) -> IssuerV2SM {
+            let issuer_sm = self
+                .to_request_received_state()
+                .await
+                .handle_message(
+                    &_anoncreds(),
+                    CredentialIssuanceV2Action::CredentialSend(),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerV2FullState::CredentialSent(_), issuer_sm.state);
+            issuer_sm
+        }
+
+        async fn to_finished_state(self) -> IssuerV2SM {
+            let issuer_sm = self.to_credential_sent_state().await;
+            let thread_id = issuer_sm.thread_id().unwrap();
+            let issuer_sm = issuer_sm
+                .handle_message(
+                    &_anoncreds(),
+                    CredentialIssuanceV2Action::CredentialAck(_credential_ack(&thread_id)),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(IssuerV2FullState::Finished(_), issuer_sm.state);
+            issuer_sm
+        }
+    }
+
+    #[test]
+    fn test_issuer_new() {
+        let issuer_sm = _issuer_sm();
+
+        assert_match!(IssuerV2FullState::Initial(_), issuer_sm.state);
+        assert_eq!("test_source_id", issuer_sm.get_source_id());
+    }
+
+    #[test]
+    fn test_issuer_from_proposal() {
+        let proposal = _credential_proposal();
+        let issuer_sm = IssuerV2SM::from_proposal("test_source_id", &proposal);
+
+        assert_match!(IssuerV2FullState::ProposalReceived(_), issuer_sm.state);
+        assert_eq!(proposal.id, issuer_sm.thread_id().unwrap());
+        assert_eq!(proposal, issuer_sm.get_proposal().unwrap());
+    }
+
+    #[test]
+    fn test_issuer_build_credential_offer_msg() {
+        let issuer_sm = _issuer_sm()
+            .build_credential_offer_msg(
+                &_libindy_cred_offer(),
+                _credential_preview(),
+                Some("comment".to_owned()),
+                &_offer_info(),
+            )
+            .unwrap();
+        assert_match!(IssuerV2FullState::OfferSet(_), issuer_sm.state);
+
+        let offer = issuer_sm.get_credential_offer_msg().unwrap();
+        assert_eq!(issuer_sm.thread_id().unwrap(), offer.decorators.thread.unwrap().thid);
+        assert_eq!(Some("comment".to_owned()), offer.content.comment);
+        assert_eq!(_credential_preview(), offer.content.credential_preview);
+        assert_eq!(
+            _libindy_cred_offer(),
+            get_attach_by_format(
+                &offer.content.formats,
+                &offer.content.offers_attach,
+                OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0,
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_issuer_build_credential_message() {
+        let msg = build_credential_message("{}".to_owned(), "test_thread_id");
+
+        assert_eq!("test_thread_id", msg.decorators.thread.thid);
+        assert!(msg.decorators.please_ack.is_some());
+        assert!(msg.decorators.timing.unwrap().out_time.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_send_credential_offer() {
+        let (sender, receiver) = async_channel::unbounded();
+
+        let issuer_sm = _issuer_sm()
+            .build_credential_offer_msg(&_libindy_cred_offer(), _credential_preview(), None, &_offer_info())
+            .unwrap()
+            .send_credential_offer(_recording_send_message(sender).unwrap())
+            .await
+            .unwrap();
+
+        assert_match!(IssuerV2FullState::OfferSent(_), issuer_sm.state);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::OfferCredential(_)),
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_proposal_from_offer_sent_state() {
+        let issuer_sm = _issuer_sm().to_offer_sent_state();
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let mut proposal = _credential_proposal();
+        proposal.decorators.thread = Some(Thread::new(thread_id.clone()));
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialProposal(proposal),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(IssuerV2FullState::ProposalReceived(_), issuer_sm.state);
+        assert_eq!(thread_id, issuer_sm.thread_id().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_request_from_offer_sent_state() {
+        let issuer_sm = _issuer_sm().to_request_received_state().await;
+
+        assert_eq!(IssuerV2State::RequestReceived, issuer_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_request_with_mismatched_thread_id() {
+        let err = _issuer_sm()
+            .to_offer_sent_state()
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequest(_credential_request("other_thread_id")),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_send_credential_from_request_received_state() {
+        let (sender, receiver) = async_channel::unbounded();
+
+        let issuer_sm = _issuer_sm()
+            .to_request_received_state()
+            .await
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialSend(),
+                _recording_send_message(sender),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(IssuerV2State::CredentialSent, issuer_sm.get_state());
+        match receiver.try_recv().unwrap() {
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::IssueCredential(credential)) => {
+                assert_eq!(issuer_sm.thread_id().unwrap(), credential.decorators.thread.thid);
+            }
+            message => panic!("Expected a credential, sent: {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issuer_send_credential_for_invalid_request() {
+        let issuer_sm = _issuer_sm().to_offer_sent_state();
+        let thread_id = issuer_sm.thread_id().unwrap();
+        let request = _credential_request_with_format(
+            &thread_id,
+            RequestCredentialAttachmentFormatType::AriesLdProofVcDetail1_0,
+        );
+
+        let (sender, receiver) = async_channel::unbounded();
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequest(request),
+                _send_message(),
+            )
+            .await
+            .unwrap()
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialSend(),
+                _recording_send_message(sender),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(IssuerV2State::Failed, issuer_sm.get_state());
+        assert_eq!(
+            Status::Failed(_problem_report("thread_id")).code(),
+            issuer_sm.credential_status()
+        );
+        assert_match!(AriesMessage::ReportProblem(_), receiver.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_ack_from_credential_sent_state() {
+        let issuer_sm = _issuer_sm().to_finished_state().await;
+
+        assert_eq!(IssuerV2State::Finished, issuer_sm.get_state());
+        assert_eq!(Status::Success.code(), issuer_sm.credential_status());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_problem_report_from_offer_sent_state() {
+        let issuer_sm = _issuer_sm().to_offer_sent_state();
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::ProblemReport(_problem_report(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(IssuerV2State::Failed, issuer_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_problem_report_from_credential_sent_state() {
+        let issuer_sm = _issuer_sm().to_credential_sent_state().await;
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::ProblemReport(_problem_report(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(IssuerV2State::Failed, issuer_sm.get_state());
+        assert_eq!(
+            Status::Failed(_problem_report("thread_id")).code(),
+            issuer_sm.credential_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuer_receive_problem_report_with_mismatched_thread_id() {
+        let err = _issuer_sm()
+            .to_credential_sent_state()
+            .await
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::ProblemReport(_problem_report("other_thread_id")),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_ignores_other_messages_from_offer_sent_state() {
+        let issuer_sm = _issuer_sm().to_offer_sent_state();
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialAck(_credential_ack(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(IssuerV2FullState::OfferSent(_), issuer_sm.state);
+
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::Credential(_credential(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(IssuerV2FullState::OfferSent(_), issuer_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_issuer_ignores_other_messages_from_credential_sent_state() {
+        let issuer_sm = _issuer_sm().to_credential_sent_state().await;
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let issuer_sm = issuer_sm
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequest(_credential_request(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(IssuerV2FullState::CredentialSent(_), issuer_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_issuer_ignores_messages_from_finished_state() {
+        let mut issuer_sm = _issuer_sm().to_finished_state().await;
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        for action in [
+            CredentialIssuanceV2Action::CredentialProposal(_credential_proposal()),
+            CredentialIssuanceV2Action::CredentialRequest(_credential_request(&thread_id)),
+            CredentialIssuanceV2Action::CredentialAck(_credential_ack(&thread_id)),
+            CredentialIssuanceV2Action::ProblemReport(_problem_report(&thread_id)),
+        ] {
+            issuer_sm = issuer_sm
+                .handle_message(&_anoncreds(), action, _send_message())
+                .await
+                .unwrap();
+            assert_eq!(IssuerV2State::Finished, issuer_sm.get_state());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issuer_actions_in_wrong_state() {
+        let err = _issuer_sm()
+            .send_credential_offer(_send_message().unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(AriesVcxErrorKind::NotReady, err.kind());
+
+        let err = _issuer_sm()
+            .to_offer_sent_state()
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialSend(),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(AriesVcxErrorKind::NotReady, err.kind());
+
+        let err = _issuer_sm().mark_credential_offer_msg_sent().unwrap_err();
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+
+        let err = _issuer_sm().get_credential_offer_msg().unwrap_err();
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+
+        let err = _issuer_sm()
+            .to_credential_sent_state()
+            .await
+            .build_credential_offer_msg(&_libindy_cred_offer(), _credential_preview(), None, &_offer_info())
+            .unwrap_err();
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_find_message_to_handle_from_initial_state() {
+        let issuer_sm = _issuer_sm();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_request("thread_id").into()),
+            ("key_2".to_owned(), _credential_proposal().into()),
+        ]);
+        let (uid, message) = issuer_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProposeCredential(_)),
+            message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuer_find_message_to_handle_from_offer_sent_state() {
+        let issuer_sm = _issuer_sm().to_offer_sent_state();
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_ack(&thread_id).into()),
+            ("key_2".to_owned(), _credential_request(&thread_id).into()),
+        ]);
+        let (uid, message) = issuer_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::RequestCredential(_)),
+            message
+        );
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_request("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report("other_thread_id").into()),
+        ]);
+        assert!(issuer_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_find_message_to_handle_from_credential_sent_state() {
+        let issuer_sm = _issuer_sm().to_credential_sent_state().await;
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_request(&thread_id).into()),
+            ("key_2".to_owned(), _credential_ack(&thread_id).into()),
+        ]);
+        let (uid, message) = issuer_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::Ack(_)),
+            message
+        );
+
+        let messages = HashMap::from([("key_1".to_owned(), _problem_report(&thread_id).into())]);
+        let (uid, _) = issuer_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_1", uid);
+
+        let messages = HashMap::from([("key_1".to_owned(), _credential_ack("other_thread_id").into())]);
+        assert!(issuer_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_find_message_to_handle_from_finished_state() {
+        let issuer_sm = _issuer_sm().to_finished_state().await;
+        let thread_id = issuer_sm.thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _credential_proposal().into()),
+            ("key_2".to_owned(), _credential_request(&thread_id).into()),
+            ("key_3".to_owned(), _credential_ack(&thread_id).into()),
+            ("key_4".to_owned(), _problem_report(&thread_id).into()),
+        ]);
+        assert!(issuer_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_get_state() {
+        assert_eq!(IssuerV2State::Initial, _issuer_sm().get_state());
+        assert_eq!(IssuerV2State::OfferSent, _issuer_sm().to_offer_sent_state().get_state());
+        assert_eq!(
+            IssuerV2State::RequestReceived,
+            _issuer_sm().to_request_received_state().await.get_state()
+        );
+        assert_eq!(
+            IssuerV2State::CredentialSent,
+            _issuer_sm().to_credential_sent_state().await.get_state()
+        );
+        assert_eq!(
+            IssuerV2State::Finished,
+            _issuer_sm().to_finished_state().await.get_state()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuer_and_holder_happy_path() {
+        let ledger: Arc<dyn AnoncredsLedgerRead> = Arc::new(MockLedger);
+        let (sender, receiver) = async_channel::unbounded();
+
+        let issuer_sm = _issuer_sm()
+            .build_credential_offer_msg(&_libindy_cred_offer(), _credential_preview(), None, &_offer_info())
+            .unwrap()
+            .send_credential_offer(_recording_send_message(sender.clone()).unwrap())
+            .await
+            .unwrap();
+        let offer = match receiver.try_recv().unwrap() {
+            AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::OfferCredential(offer)) => offer,
+            message => panic!("Expected a credential offer, sent: {:?}", message),
+        };
+
+        let holder_sm = HolderV2SM::from_offer(offer, "test_holder".to_owned())
+            .handle_message(
+                &ledger,
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialRequestSend(_my_pw_did()),
+                _recording_send_message(sender.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(HolderV2State::RequestSent, holder_sm.get_state());
+
+        let issuer_sm = issuer_sm
+            .handle_message(&_anoncreds(), receiver.try_recv().unwrap().into(), None)
+            .await
+            .unwrap()
+            .handle_message(
+                &_anoncreds(),
+                CredentialIssuanceV2Action::CredentialSend(),
+                _recording_send_message(sender.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(IssuerV2State::CredentialSent, issuer_sm.get_state());
+
+        let holder_sm = holder_sm
+            .handle_message(
+                &ledger,
+                &_anoncreds(),
+                receiver.try_recv().unwrap().into(),
+                _recording_send_message(sender),
+            )
+            .await
+            .unwrap();
+        assert_eq!(HolderV2State::Finished, holder_sm.get_state());
+
+        let issuer_sm = issuer_sm
+            .handle_message(&_anoncreds(), receiver.try_recv().unwrap().into(), None)
+            .await
+            .unwrap();
+        assert_eq!(IssuerV2State::Finished, issuer_sm.get_state());
+        assert_eq!(issuer_sm.thread_id().unwrap(), holder_sm.get_thread_id().unwrap());
+    }
+}
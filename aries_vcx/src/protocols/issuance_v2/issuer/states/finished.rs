@@ -0,0 +1,7 @@
+use crate::{handlers::util::Status, protocols::issuance::issuer::state_machine::RevocationInfoV1};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FinishedState {
+    pub revocation_info_v1: Option<RevocationInfoV1>,
+    pub status: Status,
+}
@@ -0,0 +1,2 @@
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct InitialIssuerState {}
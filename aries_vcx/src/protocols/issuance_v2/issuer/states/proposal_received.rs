@@ -0,0 +1,12 @@
+use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::ProposeCredentialV2;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalReceivedState {
+    pub credential_proposal: ProposeCredentialV2,
+}
+
+impl ProposalReceivedState {
+    pub fn new(credential_proposal: ProposeCredentialV2) -> Self {
+        Self { credential_proposal }
+    }
+}
@@ -0,0 +1,44 @@
+use messages::msg_fields::protocols::cred_issuance::v2::offer_credential::OfferCredentialV2;
+use messages::msg_fields::protocols::cred_issuance::v2::request_credential::RequestCredentialV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::handlers::util::Status;
+use crate::protocols::issuance::issuer::state_machine::RevocationInfoV1;
+use crate::protocols::issuance_v2::issuer::states::credential_sent::CredentialSentState;
+use crate::protocols::issuance_v2::issuer::states::finished::FinishedState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestReceivedState {
+    pub offer: OfferCredentialV2,
+    pub cred_data: String,
+    pub rev_reg_id: Option<String>,
+    pub tails_file: Option<String>,
+    pub request: RequestCredentialV2,
+}
+
+impl From<(RequestReceivedState, Option<String>)> for CredentialSentState {
+    fn from((state, cred_rev_id): (RequestReceivedState, Option<String>)) -> Self {
+        trace!("SM is now in CredentialSent state");
+        CredentialSentState {
+            revocation_info_v1: Some(RevocationInfoV1 {
+                cred_rev_id,
+                rev_reg_id: state.rev_reg_id,
+                tails_file: state.tails_file,
+            }),
+        }
+    }
+}
+
+impl From<(RequestReceivedState, ProblemReport)> for FinishedState {
+    fn from((state, err): (RequestReceivedState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedState {
+            revocation_info_v1: Some(RevocationInfoV1 {
+                cred_rev_id: None,
+                rev_reg_id: state.rev_reg_id,
+                tails_file: state.tails_file,
+            }),
+            status: Status::Failed(err),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::errors::error::prelude::*;
+use crate::global::settings;
+use crate::protocols::issuance_v2::actions::CredentialIssuanceV2Action;
+
+pub mod actions;
+pub mod formats;
+pub mod holder;
+pub mod issuer;
+
+pub fn verify_thread_id(thread_id: &str, message: &CredentialIssuanceV2Action) -> VcxResult<()> {
+    if !settings::indy_mocks_enabled() && !message.thread_id_matches(thread_id) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Cannot handle message {:?}: thread id does not match, expected {:?}",
+                message, thread_id
+            ),
+        ));
+    };
+    Ok(())
+}
+
+pub mod test_utils {
+    use messages::decorators::please_ack::PleaseAck;
+    use messages::decorators::thread::Thread;
+    use messages::msg_fields::protocols::cred_issuance::v2::ack::{AckCredentialV2, AckCredentialV2Content};
+    use messages::msg_fields::protocols::cred_issuance::v2::issue_credential::{
+        IssueCredentialAttachmentFormatType, IssueCredentialV2, IssueCredentialV2Content, IssueCredentialV2Decorators,
+    };
+    use messages::msg_fields::protocols::cred_issuance::v2::offer_credential::{
+        OfferCredentialAttachmentFormatType, OfferCredentialV2, OfferCredentialV2Content, OfferCredentialV2Decorators,
+    };
+    use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::{
+        ProposeCredentialAttachmentFormatType, ProposeCredentialV2, ProposeCredentialV2Content,
+        ProposeCredentialV2Decorators,
+    };
+    use messages::msg_fields::protocols::cred_issuance::v2::request_credential::{
+        RequestCredentialAttachmentFormatType, RequestCredentialV2, RequestCredentialV2Content,
+        RequestCredentialV2Decorators,
+    };
+    use messages::msg_fields::protocols::cred_issuance::v2::CredentialPreviewV2;
+    use messages::msg_fields::protocols::cred_issuance::CredentialAttr;
+    use messages::msg_fields::protocols::notification::ack::{AckDecorators, AckStatus};
+    use messages::msg_fields::protocols::report_problem::ProblemReport;
+    use messages::AriesMessage;
+    use uuid::Uuid;
+
+    use crate::errors::error::VcxResult;
+    use crate::handlers::util::OfferInfo;
+    use crate::protocols::common::build_problem_report_msg;
+    use crate::protocols::issuance_v2::formats::make_format_attach;
+    use crate::protocols::SendClosure;
+    use crate::utils::constants::{CRED_DEF_ID, REV_REG_ID, SCHEMA_ID};
+
+    pub fn _send_message() -> Option<SendClosure> {
+        Some(Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) })))
+    }
+
+    pub fn _my_pw_did() -> String {
+        String::from("FhrSrYtQcw3p9xwf7NYemf")
+    }
+
+    pub fn _credential_data() -> String {
+        json!({"name": "Alice", "age": "25"}).to_string()
+    }
+
+    pub fn _credential_preview() -> CredentialPreviewV2 {
+        CredentialPreviewV2::new(vec![
+            CredentialAttr::new("name".to_owned(), "Alice".to_owned()),
+            CredentialAttr::new("age".to_owned(), "25".to_owned()),
+        ])
+    }
+
+    pub fn _libindy_cred_offer() -> String {
+        json!({
+            "schema_id": SCHEMA_ID,
+            "cred_def_id": CRED_DEF_ID,
+            "key_correctness_proof": {},
+            "nonce": "1234567890"
+        })
+        .to_string()
+    }
+
+    pub fn _libindy_credential() -> String {
+        json!({
+            "schema_id": SCHEMA_ID,
+            "cred_def_id": CRED_DEF_ID,
+            "rev_reg_id": REV_REG_ID,
+            "values": {
+                "name": {"raw": "Alice", "encoded": "1139481716457488690172217916278103335"},
+                "age": {"raw": "25", "encoded": "25"}
+            },
+            "signature": {},
+            "signature_correctness_proof": {}
+        })
+        .to_string()
+    }
+
+    pub fn _offer_info() -> OfferInfo {
+        OfferInfo::new(_credential_data(), CRED_DEF_ID.to_owned(), None, None)
+    }
+
+    pub fn _credential_proposal() -> ProposeCredentialV2 {
+        let (format, attach) = make_format_attach(
+            &json!({ "cred_def_id": CRED_DEF_ID }).to_string(),
+            ProposeCredentialAttachmentFormatType::HyperledgerIndyCredentialFilter2_0,
+        );
+        let mut content = ProposeCredentialV2Content::new(vec![format], vec![attach]);
+        content.credential_preview = Some(_credential_preview());
+
+        ProposeCredentialV2::with_decorators(
+            Uuid::new_v4().to_string(),
+            content,
+            ProposeCredentialV2Decorators::default(),
+        )
+    }
+
+    pub fn _credential_offer(thread_id: &str) -> OfferCredentialV2 {
+        _credential_offer_with_attach(thread_id, &_libindy_cred_offer())
+    }
+
+    pub fn _credential_offer_with_attach(thread_id: &str, offer: &str) -> OfferCredentialV2 {
+        let (format, attach) = make_format_attach(
+            offer,
+            OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0,
+        );
+        let content = OfferCredentialV2Content::new(_credential_preview(), vec![format], vec![attach]);
+
+        let mut decorators = OfferCredentialV2Decorators::default();
+        decorators.thread = Some(Thread::new(thread_id.to_owned()));
+
+        OfferCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _credential_request(thread_id: &str) -> RequestCredentialV2 {
+        _credential_request_with_format(
+            thread_id,
+            RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0,
+        )
+    }
+
+    pub fn _credential_request_with_format(
+        thread_id: &str,
+        format: RequestCredentialAttachmentFormatType,
+    ) -> RequestCredentialV2 {
+        let (format, attach) = make_format_attach(&json!({ "cred_def_id": CRED_DEF_ID }).to_string(), format);
+        let content = RequestCredentialV2Content::new(vec![format], vec![attach]);
+
+        let mut decorators = RequestCredentialV2Decorators::default();
+        decorators.thread = Some(Thread::new(thread_id.to_owned()));
+
+        RequestCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _credential(thread_id: &str) -> IssueCredentialV2 {
+        _credential_with_format(
+            thread_id,
+            IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0,
+        )
+    }
+
+    pub fn _credential_with_format(thread_id: &str, format: IssueCredentialAttachmentFormatType) -> IssueCredentialV2 {
+        let (format, attach) = make_format_attach(&_libindy_credential(), format);
+        let content = IssueCredentialV2Content::new(vec![format], vec![attach]);
+
+        let mut decorators = IssueCredentialV2Decorators::new(Thread::new(thread_id.to_owned()));
+        decorators.please_ack = Some(PleaseAck::new(vec![]));
+
+        IssueCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _credential_ack(thread_id: &str) -> AckCredentialV2 {
+        let content = AckCredentialV2Content::new(AckStatus::Ok);
+        let decorators = AckDecorators::new(Thread::new(thread_id.to_owned()));
+
+        AckCredentialV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _problem_report(thread_id: &str) -> ProblemReport {
+        build_problem_report_msg(Some("test problem".to_owned()), thread_id)
+    }
+}
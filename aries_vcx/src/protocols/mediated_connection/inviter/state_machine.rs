@@ -18,6 +18,7 @@ use messages::msg_fields::protocols::connection::response::{Response, ResponseCo
 use messages::msg_fields::protocols::connection::{Connection, ConnectionData};
 use messages::msg_fields::protocols::discover_features::disclose::Disclose;
 use messages::msg_fields::protocols::discover_features::query::QueryContent;
+use messages::msg_fields::protocols::discover_features::v2::disclosures::Disclosures;
 use messages::msg_fields::protocols::discover_features::ProtocolDescriptor;
 use messages::msg_fields::protocols::trust_ping::TrustPing;
 use messages::AriesMessage;
@@ -333,6 +334,17 @@ impl SmConnectionInviter {
         Ok(Self { state, ..self })
     }
 
+    pub fn handle_disclosures(self, disclosures: Disclosures) -> VcxResult<Self> {
+        let protocols = disclosures.content.protocols();
+        let state = match self.state {
+            InviterFullState::Completed(state) if !protocols.is_empty() => {
+                InviterFullState::Completed((state, protocols).into())
+            }
+            _ => self.state,
+        };
+        Ok(Self { state, ..self })
+    }
+
     pub async fn handle_confirmation_message(self, msg: &AriesMessage) -> VcxResult<Self> {
         verify_thread_id(&self.get_thread_id(), msg)?;
         match self.state {
@@ -6,10 +6,15 @@ use crate::errors::error::VcxResult;
 
 pub mod common;
 pub mod connection;
+pub mod coordinate_mediation;
+pub mod did_exchange;
 pub mod issuance;
+pub mod issuance_v2;
 pub mod mediated_connection;
 pub mod oob;
+pub mod pickup;
 pub mod proof_presentation;
+pub mod proof_presentation_v2;
 pub mod revocation_notification;
 pub mod trustping;
 
@@ -0,0 +1,95 @@
+use messages::{
+    decorators::transport::{ReturnRoute, Transport},
+    msg_fields::protocols::pickup::{
+        delivery_request::{DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators},
+        messages_received::{MessagesReceived, MessagesReceivedContent, MessagesReceivedDecorators},
+        status_request::{StatusRequest, StatusRequestContent, StatusRequestDecorators},
+    },
+};
+use uuid::Uuid;
+
+use crate::protocols::common::make_timing;
+
+// Pickup requests are answered on the same connection (e.g. the HTTP response),
+// so all of them ask the mediator for a return route.
+
+pub fn build_status_request(recipient_key: Option<String>) -> StatusRequest {
+    let mut content = StatusRequestContent::default();
+    content.recipient_key = recipient_key;
+
+    let mut decorators = StatusRequestDecorators::default();
+    decorators.transport = Some(Transport::new(ReturnRoute::All));
+    decorators.timing = Some(make_timing());
+
+    StatusRequest::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+pub fn build_delivery_request(limit: u32, recipient_key: Option<String>) -> DeliveryRequest {
+    let mut content = DeliveryRequestContent::new(limit);
+    content.recipient_key = recipient_key;
+
+    let mut decorators = DeliveryRequestDecorators::default();
+    decorators.transport = Some(Transport::new(ReturnRoute::All));
+    decorators.timing = Some(make_timing());
+
+    DeliveryRequest::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+pub fn build_messages_received(message_id_list: Vec<String>) -> MessagesReceived {
+    let content = MessagesReceivedContent::new(message_id_list);
+
+    let mut decorators = MessagesReceivedDecorators::default();
+    decorators.transport = Some(Transport::new(ReturnRoute::All));
+    decorators.timing = Some(make_timing());
+
+    MessagesReceived::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+
+    fn assert_return_route(transport: Option<Transport>) {
+        assert_eq!(ReturnRoute::All, transport.unwrap().return_route);
+    }
+
+    #[test]
+    fn test_build_status_request() {
+        let request = build_status_request(Some("recipient_key".to_owned()));
+
+        assert_eq!(Some("recipient_key".to_owned()), request.content.recipient_key);
+        assert!(request.decorators.timing.unwrap().out_time.is_some());
+        assert_return_route(request.decorators.transport);
+    }
+
+    #[test]
+    fn test_build_status_request_without_recipient_key() {
+        let request = build_status_request(None);
+
+        assert!(request.content.recipient_key.is_none());
+        assert_return_route(request.decorators.transport);
+    }
+
+    #[test]
+    fn test_build_delivery_request() {
+        let request = build_delivery_request(10, Some("recipient_key".to_owned()));
+
+        assert_eq!(10, request.content.limit);
+        assert_eq!(Some("recipient_key".to_owned()), request.content.recipient_key);
+        assert!(request.decorators.timing.unwrap().out_time.is_some());
+        assert_return_route(request.decorators.transport);
+    }
+
+    #[test]
+    fn test_build_messages_received() {
+        let ack = build_messages_received(vec!["msg_1".to_owned(), "msg_2".to_owned()]);
+
+        assert_eq!(
+            vec!["msg_1".to_owned(), "msg_2".to_owned()],
+            ack.content.message_id_list
+        );
+        assert!(ack.decorators.timing.unwrap().out_time.is_some());
+        assert_return_route(ack.decorators.transport);
+    }
+}
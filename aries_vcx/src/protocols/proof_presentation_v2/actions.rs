@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use messages::msg_fields::protocols::notification::Notification;
+use messages::msg_fields::protocols::present_proof::v2::ack::{AckPresentationV2, AckPresentationV2Content};
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::PresentProofV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+use messages::msg_parts::MsgParts;
+use messages::AriesMessage;
+
+use crate::handlers::proof_presentation::types::SelectedCredentials;
+use crate::handlers::util::{matches_opt_thread_id, matches_thread_id};
+
+#[derive(Debug, Clone)]
+pub enum PresentProofV2Action {
+    PresentationProposalSend(ProposePresentationV2),
+    PresentationProposal(ProposePresentationV2),
+    PresentationRequest(RequestPresentationV2),
+    PresentationRequestReject(String),
+    PreparePresentation((SelectedCredentials, HashMap<String, String>)),
+    PresentationSend(),
+    Presentation(PresentationV2),
+    PresentationAck(AckPresentationV2),
+    ProblemReport(ProblemReport),
+    Unknown,
+}
+
+impl PresentProofV2Action {
+    pub fn thread_id_matches(&self, thread_id: &str) -> bool {
+        match self {
+            Self::PresentationProposal(msg) => matches_opt_thread_id!(msg, thread_id),
+            Self::PresentationRequest(msg) => matches_opt_thread_id!(msg, thread_id),
+            Self::Presentation(msg) => matches_thread_id!(msg, thread_id),
+            Self::PresentationAck(msg) => matches_thread_id!(msg, thread_id),
+            Self::ProblemReport(msg) => matches_opt_thread_id!(msg, thread_id),
+            _ => true,
+        }
+    }
+}
+
+impl From<AriesMessage> for PresentProofV2Action {
+    fn from(msg: AriesMessage) -> Self {
+        match msg {
+            AriesMessage::PresentProofV2(PresentProofV2::ProposePresentation(proposal)) => {
+                PresentProofV2Action::PresentationProposal(proposal)
+            }
+            AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(request)) => {
+                PresentProofV2Action::PresentationRequest(request)
+            }
+            AriesMessage::PresentProofV2(PresentProofV2::Presentation(presentation)) => {
+                PresentProofV2Action::Presentation(presentation)
+            }
+            AriesMessage::PresentProofV2(PresentProofV2::Ack(ack)) => PresentProofV2Action::PresentationAck(ack),
+            AriesMessage::Notification(Notification::Ack(ack)) => {
+                let MsgParts {
+                    id,
+                    content,
+                    decorators,
+                } = ack;
+                let ack = AckPresentationV2::with_decorators(id, AckPresentationV2Content(content), decorators);
+                PresentProofV2Action::PresentationAck(ack)
+            }
+            AriesMessage::ReportProblem(report) => PresentProofV2Action::ProblemReport(report),
+            AriesMessage::Notification(Notification::ProblemReport(report)) => {
+                let MsgParts {
+                    id,
+                    content,
+                    decorators,
+                } = report;
+                let report = ProblemReport::with_decorators(id, content.0, decorators);
+                PresentProofV2Action::ProblemReport(report)
+            }
+            AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(report)) => {
+                let MsgParts {
+                    id,
+                    content,
+                    decorators,
+                } = report;
+                let report = ProblemReport::with_decorators(id, content.0, decorators);
+                PresentProofV2Action::ProblemReport(report)
+            }
+            _ => PresentProofV2Action::Unknown,
+        }
+    }
+}
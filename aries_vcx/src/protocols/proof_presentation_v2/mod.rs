@@ -0,0 +1,116 @@
+use crate::errors::error::prelude::*;
+use crate::global::settings;
+use crate::protocols::proof_presentation_v2::actions::PresentProofV2Action;
+
+pub mod actions;
+pub mod prover;
+pub mod verifier;
+
+pub fn verify_thread_id(thread_id: &str, message: &PresentProofV2Action) -> VcxResult<()> {
+    if !settings::indy_mocks_enabled() && !message.thread_id_matches(thread_id) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Cannot handle message {:?}: thread id does not match, expected {:?}",
+                message, thread_id
+            ),
+        ));
+    };
+    Ok(())
+}
+
+pub mod test_utils {
+    use messages::decorators::thread::Thread;
+    use messages::msg_fields::protocols::notification::ack::{AckDecorators, AckStatus};
+    use messages::msg_fields::protocols::present_proof::v2::ack::{AckPresentationV2, AckPresentationV2Content};
+    use messages::msg_fields::protocols::present_proof::v2::present::{
+        PresentationAttachmentFormatType, PresentationV2, PresentationV2Content, PresentationV2Decorators,
+    };
+    use messages::msg_fields::protocols::present_proof::v2::propose::{
+        ProposePresentationAttachmentFormatType, ProposePresentationV2, ProposePresentationV2Content,
+        ProposePresentationV2Decorators,
+    };
+    use messages::msg_fields::protocols::present_proof::v2::request::{
+        RequestPresentationAttachmentFormatType, RequestPresentationV2, RequestPresentationV2Content,
+        RequestPresentationV2Decorators,
+    };
+    use messages::msg_fields::protocols::report_problem::ProblemReport;
+    use messages::AriesMessage;
+    use uuid::Uuid;
+
+    use crate::common::proofs::proof_request::test_utils::_presentation_request_data;
+    use crate::errors::error::VcxResult;
+    use crate::protocols::common::build_problem_report_msg;
+    use crate::protocols::issuance_v2::formats::make_format_attach;
+    use crate::protocols::SendClosure;
+    use crate::utils::constants::PROOF_JSON;
+
+    pub fn _send_message() -> Option<SendClosure> {
+        Some(Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) })))
+    }
+
+    pub fn _reason() -> String {
+        String::from("Unqualified")
+    }
+
+    pub fn _presentation_proposal() -> ProposePresentationV2 {
+        let (format, attach) = make_format_attach(
+            &json!(_presentation_request_data()).to_string(),
+            ProposePresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        );
+        let mut content = ProposePresentationV2Content::new(vec![format], vec![attach]);
+        content.comment = Some("proposal".to_owned());
+
+        ProposePresentationV2::with_decorators(
+            Uuid::new_v4().to_string(),
+            content,
+            ProposePresentationV2Decorators::default(),
+        )
+    }
+
+    pub fn _presentation_proposal_in_thread(thread_id: &str) -> ProposePresentationV2 {
+        let mut proposal = _presentation_proposal();
+        proposal.decorators.thread = Some(Thread::new(thread_id.to_owned()));
+        proposal
+    }
+
+    pub fn _presentation_request(thread_id: &str) -> RequestPresentationV2 {
+        _presentation_request_with_attach(thread_id, &json!(_presentation_request_data()).to_string())
+    }
+
+    pub fn _presentation_request_with_attach(thread_id: &str, request: &str) -> RequestPresentationV2 {
+        let (format, attach) = make_format_attach(
+            request,
+            RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        );
+        let content = RequestPresentationV2Content::new(vec![format], vec![attach]);
+
+        let mut decorators = RequestPresentationV2Decorators::default();
+        decorators.thread = Some(Thread::new(thread_id.to_owned()));
+
+        RequestPresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _presentation(thread_id: &str) -> PresentationV2 {
+        _presentation_with_format(thread_id, PresentationAttachmentFormatType::HyperledgerIndyProof2_0)
+    }
+
+    pub fn _presentation_with_format(thread_id: &str, format: PresentationAttachmentFormatType) -> PresentationV2 {
+        let (format, attach) = make_format_attach(PROOF_JSON, format);
+        let content = PresentationV2Content::new(vec![format], vec![attach]);
+        let decorators = PresentationV2Decorators::new(Thread::new(thread_id.to_owned()));
+
+        PresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _presentation_ack(thread_id: &str) -> AckPresentationV2 {
+        let content = AckPresentationV2Content::new(AckStatus::Ok);
+        let decorators = AckDecorators::new(Thread::new(thread_id.to_owned()));
+
+        AckPresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+    }
+
+    pub fn _problem_report(thread_id: &str) -> ProblemReport {
+        build_problem_report_msg(Some("test problem".to_owned()), thread_id)
+    }
+}
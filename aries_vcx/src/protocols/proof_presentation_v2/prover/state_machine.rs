@@ -0,0 +1,975 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use chrono::Utc;
+use messages::decorators::thread::Thread;
+use messages::decorators::timing::Timing;
+use messages::msg_fields::protocols::notification::Notification;
+use messages::msg_fields::protocols::present_proof::v2::ack::AckPresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::present::{
+    PresentationAttachmentFormatType, PresentationV2, PresentationV2Content, PresentationV2Decorators,
+};
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::{
+    RequestPresentationAttachmentFormatType, RequestPresentationV2,
+};
+use messages::msg_fields::protocols::present_proof::v2::PresentProofV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+use messages::AriesMessage;
+use uuid::Uuid;
+
+use crate::errors::error::prelude::*;
+use crate::handlers::proof_presentation::types::SelectedCredentials;
+use crate::handlers::util::{matches_opt_thread_id, matches_thread_id, Status};
+use crate::protocols::common::build_problem_report_msg;
+use crate::protocols::issuance_v2::formats::{get_attach_by_format, make_format_attach};
+use crate::protocols::proof_presentation_v2::actions::PresentProofV2Action;
+use crate::protocols::proof_presentation_v2::prover::states::finished::FinishedProverState;
+use crate::protocols::proof_presentation_v2::prover::states::initial::InitialProverState;
+use crate::protocols::proof_presentation_v2::prover::states::presentation_preparation_failed::PresentationPreparationFailedState;
+use crate::protocols::proof_presentation_v2::prover::states::presentation_prepared::PresentationPreparedState;
+use crate::protocols::proof_presentation_v2::prover::states::presentation_sent::PresentationSentState;
+use crate::protocols::proof_presentation_v2::prover::states::proposal_sent::ProposalSentState;
+use crate::protocols::proof_presentation_v2::prover::states::request_received::RequestReceivedState;
+use crate::protocols::proof_presentation_v2::verify_thread_id;
+use crate::protocols::SendClosure;
+
+/// A state machine that tracks the evolution of states for a Prover during
+/// the Present Proof 2.0 protocol.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProverV2SM {
+    source_id: String,
+    thread_id: String,
+    state: ProverV2FullState,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProverV2State {
+    Initial,
+    ProposalSent,
+    RequestReceived,
+    PresentationPrepared,
+    PresentationPreparationFailed,
+    PresentationSent,
+    Finished,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ProverV2FullState {
+    Initial(InitialProverState),
+    ProposalSent(ProposalSentState),
+    RequestReceived(RequestReceivedState),
+    PresentationPrepared(PresentationPreparedState),
+    PresentationPreparationFailed(PresentationPreparationFailedState),
+    PresentationSent(PresentationSentState),
+    Finished(FinishedProverState),
+}
+
+impl fmt::Display for ProverV2FullState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ProverV2FullState::Initial(_) => f.write_str("Initial"),
+            ProverV2FullState::ProposalSent(_) => f.write_str("ProposalSent"),
+            ProverV2FullState::RequestReceived(_) => f.write_str("RequestReceived"),
+            ProverV2FullState::PresentationPrepared(_) => f.write_str("PresentationPrepared"),
+            ProverV2FullState::PresentationPreparationFailed(_) => f.write_str("PresentationPreparationFailed"),
+            ProverV2FullState::PresentationSent(_) => f.write_str("PresentationSent"),
+            ProverV2FullState::Finished(_) => f.write_str("Finished"),
+        }
+    }
+}
+
+fn build_presentation_msg(thread_id: &str, presentation_attachment: String) -> PresentationV2 {
+    let (format, attach) = make_format_attach(
+        &presentation_attachment,
+        PresentationAttachmentFormatType::HyperledgerIndyProof2_0,
+    );
+    let content = PresentationV2Content::new(vec![format], vec![attach]);
+
+    let mut decorators = PresentationV2Decorators::new(Thread::new(thread_id.to_owned()));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    PresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+impl ProverV2SM {
+    pub fn new(source_id: String) -> Self {
+        ProverV2SM {
+            source_id,
+            thread_id: Uuid::new_v4().to_string(),
+            state: ProverV2FullState::Initial(InitialProverState::new()),
+        }
+    }
+
+    pub fn from_request(presentation_request: RequestPresentationV2, source_id: String) -> Self {
+        let thread_id = presentation_request
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| presentation_request.id.clone());
+        ProverV2SM {
+            source_id,
+            thread_id,
+            state: ProverV2FullState::RequestReceived(RequestReceivedState::new(presentation_request)),
+        }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn get_thread_id(&self) -> VcxResult<String> {
+        Ok(self.thread_id.clone())
+    }
+
+    pub fn get_state(&self) -> ProverV2State {
+        match self.state {
+            ProverV2FullState::Initial(_) => ProverV2State::Initial,
+            ProverV2FullState::ProposalSent(_) => ProverV2State::ProposalSent,
+            ProverV2FullState::RequestReceived(_) => ProverV2State::RequestReceived,
+            ProverV2FullState::PresentationPrepared(_) => ProverV2State::PresentationPrepared,
+            ProverV2FullState::PresentationPreparationFailed(_) => ProverV2State::PresentationPreparationFailed,
+            ProverV2FullState::PresentationSent(_) => ProverV2State::PresentationSent,
+            ProverV2FullState::Finished(ref status) => match status.status {
+                Status::Success => ProverV2State::Finished,
+                _ => ProverV2State::Failed,
+            },
+        }
+    }
+
+    pub fn progressable_by_message(&self) -> bool {
+        matches!(
+            self.state,
+            ProverV2FullState::ProposalSent(_) | ProverV2FullState::PresentationSent(_)
+        )
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        trace!(
+            "ProverV2SM::find_message_to_handle >>> messages: {:?}, state: {:?}",
+            messages,
+            self.state
+        );
+        for (uid, message) in messages {
+            match self.state {
+                ProverV2FullState::ProposalSent(_) => match &message {
+                    AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(request)) => {
+                        if matches_opt_thread_id!(request, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(problem_report) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                ProverV2FullState::PresentationSent(_) => match &message {
+                    AriesMessage::PresentProofV2(PresentProofV2::Ack(ack)) => {
+                        if matches_thread_id!(ack, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::Notification(Notification::Ack(ack)) => {
+                        if matches_thread_id!(ack, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(problem_report) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::Notification(Notification::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            };
+        }
+        None
+    }
+
+    pub async fn handle_message(
+        self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        action: PresentProofV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<Self> {
+        trace!(
+            "ProverV2SM::handle_message >>> action: {:?}, state: {:?}",
+            action,
+            self.state
+        );
+        verify_thread_id(&self.thread_id, &action)?;
+        let prover_sm = match action {
+            PresentProofV2Action::PresentationProposalSend(proposal) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.send_proposal(proposal, send_message).await?
+            }
+            PresentProofV2Action::PresentationRequest(request) => self.receive_request(request)?,
+            PresentProofV2Action::PreparePresentation((credentials, self_attested_attrs)) => {
+                self.generate_presentation(ledger, anoncreds, credentials, self_attested_attrs)
+                    .await?
+            }
+            PresentProofV2Action::PresentationSend() => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.send_presentation(send_message).await?
+            }
+            PresentProofV2Action::PresentationRequestReject(reason) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.decline_request(reason, send_message).await?
+            }
+            PresentProofV2Action::PresentationAck(ack) => self.receive_ack(ack)?,
+            PresentProofV2Action::ProblemReport(problem_report) => self.receive_problem_report(problem_report)?,
+            _ => self,
+        };
+        Ok(prover_sm)
+    }
+
+    pub async fn send_proposal(self, proposal: ProposePresentationV2, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            ProverV2FullState::Initial(_) => {
+                let mut proposal = proposal;
+                proposal.id = self.thread_id.clone();
+                send_message(proposal.clone().into()).await?;
+                ProverV2FullState::ProposalSent(ProposalSentState::new(proposal))
+            }
+            ProverV2FullState::RequestReceived(_) | ProverV2FullState::PresentationPrepared(_) => {
+                let mut proposal = proposal;
+                proposal.decorators.thread = Some(Thread::new(self.thread_id.clone()));
+                send_message(proposal.clone().into()).await?;
+                ProverV2FullState::ProposalSent(ProposalSentState::new(proposal))
+            }
+            s => {
+                warn!("Unable to send presentation proposal in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_request(self, request: RequestPresentationV2) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &PresentProofV2Action::PresentationRequest(request.clone()),
+        )?;
+        let state = match self.state {
+            ProverV2FullState::ProposalSent(_) => {
+                ProverV2FullState::RequestReceived(RequestReceivedState::new(request))
+            }
+            s => {
+                warn!("Unable to receive presentation request in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn generate_presentation(
+        self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        credentials: SelectedCredentials,
+        self_attested_attrs: HashMap<String, String>,
+    ) -> VcxResult<Self> {
+        let state = match self.state {
+            ProverV2FullState::RequestReceived(state) => {
+                match state
+                    .build_presentation(ledger, anoncreds, &credentials, &self_attested_attrs)
+                    .await
+                {
+                    Ok(presentation) => {
+                        let presentation = build_presentation_msg(&self.thread_id, presentation);
+                        ProverV2FullState::PresentationPrepared((state, presentation).into())
+                    }
+                    Err(err) => {
+                        let problem_report = build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        error!(
+                            "Failed to build presentation, sending problem report: {:?}",
+                            problem_report
+                        );
+                        ProverV2FullState::PresentationPreparationFailed((state, problem_report).into())
+                    }
+                }
+            }
+            s => {
+                warn!("Unable to generate presentation in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn send_presentation(self, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            ProverV2FullState::PresentationPrepared(state) => {
+                send_message(state.presentation.clone().into()).await?;
+                ProverV2FullState::PresentationSent(state.into())
+            }
+            ProverV2FullState::PresentationPreparationFailed(state) => {
+                send_message(state.problem_report.clone().into()).await?;
+                ProverV2FullState::Finished(state.into())
+            }
+            s => {
+                warn!("Unable to send presentation in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn decline_request(self, reason: String, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            ProverV2FullState::RequestReceived(_) | ProverV2FullState::PresentationPrepared(_) => {
+                let problem_report = build_problem_report_msg(Some(reason), &self.thread_id);
+                send_message(problem_report.clone().into()).await?;
+                ProverV2FullState::Finished(FinishedProverState::declined(problem_report))
+            }
+            ProverV2FullState::PresentationSent(_) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::ActionNotSupported,
+                    "Presentation is already sent",
+                ));
+            }
+            s => {
+                warn!("Unable to decline presentation request in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_ack(self, ack: AckPresentationV2) -> VcxResult<Self> {
+        verify_thread_id(&self.thread_id, &PresentProofV2Action::PresentationAck(ack.clone()))?;
+        let state = match self.state {
+            ProverV2FullState::PresentationSent(state) => ProverV2FullState::Finished((state, ack).into()),
+            s => {
+                warn!("Unable to receive presentation ack in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
+        let state = match self.state {
+            ProverV2FullState::ProposalSent(_) => {
+                ProverV2FullState::Finished(FinishedProverState::declined(problem_report))
+            }
+            ProverV2FullState::PresentationSent(state) => ProverV2FullState::Finished((state, problem_report).into()),
+            s => {
+                warn!("Unable to receive problem report in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn presentation_status(&self) -> u32 {
+        match self.state {
+            ProverV2FullState::Finished(ref state) => state.status.code(),
+            _ => Status::Undefined.code(),
+        }
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        matches!(self.state, ProverV2FullState::Finished(_))
+    }
+
+    pub fn get_presentation_request(&self) -> VcxResult<&RequestPresentationV2> {
+        match self.state {
+            ProverV2FullState::RequestReceived(ref state) => Ok(&state.presentation_request),
+            ProverV2FullState::PresentationPrepared(ref state) => Ok(&state.presentation_request),
+            ProverV2FullState::PresentationPreparationFailed(ref state) => Ok(&state.presentation_request),
+            ProverV2FullState::PresentationSent(ref state) => Ok(&state.presentation_request),
+            ProverV2FullState::Finished(ref state) => state.presentation_request.as_ref().ok_or(
+                AriesVcxError::from_msg(AriesVcxErrorKind::NotReady, "Presentation request is not available"),
+            ),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Presentation request is not available",
+            )),
+        }
+    }
+
+    pub fn get_presentation_request_attachment(&self) -> VcxResult<String> {
+        let presentation_request = self.get_presentation_request()?;
+        get_attach_by_format(
+            &presentation_request.content.formats,
+            &presentation_request.content.request_presentations_attach,
+            RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        )
+    }
+
+    pub fn get_presentation_msg(&self) -> VcxResult<&PresentationV2> {
+        match self.state {
+            ProverV2FullState::PresentationPrepared(ref state) => Ok(&state.presentation),
+            ProverV2FullState::PresentationSent(ref state) => Ok(&state.presentation),
+            ProverV2FullState::Finished(ref state) => state.presentation.as_ref().ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Presentation is not available in Finished state",
+            )),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Presentation is not created yet",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+    use crate::protocols::proof_presentation_v2::test_utils::*;
+    use crate::utils::constants::PROOF_JSON;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    const THREAD_ID: &str = "test_thread_id";
+
+    fn _ledger() -> Arc<dyn AnoncredsLedgerRead> {
+        Arc::new(MockLedger)
+    }
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    fn _prover_sm() -> ProverV2SM {
+        ProverV2SM::new("test_source_id".to_owned())
+    }
+
+    fn _prover_sm_from_request() -> ProverV2SM {
+        ProverV2SM::from_request(_presentation_request(THREAD_ID), "test_source_id".to_owned())
+    }
+
+    fn _prepare_presentation() -> PresentProofV2Action {
+        PresentProofV2Action::PreparePresentation((SelectedCredentials::default(), HashMap::new()))
+    }
+
+    impl ProverV2SM {
+        async fn to_proposal_sent_state(self) -> ProverV2SM {
+            let prover_sm = self
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    PresentProofV2Action::PresentationProposalSend(_presentation_proposal()),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverV2FullState::ProposalSent(_), prover_sm.state);
+            prover_sm
+        }
+
+        async fn to_presentation_prepared_state(self) -> ProverV2SM {
+            let _mock_builder = MockBuilder::init().set_mock_generate_indy_proof(PROOF_JSON);
+            let prover_sm = self
+                .handle_message(&_ledger(), &_anoncreds(), _prepare_presentation(), None)
+                .await
+                .unwrap();
+            assert_match!(ProverV2FullState::PresentationPrepared(_), prover_sm.state);
+            prover_sm
+        }
+
+        async fn to_presentation_sent_state(self) -> ProverV2SM {
+            let prover_sm = self
+                .to_presentation_prepared_state()
+                .await
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    PresentProofV2Action::PresentationSend(),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverV2FullState::PresentationSent(_), prover_sm.state);
+            prover_sm
+        }
+
+        async fn to_finished_state(self) -> ProverV2SM {
+            let prover_sm = self
+                .to_presentation_sent_state()
+                .await
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    PresentProofV2Action::PresentationAck(_presentation_ack(THREAD_ID)),
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_match!(ProverV2FullState::Finished(_), prover_sm.state);
+            prover_sm
+        }
+    }
+
+    #[test]
+    fn test_prover_new() {
+        let prover_sm = _prover_sm();
+
+        assert_match!(ProverV2FullState::Initial(_), prover_sm.state);
+        assert_eq!("test_source_id", prover_sm.get_source_id());
+    }
+
+    #[test]
+    fn test_prover_from_request() {
+        let prover_sm = _prover_sm_from_request();
+
+        assert_match!(ProverV2FullState::RequestReceived(_), prover_sm.state);
+        assert_eq!(THREAD_ID, prover_sm.get_thread_id().unwrap());
+    }
+
+    #[test]
+    fn test_prover_from_request_without_thread_uses_request_id() {
+        let mut request = _presentation_request(THREAD_ID);
+        request.decorators.thread = None;
+        let prover_sm = ProverV2SM::from_request(request.clone(), "test_source_id".to_owned());
+
+        assert_eq!(request.id, prover_sm.get_thread_id().unwrap());
+    }
+
+    #[test]
+    fn test_prover_build_presentation_message() {
+        let presentation = build_presentation_msg(THREAD_ID, PROOF_JSON.to_owned());
+
+        assert_eq!(THREAD_ID, presentation.decorators.thread.thid);
+        assert!(presentation.decorators.timing.is_some());
+        let attachment = get_attach_by_format(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            PresentationAttachmentFormatType::HyperledgerIndyProof2_0,
+        )
+        .unwrap();
+        assert_eq!(PROOF_JSON, attachment);
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_presentation_proposal_send_from_initial_state() {
+        let prover_sm = _prover_sm().to_proposal_sent_state().await;
+
+        assert_eq!(ProverV2State::ProposalSent, prover_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_presentation_proposal_send_without_send_message_fails() {
+        let err = _prover_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationProposalSend(_presentation_proposal()),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_presentation_request_from_proposal_sent_state() {
+        let prover_sm = _prover_sm().to_proposal_sent_state().await;
+        let thread_id = prover_sm.get_thread_id().unwrap();
+        let prover_sm = prover_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationRequest(_presentation_request(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(ProverV2FullState::RequestReceived(_), prover_sm.state);
+        assert!(prover_sm.get_presentation_request().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_presentation_request_with_other_thread_fails() {
+        let prover_sm = _prover_sm().to_proposal_sent_state().await;
+        let err = prover_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationRequest(_presentation_request("other_thread_id")),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_problem_report_from_proposal_sent_state() {
+        let prover_sm = _prover_sm().to_proposal_sent_state().await;
+        let thread_id = prover_sm.get_thread_id().unwrap();
+        let prover_sm = prover_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(ProverV2FullState::Finished(_), prover_sm.state);
+        assert_eq!(
+            Status::Declined(_problem_report(&thread_id)).code(),
+            prover_sm.presentation_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_presentation_proposal_send_from_request_received_state() {
+        let prover_sm = _prover_sm_from_request().to_proposal_sent_state().await;
+
+        assert_eq!(THREAD_ID, prover_sm.get_thread_id().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_prepare_presentation_from_request_received_state() {
+        let prover_sm = _prover_sm_from_request().to_presentation_prepared_state().await;
+
+        let presentation = prover_sm.get_presentation_msg().unwrap();
+        assert_eq!(THREAD_ID, presentation.decorators.thread.thid);
+        assert!(prover_sm.get_presentation_request_attachment().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_prepare_presentation_for_invalid_request_fails() {
+        let prover_sm = ProverV2SM::from_request(
+            _presentation_request_with_attach(THREAD_ID, "not a proof request"),
+            "test_source_id".to_owned(),
+        );
+        let prover_sm = prover_sm
+            .handle_message(&_ledger(), &_anoncreds(), _prepare_presentation(), None)
+            .await
+            .unwrap();
+
+        assert_match!(ProverV2FullState::PresentationPreparationFailed(_), prover_sm.state);
+        assert_eq!(ProverV2State::PresentationPreparationFailed, prover_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_send_presentation_from_preparation_failed_state() {
+        let prover_sm = ProverV2SM::from_request(
+            _presentation_request_with_attach(THREAD_ID, "not a proof request"),
+            "test_source_id".to_owned(),
+        );
+        let (sender, receiver) = async_channel::bounded(1);
+        let send_message: SendClosure = Box::new(move |message: AriesMessage| {
+            Box::pin(async move {
+                sender.try_send(message).unwrap();
+                VcxResult::Ok(())
+            })
+        });
+        let prover_sm = prover_sm
+            .handle_message(&_ledger(), &_anoncreds(), _prepare_presentation(), None)
+            .await
+            .unwrap()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationSend(),
+                Some(send_message),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(AriesMessage::ReportProblem(_), receiver.try_recv().unwrap());
+        assert_eq!(ProverV2State::Failed, prover_sm.get_state());
+        assert!(prover_sm.get_presentation_request().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_reject_presentation_request_from_request_received_state() {
+        let prover_sm = _prover_sm_from_request()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationRequestReject(_reason()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(ProverV2FullState::Finished(_), prover_sm.state);
+        assert_eq!(
+            Status::Declined(_problem_report(THREAD_ID)).code(),
+            prover_sm.presentation_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_reject_presentation_request_from_presentation_prepared_state() {
+        let prover_sm = _prover_sm_from_request()
+            .to_presentation_prepared_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationRequestReject(_reason()),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(ProverV2State::Failed, prover_sm.get_state());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_other_messages_from_request_received_state() {
+        let prover_sm = _prover_sm_from_request()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationSend(),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+        assert_match!(ProverV2FullState::RequestReceived(_), prover_sm.state);
+
+        let prover_sm = prover_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationAck(_presentation_ack(THREAD_ID)),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_match!(ProverV2FullState::RequestReceived(_), prover_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_send_presentation_from_presentation_prepared_state() {
+        let prover_sm = _prover_sm_from_request().to_presentation_sent_state().await;
+
+        assert_eq!(ProverV2State::PresentationSent, prover_sm.get_state());
+        assert!(prover_sm.get_presentation_msg().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_other_messages_from_presentation_prepared_state() {
+        let prover_sm = _prover_sm_from_request()
+            .to_presentation_prepared_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationAck(_presentation_ack(THREAD_ID)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(ProverV2FullState::PresentationPrepared(_), prover_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_ack_from_presentation_sent_state() {
+        let prover_sm = _prover_sm_from_request().to_finished_state().await;
+
+        assert_eq!(ProverV2State::Finished, prover_sm.get_state());
+        assert_eq!(Status::Success.code(), prover_sm.presentation_status());
+        assert!(prover_sm.get_presentation_msg().is_ok());
+        assert!(prover_sm.get_presentation_request().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_ack_with_other_thread_fails() {
+        let err = _prover_sm_from_request()
+            .to_presentation_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationAck(_presentation_ack("other_thread_id")),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_reject_presentation_request_from_presentation_sent_state() {
+        let err = _prover_sm_from_request()
+            .to_presentation_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationRequestReject(_reason()),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::ActionNotSupported, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_problem_report_from_presentation_sent_state() {
+        let prover_sm = _prover_sm_from_request()
+            .to_presentation_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(THREAD_ID)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(ProverV2State::Failed, prover_sm.get_state());
+        assert!(prover_sm.get_presentation_msg().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prover_handle_messages_from_finished_state() {
+        let prover_sm = _prover_sm_from_request()
+            .to_finished_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(THREAD_ID)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(ProverV2State::Finished, prover_sm.get_state());
+        assert!(prover_sm.is_terminal_state());
+    }
+
+    #[test]
+    fn test_prover_find_message_to_handle_from_request_received_state() {
+        let prover_sm = _prover_sm_from_request();
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_request(THREAD_ID).into()),
+            ("key_2".to_owned(), _presentation_ack(THREAD_ID).into()),
+            ("key_3".to_owned(), _problem_report(THREAD_ID).into()),
+        ]);
+
+        assert!(prover_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prover_find_message_to_handle_from_proposal_sent_state() {
+        let prover_sm = _prover_sm().to_proposal_sent_state().await;
+        let thread_id = prover_sm.get_thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_ack(&thread_id).into()),
+            ("key_2".to_owned(), _presentation_request(&thread_id).into()),
+        ]);
+        let (uid, message) = prover_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(
+            AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(_)),
+            message
+        );
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_request("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report(&thread_id).into()),
+        ]);
+        let (uid, message) = prover_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(AriesMessage::ReportProblem(_), message);
+    }
+
+    #[tokio::test]
+    async fn test_prover_find_message_to_handle_from_presentation_sent_state() {
+        let prover_sm = _prover_sm_from_request().to_presentation_sent_state().await;
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_request(THREAD_ID).into()),
+            ("key_2".to_owned(), _presentation_ack(THREAD_ID).into()),
+        ]);
+        let (uid, message) = prover_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(AriesMessage::PresentProofV2(PresentProofV2::Ack(_)), message);
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_ack("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report("other_thread_id").into()),
+        ]);
+        assert!(prover_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prover_find_message_to_handle_from_finished_state() {
+        let prover_sm = _prover_sm_from_request().to_finished_state().await;
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_request(THREAD_ID).into()),
+            ("key_2".to_owned(), _presentation_ack(THREAD_ID).into()),
+            ("key_3".to_owned(), _problem_report(THREAD_ID).into()),
+        ]);
+
+        assert!(prover_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_state() {
+        assert_eq!(ProverV2State::Initial, _prover_sm().get_state());
+        assert_eq!(ProverV2State::RequestReceived, _prover_sm_from_request().get_state());
+        assert_eq!(
+            ProverV2State::PresentationPrepared,
+            _prover_sm_from_request()
+                .to_presentation_prepared_state()
+                .await
+                .get_state()
+        );
+        assert_eq!(
+            ProverV2State::PresentationSent,
+            _prover_sm_from_request().to_presentation_sent_state().await.get_state()
+        );
+        assert_eq!(
+            ProverV2State::Finished,
+            _prover_sm_from_request().to_finished_state().await.get_state()
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::handlers::util::Status;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FinishedProverState {
+    pub presentation_request: Option<RequestPresentationV2>,
+    pub presentation: Option<PresentationV2>,
+    pub status: Status,
+}
+
+impl FinishedProverState {
+    pub fn declined(problem_report: ProblemReport) -> Self {
+        trace!("transit state to FinishedProverState due to a rejection");
+        FinishedProverState {
+            presentation_request: None,
+            presentation: None,
+            status: Status::Declined(problem_report),
+        }
+    }
+}
+
+impl From<ProblemReport> for FinishedProverState {
+    fn from(problem_report: ProblemReport) -> Self {
+        trace!("transit state to FinishedProverState due to a problem report");
+        FinishedProverState {
+            presentation_request: None,
+            presentation: None,
+            status: Status::Failed(problem_report),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct InitialProverState {}
+
+impl InitialProverState {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
@@ -0,0 +1,7 @@
+pub(super) mod finished;
+pub(super) mod initial;
+pub(super) mod presentation_preparation_failed;
+pub(super) mod presentation_prepared;
+pub(super) mod presentation_sent;
+pub(super) mod proposal_sent;
+pub(super) mod request_received;
@@ -0,0 +1,20 @@
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+
+use crate::protocols::proof_presentation_v2::prover::states::presentation_sent::PresentationSentState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationPreparedState {
+    pub presentation_request: RequestPresentationV2,
+    pub presentation: PresentationV2,
+}
+
+impl From<PresentationPreparedState> for PresentationSentState {
+    fn from(state: PresentationPreparedState) -> Self {
+        trace!("transit state from PresentationPreparedState to PresentationSentState");
+        PresentationSentState {
+            presentation_request: state.presentation_request,
+            presentation: state.presentation,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use messages::msg_fields::protocols::present_proof::v2::ack::AckPresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::handlers::util::Status;
+use crate::protocols::proof_presentation_v2::prover::states::finished::FinishedProverState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationSentState {
+    pub presentation_request: RequestPresentationV2,
+    pub presentation: PresentationV2,
+}
+
+impl From<(PresentationSentState, AckPresentationV2)> for FinishedProverState {
+    fn from((state, _ack): (PresentationSentState, AckPresentationV2)) -> Self {
+        trace!("transit state from PresentationSentState to FinishedProverState");
+        FinishedProverState {
+            presentation_request: Some(state.presentation_request),
+            presentation: Some(state.presentation),
+            status: Status::Success,
+        }
+    }
+}
+
+impl From<(PresentationSentState, ProblemReport)> for FinishedProverState {
+    fn from((state, problem_report): (PresentationSentState, ProblemReport)) -> Self {
+        trace!("transit state from PresentationSentState to FinishedProverState");
+        FinishedProverState {
+            presentation_request: Some(state.presentation_request),
+            presentation: Some(state.presentation),
+            status: Status::Failed(problem_report),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::{
+    RequestPresentationAttachmentFormatType, RequestPresentationV2,
+};
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::common::proofs::prover::prover::generate_indy_proof;
+use crate::errors::error::prelude::*;
+use crate::handlers::proof_presentation::types::SelectedCredentials;
+use crate::protocols::issuance_v2::formats::get_attach_by_format;
+use crate::protocols::proof_presentation_v2::prover::states::presentation_preparation_failed::PresentationPreparationFailedState;
+use crate::protocols::proof_presentation_v2::prover::states::presentation_prepared::PresentationPreparedState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestReceivedState {
+    pub presentation_request: RequestPresentationV2,
+}
+
+impl RequestReceivedState {
+    pub fn new(presentation_request: RequestPresentationV2) -> Self {
+        Self { presentation_request }
+    }
+
+    pub fn get_attachment(&self) -> VcxResult<String> {
+        get_attach_by_format(
+            &self.presentation_request.content.formats,
+            &self.presentation_request.content.request_presentations_attach,
+            RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        )
+    }
+
+    pub async fn build_presentation(
+        &self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        credentials: &SelectedCredentials,
+        self_attested_attrs: &HashMap<String, String>,
+    ) -> VcxResult<String> {
+        let proof_req_data_json = self.get_attachment()?;
+
+        generate_indy_proof(
+            ledger,
+            anoncreds,
+            credentials,
+            self_attested_attrs,
+            &proof_req_data_json,
+        )
+        .await
+    }
+}
+
+impl From<(RequestReceivedState, PresentationV2)> for PresentationPreparedState {
+    fn from((state, presentation): (RequestReceivedState, PresentationV2)) -> Self {
+        trace!("transit state from RequestReceivedState to PresentationPreparedState");
+        PresentationPreparedState {
+            presentation_request: state.presentation_request,
+            presentation,
+        }
+    }
+}
+
+impl From<(RequestReceivedState, ProblemReport)> for PresentationPreparationFailedState {
+    fn from((state, problem_report): (RequestReceivedState, ProblemReport)) -> Self {
+        trace!("transit state from RequestReceivedState to PresentationPreparationFailedState");
+        PresentationPreparationFailedState {
+            presentation_request: state.presentation_request,
+            problem_report,
+        }
+    }
+}
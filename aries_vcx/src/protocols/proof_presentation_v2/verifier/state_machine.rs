@@ -0,0 +1,971 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use chrono::Utc;
+use messages::decorators::thread::Thread;
+use messages::decorators::timing::Timing;
+use messages::msg_fields::protocols::notification::ack::{AckDecorators, AckStatus};
+use messages::msg_fields::protocols::present_proof::v2::ack::{AckPresentationV2, AckPresentationV2Content};
+use messages::msg_fields::protocols::present_proof::v2::present::{PresentationAttachmentFormatType, PresentationV2};
+use messages::msg_fields::protocols::present_proof::v2::problem_report::{
+    PresentProofV2ProblemReport, PresentProofV2ProblemReportContent,
+};
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::{
+    RequestPresentationAttachmentFormatType, RequestPresentationV2, RequestPresentationV2Content,
+    RequestPresentationV2Decorators,
+};
+use messages::msg_fields::protocols::present_proof::v2::PresentProofV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+use messages::msg_parts::MsgParts;
+use messages::AriesMessage;
+use uuid::Uuid;
+
+use crate::common::proofs::proof_request::PresentationRequestData;
+use crate::errors::error::prelude::*;
+use crate::handlers::util::{matches_opt_thread_id, matches_thread_id, Status};
+use crate::protocols::common::build_problem_report_msg;
+use crate::protocols::issuance_v2::formats::{get_attach_by_format, make_format_attach};
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+use crate::protocols::proof_presentation_v2::actions::PresentProofV2Action;
+use crate::protocols::proof_presentation_v2::verifier::states::finished::FinishedVerifierState;
+use crate::protocols::proof_presentation_v2::verifier::states::initial::InitialVerifierState;
+use crate::protocols::proof_presentation_v2::verifier::states::proposal_received::ProposalReceivedState;
+use crate::protocols::proof_presentation_v2::verifier::states::request_sent::RequestSentState;
+use crate::protocols::proof_presentation_v2::verifier::states::request_set::RequestSetState;
+use crate::protocols::proof_presentation_v2::verify_thread_id;
+use crate::protocols::SendClosure;
+
+/// A state machine that tracks the evolution of states for a Verifier during
+/// the Present Proof 2.0 protocol.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifierV2SM {
+    source_id: String,
+    thread_id: String,
+    state: VerifierV2FullState,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifierV2State {
+    Initial,
+    ProposalReceived,
+    RequestSet,
+    RequestSent,
+    Finished,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum VerifierV2FullState {
+    Initial(InitialVerifierState),
+    ProposalReceived(ProposalReceivedState),
+    RequestSet(RequestSetState),
+    RequestSent(RequestSentState),
+    Finished(FinishedVerifierState),
+}
+
+impl fmt::Display for VerifierV2FullState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            VerifierV2FullState::Initial(_) => f.write_str("Initial"),
+            VerifierV2FullState::ProposalReceived(_) => f.write_str("ProposalReceived"),
+            VerifierV2FullState::RequestSet(_) => f.write_str("RequestSet"),
+            VerifierV2FullState::RequestSent(_) => f.write_str("RequestSent"),
+            VerifierV2FullState::Finished(_) => f.write_str("Finished"),
+        }
+    }
+}
+
+fn build_verification_ack(thread_id: &str) -> AckPresentationV2 {
+    let content = AckPresentationV2Content::new(AckStatus::Ok);
+    let mut decorators = AckDecorators::new(Thread::new(thread_id.to_owned()));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    AckPresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+fn build_presentation_request(
+    thread_id: &str,
+    request_data: &PresentationRequestData,
+    comment: Option<String>,
+) -> RequestPresentationV2 {
+    let (format, attach) = make_format_attach(
+        &json!(request_data).to_string(),
+        RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+    );
+    let mut content = RequestPresentationV2Content::new(vec![format], vec![attach]);
+    content.comment = comment;
+    content.will_confirm = Some(true);
+
+    let mut decorators = RequestPresentationV2Decorators::default();
+    decorators.thread = Some(Thread::new(thread_id.to_owned()));
+    let mut timing = Timing::default();
+    timing.out_time = Some(Utc::now());
+    decorators.timing = Some(timing);
+
+    RequestPresentationV2::with_decorators(Uuid::new_v4().to_string(), content, decorators)
+}
+
+impl VerifierV2SM {
+    pub fn new(source_id: &str) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            thread_id: Uuid::new_v4().to_string(),
+            state: VerifierV2FullState::Initial(InitialVerifierState::new()),
+        }
+    }
+
+    pub fn from_request(
+        source_id: &str,
+        request_data: &PresentationRequestData,
+        comment: Option<String>,
+    ) -> VcxResult<Self> {
+        Self::new(source_id).set_request(request_data, comment)
+    }
+
+    pub fn from_proposal(source_id: &str, presentation_proposal: &ProposePresentationV2) -> Self {
+        let thread_id = presentation_proposal
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| presentation_proposal.id.clone());
+        Self {
+            source_id: source_id.to_string(),
+            thread_id,
+            state: VerifierV2FullState::ProposalReceived(ProposalReceivedState::new(presentation_proposal.clone())),
+        }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn thread_id(&self) -> VcxResult<String> {
+        Ok(self.thread_id.clone())
+    }
+
+    pub fn get_state(&self) -> VerifierV2State {
+        match self.state {
+            VerifierV2FullState::Initial(_) => VerifierV2State::Initial,
+            VerifierV2FullState::ProposalReceived(_) => VerifierV2State::ProposalReceived,
+            VerifierV2FullState::RequestSet(_) => VerifierV2State::RequestSet,
+            VerifierV2FullState::RequestSent(_) => VerifierV2State::RequestSent,
+            VerifierV2FullState::Finished(ref status) => match status.status {
+                Status::Success => VerifierV2State::Finished,
+                _ => VerifierV2State::Failed,
+            },
+        }
+    }
+
+    pub fn progressable_by_message(&self) -> bool {
+        matches!(
+            self.state,
+            VerifierV2FullState::Initial(_) | VerifierV2FullState::RequestSent(_)
+        )
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, AriesMessage>) -> Option<(String, AriesMessage)> {
+        trace!(
+            "VerifierV2SM::find_message_to_handle >>> messages: {:?}, state: {:?}",
+            messages,
+            self.state
+        );
+        for (uid, message) in messages {
+            match self.state {
+                VerifierV2FullState::Initial(_) => {
+                    if let AriesMessage::PresentProofV2(PresentProofV2::ProposePresentation(_)) = &message {
+                        return Some((uid, message));
+                    }
+                }
+                VerifierV2FullState::RequestSent(_) => match &message {
+                    AriesMessage::PresentProofV2(PresentProofV2::Presentation(presentation)) => {
+                        if matches_thread_id!(presentation, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::PresentProofV2(PresentProofV2::ProposePresentation(proposal)) => {
+                        if matches_opt_thread_id!(proposal, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(problem_report)) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    AriesMessage::ReportProblem(problem_report) => {
+                        if matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+                            return Some((uid, message));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            };
+        }
+        None
+    }
+
+    pub async fn handle_message(
+        self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        action: PresentProofV2Action,
+        send_message: Option<SendClosure>,
+    ) -> VcxResult<Self> {
+        trace!(
+            "VerifierV2SM::handle_message >>> action: {:?}, state: {:?}",
+            action,
+            self.state
+        );
+        verify_thread_id(&self.thread_id, &action)?;
+        let verifier_sm = match action {
+            PresentProofV2Action::PresentationProposal(proposal) => self.receive_proposal(proposal)?,
+            PresentProofV2Action::Presentation(presentation) => {
+                let send_message = send_message.ok_or(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Attempted to call undefined send_message callback",
+                ))?;
+                self.verify_presentation(ledger, anoncreds, presentation, send_message)
+                    .await?
+            }
+            PresentProofV2Action::ProblemReport(problem_report) => self.receive_problem_report(problem_report)?,
+            _ => self,
+        };
+        Ok(verifier_sm)
+    }
+
+    pub fn set_request(self, request_data: &PresentationRequestData, comment: Option<String>) -> VcxResult<Self> {
+        let state = match self.state {
+            VerifierV2FullState::Initial(_)
+            | VerifierV2FullState::ProposalReceived(_)
+            | VerifierV2FullState::RequestSet(_) => {
+                let presentation_request = build_presentation_request(&self.thread_id, request_data, comment);
+                VerifierV2FullState::RequestSet(RequestSetState::new(presentation_request))
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot set presentation request in this state",
+                ));
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn get_presentation_request_msg(&self) -> VcxResult<RequestPresentationV2> {
+        match self.state {
+            VerifierV2FullState::RequestSet(ref state) => Ok(state.presentation_request.clone()),
+            VerifierV2FullState::RequestSent(ref state) => Ok(state.presentation_request.clone()),
+            VerifierV2FullState::Finished(ref state) => state.presentation_request.clone().ok_or(
+                AriesVcxError::from_msg(AriesVcxErrorKind::InvalidState, "No presentation request set"),
+            ),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Presentation request not set yet",
+            )),
+        }
+    }
+
+    pub fn mark_presentation_request_msg_sent(self) -> VcxResult<Self> {
+        let state = match self.state {
+            VerifierV2FullState::RequestSet(state) => VerifierV2FullState::RequestSent(state.into()),
+            VerifierV2FullState::RequestSent(state) => VerifierV2FullState::RequestSent(state),
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Can not mark_presentation_request_msg_sent in current state.",
+                ));
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn send_presentation_request(self, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            VerifierV2FullState::RequestSet(state) => {
+                send_message(state.presentation_request.clone().into()).await?;
+                VerifierV2FullState::RequestSent(state.into())
+            }
+            s => {
+                warn!("Unable to send presentation request in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_proposal(self, proposal: ProposePresentationV2) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &PresentProofV2Action::PresentationProposal(proposal.clone()),
+        )?;
+        let (state, thread_id) = match self.state {
+            VerifierV2FullState::Initial(_) => {
+                let thread_id = match proposal.decorators.thread {
+                    Some(ref thread) => thread.thid.clone(),
+                    None => proposal.id.clone(),
+                };
+                (
+                    VerifierV2FullState::ProposalReceived(ProposalReceivedState::new(proposal)),
+                    thread_id,
+                )
+            }
+            VerifierV2FullState::RequestSent(_) => (
+                VerifierV2FullState::ProposalReceived(ProposalReceivedState::new(proposal)),
+                self.thread_id.clone(),
+            ),
+            s => {
+                warn!("Unable to receive presentation proposal in state {}", s);
+                (s, self.thread_id.clone())
+            }
+        };
+        Ok(Self {
+            state,
+            thread_id,
+            ..self
+        })
+    }
+
+    pub async fn decline_proposal(self, reason: String, send_message: SendClosure) -> VcxResult<Self> {
+        let state = match self.state {
+            VerifierV2FullState::ProposalReceived(_) => {
+                let problem_report = build_problem_report_msg(Some(reason), &self.thread_id);
+                send_message(problem_report.clone().into()).await?;
+                VerifierV2FullState::Finished(FinishedVerifierState::declined(problem_report))
+            }
+            s => {
+                warn!("Unable to decline presentation proposal in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub async fn verify_presentation<'a>(
+        self,
+        ledger: &'a Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &'a Arc<dyn BaseAnonCreds>,
+        presentation: PresentationV2,
+        send_message: SendClosure,
+    ) -> VcxResult<Self> {
+        verify_thread_id(
+            &self.thread_id,
+            &PresentProofV2Action::Presentation(presentation.clone()),
+        )?;
+        let state = match self.state {
+            VerifierV2FullState::RequestSent(state) => {
+                let (state, message) = match state.verify_presentation(ledger, anoncreds, &presentation).await {
+                    Ok(()) => {
+                        let ack: AriesMessage = build_verification_ack(&self.thread_id).into();
+                        (
+                            VerifierV2FullState::Finished(
+                                (state, presentation, PresentationVerificationStatus::Valid).into(),
+                            ),
+                            ack,
+                        )
+                    }
+                    Err(err) => {
+                        let problem_report = build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let state = match err.kind() {
+                            AriesVcxErrorKind::InvalidProof => VerifierV2FullState::Finished(
+                                (state, presentation, PresentationVerificationStatus::Invalid).into(),
+                            ),
+                            _ => VerifierV2FullState::Finished((state, problem_report.clone()).into()),
+                        };
+
+                        let MsgParts {
+                            id,
+                            content,
+                            decorators,
+                        } = problem_report;
+                        let problem_report = PresentProofV2ProblemReport::with_decorators(
+                            id,
+                            PresentProofV2ProblemReportContent(content),
+                            decorators,
+                        );
+                        (state, AriesMessage::from(problem_report))
+                    }
+                };
+                send_message(message).await?;
+                state
+            }
+            s => {
+                warn!("Unable to verify presentation in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn receive_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
+        let state = match self.state {
+            VerifierV2FullState::RequestSent(state) => VerifierV2FullState::Finished((state, problem_report).into()),
+            s => {
+                warn!("Unable to receive problem report in state {}", s);
+                s
+            }
+        };
+        Ok(Self { state, ..self })
+    }
+
+    pub fn is_terminal_state(&self) -> bool {
+        matches!(self.state, VerifierV2FullState::Finished(_))
+    }
+
+    pub fn get_verification_status(&self) -> PresentationVerificationStatus {
+        match self.state {
+            VerifierV2FullState::Finished(ref state) => state.verification_status.clone(),
+            _ => PresentationVerificationStatus::Unavailable,
+        }
+    }
+
+    pub fn get_proposal(&self) -> VcxResult<ProposePresentationV2> {
+        match self.state {
+            VerifierV2FullState::ProposalReceived(ref state) => Ok(state.presentation_proposal.clone()),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Presentation proposal not received yet",
+            )),
+        }
+    }
+
+    pub fn get_presentation_msg(&self) -> VcxResult<PresentationV2> {
+        match self.state {
+            VerifierV2FullState::Finished(ref state) => state.presentation.clone().ok_or(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "State machine is final state, but presentation is not available",
+            )),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Presentation not received yet",
+            )),
+        }
+    }
+
+    pub fn get_presentation_request_attachment(&self) -> VcxResult<String> {
+        let presentation_request = self.get_presentation_request_msg()?;
+        get_attach_by_format(
+            &presentation_request.content.formats,
+            &presentation_request.content.request_presentations_attach,
+            RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        )
+    }
+
+    pub fn get_presentation_attachment(&self) -> VcxResult<String> {
+        let presentation = self.get_presentation_msg()?;
+        get_attach_by_format(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            PresentationAttachmentFormatType::HyperledgerIndyProof2_0,
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use super::*;
+    use crate::common::proofs::proof_request::test_utils::_presentation_request_data;
+    use crate::protocols::proof_presentation_v2::test_utils::*;
+    use crate::utils::constants::PROOF_JSON;
+    use crate::utils::devsetup::was_in_past;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+    use crate::utils::mockdata::profile::mock_anoncreds::MockAnoncreds;
+    use crate::utils::mockdata::profile::mock_ledger::MockLedger;
+
+    fn _ledger() -> Arc<dyn AnoncredsLedgerRead> {
+        Arc::new(MockLedger)
+    }
+
+    fn _anoncreds() -> Arc<dyn BaseAnonCreds> {
+        Arc::new(MockAnoncreds)
+    }
+
+    fn _verifier_sm() -> VerifierV2SM {
+        VerifierV2SM::new("test_source_id")
+    }
+
+    fn _verifier_sm_from_request() -> VerifierV2SM {
+        VerifierV2SM::from_request("test_source_id", &_presentation_request_data(), None).unwrap()
+    }
+
+    fn _recording_send_message(sender: async_channel::Sender<AriesMessage>) -> Option<SendClosure> {
+        Some(Box::new(move |message: AriesMessage| {
+            Box::pin(async move {
+                sender.try_send(message).unwrap();
+                VcxResult::Ok(())
+            })
+        }))
+    }
+
+    impl VerifierV2SM {
+        async fn to_proposal_received_state(self) -> VerifierV2SM {
+            let verifier_sm = self
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    PresentProofV2Action::PresentationProposal(_presentation_proposal()),
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_match!(VerifierV2FullState::ProposalReceived(_), verifier_sm.state);
+            verifier_sm
+        }
+
+        async fn to_request_sent_state(self) -> VerifierV2SM {
+            let verifier_sm = self.send_presentation_request(_send_message().unwrap()).await.unwrap();
+            assert_match!(VerifierV2FullState::RequestSent(_), verifier_sm.state);
+            verifier_sm
+        }
+
+        async fn to_finished_state(self, result: VcxResult<bool>) -> VerifierV2SM {
+            let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(result);
+            let verifier_sm = self.to_request_sent_state().await;
+            let thread_id = verifier_sm.thread_id().unwrap();
+            let verifier_sm = verifier_sm
+                .handle_message(
+                    &_ledger(),
+                    &_anoncreds(),
+                    PresentProofV2Action::Presentation(_presentation(&thread_id)),
+                    _send_message(),
+                )
+                .await
+                .unwrap();
+            assert_match!(VerifierV2FullState::Finished(_), verifier_sm.state);
+            verifier_sm
+        }
+    }
+
+    #[test]
+    fn test_verifier_build_verification_ack() {
+        let ack = build_verification_ack("thread_id");
+
+        assert_eq!("thread_id", ack.decorators.thread.thid);
+        assert!(ack.decorators.timing.is_some());
+    }
+
+    #[test]
+    fn test_verifier_build_presentation_request() {
+        let request = build_presentation_request("thread_id", &_presentation_request_data(), Some("foo".into()));
+
+        assert_eq!("thread_id", request.decorators.thread.unwrap().thid);
+        assert_eq!(Some("foo".to_owned()), request.content.comment);
+        assert_eq!(Some(true), request.content.will_confirm);
+        let out_time = request.decorators.timing.unwrap().out_time.unwrap();
+        assert!(was_in_past(&out_time.to_rfc3339(), chrono::Duration::milliseconds(100)).unwrap());
+    }
+
+    #[test]
+    fn test_verifier_new() {
+        let verifier_sm = _verifier_sm();
+
+        assert_match!(VerifierV2FullState::Initial(_), verifier_sm.state);
+        assert_eq!("test_source_id", verifier_sm.get_source_id());
+    }
+
+    #[test]
+    fn test_verifier_from_request() {
+        let verifier_sm = _verifier_sm_from_request();
+
+        assert_match!(VerifierV2FullState::RequestSet(_), verifier_sm.state);
+        let request_data = verifier_sm.get_presentation_request_attachment().unwrap();
+        assert_eq!(json!(_presentation_request_data()).to_string(), request_data);
+    }
+
+    #[test]
+    fn test_verifier_from_proposal() {
+        let proposal = _presentation_proposal();
+        let verifier_sm = VerifierV2SM::from_proposal("test_source_id", &proposal);
+
+        assert_match!(VerifierV2FullState::ProposalReceived(_), verifier_sm.state);
+        assert_eq!(proposal.id, verifier_sm.thread_id().unwrap());
+        assert_eq!(proposal, verifier_sm.get_proposal().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_proposal_from_initial_state() {
+        let proposal = _presentation_proposal();
+        let verifier_sm = _verifier_sm()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationProposal(proposal.clone()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::ProposalReceived(_), verifier_sm.state);
+        assert_eq!(proposal.id, verifier_sm.thread_id().unwrap());
+    }
+
+    #[test]
+    fn test_verifier_mark_request_sent_from_request_set_state() {
+        let verifier_sm = _verifier_sm_from_request()
+            .mark_presentation_request_msg_sent()
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::RequestSent(_), verifier_sm.state);
+        assert!(verifier_sm.get_presentation_request_msg().is_ok());
+    }
+
+    #[test]
+    fn test_verifier_mark_request_sent_from_initial_state_fails() {
+        let err = _verifier_sm().mark_presentation_request_msg_sent().unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_send_presentation_request_from_request_set_state() {
+        let (sender, receiver) = async_channel::bounded(1);
+        let verifier_sm = _verifier_sm_from_request()
+            .send_presentation_request(_recording_send_message(sender).unwrap())
+            .await
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::RequestSent(_), verifier_sm.state);
+        assert_match!(
+            AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(_)),
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_other_messages_from_request_set_state() {
+        let verifier_sm = _verifier_sm_from_request();
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap()
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::RequestSet(_), verifier_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_verifier_set_request_from_proposal_received_state() {
+        let verifier_sm = _verifier_sm().to_proposal_received_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .set_request(&_presentation_request_data(), Some("foo".into()))
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::RequestSet(_), verifier_sm.state);
+        let request = verifier_sm.get_presentation_request_msg().unwrap();
+        assert_eq!(thread_id, request.decorators.thread.unwrap().thid);
+    }
+
+    #[tokio::test]
+    async fn test_verifier_decline_proposal_from_proposal_received_state() {
+        let (sender, receiver) = async_channel::bounded(1);
+        let verifier_sm = _verifier_sm()
+            .to_proposal_received_state()
+            .await
+            .decline_proposal(_reason(), _recording_send_message(sender).unwrap())
+            .await
+            .unwrap();
+
+        assert_match!(AriesMessage::ReportProblem(_), receiver.try_recv().unwrap());
+        assert_eq!(VerifierV2State::Failed, verifier_sm.get_state());
+        assert_eq!(
+            PresentationVerificationStatus::Unavailable,
+            verifier_sm.get_verification_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_other_messages_from_proposal_received_state() {
+        let verifier_sm = _verifier_sm().to_proposal_received_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation(&thread_id)),
+                _send_message(),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::ProposalReceived(_), verifier_sm.state);
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_valid_presentation_from_request_sent_state() {
+        let verifier_sm = _verifier_sm_from_request().to_finished_state(Ok(true)).await;
+
+        assert_eq!(VerifierV2State::Finished, verifier_sm.get_state());
+        assert_eq!(
+            PresentationVerificationStatus::Valid,
+            verifier_sm.get_verification_status()
+        );
+        assert_eq!(PROOF_JSON, verifier_sm.get_presentation_attachment().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_sends_ack_for_valid_presentation() {
+        let _mock_builder = MockBuilder::init().set_mock_result_for_validate_indy_proof(Ok(true));
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+
+        let (sender, receiver) = async_channel::bounded(1);
+        verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation(&thread_id)),
+                _recording_send_message(sender),
+            )
+            .await
+            .unwrap();
+
+        assert_match!(
+            AriesMessage::PresentProofV2(PresentProofV2::Ack(_)),
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_invalid_presentation_from_request_sent_state() {
+        let verifier_sm = _verifier_sm_from_request().to_finished_state(Ok(false)).await;
+
+        assert_eq!(VerifierV2State::Finished, verifier_sm.get_state());
+        assert_eq!(
+            PresentationVerificationStatus::Invalid,
+            verifier_sm.get_verification_status()
+        );
+        assert!(verifier_sm.get_presentation_msg().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_presentation_failing_verification() {
+        let verifier_sm = _verifier_sm_from_request()
+            .to_finished_state(Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidLedgerResponse,
+                "ledger unavailable",
+            )))
+            .await;
+
+        assert_eq!(VerifierV2State::Failed, verifier_sm.get_state());
+        assert_eq!(
+            PresentationVerificationStatus::Unavailable,
+            verifier_sm.get_verification_status()
+        );
+        assert!(verifier_sm.get_presentation_msg().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_sends_problem_report_for_unknown_presentation_format() {
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+
+        let (sender, receiver) = async_channel::bounded(1);
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation_with_format(
+                    &thread_id,
+                    PresentationAttachmentFormatType::DifPresentationExchangeSubmission1_0,
+                )),
+                _recording_send_message(sender),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(VerifierV2State::Failed, verifier_sm.get_state());
+        assert_match!(
+            AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(_)),
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_presentation_with_other_thread_fails() {
+        let err = _verifier_sm_from_request()
+            .to_request_sent_state()
+            .await
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation("other_thread_id")),
+                _send_message(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidJson, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_presentation_without_send_message_fails() {
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let err = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::Presentation(_presentation(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(AriesVcxErrorKind::InvalidState, err.kind());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_proposal_from_request_sent_state() {
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::PresentationProposal(_presentation_proposal_in_thread(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_match!(VerifierV2FullState::ProposalReceived(_), verifier_sm.state);
+        assert_eq!(thread_id, verifier_sm.thread_id().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_problem_report_from_request_sent_state() {
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(VerifierV2State::Failed, verifier_sm.get_state());
+        assert!(verifier_sm.get_presentation_request_msg().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_handle_messages_from_finished_state() {
+        let verifier_sm = _verifier_sm_from_request().to_finished_state(Ok(true)).await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+        let verifier_sm = verifier_sm
+            .handle_message(
+                &_ledger(),
+                &_anoncreds(),
+                PresentProofV2Action::ProblemReport(_problem_report(&thread_id)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(VerifierV2State::Finished, verifier_sm.get_state());
+        assert!(verifier_sm.is_terminal_state());
+        assert!(verifier_sm.set_request(&_presentation_request_data(), None).is_err());
+    }
+
+    #[test]
+    fn test_verifier_find_message_to_handle_from_initial_state() {
+        let verifier_sm = _verifier_sm();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation("thread_id").into()),
+            ("key_2".to_owned(), _presentation_proposal().into()),
+        ]);
+        let (uid, message) = verifier_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(
+            AriesMessage::PresentProofV2(PresentProofV2::ProposePresentation(_)),
+            message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifier_find_message_to_handle_from_request_sent_state() {
+        let verifier_sm = _verifier_sm_from_request().to_request_sent_state().await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation_ack(&thread_id).into()),
+            ("key_2".to_owned(), _presentation(&thread_id).into()),
+        ]);
+        let (uid, message) = verifier_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(AriesMessage::PresentProofV2(PresentProofV2::Presentation(_)), message);
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report(&thread_id).into()),
+        ]);
+        let (uid, message) = verifier_sm.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+        assert_match!(AriesMessage::ReportProblem(_), message);
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation("other_thread_id").into()),
+            ("key_2".to_owned(), _problem_report("other_thread_id").into()),
+        ]);
+        assert!(verifier_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verifier_find_message_to_handle_from_finished_state() {
+        let verifier_sm = _verifier_sm_from_request().to_finished_state(Ok(true)).await;
+        let thread_id = verifier_sm.thread_id().unwrap();
+
+        let messages = HashMap::from([
+            ("key_1".to_owned(), _presentation(&thread_id).into()),
+            ("key_2".to_owned(), _presentation_proposal().into()),
+            ("key_3".to_owned(), _problem_report(&thread_id).into()),
+        ]);
+        assert!(verifier_sm.find_message_to_handle(messages).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_state() {
+        assert_eq!(VerifierV2State::Initial, _verifier_sm().get_state());
+        assert_eq!(
+            VerifierV2State::ProposalReceived,
+            _verifier_sm().to_proposal_received_state().await.get_state()
+        );
+        assert_eq!(VerifierV2State::RequestSet, _verifier_sm_from_request().get_state());
+        assert_eq!(
+            VerifierV2State::RequestSent,
+            _verifier_sm_from_request().to_request_sent_state().await.get_state()
+        );
+        assert_eq!(
+            VerifierV2State::Finished,
+            _verifier_sm_from_request()
+                .to_finished_state(Ok(true))
+                .await
+                .get_state()
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use messages::msg_fields::protocols::present_proof::v2::present::PresentationV2;
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::handlers::util::Status;
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FinishedVerifierState {
+    pub presentation_request: Option<RequestPresentationV2>,
+    pub presentation: Option<PresentationV2>,
+    pub status: Status,
+    pub verification_status: PresentationVerificationStatus,
+}
+
+impl FinishedVerifierState {
+    pub fn declined(problem_report: ProblemReport) -> Self {
+        trace!("transit state to FinishedVerifierState due to a rejection");
+        FinishedVerifierState {
+            presentation_request: None,
+            presentation: None,
+            status: Status::Declined(problem_report),
+            verification_status: PresentationVerificationStatus::Unavailable,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct InitialVerifierState {}
+
+impl InitialVerifierState {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
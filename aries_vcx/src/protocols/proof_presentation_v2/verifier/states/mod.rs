@@ -0,0 +1,5 @@
+pub(super) mod finished;
+pub(super) mod initial;
+pub(super) mod proposal_received;
+pub(super) mod request_sent;
+pub(super) mod request_set;
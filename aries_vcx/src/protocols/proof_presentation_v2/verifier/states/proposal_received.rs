@@ -0,0 +1,12 @@
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalReceivedState {
+    pub presentation_proposal: ProposePresentationV2,
+}
+
+impl ProposalReceivedState {
+    pub fn new(presentation_proposal: ProposePresentationV2) -> Self {
+        Self { presentation_proposal }
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use aries_vcx_core::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_core::ledger::base_ledger::AnoncredsLedgerRead;
+use messages::msg_fields::protocols::present_proof::v2::present::{PresentationAttachmentFormatType, PresentationV2};
+use messages::msg_fields::protocols::present_proof::v2::request::{
+    RequestPresentationAttachmentFormatType, RequestPresentationV2,
+};
+use messages::msg_fields::protocols::report_problem::ProblemReport;
+
+use crate::common::proofs::verifier::verifier::validate_indy_proof;
+use crate::errors::error::prelude::*;
+use crate::handlers::util::Status;
+use crate::protocols::issuance_v2::formats::get_attach_by_format;
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+use crate::protocols::proof_presentation_v2::verifier::states::finished::FinishedVerifierState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestSentState {
+    pub presentation_request: RequestPresentationV2,
+}
+
+impl RequestSentState {
+    pub async fn verify_presentation(
+        &self,
+        ledger: &Arc<dyn AnoncredsLedgerRead>,
+        anoncreds: &Arc<dyn BaseAnonCreds>,
+        presentation: &PresentationV2,
+    ) -> VcxResult<()> {
+        let proof_json = get_attach_by_format(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            PresentationAttachmentFormatType::HyperledgerIndyProof2_0,
+        )?;
+        let proof_req_json = get_attach_by_format(
+            &self.presentation_request.content.formats,
+            &self.presentation_request.content.request_presentations_attach,
+            RequestPresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0,
+        )?;
+
+        let valid = validate_indy_proof(ledger, anoncreds, &proof_json, &proof_req_json).await?;
+
+        if !valid {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidProof,
+                "Presentation verification failed",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<(RequestSentState, PresentationV2, PresentationVerificationStatus)> for FinishedVerifierState {
+    fn from(
+        (state, presentation, verification_status): (RequestSentState, PresentationV2, PresentationVerificationStatus),
+    ) -> Self {
+        trace!("transit state from RequestSentState to FinishedVerifierState");
+        FinishedVerifierState {
+            presentation_request: Some(state.presentation_request),
+            presentation: Some(presentation),
+            status: Status::Success,
+            verification_status,
+        }
+    }
+}
+
+impl From<(RequestSentState, ProblemReport)> for FinishedVerifierState {
+    fn from((state, problem_report): (RequestSentState, ProblemReport)) -> Self {
+        trace!("transit state from RequestSentState to FinishedVerifierState");
+        FinishedVerifierState {
+            presentation_request: Some(state.presentation_request),
+            presentation: None,
+            status: Status::Failed(problem_report),
+            verification_status: PresentationVerificationStatus::Unavailable,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+
+use crate::protocols::proof_presentation_v2::verifier::states::request_sent::RequestSentState;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestSetState {
+    pub presentation_request: RequestPresentationV2,
+}
+
+impl RequestSetState {
+    pub fn new(presentation_request: RequestPresentationV2) -> Self {
+        Self { presentation_request }
+    }
+}
+
+impl From<RequestSetState> for RequestSentState {
+    fn from(state: RequestSetState) -> Self {
+        trace!("transit state from RequestSetState to RequestSentState");
+        RequestSentState {
+            presentation_request: state.presentation_request,
+        }
+    }
+}
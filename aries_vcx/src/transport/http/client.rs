@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
+use url::Url;
+
+use super::DIDCOMM_ENVELOPE_CONTENT_TYPE;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::transport::Transport;
+
+lazy_static! {
+    static ref HTTP_CLIENT: Client = {
+        match reqwest::ClientBuilder::new()
+            .pool_idle_timeout(Some(Duration::from_secs(4)))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => panic!("Building reqwest client failed: {:?}", e),
+        }
+    };
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpTransportConfig {
+    /// Timeout of a single attempt, from connecting until the response is received.
+    pub timeout: Duration,
+    /// How many times a failed attempt is retried before giving up.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every subsequent one.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for HttpTransportConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl HttpTransportConfig {
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// [`Transport`] POSTing packed messages to HTTP(S) endpoints.
+///
+/// Attempts failing to connect, timing out or answered with a `5xx` or `429` status are retried
+/// with an exponential backoff, other failures are returned right away.
+#[derive(Clone, Debug, Default)]
+pub struct HttpTransport {
+    config: HttpTransportConfig,
+}
+
+impl HttpTransport {
+    pub fn new(config: HttpTransportConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &HttpTransportConfig {
+        &self.config
+    }
+
+    async fn post(&self, msg: Vec<u8>, service_endpoint: &Url) -> Result<(), FailedAttempt> {
+        let response = HTTP_CLIENT
+            .post(service_endpoint.clone())
+            .timeout(self.config.timeout)
+            .header(CONTENT_TYPE, DIDCOMM_ENVELOPE_CONTENT_TYPE)
+            .body(msg)
+            .send()
+            .await
+            .map_err(|err| FailedAttempt {
+                retryable: err.is_connect() || err.is_timeout(),
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::PostMessageFailed,
+                    format!("HTTP transport could not reach {}, err: {}", service_endpoint, err),
+                ),
+            })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(FailedAttempt {
+            retryable: status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+            error: AriesVcxError::from_msg(
+                AriesVcxErrorKind::PostMessageFailed,
+                format!(
+                    "POST to {} failed due to non-success HTTP status: {}, response body: {}",
+                    service_endpoint, status, body
+                ),
+            ),
+        })
+    }
+}
+
+struct FailedAttempt {
+    retryable: bool,
+    error: AriesVcxError,
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: Url) -> VcxResult<()> {
+        let mut retry = 0;
+        loop {
+            match self.post(msg.clone(), &service_endpoint).await {
+                Ok(()) => return Ok(()),
+                Err(FailedAttempt { retryable: true, error }) if retry < self.config.max_retries => {
+                    let backoff = self.config.backoff(retry);
+                    warn!(
+                        "HttpTransport::send_message >> attempt {} failed, retrying in {:?}, err: {}",
+                        retry + 1,
+                        backoff,
+                        error
+                    );
+                    tokio::time::sleep(backoff).await;
+                    retry += 1;
+                }
+                Err(FailedAttempt { error, .. }) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+
+    use super::*;
+
+    /// Serves the given statuses in order, repeating the last one, and counts the requests
+    /// received with the DIDComm content type.
+    fn spawn_server(statuses: Vec<u16>) -> (Url, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let make_svc = make_service_fn(move |_conn| {
+            let statuses = statuses.clone();
+            let counter = counter.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    assert_eq!(req.headers()[CONTENT_TYPE], DIDCOMM_ENVELOPE_CONTENT_TYPE);
+                    let served = counter.fetch_add(1, Ordering::SeqCst);
+                    let status = statuses[served.min(statuses.len() - 1)];
+                    async move { Response::builder().status(status).body(Body::empty()) }
+                }))
+            }
+        });
+
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_svc);
+        let url = Url::parse(&format!("http://{}", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        (url, requests)
+    }
+
+    fn transport(max_retries: u32) -> HttpTransport {
+        HttpTransport::new(HttpTransportConfig {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let config = HttpTransportConfig::default();
+        assert_eq!(config.backoff(0), Duration::from_millis(500));
+        assert_eq!(config.backoff(2), Duration::from_secs(2));
+        assert_eq!(config.backoff(5), Duration::from_secs(10));
+        assert_eq!(config.backoff(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_send_message_retries_server_errors() {
+        let (url, requests) = spawn_server(vec![503, 500, 202]);
+        transport(3).send_message(b"msg".to_vec(), url).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_gives_up_after_max_retries() {
+        let (url, requests) = spawn_server(vec![503]);
+        let err = transport(2).send_message(b"msg".to_vec(), url).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::PostMessageFailed);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_does_not_retry_client_errors() {
+        let (url, requests) = spawn_server(vec![400, 202]);
+        assert!(transport(3).send_message(b"msg".to_vec(), url).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,202 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use messages::AriesMessage;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+/// Inbound HTTP endpoint receiving packed messages.
+///
+/// Every POSTed envelope is unpacked with the wallet and handed to the handler along with the
+/// verkey of its sender, if it was authcrypted. The request is answered with `202 Accepted` once
+/// the handler succeeds, `400 Bad Request` if the envelope could not be unpacked and
+/// `500 Internal Server Error` if the handler failed.
+pub struct HttpListener {
+    local_addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<()>,
+}
+
+impl HttpListener {
+    /// Starts listening on the given address, which may use port `0` to pick any free port.
+    /// Must be called from within a Tokio runtime.
+    pub fn bind<F, Fut>(addr: SocketAddr, wallet: Arc<dyn BaseWallet>, handler: F) -> VcxResult<Self>
+    where
+        F: Fn(AriesMessage, Option<String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = VcxResult<()>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let make_svc = make_service_fn(move |_conn| {
+            let wallet = wallet.clone();
+            let handler = handler.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    handle_request(req, wallet.clone(), handler.clone())
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&addr)
+            .map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::IOError,
+                    format!("HTTP listener could not bind {}, err: {}", addr, err),
+                )
+            })?
+            .serve(make_svc);
+        let local_addr = server.local_addr();
+
+        let (shutdown, shutdown_signal) = oneshot::channel();
+        let server = server.with_graceful_shutdown(async {
+            shutdown_signal.await.ok();
+        });
+        let server = tokio::spawn(async move {
+            if let Err(err) = server.await {
+                error!("HttpListener >> server on {} failed, err: {}", local_addr, err);
+            }
+        });
+        info!("HttpListener >> listening on {}", local_addr);
+
+        Ok(Self {
+            local_addr,
+            shutdown,
+            server,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections and waits for the requests in flight to be handled.
+    pub async fn shutdown(self) -> VcxResult<()> {
+        self.shutdown.send(()).ok();
+        self.server.await.map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::IOError,
+                format!(
+                    "HTTP listener on {} did not shut down cleanly, err: {}",
+                    self.local_addr, err
+                ),
+            )
+        })
+    }
+}
+
+async fn handle_request<F, Fut>(
+    req: Request<Body>,
+    wallet: Arc<dyn BaseWallet>,
+    handler: Arc<F>,
+) -> Result<Response<Body>, Infallible>
+where
+    F: Fn(AriesMessage, Option<String>) -> Fut,
+    Fut: Future<Output = VcxResult<()>>,
+{
+    if req.method() != Method::POST {
+        return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    let payload = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(payload) => payload.to_vec(),
+        Err(err) => {
+            warn!("HttpListener >> failed to read request body, err: {}", err);
+            return Ok(status_response(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    let (message, sender_vk) = match EncryptionEnvelope::anon_unpack(&wallet, payload).await {
+        Ok(unpacked) => unpacked,
+        Err(err) => {
+            warn!("HttpListener >> failed to unpack envelope, err: {}", err);
+            return Ok(status_response(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    match handler(message, sender_vk).await {
+        Ok(()) => Ok(status_response(StatusCode::ACCEPTED)),
+        Err(err) => {
+            error!("HttpListener >> handler failed to process message, err: {}", err);
+            Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use messages::msg_fields::protocols::notification::Notification;
+    use url::Url;
+
+    use super::*;
+    use crate::transport::http::{HttpTransport, HttpTransportConfig};
+    use crate::transport::Transport;
+    use crate::utils::constants::VERKEY;
+    use crate::utils::mockdata::mockdata_mediated_connection::ARIES_CONNECTION_ACK;
+    use crate::utils::mockdata::profile::mock_wallet::MockWallet;
+
+    // The mock wallet "unpacks" envelopes by returning them as they are.
+    fn bind_listener(sender: async_channel::Sender<(AriesMessage, Option<String>)>) -> (HttpListener, Url) {
+        let listener = HttpListener::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            Arc::new(MockWallet),
+            move |message, sender_vk| {
+                let sender = sender.clone();
+                async move {
+                    sender.send((message, sender_vk)).await.unwrap();
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr())).unwrap();
+        (listener, url)
+    }
+
+    #[tokio::test]
+    async fn test_listener_hands_unpacked_messages_to_handler() {
+        let (sender, receiver) = async_channel::unbounded();
+        let (listener, url) = bind_listener(sender);
+
+        let envelope = json!({
+            "message": ARIES_CONNECTION_ACK,
+            "sender_verkey": VERKEY,
+        });
+        HttpTransport::default()
+            .send_message(envelope.to_string().into_bytes(), url)
+            .await
+            .unwrap();
+
+        let (message, sender_vk) = receiver.recv().await.unwrap();
+        assert!(matches!(message, AriesMessage::Notification(Notification::Ack(_))));
+        assert_eq!(sender_vk.as_deref(), Some(VERKEY));
+        listener.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_listener_rejects_envelopes_it_cannot_unpack() {
+        let (sender, receiver) = async_channel::unbounded();
+        let (listener, url) = bind_listener(sender);
+
+        let transport = HttpTransport::new(HttpTransportConfig {
+            max_retries: 0,
+            ..Default::default()
+        });
+        assert!(transport.send_message(b"envelope".to_vec(), url).await.is_err());
+        assert!(receiver.is_empty());
+        listener.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+mod client;
+mod listener;
+
+pub use client::{HttpTransport, HttpTransportConfig};
+pub use listener::HttpListener;
+
+/// Media type of packed DIDComm v1 messages, see
+/// [Aries RFC 0044](https://github.com/hyperledger/aries-rfcs/tree/main/features/0044-didcomm-file-and-mime-types).
+pub const DIDCOMM_ENVELOPE_CONTENT_TYPE: &str = "application/didcomm-envelope-enc";
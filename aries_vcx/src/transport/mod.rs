@@ -1,3 +1,8 @@
+#[cfg(feature = "http_transport")]
+pub mod http;
+#[cfg(feature = "ws_transport")]
+pub mod ws;
+
 use async_trait::async_trait;
 use url::Url;
 
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use messages::decorators::transport::{ReturnRoute, Transport as TransportDecorator};
+use messages::AriesMessage;
+use tokio::sync::Mutex;
+use url::Url;
+
+use super::{boxed_handler, InboundHandler, WsSession};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::transport::Transport;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+
+/// [`Transport`] sending packed messages over WebSockets, keeping a session open per endpoint.
+///
+/// Messages received on these sessions, such as the responses of peers honouring the
+/// `~transport` decorator of our messages, are unpacked with the wallet and handed to the handler
+/// along with the verkey of their sender and, if they asked for return route themselves, the
+/// session to respond on.
+#[derive(Clone)]
+pub struct WsTransport {
+    wallet: Arc<dyn BaseWallet>,
+    handler: InboundHandler,
+    sessions: Arc<Mutex<HashMap<Url, WsSession>>>,
+}
+
+impl WsTransport {
+    pub fn new<F, Fut>(wallet: Arc<dyn BaseWallet>, handler: F) -> Self
+    where
+        F: Fn(AriesMessage, Option<String>, Option<WsSession>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = VcxResult<()>> + Send + 'static,
+    {
+        Self {
+            wallet,
+            handler: boxed_handler(handler),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the session open to the `ws://` or `wss://` endpoint, connecting if there is none.
+    pub async fn session(&self, service_endpoint: &Url) -> VcxResult<WsSession> {
+        if !matches!(service_endpoint.scheme(), "ws" | "wss") {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidUrl,
+                format!("Not a WebSocket endpoint: {}", service_endpoint),
+            ));
+        }
+
+        let mut sessions = self.sessions.lock().await;
+        if let Some(session) = sessions.get(service_endpoint).filter(|session| !session.is_closed()) {
+            return Ok(session.clone());
+        }
+
+        debug!("WsTransport::session >> connecting to {}", service_endpoint);
+        let (socket, _) = tokio_tungstenite::connect_async(service_endpoint.as_str())
+            .await
+            .map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::PostMessageFailed,
+                    format!(
+                        "WebSocket transport could not connect to {}, err: {}",
+                        service_endpoint, err
+                    ),
+                )
+            })?;
+        let session = WsSession::spawn(socket, self.wallet.clone(), self.handler.clone());
+        sessions.insert(service_endpoint.clone(), session.clone());
+        Ok(session)
+    }
+
+    /// Packs the message for the recipient of the DID document and sends it over the session open
+    /// to its endpoint, asking the recipient through the `~transport` decorator to return its
+    /// responses, of any thread, on the same session.
+    pub async fn send_with_return_route(
+        &self,
+        message: &AriesMessage,
+        sender_verkey: &str,
+        did_doc: &AriesDidDoc,
+    ) -> VcxResult<()> {
+        let service_endpoint = did_doc
+            .get_endpoint()
+            .ok_or_else(|| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc"))?;
+        let EncryptionEnvelope(envelope) = EncryptionEnvelope::create_with_transport(
+            &self.wallet,
+            message,
+            &TransportDecorator::new(ReturnRoute::All),
+            Some(sender_verkey),
+            did_doc,
+        )
+        .await?;
+        self.send_message(envelope, service_endpoint).await
+    }
+
+    pub async fn close(&self, service_endpoint: &Url) {
+        if let Some(session) = self.sessions.lock().await.remove(service_endpoint) {
+            session.close();
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for WsTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: Url) -> VcxResult<()> {
+        let session = self.session(&service_endpoint).await?;
+        session.send_message(msg, service_endpoint).await
+    }
+}
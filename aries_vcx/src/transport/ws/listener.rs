@@ -0,0 +1,213 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use messages::AriesMessage;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::{boxed_handler, WsSession};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Inbound WebSocket endpoint, meant for peers that cannot expose an endpoint of their own.
+///
+/// Every message received on an accepted socket is unpacked with the wallet and handed to the
+/// handler along with the verkey of its sender and, if the message asked for return route through
+/// its `~transport` decorator, the session to respond on.
+pub struct WsListener {
+    local_addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl WsListener {
+    /// Starts listening on the given address, which may use port `0` to pick any free port.
+    /// Must be called from within a Tokio runtime.
+    pub fn bind<F, Fut>(addr: SocketAddr, wallet: Arc<dyn BaseWallet>, handler: F) -> VcxResult<Self>
+    where
+        F: Fn(AriesMessage, Option<String>, Option<WsSession>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = VcxResult<()>> + Send + 'static,
+    {
+        let bind_error = |err: std::io::Error| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::IOError,
+                format!("WebSocket listener could not bind {}, err: {}", addr, err),
+            )
+        };
+        let listener = std::net::TcpListener::bind(addr).map_err(bind_error)?;
+        listener.set_nonblocking(true).map_err(bind_error)?;
+        let listener = TcpListener::from_std(listener).map_err(bind_error)?;
+        let local_addr = listener.local_addr().map_err(bind_error)?;
+
+        let handler = boxed_handler(handler);
+        let server = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(err) => {
+                        warn!("WsListener >> failed to accept connection, err: {}", err);
+                        continue;
+                    }
+                };
+                let wallet = wallet.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    match tokio_tungstenite::accept_async(stream).await {
+                        Ok(socket) => {
+                            debug!("WsListener >> opened session with {}", peer);
+                            WsSession::spawn(socket, wallet, handler);
+                        }
+                        Err(err) => warn!("WsListener >> handshake with {} failed, err: {}", peer, err),
+                    }
+                });
+            }
+        });
+        info!("WsListener >> listening on {}", local_addr);
+
+        Ok(Self { local_addr, server })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections. Sessions already open are kept until either side closes them.
+    pub fn shutdown(self) {
+        self.server.abort();
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use messages::msg_fields::protocols::notification::Notification;
+    use url::Url;
+
+    use super::*;
+    use crate::transport::ws::WsTransport;
+    use crate::transport::Transport;
+    use crate::utils::constants::VERKEY;
+    use crate::utils::mockdata::mockdata_mediated_connection::ARIES_CONNECTION_ACK;
+    use crate::utils::mockdata::profile::mock_wallet::MockWallet;
+
+    const ACK_THREAD_ID: &str = "b5517062-303f-4267-9a29-09bc89497c06";
+
+    // The mock wallet "unpacks" envelopes by returning them as they are.
+    fn envelope(transport: Option<serde_json::Value>) -> Vec<u8> {
+        let mut message: serde_json::Value = serde_json::from_str(ARIES_CONNECTION_ACK).unwrap();
+        if let Some(transport) = transport {
+            message["~transport"] = transport;
+        }
+        json!({
+            "message": message.to_string(),
+            "sender_verkey": VERKEY,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    /// Binds a listener which echoes messages on the session when return route was requested,
+    /// reporting whether it was.
+    fn bind_echo_listener(return_routes: async_channel::Sender<bool>) -> (WsListener, Url) {
+        let listener = WsListener::bind(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            Arc::new(MockWallet),
+            move |_, _, session| {
+                let return_routes = return_routes.clone();
+                async move {
+                    return_routes.send(session.is_some()).await.unwrap();
+                    if let Some(session) = session {
+                        let url = Url::parse("ws://unused").unwrap();
+                        session.send_message(envelope(None), url).await?;
+                    }
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+        let url = Url::parse(&format!("ws://{}", listener.local_addr())).unwrap();
+        (listener, url)
+    }
+
+    fn transport(sender: async_channel::Sender<(AriesMessage, Option<String>)>) -> WsTransport {
+        WsTransport::new(Arc::new(MockWallet), move |message, sender_vk, _| {
+            let sender = sender.clone();
+            async move {
+                sender.send((message, sender_vk)).await.unwrap();
+                Ok(())
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_returned_on_the_session() {
+        let (return_routes, _) = async_channel::unbounded();
+        let (listener, url) = bind_echo_listener(return_routes);
+        let (sender, receiver) = async_channel::unbounded();
+        let transport = transport(sender);
+
+        transport
+            .send_message(envelope(Some(json!({ "return_route": "all" }))), url.clone())
+            .await
+            .unwrap();
+        let (message, sender_vk) = receiver.recv().await.unwrap();
+        assert!(matches!(message, AriesMessage::Notification(Notification::Ack(_))));
+        assert_eq!(sender_vk.as_deref(), Some(VERKEY));
+
+        // The session is reused for subsequent messages.
+        let session = transport.session(&url).await.unwrap();
+        transport
+            .send_message(
+                envelope(Some(
+                    json!({ "return_route": "thread", "return_route_thread": ACK_THREAD_ID }),
+                )),
+                url.clone(),
+            )
+            .await
+            .unwrap();
+        receiver.recv().await.unwrap();
+        assert!(!session.is_closed());
+
+        transport.close(&url).await;
+        listener.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_no_session_without_return_route() {
+        let (return_routes, listener_receiver) = async_channel::unbounded();
+        let (listener, url) = bind_echo_listener(return_routes);
+        let (sender, _receiver) = async_channel::unbounded();
+        let transport = transport(sender);
+
+        let transports = [
+            None,
+            Some(json!({ "return_route": "none" })),
+            Some(json!({ "return_route": "all" })),
+            Some(json!({ "return_route": "thread" })),
+            Some(json!({ "return_route": "thread", "return_route_thread": "other-thread" })),
+            Some(json!({ "return_route": "thread", "return_route_thread": ACK_THREAD_ID })),
+        ];
+        for transport_decorator in transports {
+            transport
+                .send_message(envelope(transport_decorator), url.clone())
+                .await
+                .unwrap();
+        }
+        for expected in [false, false, true, false, false, true] {
+            assert_eq!(listener_receiver.recv().await.unwrap(), expected);
+        }
+
+        transport.close(&url).await;
+        listener.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_non_websocket_endpoints() {
+        let (sender, _receiver) = async_channel::unbounded();
+        let err = transport(sender)
+            .send_message(envelope(None), Url::parse("http://localhost:8080").unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidUrl);
+    }
+}
@@ -0,0 +1,26 @@
+mod client;
+mod listener;
+mod session;
+
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use messages::AriesMessage;
+
+use crate::errors::error::VcxResult;
+
+pub use client::WsTransport;
+pub use listener::WsListener;
+pub use session::WsSession;
+
+type InboundHandler =
+    Arc<dyn Fn(AriesMessage, Option<String>, Option<WsSession>) -> BoxFuture<'static, VcxResult<()>> + Send + Sync>;
+
+fn boxed_handler<F, Fut>(handler: F) -> InboundHandler
+where
+    F: Fn(AriesMessage, Option<String>, Option<WsSession>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = VcxResult<()>> + Send + 'static,
+{
+    Arc::new(move |message, sender_vk, session| Box::pin(handler(message, sender_vk, session)))
+}
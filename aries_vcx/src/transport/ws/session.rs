@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::future::{self, Either};
+use futures::stream::SplitStream;
+use futures::StreamExt;
+use messages::decorators::transport::{ReturnRoute, Transport as TransportDecorator};
+use messages::AriesMessage;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use url::Url;
+
+use super::InboundHandler;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+use crate::transport::Transport;
+use crate::utils::encryption_envelope::EncryptionEnvelope;
+use crate::utils::thread::message_thread;
+
+/// Handle to an open WebSocket through which packed messages are written.
+///
+/// As a [`Transport`], a session ignores the service endpoint it is given and writes messages to
+/// its socket. This is how responses are returned to peers that asked for it through the
+/// `~transport` decorator of their messages.
+#[derive(Clone, Debug)]
+pub struct WsSession {
+    sender: UnboundedSender<Message>,
+}
+
+impl WsSession {
+    /// Spawns the task driving the socket, which hands every message it receives to the handler
+    /// until either side closes the socket.
+    pub(super) fn spawn<S>(socket: WebSocketStream<S>, wallet: Arc<dyn BaseWallet>, handler: InboundHandler) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (sink, stream) = socket.split();
+        let (sender, receiver) = mpsc::unbounded();
+        let session = Self { sender };
+
+        let writer = receiver.map(Ok).forward(sink);
+        let reader = read_messages(stream, wallet, handler, session.clone());
+        tokio::spawn(async move {
+            if let Either::Left((Err(err), _)) = future::select(Box::pin(writer), Box::pin(reader)).await {
+                warn!("WsSession >> failed to write to socket, err: {}", err);
+            }
+        });
+
+        session
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Starts the closing handshake, messages sent afterwards are rejected.
+    pub fn close(&self) {
+        self.sender.unbounded_send(Message::Close(None)).ok();
+    }
+}
+
+#[async_trait]
+impl Transport for WsSession {
+    async fn send_message(&self, msg: Vec<u8>, _service_endpoint: Url) -> VcxResult<()> {
+        self.sender
+            .unbounded_send(Message::Binary(msg))
+            .map_err(|_| AriesVcxError::from_msg(AriesVcxErrorKind::PostMessageFailed, "WebSocket session is closed"))
+    }
+}
+
+async fn read_messages<S>(
+    mut stream: SplitStream<WebSocketStream<S>>,
+    wallet: Arc<dyn BaseWallet>,
+    handler: InboundHandler,
+    session: WsSession,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    while let Some(frame) = stream.next().await {
+        let payload = match frame {
+            Ok(Message::Binary(payload)) => payload,
+            Ok(Message::Text(payload)) => payload.into_bytes(),
+            Ok(Message::Close(_)) => break,
+            // Pings are answered by the socket itself.
+            Ok(_) => continue,
+            Err(err) => {
+                warn!("WsSession >> failed to read from socket, err: {}", err);
+                break;
+            }
+        };
+
+        let (message, sender_vk, transport) =
+            match EncryptionEnvelope::anon_unpack_with_transport(&wallet, payload).await {
+                Ok(unpacked) => unpacked,
+                Err(err) => {
+                    warn!("WsSession >> failed to unpack envelope, err: {}", err);
+                    continue;
+                }
+            };
+
+        // Without return route, responses are sent to the service endpoint of the sender.
+        let return_route = transport
+            .filter(|transport| returns_route(transport, &message))
+            .map(|_| session.clone());
+        if let Err(err) = handler(message, sender_vk, return_route).await {
+            error!("WsSession >> handler failed to process message, err: {}", err);
+        }
+    }
+}
+
+/// Whether responses to the message are to be returned on the session it was received on, which
+/// the `thread` return route only grants for messages on the thread it names.
+fn returns_route(transport: &TransportDecorator, message: &AriesMessage) -> bool {
+    match transport.return_route {
+        ReturnRoute::None => false,
+        ReturnRoute::All => true,
+        ReturnRoute::Thread => transport.return_route_thread.as_deref() == Some(message_thread(message).thid.as_str()),
+    }
+}
@@ -0,0 +1,503 @@
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use did_doc::schema::{did_doc::DidDocument, service::didcomm::select_service};
+use messages::AriesMessage;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::jwe::{Jwe, JweAlg, ENCRYPTED_MESSAGE_TYPE};
+use super::jws::Jws;
+use super::keys::{get_secret, KeyAgreementKey};
+use super::plaintext::{PlaintextMessage, PLAINTEXT_MESSAGE_TYPE};
+use crate::common::keys::verkey_to_did_key;
+use crate::errors::error::prelude::*;
+
+const FORWARD_V2_TYPE: &str = "https://didcomm.org/routing/2.0/forward";
+const DIDCOMM_V2_MEDIA_TYPE: &str = "didcomm/v2";
+
+/// DIDComm v2 counterpart of [`EncryptionEnvelope`](crate::utils::encryption_envelope::EncryptionEnvelope),
+/// holding a JWE encrypted message.
+///
+/// The [`AriesMessage`] is encrypted as a DIDComm v2 [`PlaintextMessage`], so existing protocols
+/// can be carried over DIDComm v2 envelopes while connections still using the legacy format keep working.
+#[derive(Debug)]
+pub struct EncryptionEnvelopeV2(pub Vec<u8>);
+
+/// A message unpacked from a [`EncryptionEnvelopeV2`].
+#[derive(Debug, Clone)]
+pub struct UnpackedMessageV2 {
+    pub message: AriesMessage,
+    /// DID of the sender, as stated by the `from` header of the plaintext message.
+    pub from: Option<String>,
+    /// Key ID of the authenticated sender, for authcrypted messages.
+    pub sender_kid: Option<String>,
+    /// Verkey which signed the message, for signed messages.
+    pub signer_verkey: Option<String>,
+}
+
+impl EncryptionEnvelopeV2 {
+    /// Packs `message` for the key agreement keys of `did_doc`, wrapping it into forward messages
+    /// for the routing keys of its DIDComm service.
+    ///
+    /// The message is authcrypted when `sender_verkey` is provided, which requires its key agreement
+    /// key to have been created through [`create_keys`](super::keys::create_keys), and anoncrypted otherwise.
+    pub async fn create<E: Serialize>(
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        sender_verkey: Option<&str>,
+        did_doc: &DidDocument<E>,
+    ) -> VcxResult<Self> {
+        trace!(
+            "EncryptionEnvelopeV2::create >>> message: {:?}, sender_verkey: {:?}, did_doc: {}",
+            message,
+            sender_verkey,
+            did_doc.id()
+        );
+
+        let recipients = KeyAgreementKey::from_did_doc(did_doc)?;
+        let routing_keys = routing_keys(did_doc)?;
+
+        // The sender is identified by the did:key of its verkey, which its key ID refers to.
+        let from = sender_verkey.map(verkey_to_did_key).transpose()?;
+        let plaintext = PlaintextMessage::from_aries_message(message, from, Some(vec![did_doc.id().to_string()]))?;
+        let plaintext = serde_json::to_vec(&plaintext)?;
+
+        let jwe = match sender_verkey {
+            Some(sender_verkey) => Self::authcrypt(wallet, &plaintext, sender_verkey, &recipients).await?,
+            None => Jwe::anoncrypt(&plaintext, &recipients)?,
+        };
+
+        Self::wrap_into_forward_messages(jwe, &did_doc.id().to_string(), &routing_keys).map(Self)
+    }
+
+    /// Signs `message` with `signer_verkey` and anoncrypts it for the key agreement keys of `did_doc`,
+    /// for messages which must be non-repudiable.
+    pub async fn create_signed<E: Serialize>(
+        wallet: &Arc<dyn BaseWallet>,
+        message: &AriesMessage,
+        signer_verkey: &str,
+        did_doc: &DidDocument<E>,
+    ) -> VcxResult<Self> {
+        let recipients = KeyAgreementKey::from_did_doc(did_doc)?;
+        let routing_keys = routing_keys(did_doc)?;
+
+        let from = verkey_to_did_key(signer_verkey)?;
+        let plaintext =
+            PlaintextMessage::from_aries_message(message, Some(from), Some(vec![did_doc.id().to_string()]))?;
+
+        let jws = Jws::sign(wallet, &serde_json::to_vec(&plaintext)?, signer_verkey).await?;
+        let jwe = Jwe::anoncrypt(&serde_json::to_vec(&jws)?, &recipients)?;
+
+        Self::wrap_into_forward_messages(jwe, &did_doc.id().to_string(), &routing_keys).map(Self)
+    }
+
+    async fn authcrypt(
+        wallet: &Arc<dyn BaseWallet>,
+        plaintext: &[u8],
+        sender_verkey: &str,
+        recipients: &[KeyAgreementKey],
+    ) -> VcxResult<Jwe> {
+        let sender = KeyAgreementKey::from_verkey(sender_verkey)?;
+        let sender_secret = get_secret(wallet, &sender.kid).await?.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::WalletRecordNotFound,
+                format!("No key agreement key found for sender verkey {}", sender_verkey),
+            )
+        })?;
+
+        Jwe::authcrypt(plaintext, &sender.kid, &sender_secret, recipients)
+    }
+
+    fn wrap_into_forward_messages(jwe: Jwe, recipient: &str, routing_keys: &[KeyAgreementKey]) -> VcxResult<Vec<u8>> {
+        let mut message = jwe;
+        let mut next = recipient.to_owned();
+
+        for routing_key in routing_keys {
+            let forward = json!({
+                "id": Uuid::new_v4().to_string(),
+                "typ": PLAINTEXT_MESSAGE_TYPE,
+                "type": FORWARD_V2_TYPE,
+                "to": [routing_key.kid],
+                "body": { "next": next },
+                "attachments": [{ "data": { "json": message } }],
+            });
+
+            message = Jwe::anoncrypt(&serde_json::to_vec(&forward)?, std::slice::from_ref(routing_key))?;
+            next = routing_key.kid.clone();
+        }
+
+        Ok(serde_json::to_vec(&message)?)
+    }
+
+    /// Unpacks a DIDComm v2 message encrypted for one of our key agreement keys. Authcrypted
+    /// messages need the sender's DID document, unless the sender is identified by a `did:key`.
+    pub async fn unpack<E>(
+        wallet: &Arc<dyn BaseWallet>,
+        payload: &[u8],
+        sender_did_doc: Option<&DidDocument<E>>,
+    ) -> VcxResult<UnpackedMessageV2> {
+        trace!(
+            "EncryptionEnvelopeV2::unpack >>> processing payload of {} bytes",
+            payload.len()
+        );
+
+        let jwe: Jwe = serde_json::from_slice(payload).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Cannot deserialize JWE: {}", err),
+            )
+        })?;
+        let header = jwe.protected_header()?;
+
+        let mut recipient = None;
+        for kid in jwe.recipients.iter().map(|r| &r.header.kid) {
+            if let Some(secret) = get_secret(wallet, kid).await? {
+                recipient = Some((kid.as_str(), secret));
+                break;
+            }
+        }
+        let (recipient_kid, recipient_secret) = recipient.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::WalletRecordNotFound,
+                "Message is not encrypted for any of our key agreement keys",
+            )
+        })?;
+
+        let sender = match (header.alg, header.skid.as_deref()) {
+            (JweAlg::Ecdh1puA256kw, Some(skid)) => Some(resolve_sender_key(skid, sender_did_doc)?),
+            _ => None,
+        };
+
+        let decrypted = jwe.decrypt(recipient_kid, &recipient_secret, sender.as_ref())?;
+        let decrypted: Value = serde_json::from_slice(&decrypted)?;
+
+        let (plaintext, signer_verkey) = if decrypted.get("signatures").is_some() {
+            let jws: Jws = serde_json::from_value(decrypted)?;
+            let (payload, signer_verkey) = jws.verify(wallet, None).await?;
+            (parse_plaintext(serde_json::from_slice(&payload)?)?, Some(signer_verkey))
+        } else {
+            (parse_plaintext(decrypted)?, None)
+        };
+
+        // The sender must not claim to be anybody else than the owner of the key it was
+        // authenticated, or signed the message, with.
+        if let Some(sender) = sender.as_ref() {
+            check_sender(&plaintext, &sender.kid)?;
+        }
+        if let Some(signer_verkey) = signer_verkey.as_deref() {
+            check_sender(&plaintext, &verkey_to_did_key(signer_verkey)?)?;
+        }
+
+        let from = plaintext.from.clone();
+        Ok(UnpackedMessageV2 {
+            message: plaintext.into_aries_message()?,
+            from,
+            sender_kid: sender.map(|sender| sender.kid),
+            signer_verkey,
+        })
+    }
+
+    /// Tells DIDComm v2 encrypted messages apart from legacy (indy pack) envelopes.
+    pub fn is_v2(payload: &[u8]) -> bool {
+        serde_json::from_slice::<Jwe>(payload)
+            .ok()
+            .and_then(|jwe| jwe.protected_header().ok())
+            .map_or(false, |header| header.typ == ENCRYPTED_MESSAGE_TYPE)
+    }
+}
+
+fn parse_plaintext(value: Value) -> VcxResult<PlaintextMessage> {
+    serde_json::from_value(value).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot deserialize DIDComm v2 plaintext message: {}", err),
+        )
+    })
+}
+
+/// Checks the `from` header of `plaintext` is the DID of the key ID `kid`.
+fn check_sender(plaintext: &PlaintextMessage, kid: &str) -> VcxResult<()> {
+    let did = kid.split('#').next().unwrap_or_default();
+
+    match plaintext.from.as_deref() {
+        Some(from) if from == did => Ok(()),
+        from => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Message from {:?} was sent with key {} of another DID", from, kid),
+        )),
+    }
+}
+
+/// Looks up the sender key `skid` in the sender's DID document, by its full DID URL. Relative key
+/// IDs of the document are resolved against the document's DID.
+fn resolve_sender_key<E>(skid: &str, sender_did_doc: Option<&DidDocument<E>>) -> VcxResult<KeyAgreementKey> {
+    if skid.starts_with("did:key:") {
+        return KeyAgreementKey::from_did_key(skid);
+    }
+
+    let did_doc = sender_did_doc.ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("Sender DID document is required to resolve sender key {}", skid),
+        )
+    })?;
+    let did = did_doc.id().to_string();
+
+    KeyAgreementKey::from_did_doc(did_doc)?
+        .into_iter()
+        .find(|key| match key.kid.strip_prefix('#') {
+            Some(fragment) => skid.strip_prefix(did.as_str()).and_then(|rest| rest.strip_prefix('#')) == Some(fragment),
+            None => key.kid == skid,
+        })
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Sender key {} not found in DID document {}", skid, did),
+            )
+        })
+}
+
+/// Routing keys of the DIDComm service of `did_doc` to deliver DIDComm v2 messages to,
+/// falling back to its best DIDComm service of any kind. Only `did:key` routing keys are supported.
+fn routing_keys<E: Serialize>(did_doc: &DidDocument<E>) -> VcxResult<Vec<KeyAgreementKey>> {
+    let services = did_doc.didcomm_services().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidDid,
+            format!("Invalid DIDComm service in DID document {}: {}", did_doc.id(), err),
+        )
+    })?;
+    let service =
+        select_service(&services, None, Some(DIDCOMM_V2_MEDIA_TYPE)).or_else(|| select_service(&services, None, None));
+
+    let Some(service) = service else {
+        return Ok(Vec::new());
+    };
+
+    service
+        .routing_keys()
+        .iter()
+        .map(|key| KeyAgreementKey::from_did_key(key))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use std::collections::HashMap;
+
+    use aries_vcx_core::wallet::indy_wallet::IndySdkWallet;
+    use messages::msg_fields::protocols::trust_ping::ping::{Ping, PingContent, PingDecorators};
+
+    use super::*;
+    use crate::utils::{
+        devsetup::SetupLibraryWallet,
+        didcomm_v2::keys::{add_key_id, create_keys},
+    };
+
+    type ExtraFields = HashMap<String, Value>;
+
+    const BOB_DID: &str = "did:example:bob";
+    const ALICE_DID: &str = "did:example:alice";
+
+    fn _message() -> AriesMessage {
+        Ping::with_decorators(
+            Uuid::new_v4().to_string(),
+            PingContent::default(),
+            PingDecorators::default(),
+        )
+        .into()
+    }
+
+    fn _did_doc(did: &str, relationship: &str, verkey: &str) -> DidDocument<ExtraFields> {
+        serde_json::from_value(json!({
+            "id": did,
+            relationship: [{
+                "id": format!("{did}#key-1"),
+                "type": "Ed25519VerificationKey2018",
+                "controller": did,
+                "publicKeyBase58": verkey
+            }]
+        }))
+        .unwrap()
+    }
+
+    async fn _bob(wallet: &Arc<dyn BaseWallet>) -> DidDocument<ExtraFields> {
+        let (_, verkey) = create_keys(wallet).await.unwrap();
+        add_key_id(wallet, &verkey, &format!("{BOB_DID}#key-1")).await.unwrap();
+        _did_doc(BOB_DID, "authentication", &verkey)
+    }
+
+    async fn _authcrypt(
+        wallet: &Arc<dyn BaseWallet>,
+        plaintext: &PlaintextMessage,
+        sender_verkey: &str,
+        skid: &str,
+        recipient_doc: &DidDocument<ExtraFields>,
+    ) -> Vec<u8> {
+        let did_key_kid = KeyAgreementKey::from_verkey(sender_verkey).unwrap().kid;
+        let secret = get_secret(wallet, &did_key_kid).await.unwrap().unwrap();
+        let recipients = KeyAgreementKey::from_did_doc(recipient_doc).unwrap();
+
+        let jwe = Jwe::authcrypt(&serde_json::to_vec(plaintext).unwrap(), skid, &secret, &recipients).unwrap();
+        serde_json::to_vec(&jwe).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_anoncrypt_plaintext_message() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let message = _message();
+
+            let EncryptionEnvelopeV2(packed) = EncryptionEnvelopeV2::create(&wallet, &message, None, &bob)
+                .await
+                .unwrap();
+            assert!(EncryptionEnvelopeV2::is_v2(&packed));
+
+            let jwe: Jwe = serde_json::from_slice(&packed).unwrap();
+            let kid = format!("{BOB_DID}#key-1");
+            let secret = get_secret(&wallet, &kid).await.unwrap().unwrap();
+            let plaintext: Value = serde_json::from_slice(&jwe.decrypt(&kid, &secret, None).unwrap()).unwrap();
+            assert_eq!(plaintext["typ"], PLAINTEXT_MESSAGE_TYPE);
+            assert_eq!(plaintext["type"], "https://didcomm.org/trust_ping/1.0/ping");
+            assert_eq!(plaintext["to"], json!([BOB_DID]));
+            assert!(plaintext["id"].is_string());
+            assert!(plaintext["body"].is_object());
+            assert!(plaintext.get("from").is_none());
+
+            let unpacked = EncryptionEnvelopeV2::unpack::<ExtraFields>(&wallet, &packed, None)
+                .await
+                .unwrap();
+            assert_eq!(unpacked.message, message);
+            assert_eq!(unpacked.from, None);
+            assert_eq!(unpacked.sender_kid, None);
+            assert_eq!(unpacked.signer_verkey, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_authcrypt_plaintext_message() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let (_, alice_verkey) = create_keys(&wallet).await.unwrap();
+            let message = _message();
+
+            let EncryptionEnvelopeV2(packed) =
+                EncryptionEnvelopeV2::create(&wallet, &message, Some(&alice_verkey), &bob)
+                    .await
+                    .unwrap();
+
+            let unpacked = EncryptionEnvelopeV2::unpack::<ExtraFields>(&wallet, &packed, None)
+                .await
+                .unwrap();
+            assert_eq!(unpacked.message, message);
+            assert_eq!(unpacked.from, Some(verkey_to_did_key(&alice_verkey).unwrap()));
+            assert_eq!(
+                unpacked.sender_kid,
+                Some(KeyAgreementKey::from_verkey(&alice_verkey).unwrap().kid)
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_signed_plaintext_message() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let (_, alice_verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let message = _message();
+
+            let EncryptionEnvelopeV2(packed) =
+                EncryptionEnvelopeV2::create_signed(&wallet, &message, &alice_verkey, &bob)
+                    .await
+                    .unwrap();
+
+            let unpacked = EncryptionEnvelopeV2::unpack::<ExtraFields>(&wallet, &packed, None)
+                .await
+                .unwrap();
+            assert_eq!(unpacked.message, message);
+            assert_eq!(unpacked.from, Some(verkey_to_did_key(&alice_verkey).unwrap()));
+            assert_eq!(unpacked.signer_verkey, Some(alice_verkey));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_authcrypt_with_sender_did_doc() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let (_, alice_verkey) = create_keys(&wallet).await.unwrap();
+            let alice = _did_doc(ALICE_DID, "keyAgreement", &alice_verkey);
+            let skid = format!("{ALICE_DID}#key-1");
+
+            let message = _message();
+            let plaintext = PlaintextMessage::from_aries_message(
+                &message,
+                Some(ALICE_DID.to_owned()),
+                Some(vec![BOB_DID.to_owned()]),
+            )
+            .unwrap();
+            let packed = _authcrypt(&wallet, &plaintext, &alice_verkey, &skid, &bob).await;
+
+            EncryptionEnvelopeV2::unpack::<ExtraFields>(&wallet, &packed, None)
+                .await
+                .unwrap_err();
+
+            let unpacked = EncryptionEnvelopeV2::unpack(&wallet, &packed, Some(&alice))
+                .await
+                .unwrap();
+            assert_eq!(unpacked.message, message);
+            assert_eq!(unpacked.from.as_deref(), Some(ALICE_DID));
+            assert_eq!(unpacked.sender_kid, Some(skid));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_sender_key_of_other_did_is_rejected() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let (_, alice_verkey) = create_keys(&wallet).await.unwrap();
+            let alice = _did_doc(ALICE_DID, "keyAgreement", &alice_verkey);
+
+            // Same fragment as Alice's key, but the key ID of another DID.
+            let skid = "did:example:mallory#key-1";
+            let plaintext =
+                PlaintextMessage::from_aries_message(&_message(), Some("did:example:mallory".to_owned()), None)
+                    .unwrap();
+            let packed = _authcrypt(&wallet, &plaintext, &alice_verkey, skid, &bob).await;
+
+            EncryptionEnvelopeV2::unpack(&wallet, &packed, Some(&alice))
+                .await
+                .unwrap_err();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_from_is_rejected() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let bob = _bob(&wallet).await;
+            let (_, alice_verkey) = create_keys(&wallet).await.unwrap();
+            let skid = KeyAgreementKey::from_verkey(&alice_verkey).unwrap().kid;
+
+            for from in [Some("did:example:mallory".to_owned()), None] {
+                let plaintext = PlaintextMessage::from_aries_message(&_message(), from, None).unwrap();
+                let packed = _authcrypt(&wallet, &plaintext, &alice_verkey, &skid, &bob).await;
+
+                EncryptionEnvelopeV2::unpack::<ExtraFields>(&wallet, &packed, None)
+                    .await
+                    .unwrap_err();
+            }
+        })
+        .await;
+    }
+}
@@ -0,0 +1,435 @@
+//! JWE general JSON serialization of DIDComm v2 encrypted messages,
+//! see <https://identity.foundation/didcomm-messaging/spec/#didcomm-encrypted-messages>.
+//!
+//! Anoncrypt uses `ECDH-ES+A256KW`, authcrypt uses `ECDH-1PU+A256KW`, both over X25519 and
+//! with `A256CBC-HS512` content encryption.
+
+use openssl::aes::{unwrap_key, wrap_key, AesKey};
+use openssl::hash::MessageDigest;
+use openssl::memcmp;
+use openssl::pkey::PKey;
+use openssl::rand::rand_bytes;
+use openssl::sha::sha256;
+use openssl::sign::Signer;
+use openssl::symm::{decrypt, encrypt, Cipher};
+
+use super::keys::{ecdh, generate_x25519, to_key_array, KeyAgreementKey};
+use super::{decode_b64, encode_b64};
+use crate::errors::error::prelude::*;
+
+pub const ENCRYPTED_MESSAGE_TYPE: &str = "application/didcomm-encrypted+json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JweAlg {
+    #[serde(rename = "ECDH-ES+A256KW")]
+    EcdhEsA256kw,
+    #[serde(rename = "ECDH-1PU+A256KW")]
+    Ecdh1puA256kw,
+}
+
+impl JweAlg {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JweAlg::EcdhEsA256kw => "ECDH-ES+A256KW",
+            JweAlg::Ecdh1puA256kw => "ECDH-1PU+A256KW",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JweEnc {
+    #[serde(rename = "A256CBC-HS512")]
+    A256CbcHs512,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EphemeralJwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProtectedHeader {
+    pub typ: String,
+    pub alg: JweAlg,
+    pub enc: JweEnc,
+    pub epk: EphemeralJwk,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apu: Option<String>,
+    pub apv: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecipientHeader {
+    pub kid: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recipient {
+    pub header: RecipientHeader,
+    pub encrypted_key: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Jwe {
+    pub protected: String,
+    pub recipients: Vec<Recipient>,
+    pub iv: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+impl Jwe {
+    pub fn protected_header(&self) -> VcxResult<ProtectedHeader> {
+        Ok(serde_json::from_slice(&decode_b64(&self.protected)?)?)
+    }
+
+    /// Encrypts `plaintext` for `recipients` without authenticating the sender.
+    pub fn anoncrypt(plaintext: &[u8], recipients: &[KeyAgreementKey]) -> VcxResult<Self> {
+        Self::encrypt(plaintext, None, recipients)
+    }
+
+    /// Encrypts `plaintext` for `recipients`, authenticating the sender through its key agreement
+    /// key identified by `sender_kid` and its X25519 `sender_secret`.
+    pub fn authcrypt(
+        plaintext: &[u8],
+        sender_kid: &str,
+        sender_secret: &[u8; 32],
+        recipients: &[KeyAgreementKey],
+    ) -> VcxResult<Self> {
+        Self::encrypt(plaintext, Some((sender_kid, sender_secret)), recipients)
+    }
+
+    fn encrypt(plaintext: &[u8], sender: Option<(&str, &[u8; 32])>, recipients: &[KeyAgreementKey]) -> VcxResult<Self> {
+        if recipients.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Cannot encrypt message without recipients",
+            ));
+        }
+
+        let (ephemeral_secret, ephemeral_public) = generate_x25519()?;
+
+        let alg = match sender {
+            Some(_) => JweAlg::Ecdh1puA256kw,
+            None => JweAlg::EcdhEsA256kw,
+        };
+
+        let header = ProtectedHeader {
+            typ: ENCRYPTED_MESSAGE_TYPE.to_owned(),
+            alg,
+            enc: JweEnc::A256CbcHs512,
+            epk: EphemeralJwk {
+                kty: "OKP".to_owned(),
+                crv: "X25519".to_owned(),
+                x: encode_b64(&ephemeral_public),
+            },
+            skid: sender.map(|(kid, _)| kid.to_owned()),
+            apu: sender.map(|(kid, _)| encode_b64(kid.as_bytes())),
+            apv: encode_b64(&apv(recipients.iter().map(|r| r.kid.as_str()))),
+        };
+
+        let protected = encode_b64(&serde_json::to_vec(&header)?);
+        let cek = random_bytes(64)?;
+        let iv = random_bytes(16)?;
+        let (ciphertext, tag) = encrypt_content(header.enc, &cek, &iv, protected.as_bytes(), plaintext)?;
+
+        let recipients = recipients
+            .iter()
+            .map(|recipient| {
+                let mut z = ecdh(&ephemeral_secret, &recipient.public_key)?;
+                if let Some((_, sender_secret)) = sender {
+                    z.extend(ecdh(sender_secret, &recipient.public_key)?);
+                }
+
+                let kek = derive_kek(&z, &header, &tag)?;
+                Ok(Recipient {
+                    header: RecipientHeader {
+                        kid: recipient.kid.clone(),
+                    },
+                    encrypted_key: encode_b64(&aes_key_wrap(&kek, &cek)?),
+                })
+            })
+            .collect::<VcxResult<Vec<_>>>()?;
+
+        Ok(Self {
+            protected,
+            recipients,
+            iv: encode_b64(&iv),
+            ciphertext: encode_b64(&ciphertext),
+            tag: encode_b64(&tag),
+        })
+    }
+
+    /// Decrypts the message as the recipient `recipient_kid`, owning the X25519 `recipient_secret`.
+    /// Authcrypted messages additionally require the sender's key agreement key, as referenced by `skid`.
+    pub fn decrypt(
+        &self,
+        recipient_kid: &str,
+        recipient_secret: &[u8; 32],
+        sender: Option<&KeyAgreementKey>,
+    ) -> VcxResult<Vec<u8>> {
+        let header = self.protected_header()?;
+
+        let recipient = self
+            .recipients
+            .iter()
+            .find(|recipient| recipient.header.kid == recipient_kid)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Message is not encrypted for {}", recipient_kid),
+                )
+            })?;
+
+        if header.apv != encode_b64(&apv(self.recipients.iter().map(|r| r.header.kid.as_str()))) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "JWE apv does not match its recipients",
+            ));
+        }
+
+        let ephemeral_public = to_key_array(&decode_b64(&header.epk.x)?)?;
+        let mut z = ecdh(recipient_secret, &ephemeral_public)?;
+
+        match (header.alg, sender) {
+            (JweAlg::EcdhEsA256kw, _) => {}
+            (JweAlg::Ecdh1puA256kw, Some(sender)) if header.skid.as_deref() == Some(sender.kid.as_str()) => {
+                z.extend(ecdh(recipient_secret, &sender.public_key)?);
+            }
+            (JweAlg::Ecdh1puA256kw, _) => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Sender key {:?} of authcrypted message is missing", header.skid),
+                ))
+            }
+        }
+
+        let tag = decode_b64(&self.tag)?;
+        let kek = derive_kek(&z, &header, &tag)?;
+        let cek = aes_key_unwrap(&kek, &decode_b64(&recipient.encrypted_key)?)?;
+
+        decrypt_content(
+            header.enc,
+            &cek,
+            &decode_b64(&self.iv)?,
+            self.protected.as_bytes(),
+            &decode_b64(&self.ciphertext)?,
+            &tag,
+        )
+    }
+}
+
+fn apv<'a>(kids: impl Iterator<Item = &'a str>) -> [u8; 32] {
+    let mut kids = kids.collect::<Vec<_>>();
+    kids.sort_unstable();
+    sha256(kids.join(".").as_bytes())
+}
+
+/// Concat KDF of [RFC 7518](https://www.rfc-editor.org/rfc/rfc7518#section-4.6.2), the ECDH-1PU
+/// variant appending the content encryption tag to the `SuppPubInfo`.
+fn derive_kek(z: &[u8], header: &ProtectedHeader, tag: &[u8]) -> VcxResult<[u8; 32]> {
+    fn with_length(data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u32).to_be_bytes().to_vec();
+        out.extend(data);
+        out
+    }
+
+    let apu = header.apu.as_deref().map(decode_b64).transpose()?.unwrap_or_default();
+    let apv = decode_b64(&header.apv)?;
+
+    let mut input = 1u32.to_be_bytes().to_vec();
+    input.extend(z);
+    input.extend(with_length(header.alg.as_str().as_bytes()));
+    input.extend(with_length(&apu));
+    input.extend(with_length(&apv));
+    input.extend(256u32.to_be_bytes());
+    if header.alg == JweAlg::Ecdh1puA256kw {
+        input.extend(with_length(tag));
+    }
+
+    Ok(sha256(&input))
+}
+
+fn encrypt_content(enc: JweEnc, cek: &[u8], iv: &[u8], aad: &[u8], plaintext: &[u8]) -> VcxResult<(Vec<u8>, Vec<u8>)> {
+    match enc {
+        JweEnc::A256CbcHs512 => {
+            let (mac_key, enc_key) = cek.split_at(32);
+            let ciphertext = encrypt(Cipher::aes_256_cbc(), enc_key, Some(iv), plaintext).map_err(crypto_err)?;
+            let tag = cbc_hmac_tag(mac_key, iv, aad, &ciphertext)?;
+            Ok((ciphertext, tag))
+        }
+    }
+}
+
+fn decrypt_content(
+    enc: JweEnc,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> VcxResult<Vec<u8>> {
+    let decrypted = match enc {
+        JweEnc::A256CbcHs512 => {
+            if cek.len() != 64 {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "Invalid A256CBC-HS512 content encryption key",
+                ));
+            }
+            let (mac_key, enc_key) = cek.split_at(32);
+            let expected_tag = cbc_hmac_tag(mac_key, iv, aad, ciphertext)?;
+            if tag.len() != expected_tag.len() || !memcmp::eq(tag, &expected_tag) {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "JWE authentication tag mismatch",
+                ));
+            }
+            decrypt(Cipher::aes_256_cbc(), enc_key, Some(iv), ciphertext)
+        }
+    };
+
+    decrypted.map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot decrypt JWE: {}", err),
+        )
+    })
+}
+
+fn cbc_hmac_tag(mac_key: &[u8], iv: &[u8], aad: &[u8], ciphertext: &[u8]) -> VcxResult<Vec<u8>> {
+    let key = PKey::hmac(mac_key).map_err(crypto_err)?;
+    let mut signer = Signer::new(MessageDigest::sha512(), &key).map_err(crypto_err)?;
+
+    let aad_length = (aad.len() as u64 * 8).to_be_bytes();
+    for data in [aad, iv, ciphertext, &aad_length[..]] {
+        signer.update(data).map_err(crypto_err)?;
+    }
+
+    let mut tag = signer.sign_to_vec().map_err(crypto_err)?;
+    tag.truncate(32);
+    Ok(tag)
+}
+
+fn aes_key_wrap(kek: &[u8; 32], cek: &[u8]) -> VcxResult<Vec<u8>> {
+    let key = AesKey::new_encrypt(kek)
+        .map_err(|_| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidInput, "Invalid key encryption key"))?;
+
+    let mut wrapped = vec![0; cek.len() + 8];
+    wrap_key(&key, None, &mut wrapped, cek)
+        .map_err(|_| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidInput, "Cannot wrap content encryption key"))?;
+    Ok(wrapped)
+}
+
+fn aes_key_unwrap(kek: &[u8; 32], wrapped: &[u8]) -> VcxResult<Vec<u8>> {
+    let key = AesKey::new_decrypt(kek)
+        .map_err(|_| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidInput, "Invalid key encryption key"))?;
+
+    if wrapped.len() < 16 {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Invalid wrapped content encryption key",
+        ));
+    }
+
+    let mut cek = vec![0; wrapped.len() - 8];
+    unwrap_key(&key, None, &mut cek, wrapped).map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Cannot unwrap content encryption key",
+        )
+    })?;
+    Ok(cek)
+}
+
+fn random_bytes(len: usize) -> VcxResult<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    rand_bytes(&mut bytes).map_err(crypto_err)?;
+    Ok(bytes)
+}
+
+fn crypto_err(err: openssl::error::ErrorStack) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::UnknownError,
+        format!("Cryptographic operation failed: {}", err),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn make_key(kid: &str) -> ([u8; 32], KeyAgreementKey) {
+        let (secret, public_key) = generate_x25519().unwrap();
+        (secret, KeyAgreementKey::new(kid.to_owned(), public_key))
+    }
+
+    #[test]
+    fn test_anoncrypt_roundtrip() {
+        let (alice_secret, alice) = make_key("did:example:alice#key-x25519-1");
+        let (_, bob) = make_key("did:example:bob#key-x25519-1");
+
+        let jwe = Jwe::anoncrypt(b"hello", &[alice.clone(), bob]).unwrap();
+        let header = jwe.protected_header().unwrap();
+        assert_eq!(header.alg, JweAlg::EcdhEsA256kw);
+        assert_eq!(header.skid, None);
+
+        let decrypted = jwe.decrypt(&alice.kid, &alice_secret, None).unwrap();
+        assert_eq!(decrypted, b"hello");
+    }
+
+    #[test]
+    fn test_authcrypt_roundtrip() {
+        let (sender_secret, sender) = make_key("did:example:alice#key-x25519-1");
+        let (bob_secret, bob) = make_key("did:example:bob#key-x25519-1");
+
+        let jwe = Jwe::authcrypt(b"hello", &sender.kid, &sender_secret, &[bob.clone()]).unwrap();
+        let header = jwe.protected_header().unwrap();
+        assert_eq!(header.alg, JweAlg::Ecdh1puA256kw);
+        assert_eq!(header.skid, Some(sender.kid.clone()));
+
+        let decrypted = jwe.decrypt(&bob.kid, &bob_secret, Some(&sender)).unwrap();
+        assert_eq!(decrypted, b"hello");
+
+        jwe.decrypt(&bob.kid, &bob_secret, None).unwrap_err();
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_is_rejected() {
+        let (bob_secret, bob) = make_key("did:example:bob#key-x25519-1");
+
+        let mut jwe = Jwe::anoncrypt(b"hello", &[bob.clone()]).unwrap();
+        let mut ciphertext = decode_b64(&jwe.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        jwe.ciphertext = encode_b64(&ciphertext);
+
+        jwe.decrypt(&bob.kid, &bob_secret, None).unwrap_err();
+    }
+
+    #[test]
+    fn test_unsupported_content_encryption_is_rejected() {
+        let (bob_secret, bob) = make_key("did:example:bob#key-x25519-1");
+
+        let mut jwe = Jwe::anoncrypt(b"hello", &[bob.clone()]).unwrap();
+        let mut header = serde_json::to_value(jwe.protected_header().unwrap()).unwrap();
+        header["enc"] = json!("A256GCM");
+        jwe.protected = encode_b64(&serde_json::to_vec(&header).unwrap());
+
+        jwe.protected_header().unwrap_err();
+        jwe.decrypt(&bob.kid, &bob_secret, None).unwrap_err();
+    }
+
+    #[test]
+    fn test_wrong_recipient_is_rejected() {
+        let (_, bob) = make_key("did:example:bob#key-x25519-1");
+        let (eve_secret, eve) = make_key("did:example:eve#key-x25519-1");
+
+        let jwe = Jwe::anoncrypt(b"hello", &[bob]).unwrap();
+        jwe.decrypt(&eve.kid, &eve_secret, None).unwrap_err();
+    }
+}
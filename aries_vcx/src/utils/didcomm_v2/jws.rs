@@ -0,0 +1,263 @@
+//! JWS general JSON serialization of DIDComm v2 signed messages,
+//! see <https://identity.foundation/didcomm-messaging/spec/#didcomm-signed-messages>.
+
+use std::sync::Arc;
+
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+
+use super::{decode_b64, encode_b64};
+use crate::common::keys::{did_key_to_verkey, verkey_to_did_key};
+use crate::errors::error::prelude::*;
+
+pub const SIGNED_MESSAGE_TYPE: &str = "application/didcomm-signed+json";
+
+/// The only signature algorithm supported, as our keys are Ed25519 keys.
+const EDDSA_ALG: &str = "EdDSA";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProtectedSignatureHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    pub alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignatureHeader {
+    pub kid: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Signature {
+    pub protected: String,
+    pub signature: String,
+    pub header: SignatureHeader,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Jws {
+    pub payload: String,
+    pub signatures: Vec<Signature>,
+}
+
+impl Jws {
+    /// Signs `payload` with the Ed25519 key `signer_verkey` held in the wallet. The signature
+    /// is identified by the key's `did:key` key ID.
+    pub async fn sign(wallet: &Arc<dyn BaseWallet>, payload: &[u8], signer_verkey: &str) -> VcxResult<Self> {
+        let did_key = verkey_to_did_key(signer_verkey)?;
+        let kid = format!("{}#{}", did_key, did_key.trim_start_matches("did:key:"));
+
+        let protected = ProtectedSignatureHeader {
+            typ: Some(SIGNED_MESSAGE_TYPE.to_owned()),
+            alg: EDDSA_ALG.to_owned(),
+            kid: Some(kid.clone()),
+        };
+        let protected = encode_b64(&serde_json::to_vec(&protected)?);
+        let payload = encode_b64(payload);
+
+        let signature = wallet
+            .sign(signer_verkey, format!("{}.{}", protected, payload).as_bytes())
+            .await?;
+
+        Ok(Self {
+            payload,
+            signatures: vec![Signature {
+                protected,
+                signature: encode_b64(&signature),
+                header: SignatureHeader { kid },
+            }],
+        })
+    }
+
+    /// Verifies the first signature, returning the payload and the verkey it was signed with.
+    ///
+    /// The signature must be an `EdDSA` signature of the `did:key` key ID of its header, which must
+    /// be the one of `signer_verkey` when provided.
+    pub async fn verify(
+        &self,
+        wallet: &Arc<dyn BaseWallet>,
+        signer_verkey: Option<&str>,
+    ) -> VcxResult<(Vec<u8>, String)> {
+        let signature = self.signatures.first().ok_or_else(|| {
+            AriesVcxError::from_msg(AriesVcxErrorKind::InvalidMessageFormat, "JWS contains no signature")
+        })?;
+
+        let protected: ProtectedSignatureHeader =
+            serde_json::from_slice(&decode_b64(&signature.protected)?).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("Cannot deserialize JWS protected header: {}", err),
+                )
+            })?;
+
+        if protected.alg != EDDSA_ALG {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Unsupported JWS algorithm {}", protected.alg),
+            ));
+        }
+
+        let kid = &signature.header.kid;
+        if protected
+            .kid
+            .as_ref()
+            .map_or(false, |protected_kid| protected_kid != kid)
+        {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!(
+                    "JWS protected header key ID {:?} does not match signature key ID {}",
+                    protected.kid, kid
+                ),
+            ));
+        }
+
+        if !kid.starts_with("did:key:") {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("JWS key ID {} is not a did:key", kid),
+            ));
+        }
+        let verkey = did_key_to_verkey(kid)?;
+
+        if let Some(signer_verkey) = signer_verkey {
+            if signer_verkey != verkey {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("JWS was signed by {} instead of {}", verkey, signer_verkey),
+                ));
+            }
+        }
+
+        let signature_bytes = decode_b64(&signature.signature)?;
+        let signing_input = format!("{}.{}", signature.protected, self.payload);
+
+        if !wallet
+            .verify(&verkey, signing_input.as_bytes(), &signature_bytes)
+            .await?
+        {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Invalid JWS signature of {}", verkey),
+            ));
+        }
+
+        Ok((decode_b64(&self.payload)?, verkey))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use aries_vcx_core::wallet::indy_wallet::IndySdkWallet;
+
+    use super::*;
+    use crate::utils::devsetup::SetupLibraryWallet;
+
+    fn _with_protected(mut jws: Jws, protected: &ProtectedSignatureHeader) -> Jws {
+        jws.signatures[0].protected = encode_b64(&serde_json::to_vec(protected).unwrap());
+        jws
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            let jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+            assert!(jws.signatures[0]
+                .header
+                .kid
+                .starts_with(&verkey_to_did_key(&verkey).unwrap()));
+
+            let (payload, signer) = jws.verify(&wallet, None).await.unwrap();
+            assert_eq!(payload, b"hello");
+            assert_eq!(signer, verkey);
+
+            let (payload, _) = jws.verify(&wallet, Some(&verkey)).await.unwrap();
+            assert_eq!(payload, b"hello");
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_other_signer() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let (_, other_verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            let jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+            jws.verify(&wallet, Some(&other_verkey)).await.unwrap_err();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_tampered_payload() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            let mut jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+            jws.payload = encode_b64(b"goodbye");
+            jws.verify(&wallet, None).await.unwrap_err();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_replaced_kid() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let (_, other_verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+            let other_kid = verkey_to_did_key(&other_verkey).unwrap();
+
+            // The unprotected key ID no longer matches the protected one.
+            let mut jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+            jws.signatures[0].header.kid = other_kid.clone();
+            jws.verify(&wallet, None).await.unwrap_err();
+
+            // Key IDs which are not did:keys cannot be verified.
+            let jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+            let mut jws = _with_protected(
+                jws,
+                &ProtectedSignatureHeader {
+                    typ: Some(SIGNED_MESSAGE_TYPE.to_owned()),
+                    alg: EDDSA_ALG.to_owned(),
+                    kid: None,
+                },
+            );
+            jws.signatures[0].header.kid = verkey.clone();
+            jws.verify(&wallet, None).await.unwrap_err();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_other_algorithms() {
+        SetupLibraryWallet::run(|setup| async move {
+            let wallet: Arc<dyn BaseWallet> = Arc::new(IndySdkWallet::new(setup.wallet_handle));
+            let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+            for alg in ["ES256", "none", ""] {
+                let jws = Jws::sign(&wallet, b"hello", &verkey).await.unwrap();
+                let kid = jws.signatures[0].header.kid.clone();
+                let jws = _with_protected(
+                    jws,
+                    &ProtectedSignatureHeader {
+                        typ: Some(SIGNED_MESSAGE_TYPE.to_owned()),
+                        alg: alg.to_owned(),
+                        kid: Some(kid),
+                    },
+                );
+
+                jws.verify(&wallet, None).await.unwrap_err();
+            }
+        })
+        .await;
+    }
+}
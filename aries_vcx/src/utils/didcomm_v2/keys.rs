@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aries_vcx_core::errors::error::AriesVcxCoreErrorKind;
+use aries_vcx_core::wallet::base_wallet::BaseWallet;
+use did_doc::schema::{
+    did_doc::DidDocument,
+    types::public_key::KeyType,
+    verification_method::{VerificationMethod, VerificationMethodKind},
+};
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::derive::Deriver;
+use openssl::pkey::{Id, PKey};
+use openssl::sha::sha512;
+
+use crate::common::keys::verkey_to_did_key;
+use crate::errors::error::prelude::*;
+use crate::utils::random::generate_random_seed;
+
+const ED25519_MULTICODEC_PREFIX: [u8; 2] = [0xed, 0x01];
+const X25519_MULTICODEC_PREFIX: [u8; 2] = [0xec, 0x01];
+
+/// Wallet record type under which the X25519 secrets of our key agreement keys are stored,
+/// keyed by their key ID.
+const KEY_AGREEMENT_RECORD_TYPE: &str = "DidCommV2KeyAgreement";
+
+/// X25519 public key used for ECDH key agreement, identified by its key ID (a DID URL).
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyAgreementKey {
+    pub kid: String,
+    pub public_key: [u8; 32],
+}
+
+impl KeyAgreementKey {
+    pub fn new(kid: String, public_key: [u8; 32]) -> Self {
+        Self { kid, public_key }
+    }
+
+    /// Creates the key agreement key derived from an Ed25519 verkey,
+    /// identified the same way as in the expanded `did:key` document.
+    pub fn from_verkey(verkey: &str) -> VcxResult<Self> {
+        let public_key = ed25519_to_x25519_public(&decode_verkey(verkey)?)?;
+        let kid = format!("{}#{}", verkey_to_did_key(verkey)?, x25519_to_multibase(&public_key));
+        Ok(Self { kid, public_key })
+    }
+
+    /// Resolves a `did:key` key ID, holding either an Ed25519 or a X25519 key.
+    pub fn from_did_key(kid: &str) -> VcxResult<Self> {
+        let Some(did_key) = kid.strip_prefix("did:key:") else {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidDid,
+                format!("Key ID {} is not a did:key", kid),
+            ));
+        };
+
+        // The fragment of a did:key key agreement key ID is the X25519 key itself.
+        let multibase_key = did_key.split('#').last().unwrap_or_default();
+        let public_key = decode_multicodec_key(multibase_key)?;
+
+        Ok(Self {
+            kid: kid.to_owned(),
+            public_key,
+        })
+    }
+
+    /// Collects the key agreement keys of a [`DidDocument`]. Documents without `keyAgreement`
+    /// keys fall back to converting their Ed25519 authentication keys.
+    pub fn from_did_doc<E>(did_doc: &DidDocument<E>) -> VcxResult<Vec<Self>> {
+        let mut keys = did_doc
+            .key_agreement()
+            .iter()
+            .map(|method| resolve_verification_method(did_doc, method).and_then(Self::from_verification_method))
+            .collect::<VcxResult<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            keys = did_doc
+                .authentication()
+                .iter()
+                .map(|method| resolve_verification_method(did_doc, method).and_then(Self::from_verification_method))
+                .collect::<VcxResult<Vec<_>>>()?;
+        }
+
+        if keys.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidDid,
+                format!("DID document {} contains no key agreement keys", did_doc.id()),
+            ));
+        }
+
+        Ok(keys)
+    }
+
+    pub fn from_verification_method(method: &VerificationMethod) -> VcxResult<Self> {
+        let kid = method.id().to_string();
+
+        let public_key = method.public_key().map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidVerkey,
+                format!("Cannot decode key of {}: {}", kid, err),
+            )
+        })?;
+
+        let public_key = match public_key.key_type() {
+            KeyType::X25519 => to_key_array(public_key.key())?,
+            KeyType::Ed25519 => ed25519_to_x25519_public(public_key.key())?,
+            key_type => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidVerkey,
+                    format!("Unsupported key of type {} in {}", key_type, kid),
+                ))
+            }
+        };
+
+        Ok(Self { kid, public_key })
+    }
+}
+
+pub(crate) fn resolve_verification_method<'a, E>(
+    did_doc: &'a DidDocument<E>,
+    method: &'a VerificationMethodKind,
+) -> VcxResult<&'a VerificationMethod> {
+    match method {
+        VerificationMethodKind::Resolved(method) => Ok(method),
+        VerificationMethodKind::Resolvable(reference) => did_doc
+            .verification_method()
+            .iter()
+            .find(|method| {
+                method.id() == reference
+                    || (method.id().fragment().is_some() && method.id().fragment() == reference.fragment())
+            })
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidDid,
+                    format!("Verification method {} not found in DID document", reference),
+                )
+            }),
+    }
+}
+
+/// Creates a new DID in the wallet together with its X25519 key agreement key, derived from the
+/// DID's Ed25519 key. Returns the DID and its verkey.
+///
+/// The derived X25519 secret is stored in the wallet under the `did:key` key ID of
+/// [`KeyAgreementKey::from_verkey`], as wallets do not expose the Ed25519 secret itself.
+pub async fn create_keys(wallet: &Arc<dyn BaseWallet>) -> VcxResult<(String, String)> {
+    let seed = generate_random_seed();
+    let (did, verkey) = wallet.create_and_store_my_did(Some(&seed), None).await?;
+
+    let secret = ed25519_seed_to_x25519_secret(seed.as_bytes());
+    let kid = KeyAgreementKey::from_verkey(&verkey)?.kid;
+    store_secret(wallet, &kid, &verkey, &secret).await?;
+
+    Ok((did, verkey))
+}
+
+/// Makes the key agreement key of `verkey` also available under `kid`, such as the ID of the
+/// verification method which references it in our published DID document.
+pub async fn add_key_id(wallet: &Arc<dyn BaseWallet>, verkey: &str, kid: &str) -> VcxResult<()> {
+    let did_key_kid = KeyAgreementKey::from_verkey(verkey)?.kid;
+    let secret = get_secret(wallet, &did_key_kid).await?.ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::WalletRecordNotFound,
+            format!("No key agreement key found for verkey {}", verkey),
+        )
+    })?;
+
+    store_secret(wallet, kid, verkey, &secret).await
+}
+
+async fn store_secret(wallet: &Arc<dyn BaseWallet>, kid: &str, verkey: &str, secret: &[u8; 32]) -> VcxResult<()> {
+    let value = base64::encode_config(secret, base64::URL_SAFE_NO_PAD);
+    let tags = HashMap::from([("verkey".to_owned(), verkey.to_owned())]);
+
+    wallet
+        .add_wallet_record(KEY_AGREEMENT_RECORD_TYPE, kid, &value, Some(tags))
+        .await
+        .map_err(From::from)
+}
+
+/// Retrieves the X25519 secret of one of our key agreement keys, if the wallet has it.
+pub(super) async fn get_secret(wallet: &Arc<dyn BaseWallet>, kid: &str) -> VcxResult<Option<[u8; 32]>> {
+    let value = match wallet.get_wallet_record_value(KEY_AGREEMENT_RECORD_TYPE, kid).await {
+        Ok(value) => value,
+        Err(err) if err.kind() == AriesVcxCoreErrorKind::WalletRecordNotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let secret = base64::decode_config(&value, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("Corrupted key agreement key {}: {:?}", kid, err),
+        )
+    })?;
+
+    to_key_array(&secret).map(Some)
+}
+
+/// Converts an Ed25519 public key into its X25519 counterpart, using the birational map
+/// `u = (1 + y) / (1 - y) mod p` between the Edwards and Montgomery forms of Curve25519.
+pub fn ed25519_to_x25519_public(ed25519_key: &[u8]) -> VcxResult<[u8; 32]> {
+    let mut y = to_key_array(ed25519_key)?;
+    // Keys are little endian, the highest bit being the sign of the x coordinate.
+    y[31] &= 0x7f;
+    y.reverse();
+
+    let convert = || -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let mut ctx = BigNumContext::new()?;
+        // p = 2^255 - 19
+        let p = BigNum::from_hex_str("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed")?;
+
+        let y = BigNum::from_slice(&y)?;
+        let one = BigNum::from_u32(1)?;
+
+        let mut numerator = BigNum::new()?;
+        numerator.mod_add(&one, &y, &p, &mut ctx)?;
+        let mut denominator = BigNum::new()?;
+        denominator.mod_sub(&one, &y, &p, &mut ctx)?;
+        let mut inverse = BigNum::new()?;
+        inverse.mod_inverse(&denominator, &p, &mut ctx)?;
+        let mut u = BigNum::new()?;
+        u.mod_mul(&numerator, &inverse, &p, &mut ctx)?;
+
+        u.to_vec_padded(32)
+    };
+
+    let mut u = convert().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Cannot convert Ed25519 key to X25519: {}", err),
+        )
+    })?;
+    u.reverse();
+
+    to_key_array(&u)
+}
+
+/// Derives the X25519 secret matching [`ed25519_to_x25519_public`] from an Ed25519 seed.
+pub fn ed25519_seed_to_x25519_secret(seed: &[u8]) -> [u8; 32] {
+    let hash = sha512(seed);
+
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&hash[..32]);
+    secret[0] &= 248;
+    secret[31] &= 127;
+    secret[31] |= 64;
+    secret
+}
+
+/// Computes the X25519 shared secret between our secret and their public key.
+pub(super) fn ecdh(secret: &[u8; 32], public_key: &[u8; 32]) -> VcxResult<Vec<u8>> {
+    let derive = || -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let secret = PKey::private_key_from_raw_bytes(secret, Id::X25519)?;
+        let public_key = PKey::public_key_from_raw_bytes(public_key, Id::X25519)?;
+
+        let mut deriver = Deriver::new(&secret)?;
+        deriver.set_peer(&public_key)?;
+        deriver.derive_to_vec()
+    };
+
+    derive().map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidInput, format!("ECDH failed: {}", err)))
+}
+
+/// Generates an ephemeral X25519 key pair, returned as `(secret, public_key)`.
+pub(super) fn generate_x25519() -> VcxResult<([u8; 32], [u8; 32])> {
+    let generate = || -> Result<(Vec<u8>, Vec<u8>), openssl::error::ErrorStack> {
+        let key = PKey::generate_x25519()?;
+        Ok((key.raw_private_key()?, key.raw_public_key()?))
+    };
+
+    let (secret, public_key) = generate().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::UnknownError,
+            format!("Cannot generate ephemeral key: {}", err),
+        )
+    })?;
+
+    Ok((to_key_array(&secret)?, to_key_array(&public_key)?))
+}
+
+pub fn x25519_to_multibase(public_key: &[u8; 32]) -> String {
+    let mut multicodec_key = X25519_MULTICODEC_PREFIX.to_vec();
+    multicodec_key.extend(public_key);
+    format!("z{}", bs58::encode(multicodec_key).into_string())
+}
+
+/// Decodes a base58btc multibase, multicodec prefixed key into a X25519 key,
+/// converting it if it's an Ed25519 key.
+fn decode_multicodec_key(multibase_key: &str) -> VcxResult<[u8; 32]> {
+    let decoded = multibase_key
+        .strip_prefix('z')
+        .map(|key| bs58::decode(key).into_vec())
+        .transpose()
+        .map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidVerkey,
+                format!("Cannot decode multibase key {}: {:?}", multibase_key, err),
+            )
+        })?;
+
+    match decoded {
+        Some(bytes) if bytes.starts_with(&X25519_MULTICODEC_PREFIX) => to_key_array(&bytes[2..]),
+        Some(bytes) if bytes.starts_with(&ED25519_MULTICODEC_PREFIX) => ed25519_to_x25519_public(&bytes[2..]),
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Unsupported multibase key {}", multibase_key),
+        )),
+    }
+}
+
+fn decode_verkey(verkey: &str) -> VcxResult<Vec<u8>> {
+    bs58::decode(verkey).into_vec().map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::NotBase58,
+            format!("Cannot decode verkey {}: {:?}", verkey, err),
+        )
+    })
+}
+
+pub(super) fn to_key_array(key: &[u8]) -> VcxResult<[u8; 32]> {
+    key.try_into().map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            format!("Expected a 32 bytes key, found {} bytes", key.len()),
+        )
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_to_x25519_did_key() {
+        // Key agreement key of the did:key test vectors.
+        let key = KeyAgreementKey::from_verkey("B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u").unwrap();
+        assert_eq!(
+            key.kid,
+            "did:key:z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH#z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc"
+        );
+        assert_eq!(KeyAgreementKey::from_did_key(&key.kid).unwrap(), key);
+    }
+
+    #[test]
+    fn test_seed_derived_key_pair_matches() {
+        let seed = [7u8; 32];
+        let ed25519 = PKey::private_key_from_raw_bytes(&seed, Id::ED25519).unwrap();
+        let ed25519_public = ed25519.raw_public_key().unwrap();
+
+        let secret = ed25519_seed_to_x25519_secret(&seed);
+        let x25519 = PKey::private_key_from_raw_bytes(&secret, Id::X25519).unwrap();
+
+        assert_eq!(
+            x25519.raw_public_key().unwrap(),
+            ed25519_to_x25519_public(&ed25519_public).unwrap().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_ecdh_agrees() {
+        let (alice_secret, alice_public) = generate_x25519().unwrap();
+        let (bob_secret, bob_public) = generate_x25519().unwrap();
+
+        assert_eq!(
+            ecdh(&alice_secret, &bob_public).unwrap(),
+            ecdh(&bob_secret, &alice_public).unwrap()
+        );
+    }
+}
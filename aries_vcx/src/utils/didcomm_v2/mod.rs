@@ -0,0 +1,30 @@
+//! DIDComm v2 envelopes, as defined in the
+//! [spec](https://identity.foundation/didcomm-messaging/spec/#message-encryption), used alongside
+//! the legacy (indy pack) envelopes of [`EncryptionEnvelope`](super::encryption_envelope::EncryptionEnvelope).
+//!
+//! Key agreement uses X25519 keys derived from Ed25519 keys, so peers can address us
+//! through the same keys as in DIDComm v1.
+
+pub mod envelope;
+pub mod jwe;
+pub mod jws;
+pub mod keys;
+pub mod plaintext;
+
+pub use envelope::{EncryptionEnvelopeV2, UnpackedMessageV2};
+pub use plaintext::PlaintextMessage;
+
+use crate::errors::error::prelude::*;
+
+fn encode_b64(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn decode_b64(data: &str) -> VcxResult<Vec<u8>> {
+    base64::decode_config(data, base64::URL_SAFE_NO_PAD).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Cannot decode base64url value: {:?}", err),
+        )
+    })
+}
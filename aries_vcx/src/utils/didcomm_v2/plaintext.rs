@@ -0,0 +1,197 @@
+//! DIDComm v2 plaintext messages, see
+//! <https://identity.foundation/didcomm-messaging/spec/#plaintext-message-structure>.
+
+use messages::AriesMessage;
+use serde_json::{Map, Value};
+
+use crate::errors::error::prelude::*;
+
+pub const PLAINTEXT_MESSAGE_TYPE: &str = "application/didcomm-plain+json";
+
+/// Plaintext DIDComm v2 message, as encrypted into [`Jwe`](super::jwe::Jwe)s and signed into
+/// [`Jws`](super::jws::Jws)s.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaintextMessage {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub body: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pthid: Option<String>,
+}
+
+impl PlaintextMessage {
+    /// Converts an [`AriesMessage`] into a plaintext message sent by the DID `from` to the DIDs `to`.
+    ///
+    /// The `@id` and `@type` of the message become the `id` and `type` headers, its other fields,
+    /// decorators included, make up the `body`. The thread of the `~thread` decorator is
+    /// also exposed through the `thid` and `pthid` headers.
+    pub fn from_aries_message(
+        message: &AriesMessage,
+        from: Option<String>,
+        to: Option<Vec<String>>,
+    ) -> VcxResult<Self> {
+        let Value::Object(mut body) = serde_json::to_value(message)? else {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::SerializationError,
+                "Message is not serialized as a JSON object",
+            ));
+        };
+
+        let id = take_string(&mut body, "@id")?;
+        let type_ = take_string(&mut body, "@type")?;
+        let thread_field = |field: &str| {
+            body.get("~thread")
+                .and_then(|thread| thread.get(field))
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+        };
+        let (thid, pthid) = (thread_field("thid"), thread_field("pthid"));
+
+        Ok(Self {
+            id,
+            typ: Some(PLAINTEXT_MESSAGE_TYPE.to_owned()),
+            type_,
+            body: Value::Object(body),
+            from,
+            to,
+            thid,
+            pthid,
+        })
+    }
+
+    /// Converts the plaintext message back into an [`AriesMessage`]. Messages of DIDComm v2 peers,
+    /// which carry their thread in the headers only, get the matching `~thread` decorator.
+    pub fn into_aries_message(self) -> VcxResult<AriesMessage> {
+        let mut fields = match self.body {
+            Value::Object(fields) => fields,
+            Value::Null => Map::new(),
+            body => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    format!("Body of plaintext message must be a JSON object, found: {}", body),
+                ))
+            }
+        };
+
+        fields.insert("@id".to_owned(), Value::String(self.id));
+        fields.insert("@type".to_owned(), Value::String(self.type_));
+        if !fields.contains_key("~thread") {
+            if let Some(thid) = self.thid {
+                let mut thread = json!({ "thid": thid });
+                if let Some(pthid) = self.pthid {
+                    thread["pthid"] = Value::String(pthid);
+                }
+                fields.insert("~thread".to_owned(), thread);
+            }
+        }
+
+        serde_json::from_value(Value::Object(fields)).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Cannot deserialize message from plaintext message: {}", err),
+            )
+        })
+    }
+}
+
+fn take_string(fields: &mut Map<String, Value>, key: &str) -> VcxResult<String> {
+    match fields.remove(key) {
+        Some(Value::String(value)) => Ok(value),
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::SerializationError,
+            format!("Message has no {} field", key),
+        )),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use messages::{
+        decorators::thread::Thread,
+        msg_fields::protocols::trust_ping::{
+            ping::{Ping, PingContent, PingDecorators},
+            ping_response::{PingResponse, PingResponseDecorators},
+            TrustPing,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_plaintext_message_roundtrip() {
+        let mut content = PingContent::default();
+        content.response_requested = true;
+        let message: AriesMessage =
+            Ping::with_decorators("ping_id".to_owned(), content, PingDecorators::default()).into();
+
+        let plaintext =
+            PlaintextMessage::from_aries_message(&message, Some("did:example:alice".to_owned()), None).unwrap();
+        let value = serde_json::to_value(&plaintext).unwrap();
+
+        assert_eq!(value["id"], "ping_id");
+        assert_eq!(value["typ"], PLAINTEXT_MESSAGE_TYPE);
+        assert_eq!(value["type"], "https://didcomm.org/trust_ping/1.0/ping");
+        assert_eq!(value["body"], json!({ "response_requested": true }));
+        assert_eq!(value["from"], "did:example:alice");
+        assert!(value.get("to").is_none());
+
+        assert_eq!(plaintext.into_aries_message().unwrap(), message);
+    }
+
+    #[test]
+    fn test_plaintext_message_exposes_thread() {
+        let mut thread = Thread::new("thread_id".to_owned());
+        thread.pthid = Some("parent_thread_id".to_owned());
+        let message: AriesMessage = PingResponse::with_decorators(
+            "response_id".to_owned(),
+            Default::default(),
+            PingResponseDecorators::new(thread),
+        )
+        .into();
+
+        let plaintext =
+            PlaintextMessage::from_aries_message(&message, None, Some(vec!["did:example:bob".to_owned()])).unwrap();
+
+        assert_eq!(plaintext.thid.as_deref(), Some("thread_id"));
+        assert_eq!(plaintext.pthid.as_deref(), Some("parent_thread_id"));
+        assert_eq!(plaintext.into_aries_message().unwrap(), message);
+    }
+
+    #[test]
+    fn test_plaintext_message_thread_from_headers() {
+        let plaintext: PlaintextMessage = serde_json::from_value(json!({
+            "id": "response_id",
+            "type": "https://didcomm.org/trust_ping/1.0/ping_response",
+            "body": {},
+            "thid": "thread_id"
+        }))
+        .unwrap();
+
+        let AriesMessage::TrustPing(TrustPing::PingResponse(response)) = plaintext.into_aries_message().unwrap() else {
+            panic!("expected ping response");
+        };
+        assert_eq!(response.decorators.thread.thid, "thread_id");
+    }
+
+    #[test]
+    fn test_plaintext_message_body_must_be_object() {
+        let plaintext: PlaintextMessage = serde_json::from_value(json!({
+            "id": "ping_id",
+            "type": "https://didcomm.org/trust_ping/1.0/ping",
+            "body": "not an object"
+        }))
+        .unwrap();
+
+        plaintext.into_aries_message().unwrap_err();
+    }
+}
@@ -5,10 +5,12 @@ use diddoc_legacy::aries::diddoc::AriesDidDoc;
 use futures::TryFutureExt;
 
 use agency_client::testing::mocking::AgencyMockDecrypted;
+use messages::decorators::transport::Transport as TransportDecorator;
 use messages::msg_fields::protocols::routing::{Forward, ForwardContent};
 use messages::AriesMessage;
 use uuid::Uuid;
 
+use crate::common::keys::did_key_to_verkey;
 use crate::errors::error::prelude::*;
 use crate::global::settings;
 use crate::utils::constants;
@@ -34,7 +36,7 @@ impl EncryptionEnvelope {
             return Ok(EncryptionEnvelope(vec![]));
         }
 
-        EncryptionEnvelope::encrypt_for_pairwise(wallet, message, pw_verkey, did_doc)
+        EncryptionEnvelope::encrypt_for_pairwise(wallet, json!(message).to_string(), pw_verkey, did_doc)
             .and_then(|message| async move {
                 EncryptionEnvelope::wrap_into_forward_messages(wallet, message, did_doc).await
             })
@@ -42,15 +44,49 @@ impl EncryptionEnvelope {
             .map(EncryptionEnvelope)
     }
 
-    async fn encrypt_for_pairwise(
+    /// Same as [`EncryptionEnvelope::create`], additionally setting the `~transport` decorator of
+    /// the message, which [`AriesMessage`] only retains for a few message kinds.
+    pub async fn create_with_transport(
         wallet: &Arc<dyn BaseWallet>,
         message: &AriesMessage,
+        transport: &TransportDecorator,
         pw_verkey: Option<&str>,
         did_doc: &AriesDidDoc,
-    ) -> VcxResult<Vec<u8>> {
-        let message = json!(message).to_string();
+    ) -> VcxResult<EncryptionEnvelope> {
+        trace!(
+            "EncryptionEnvelope::create_with_transport >>> message: {:?}, transport: {:?}, pw_verkey: {:?}",
+            message,
+            transport,
+            pw_verkey
+        );
+
+        if settings::indy_mocks_enabled() {
+            return Ok(EncryptionEnvelope(vec![]));
+        }
+
+        let mut message = json!(message);
+        message["~transport"] = json!(transport);
+
+        EncryptionEnvelope::encrypt_for_pairwise(wallet, message.to_string(), pw_verkey, did_doc)
+            .and_then(|message| async move {
+                EncryptionEnvelope::wrap_into_forward_messages(wallet, message, did_doc).await
+            })
+            .await
+            .map(EncryptionEnvelope)
+    }
 
-        let receiver_keys = json!(did_doc.recipient_keys()?).to_string();
+    async fn encrypt_for_pairwise(
+        wallet: &Arc<dyn BaseWallet>,
+        message: String,
+        pw_verkey: Option<&str>,
+        did_doc: &AriesDidDoc,
+    ) -> VcxResult<Vec<u8>> {
+        let receiver_keys = did_doc
+            .recipient_keys()?
+            .iter()
+            .map(|key| did_key_to_verkey(key))
+            .collect::<VcxResult<Vec<_>>>()?;
+        let receiver_keys = json!(receiver_keys).to_string();
 
         debug!(
             "Encrypting for pairwise; pw_verkey: {:?}, receiver_keys: {:?}",
@@ -69,12 +105,19 @@ impl EncryptionEnvelope {
         did_doc: &AriesDidDoc,
     ) -> VcxResult<Vec<u8>> {
         let recipient_keys = did_doc.recipient_keys()?;
-        let routing_keys = did_doc.routing_keys();
+        // Routing keys granted by RFC 0211 mediators are commonly expressed as `did:key`s,
+        // while packing and forwarding works with raw verkeys.
+        let routing_keys = did_doc
+            .routing_keys()
+            .iter()
+            .map(|key| did_key_to_verkey(key))
+            .collect::<VcxResult<Vec<_>>>()?;
 
         let mut to = recipient_keys.get(0).map(String::from).ok_or(AriesVcxError::from_msg(
             AriesVcxErrorKind::InvalidState,
             format!("Recipient Key not found in DIDDoc: {:?}", did_doc),
         ))?;
+        to = did_key_to_verkey(&to)?;
 
         for routing_key in routing_keys.iter() {
             message = EncryptionEnvelope::wrap_into_forward(wallet, message, &to, routing_key).await?;
@@ -142,15 +185,7 @@ impl EncryptionEnvelope {
             "EncryptionEnvelope::anon_unpack >>> processing payload of {} bytes",
             payload.len()
         );
-        let (message, sender_vk) = if AgencyMockDecrypted::has_decrypted_mock_messages() {
-            trace!("EncryptionEnvelope::anon_unpack >>> returning decrypted mock message");
-            (
-                AgencyMockDecrypted::get_next_decrypted_message(),
-                Some(constants::VERKEY.to_string()),
-            )
-        } else {
-            Self::_unpack_a2a_message(wallet, payload).await?
-        };
+        let (message, sender_vk) = Self::_anon_unpack_a2a_message(wallet, payload).await?;
         let a2a_message = serde_json::from_str(&message).map_err(|err| {
             AriesVcxError::from_msg(
                 AriesVcxErrorKind::InvalidJson,
@@ -160,6 +195,58 @@ impl EncryptionEnvelope {
         Ok((a2a_message, sender_vk))
     }
 
+    /// Same as [`EncryptionEnvelope::anon_unpack`], additionally returning the `~transport`
+    /// decorator of the message, which [`AriesMessage`] only retains for a few message kinds.
+    pub async fn anon_unpack_with_transport(
+        wallet: &Arc<dyn BaseWallet>,
+        payload: Vec<u8>,
+    ) -> VcxResult<(AriesMessage, Option<String>, Option<TransportDecorator>)> {
+        trace!(
+            "EncryptionEnvelope::anon_unpack_with_transport >>> processing payload of {} bytes",
+            payload.len()
+        );
+        let (message, sender_vk) = Self::_anon_unpack_a2a_message(wallet, payload).await?;
+        let message: serde_json::Value = serde_json::from_str(&message).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize A2A message: {}", err),
+            )
+        })?;
+        let transport = message
+            .get("~transport")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Cannot deserialize ~transport decorator: {}", err),
+                )
+            })?;
+        let a2a_message = serde_json::from_value(message).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize A2A message: {}", err),
+            )
+        })?;
+        Ok((a2a_message, sender_vk, transport))
+    }
+
+    async fn _anon_unpack_a2a_message(
+        wallet: &Arc<dyn BaseWallet>,
+        payload: Vec<u8>,
+    ) -> VcxResult<(String, Option<String>)> {
+        if AgencyMockDecrypted::has_decrypted_mock_messages() {
+            trace!("EncryptionEnvelope::anon_unpack >>> returning decrypted mock message");
+            Ok((
+                AgencyMockDecrypted::get_next_decrypted_message(),
+                Some(constants::VERKEY.to_string()),
+            ))
+        } else {
+            Self::_unpack_a2a_message(wallet, payload).await
+        }
+    }
+
     pub async fn auth_unpack(
         wallet: &Arc<dyn BaseWallet>,
         payload: Vec<u8>,
@@ -208,6 +295,41 @@ impl EncryptionEnvelope {
     }
 }
 
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod unit_tests {
+    use messages::decorators::transport::ReturnRoute;
+
+    use super::*;
+    use crate::utils::constants::VERKEY;
+    use crate::utils::mockdata::mockdata_mediated_connection::ARIES_CONNECTION_ACK;
+    use crate::utils::mockdata::profile::mock_wallet::MockWallet;
+
+    // The mock wallet "packs" messages by returning them as they are.
+    #[tokio::test]
+    async fn test_create_with_transport_sets_decorator() {
+        let wallet: Arc<dyn BaseWallet> = Arc::new(MockWallet);
+        let message: AriesMessage = serde_json::from_str(ARIES_CONNECTION_ACK).unwrap();
+        let mut did_doc = AriesDidDoc::default();
+        did_doc.set_recipient_keys(vec![VERKEY.to_owned()]);
+
+        let EncryptionEnvelope(envelope) = EncryptionEnvelope::create_with_transport(
+            &wallet,
+            &message,
+            &TransportDecorator::new(ReturnRoute::All),
+            Some(VERKEY),
+            &did_doc,
+        )
+        .await
+        .unwrap();
+
+        let mut packed: serde_json::Value = serde_json::from_slice(&envelope).unwrap();
+        assert_eq!(packed["~transport"], json!({ "return_route": "all" }));
+        packed.as_object_mut().unwrap().remove("~transport");
+        assert_eq!(serde_json::from_value::<AriesMessage>(packed).unwrap(), message);
+    }
+}
+
 // #[cfg(test)]
 // pub mod unit_tests {
 //     use crate::common::test_utils::{create_key, create_trustee_key, indy_handles_to_profile};
@@ -55,6 +55,10 @@ impl MockBuilder {
         warn!("MockBuilder::reset_mock_settings >>>");
         let mut config = MOCK_SETTINGS.write().expect("Unable to access MOCK_SETTINGS");
         config.clear();
+        let mut config = MOCK_SETTINGS_RESULT_BOOL
+            .write()
+            .expect("Unable to access MOCK_SETTINGS_RESULT_BOOL");
+        config.clear();
     }
 }
 
@@ -23,6 +23,14 @@ impl IndyLedgerRead for MockLedger {
         ))
     }
 
+    async fn get_nym_version(&self, did: &str, seq_no: Option<i32>, timestamp: Option<u64>) -> VcxCoreResult<String> {
+        // not needed yet
+        Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::UnimplementedFeature,
+            "unimplemented mock method: get_nym_version",
+        ))
+    }
+
     async fn get_attr(&self, target_did: &str, attr_name: &str) -> VcxCoreResult<String> {
         Ok(r#"{"rc":"success"}"#.to_string())
     }
@@ -140,5 +148,6 @@ mod unit_tests {
         let ledger: Box<dyn IndyLedgerRead> = Box::new(MockLedger);
 
         assert_unimplemented(ledger.get_nym("").await);
+        assert_unimplemented(ledger.get_nym_version("", None, None).await);
     }
 }
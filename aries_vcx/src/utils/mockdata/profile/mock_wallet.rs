@@ -105,4 +105,12 @@ impl BaseWallet for MockWallet {
     async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
         Ok(msg.to_vec())
     }
+
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        Ok(())
+    }
+
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        Ok(())
+    }
 }
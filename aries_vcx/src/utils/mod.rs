@@ -54,9 +54,11 @@ pub mod uuid;
 
 #[macro_use]
 pub mod test_logger;
+pub mod didcomm_v2;
 pub mod encryption_envelope;
 pub mod filters;
 pub mod serialization;
+pub mod thread;
 pub mod validation;
 
 pub fn get_temp_dir_path(filename: &str) -> PathBuf {
@@ -0,0 +1,145 @@
+use messages::{
+    msg_fields::protocols::{
+        connection::{invitation::Invitation, Connection},
+        coordinate_mediation::CoordinateMediation,
+        cred_issuance::{v2::CredentialIssuanceV2, CredentialIssuance},
+        did_exchange::DidExchange,
+        discover_features::{v2::DiscoverFeaturesV2, DiscoverFeatures},
+        notification::Notification,
+        out_of_band::OutOfBand,
+        pickup::Pickup,
+        present_proof::{v2::PresentProofV2, PresentProof},
+        revocation::Revocation,
+        trust_ping::TrustPing,
+    },
+    AriesMessage,
+};
+
+/// Thread a message belongs to, as identified by its `~thread` decorator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageThread {
+    pub thid: String,
+    pub pthid: Option<String>,
+    /// Set for messages which may have a `~thread` decorator but have none. Such messages start
+    /// a thread whose id is their own, yet are not bound to it.
+    pub unthreaded: bool,
+}
+
+impl MessageThread {
+    pub fn new(thid: String) -> Self {
+        Self {
+            thid,
+            pthid: None,
+            unthreaded: false,
+        }
+    }
+
+    pub fn unthreaded(id: String) -> Self {
+        Self {
+            thid: id,
+            pthid: None,
+            unthreaded: true,
+        }
+    }
+
+    /// Whether the message can be handled on the thread `thread_id`, i.e. when it is the thread
+    /// or parent thread of the message, or the message is unthreaded.
+    pub fn matches(&self, thread_id: &str) -> bool {
+        self.unthreaded || self.thid == thread_id || self.pthid.as_deref() == Some(thread_id)
+    }
+}
+
+macro_rules! thread_of {
+    ($msg:expr) => {
+        MessageThread {
+            thid: $msg.decorators.thread.thid.clone(),
+            pthid: $msg.decorators.thread.pthid.clone(),
+            unthreaded: false,
+        }
+    };
+}
+
+macro_rules! opt_thread_of {
+    ($msg:expr) => {
+        match $msg.decorators.thread.as_ref() {
+            Some(thread) => MessageThread {
+                thid: thread.thid.clone(),
+                pthid: thread.pthid.clone(),
+                unthreaded: false,
+            },
+            None => MessageThread::unthreaded($msg.id.clone()),
+        }
+    };
+}
+
+/// Returns the thread of the message. Messages which cannot have a `~thread` decorator, such as
+/// invitations, start a thread whose id is their own.
+pub fn message_thread(message: &AriesMessage) -> MessageThread {
+    match message {
+        AriesMessage::BasicMessage(msg) => opt_thread_of!(msg),
+        AriesMessage::Connection(Connection::Invitation(Invitation::Public(msg))) => MessageThread::new(msg.id.clone()),
+        AriesMessage::Connection(Connection::Invitation(Invitation::Pairwise(msg))) => {
+            MessageThread::new(msg.id.clone())
+        }
+        AriesMessage::Connection(Connection::Invitation(Invitation::PairwiseDID(msg))) => {
+            MessageThread::new(msg.id.clone())
+        }
+        AriesMessage::Connection(Connection::ProblemReport(msg)) => thread_of!(msg),
+        AriesMessage::Connection(Connection::Request(msg)) => opt_thread_of!(msg),
+        AriesMessage::Connection(Connection::Response(msg)) => thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::MediateRequest(msg)) => opt_thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(msg)) => thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::MediateDeny(msg)) => thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(msg)) => opt_thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(msg)) => thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistQuery(msg)) => opt_thread_of!(msg),
+        AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(msg)) => thread_of!(msg),
+        AriesMessage::DidExchange(DidExchange::Request(msg)) => opt_thread_of!(msg),
+        AriesMessage::DidExchange(DidExchange::Response(msg)) => thread_of!(msg),
+        AriesMessage::DidExchange(DidExchange::Complete(msg)) => thread_of!(msg),
+        AriesMessage::DidExchange(DidExchange::ProblemReport(msg)) => thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::Ack(msg)) => thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::IssueCredential(msg)) => thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::OfferCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::ProposeCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::RequestCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::ProblemReport(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::Ack(msg)) => thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::IssueCredential(msg)) => thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::OfferCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProposeCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::RequestCredential(msg)) => opt_thread_of!(msg),
+        AriesMessage::CredentialIssuanceV2(CredentialIssuanceV2::ProblemReport(msg)) => opt_thread_of!(msg),
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(msg)) => MessageThread::new(msg.id.clone()),
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => thread_of!(msg),
+        AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Queries(msg)) => MessageThread::new(msg.id.clone()),
+        AriesMessage::DiscoverFeaturesV2(DiscoverFeaturesV2::Disclosures(msg)) => opt_thread_of!(msg),
+        AriesMessage::Notification(Notification::Ack(msg)) => thread_of!(msg),
+        AriesMessage::Notification(Notification::ProblemReport(msg)) => opt_thread_of!(msg),
+        AriesMessage::OutOfBand(OutOfBand::Invitation(msg)) => MessageThread::new(msg.id.clone()),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuse(msg)) => thread_of!(msg),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuseAccepted(msg)) => thread_of!(msg),
+        AriesMessage::Pickup(Pickup::StatusRequest(msg)) => opt_thread_of!(msg),
+        AriesMessage::Pickup(Pickup::Status(msg)) => opt_thread_of!(msg),
+        AriesMessage::Pickup(Pickup::DeliveryRequest(msg)) => opt_thread_of!(msg),
+        AriesMessage::Pickup(Pickup::Delivery(msg)) => opt_thread_of!(msg),
+        AriesMessage::Pickup(Pickup::MessagesReceived(msg)) => opt_thread_of!(msg),
+        AriesMessage::Pickup(Pickup::LiveDeliveryChange(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProof(PresentProof::Ack(msg)) => thread_of!(msg),
+        AriesMessage::PresentProof(PresentProof::Presentation(msg)) => thread_of!(msg),
+        AriesMessage::PresentProof(PresentProof::ProposePresentation(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProof(PresentProof::RequestPresentation(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProof(PresentProof::ProblemReport(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProofV2(PresentProofV2::Ack(msg)) => thread_of!(msg),
+        AriesMessage::PresentProofV2(PresentProofV2::Presentation(msg)) => thread_of!(msg),
+        AriesMessage::PresentProofV2(PresentProofV2::ProposePresentation(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProofV2(PresentProofV2::RequestPresentation(msg)) => opt_thread_of!(msg),
+        AriesMessage::PresentProofV2(PresentProofV2::ProblemReport(msg)) => opt_thread_of!(msg),
+        AriesMessage::ReportProblem(msg) => opt_thread_of!(msg),
+        AriesMessage::Revocation(Revocation::Revoke(msg)) => opt_thread_of!(msg),
+        AriesMessage::Revocation(Revocation::Ack(msg)) => thread_of!(msg),
+        AriesMessage::Routing(msg) => MessageThread::new(msg.id.clone()),
+        AriesMessage::TrustPing(TrustPing::Ping(msg)) => opt_thread_of!(msg),
+        AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => thread_of!(msg),
+    }
+}
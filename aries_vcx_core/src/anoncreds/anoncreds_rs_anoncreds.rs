@@ -0,0 +1,1626 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anoncreds::{
+    data_types::{
+        cred_def::CredentialDefinitionId,
+        rev_reg_def::RevocationRegistryDefinitionId,
+        schema::{Schema, SchemaId},
+    },
+    tails::TailsFileWriter,
+    types::{
+        Credential, CredentialDefinition, CredentialDefinitionConfig, CredentialDefinitionPrivate,
+        CredentialKeyCorrectnessProof, CredentialOffer, CredentialRequest, CredentialRequestMetadata,
+        CredentialRevocationConfig, CredentialRevocationState, CredentialValues, LinkSecret, PresentCredentials,
+        Presentation, PresentationRequest, RegistryType, RevocationRegistryDefinition,
+        RevocationRegistryDefinitionPrivate, RevocationStatusList, SignatureType,
+    },
+};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    constants::ATTRS,
+    json::{AsTypeOrDeserializationError, TryGetIndex},
+};
+use crate::wallet::base_wallet::AsyncFnIteratorCollect;
+use crate::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    wallet::base_wallet::BaseWallet,
+};
+
+use super::base_anoncreds::BaseAnonCreds;
+
+// The categories are shared with the indy-credx implementation, so that wallets
+// holding link secrets and credentials can be used with either of them.
+const CATEGORY_LINK_SECRET: &str = "VCX_LINK_SECRET";
+
+const CATEGORY_CREDENTIAL: &str = "VCX_CREDENTIAL";
+const CATEGORY_CRED_DEF: &str = "VCX_CRED_DEF";
+const CATEGORY_CRED_KEY_CORRECTNESS_PROOF: &str = "VCX_CRED_KEY_CORRECTNESS_PROOF";
+const CATEGORY_CRED_DEF_PRIV: &str = "VCX_CRED_DEF_PRIV";
+const CATEGORY_CRED_SCHEMA: &str = "VCX_CRED_SCHEMA";
+
+// Category used for mapping a cred_def_id to a schema_id
+const CATEGORY_CRED_MAP_SCHEMA_ID: &str = "VCX_CRED_MAP_SCHEMA_ID";
+
+const CATEGORY_REV_STATUS_LIST: &str = "VCX_REV_STATUS_LIST";
+const CATEGORY_REV_REG_DELTA: &str = "VCX_REV_REG_DELTA";
+const CATEGORY_REV_REG_INFO: &str = "VCX_REV_REG_INFO";
+const CATEGORY_REV_REG_DEF: &str = "VCX_REV_REG_DEF";
+const CATEGORY_REV_REG_DEF_PRIV: &str = "VCX_REV_REG_DEF_PRIV";
+
+type ProofDetails = (
+    Credential,
+    Option<u64>,
+    Option<CredentialRevocationState>,
+    Vec<(String, bool)>,
+    Vec<String>,
+);
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RevocationRegistryInfo {
+    pub id: String,
+    pub curr_id: u32,
+    pub used_ids: HashSet<u32>,
+}
+
+/// [BaseAnonCreds] implementation on top of [anoncreds-rs](https://github.com/hyperledger/anoncreds-rs),
+/// the AnonCreds v1 specification library replacing indy-credx.
+///
+/// The objects exchanged through [BaseAnonCreds] keep the legacy indy format (as written to and read
+/// from an indy ledger) and are converted to the anoncreds-rs ones, which identify their issuer explicitly
+/// and track revocation through status lists rather than registry deltas.
+#[derive(Debug)]
+pub struct AnoncredsRsAnonCreds {
+    wallet: Arc<dyn BaseWallet>,
+}
+
+impl AnoncredsRsAnonCreds {
+    pub fn new(wallet: Arc<dyn BaseWallet>) -> Self {
+        AnoncredsRsAnonCreds { wallet }
+    }
+
+    async fn get_wallet_record_value<T>(&self, category: &str, id: &str) -> VcxCoreResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let str_record = self.wallet.get_wallet_record_value(category, id).await?;
+        serde_json::from_str(&str_record).map_err(From::from)
+    }
+
+    async fn get_link_secret(&self, link_secret_id: &str) -> VcxCoreResult<LinkSecret> {
+        let ms_decimal = self
+            .wallet
+            .get_wallet_record_value(CATEGORY_LINK_SECRET, link_secret_id)
+            .await?;
+
+        LinkSecret::try_from(ms_decimal.as_str()).map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Failed to read link secret {link_secret_id}: {err}"),
+            )
+        })
+    }
+
+    async fn _get_credential(&self, credential_id: &str) -> VcxCoreResult<Credential> {
+        self.get_wallet_record_value(CATEGORY_CREDENTIAL, credential_id).await
+    }
+
+    async fn _get_credentials(&self, wql: &str) -> VcxCoreResult<Vec<(String, Credential)>> {
+        let mut record_iterator = self
+            .wallet
+            .iterate_wallet_records(CATEGORY_CREDENTIAL, wql, "{}")
+            .await?;
+        let records = record_iterator.collect().await?;
+
+        records
+            .iter()
+            .map(|record| {
+                let cred_record: Value = serde_json::from_str(record)?;
+
+                let cred_record_id = (&cred_record).try_get("id")?.try_as_str()?.to_string();
+                let cred_json = (&cred_record).try_get("value")?.try_as_str()?;
+
+                let credential: Credential = serde_json::from_str(cred_json)?;
+
+                Ok((cred_record_id, credential))
+            })
+            .collect()
+    }
+
+    async fn _get_credentials_for_proof_req_for_attr_name(
+        &self,
+        restrictions: Option<&Value>,
+        attr_name: &str,
+    ) -> VcxCoreResult<Vec<(String, Credential)>> {
+        let attr_marker_tag_name = _format_attribute_as_marker_tag_name(attr_name);
+
+        let wql_attr_query = json!({
+            attr_marker_tag_name: "1"
+        });
+
+        let wql_query = match restrictions.cloned() {
+            Some(Value::Array(mut arr)) => {
+                arr.push(wql_attr_query);
+                json!({ "$and": arr })
+            }
+            Some(Value::Object(obj)) => json!({ "$and": vec![wql_attr_query, Value::Object(obj)] }),
+            _ => wql_attr_query,
+        };
+
+        let wql_query = serde_json::to_string(&wql_query)?;
+
+        self._get_credentials(&wql_query).await
+    }
+}
+
+#[async_trait]
+impl BaseAnonCreds for AnoncredsRsAnonCreds {
+    async fn verifier_verify_proof(
+        &self,
+        proof_req_json: &str,
+        proof_json: &str,
+        schemas_json: &str,
+        credential_defs_json: &str,
+        rev_reg_defs_json: &str,
+        rev_regs_json: &str,
+    ) -> VcxCoreResult<bool> {
+        let presentation: Presentation = serde_json::from_str(proof_json)?;
+        let pres_req: PresentationRequest = serde_json::from_str(proof_req_json)?;
+
+        let schemas: HashMap<SchemaId, Schema> = from_legacy_map(serde_json::from_str(schemas_json)?)?;
+        let cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition> =
+            from_legacy_map(serde_json::from_str(credential_defs_json)?)?;
+
+        let rev_reg_defs: Option<HashMap<RevocationRegistryDefinitionId, RevocationRegistryDefinition>> =
+            serde_json::from_str::<Option<Map<String, Value>>>(rev_reg_defs_json)?
+                .map(from_legacy_map)
+                .transpose()?;
+
+        // Legacy revocation registry entries only hold the accumulator, which is all that
+        // verification needs from the status lists
+        let rev_regs: Option<HashMap<String, HashMap<u64, Value>>> = serde_json::from_str(rev_regs_json)?;
+        let rev_status_lists = match (&rev_reg_defs, rev_regs) {
+            (Some(rev_reg_defs), Some(rev_regs)) => {
+                let mut rev_status_lists = Vec::new();
+                for (rev_reg_id, entries) in rev_regs {
+                    let rev_reg_def = rev_reg_defs
+                        .iter()
+                        .find_map(|(id, rev_reg_def)| (id.0 == rev_reg_id).then_some(rev_reg_def))
+                        .ok_or_else(|| {
+                            AriesVcxCoreError::from_msg(
+                                AriesVcxCoreErrorKind::RevRegDefNotFound,
+                                format!("No revocation registry definition provided for {rev_reg_id}"),
+                            )
+                        })?;
+
+                    for (timestamp, entry) in entries {
+                        let accum = (&entry).try_get("value")?.try_get("accum")?;
+                        rev_status_lists.push(status_list_from_legacy(
+                            &rev_reg_id,
+                            rev_reg_def,
+                            accum,
+                            &[],
+                            Some(timestamp),
+                        )?);
+                    }
+                }
+                Some(rev_status_lists)
+            }
+            _ => None,
+        };
+
+        Ok(anoncreds::verifier::verify_presentation(
+            &presentation,
+            &pres_req,
+            &schemas.iter().collect(),
+            &cred_defs.iter().collect(),
+            rev_reg_defs.as_ref().map(|defs| defs.iter().collect()).as_ref(),
+            rev_status_lists,
+            None,
+        )?)
+    }
+
+    async fn issuer_create_and_store_revoc_reg(
+        &self,
+        issuer_did: &str,
+        cred_def_id: &str,
+        tails_dir: &str,
+        max_creds: u32,
+        tag: &str,
+    ) -> VcxCoreResult<(String, String, String)> {
+        let rev_reg_id = format!("{issuer_did}:4:{cred_def_id}:CL_ACCUM:{tag}");
+
+        let res_rev_reg_def = self
+            .get_wallet_record_value::<RevocationRegistryDefinition>(CATEGORY_REV_REG_DEF, &rev_reg_id)
+            .await;
+        let res_status_list = self
+            .get_wallet_record_value::<RevocationStatusList>(CATEGORY_REV_STATUS_LIST, &rev_reg_id)
+            .await;
+
+        if let (Ok(rev_reg_def), Ok(status_list)) = (res_rev_reg_def, res_status_list) {
+            return Ok((
+                rev_reg_id.clone(),
+                legacy_rev_reg_def(&rev_reg_id, &rev_reg_def)?.to_string(),
+                legacy_rev_reg(&status_list)?.to_string(),
+            ));
+        }
+
+        let cred_def: CredentialDefinition = self.get_wallet_record_value(CATEGORY_CRED_DEF, cred_def_id).await?;
+
+        let mut tails_writer = TailsFileWriter::new(Some(tails_dir.to_owned()));
+        let (rev_reg_def, rev_reg_def_priv) = anoncreds::issuer::create_revocation_registry_def(
+            &cred_def,
+            cred_def_id,
+            issuer_did,
+            tag,
+            RegistryType::CL_ACCUM,
+            max_creds,
+            &mut tails_writer,
+        )?;
+
+        let status_list = anoncreds::issuer::create_revocation_status_list(
+            &cred_def,
+            rev_reg_id.as_str(),
+            &rev_reg_def,
+            &rev_reg_def_priv,
+            issuer_did,
+            true,
+            None,
+        )?;
+
+        // Store stuff in wallet
+        let rev_reg_info = RevocationRegistryInfo {
+            id: rev_reg_id.clone(),
+            curr_id: 0,
+            used_ids: HashSet::new(),
+        };
+
+        let str_rev_reg_info = serde_json::to_string(&rev_reg_info)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_REV_REG_INFO, &rev_reg_id, &str_rev_reg_info, None)
+            .await?;
+
+        let str_rev_reg_def = serde_json::to_string(&rev_reg_def)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_REV_REG_DEF, &rev_reg_id, &str_rev_reg_def, None)
+            .await?;
+
+        let str_rev_reg_def_priv = serde_json::to_string(&rev_reg_def_priv)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_REV_REG_DEF_PRIV, &rev_reg_id, &str_rev_reg_def_priv, None)
+            .await?;
+
+        let str_status_list = serde_json::to_string(&status_list)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_REV_STATUS_LIST, &rev_reg_id, &str_status_list, None)
+            .await?;
+
+        Ok((
+            rev_reg_id.clone(),
+            legacy_rev_reg_def(&rev_reg_id, &rev_reg_def)?.to_string(),
+            legacy_rev_reg(&status_list)?.to_string(),
+        ))
+    }
+
+    async fn issuer_create_and_store_credential_def(
+        &self,
+        issuer_did: &str,
+        schema_json: &str,
+        tag: &str,
+        sig_type: Option<&str>,
+        config_json: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let legacy_schema: Value = serde_json::from_str(schema_json)?;
+        let schema_id = (&legacy_schema).try_get("id")?.try_as_str()?.to_owned();
+        let schema_seq_no = legacy_schema.get("seqNo").and_then(Value::as_u64).ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidSchemaSeqNo,
+                format!("Schema {schema_id} has no sequence number"),
+            )
+        })?;
+        let schema: Schema = serde_json::from_value(with_issuer_id(legacy_schema)?)?;
+        let sig_type = sig_type.map(serde_json::from_str).unwrap_or(Ok(SignatureType::CL))?;
+        let support_revocation = serde_json::from_str::<Value>(config_json)?
+            .get("support_revocation")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let cred_def_id = format!("{issuer_did}:3:CL:{schema_seq_no}:{tag}");
+
+        // If cred def already exists, return it
+        if let Ok(cred_def) = self
+            .get_wallet_record_value::<CredentialDefinition>(CATEGORY_CRED_DEF, &cred_def_id)
+            .await
+        {
+            return Ok((
+                cred_def_id.clone(),
+                legacy_cred_def(&cred_def_id, &cred_def)?.to_string(),
+            ));
+        }
+
+        // Otherwise, create cred def
+        let (cred_def, cred_def_priv, cred_key_correctness_proof) = anoncreds::issuer::create_credential_definition(
+            schema_id.as_str(),
+            &schema,
+            issuer_did,
+            tag,
+            sig_type,
+            CredentialDefinitionConfig { support_revocation },
+        )?;
+
+        let str_cred_def = serde_json::to_string(&cred_def)?;
+
+        // Store stuff in wallet
+        self.wallet
+            .add_wallet_record(CATEGORY_CRED_DEF, &cred_def_id, &str_cred_def, None)
+            .await?;
+
+        let str_cred_def_priv = serde_json::to_string(&cred_def_priv)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_CRED_DEF_PRIV, &cred_def_id, &str_cred_def_priv, None)
+            .await?;
+
+        let str_cred_key_proof = serde_json::to_string(&cred_key_correctness_proof)?;
+
+        self.wallet
+            .add_wallet_record(
+                CATEGORY_CRED_KEY_CORRECTNESS_PROOF,
+                &cred_def_id,
+                &str_cred_key_proof,
+                None,
+            )
+            .await?;
+
+        let store_schema_res = self
+            .wallet
+            .add_wallet_record(CATEGORY_CRED_SCHEMA, &schema_id, schema_json, None)
+            .await;
+
+        if let Err(e) = store_schema_res {
+            warn!("Storing schema {schema_json} failed - {e}. It's possible it is already stored.")
+        }
+
+        let str_schema_id = serde_json::to_string(&schema_id)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_CRED_MAP_SCHEMA_ID, &cred_def_id, &str_schema_id, None)
+            .await?;
+
+        // Return the ID and the cred def
+        Ok((
+            cred_def_id.clone(),
+            legacy_cred_def(&cred_def_id, &cred_def)?.to_string(),
+        ))
+    }
+
+    async fn issuer_create_credential_offer(&self, cred_def_id: &str) -> VcxCoreResult<String> {
+        let correctness_proof: CredentialKeyCorrectnessProof = self
+            .get_wallet_record_value(CATEGORY_CRED_KEY_CORRECTNESS_PROOF, cred_def_id)
+            .await?;
+
+        let schema_id: String = self
+            .get_wallet_record_value(CATEGORY_CRED_MAP_SCHEMA_ID, cred_def_id)
+            .await?;
+
+        let offer = anoncreds::issuer::create_credential_offer(schema_id.as_str(), cred_def_id, &correctness_proof)?;
+
+        serde_json::to_string(&offer).map_err(From::from)
+    }
+
+    /// Credentials are issued against the current revocation status list, whose registry is
+    /// issued by default, so no revocation registry delta results from the issuance.
+    async fn issuer_create_credential(
+        &self,
+        cred_offer_json: &str,
+        cred_req_json: &str,
+        cred_values_json: &str,
+        rev_reg_id: Option<String>,
+        _tails_dir: Option<String>,
+    ) -> VcxCoreResult<(String, Option<String>, Option<String>)> {
+        let cred_offer: CredentialOffer = serde_json::from_str(cred_offer_json)?;
+        let cred_request: CredentialRequest = serde_json::from_str(cred_req_json)?;
+        let cred_values: CredentialValues = serde_json::from_str(cred_values_json)?;
+
+        let cred_def_id = &cred_offer.cred_def_id.0;
+
+        let cred_def: CredentialDefinition = self.get_wallet_record_value(CATEGORY_CRED_DEF, cred_def_id).await?;
+
+        let cred_def_private: CredentialDefinitionPrivate = self
+            .get_wallet_record_value(CATEGORY_CRED_DEF_PRIV, cred_def_id)
+            .await?;
+
+        let revocation_parts = match &rev_reg_id {
+            Some(rev_reg_id) => {
+                let rev_reg_def: RevocationRegistryDefinition =
+                    self.get_wallet_record_value(CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+
+                let rev_reg_def_priv: RevocationRegistryDefinitionPrivate = self
+                    .get_wallet_record_value(CATEGORY_REV_REG_DEF_PRIV, rev_reg_id)
+                    .await?;
+
+                let status_list: RevocationStatusList = self
+                    .get_wallet_record_value(CATEGORY_REV_STATUS_LIST, rev_reg_id)
+                    .await?;
+
+                let mut rev_reg_info: RevocationRegistryInfo =
+                    self.get_wallet_record_value(CATEGORY_REV_REG_INFO, rev_reg_id).await?;
+
+                rev_reg_info.curr_id += 1;
+
+                if rev_reg_info.curr_id > rev_reg_def.value.max_cred_num {
+                    return Err(AriesVcxCoreError::from_msg(
+                        AriesVcxCoreErrorKind::ActionNotSupported,
+                        "The revocation registry is full",
+                    ));
+                }
+
+                Some((rev_reg_def, rev_reg_def_priv, status_list, rev_reg_info))
+            }
+            None => None,
+        };
+
+        let revocation_config =
+            revocation_parts
+                .as_ref()
+                .map(
+                    |(rev_reg_def, rev_reg_def_priv, status_list, rev_reg_info)| CredentialRevocationConfig {
+                        reg_def: rev_reg_def,
+                        reg_def_private: rev_reg_def_priv,
+                        status_list,
+                        registry_idx: rev_reg_info.curr_id,
+                    },
+                );
+
+        let cred = anoncreds::issuer::create_credential(
+            &cred_def,
+            &cred_def_private,
+            &cred_offer,
+            &cred_request,
+            cred_values,
+            revocation_config,
+        )?;
+
+        let cred_rev_id = if let (Some(rev_reg_id), Some((_, _, _, rev_reg_info))) = (rev_reg_id, revocation_parts) {
+            let str_rev_reg_info = serde_json::to_string(&rev_reg_info)?;
+
+            self.wallet
+                .update_wallet_record_value(CATEGORY_REV_REG_INFO, &rev_reg_id, &str_rev_reg_info)
+                .await?;
+
+            Some(rev_reg_info.curr_id.to_string())
+        } else {
+            None
+        };
+
+        let str_cred = serde_json::to_string(&cred)?;
+
+        Ok((str_cred, cred_rev_id, None))
+    }
+
+    /// * `requested_credentials_json`: either a credential or self-attested attribute for each requested attribute
+    ///     {
+    ///         "self_attested_attributes": {
+    ///             "self_attested_attribute_referent": string
+    ///         },
+    ///         "requested_attributes": {
+    ///             "requested_attribute_referent_1": {"cred_id": string, "timestamp": Optional<number>, revealed: <bool> }},
+    ///             "requested_attribute_referent_2": {"cred_id": string, "timestamp": Optional<number>, revealed: <bool> }}
+    ///         },
+    ///         "requested_predicates": {
+    ///             "requested_predicates_referent_1": {"cred_id": string, "timestamp": Optional<number> }},
+    ///         }
+    ///     }
+    async fn prover_create_proof(
+        &self,
+        proof_req_json: &str,
+        requested_credentials_json: &str,
+        link_secret_id: &str,
+        schemas_json: &str,
+        credential_defs_json: &str,
+        revoc_states_json: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        let pres_req: PresentationRequest = serde_json::from_str(proof_req_json)?;
+
+        let requested_credentials: Value = serde_json::from_str(requested_credentials_json)?;
+        let requested_attributes = (&requested_credentials).try_get("requested_attributes")?;
+
+        let requested_predicates = (&requested_credentials).try_get("requested_predicates")?;
+        let self_attested_attributes = requested_credentials.get("self_attested_attributes");
+
+        let rev_states: Option<Value> = revoc_states_json.map(serde_json::from_str).transpose()?;
+
+        let schemas: HashMap<SchemaId, Schema> = from_legacy_map(serde_json::from_str(schemas_json)?)?;
+        let cred_defs: HashMap<CredentialDefinitionId, CredentialDefinition> =
+            from_legacy_map(serde_json::from_str(credential_defs_json)?)?;
+
+        let mut proof_details_by_cred_id: HashMap<String, ProofDetails> = HashMap::new();
+
+        // add cred data and referent details for each requested attribute
+        for (reft, detail) in requested_attributes.try_as_object()?.iter() {
+            let cred_id = detail.try_get("cred_id")?.try_as_str()?;
+            let revealed = detail.try_get("revealed")?.try_as_bool()?;
+
+            if let Some((_, _, _, req_attr_refts_revealed, _)) = proof_details_by_cred_id.get_mut(cred_id) {
+                // mapping made for this credential already, add reft and its revealed status
+                req_attr_refts_revealed.push((reft.to_string(), revealed));
+            } else {
+                let credential = self._get_credential(cred_id).await?;
+
+                let (timestamp, rev_state) = get_rev_state(cred_id, &credential, detail, rev_states.as_ref())?;
+
+                proof_details_by_cred_id.insert(
+                    cred_id.to_string(),
+                    (
+                        credential,
+                        timestamp,
+                        rev_state,
+                        vec![(reft.to_string(), revealed)],
+                        vec![],
+                    ),
+                );
+            }
+        }
+
+        // add cred data and referent details for each requested predicate
+        for (reft, detail) in requested_predicates.try_as_object()?.iter() {
+            let cred_id = detail.try_get("cred_id")?.try_as_str()?;
+
+            if let Some((_, _, _, _, req_preds_refts)) = proof_details_by_cred_id.get_mut(cred_id) {
+                // mapping made for this credential already, add reft
+                req_preds_refts.push(reft.to_string());
+            } else {
+                let credential = self._get_credential(cred_id).await?;
+
+                let (timestamp, rev_state) = get_rev_state(cred_id, &credential, detail, rev_states.as_ref())?;
+
+                proof_details_by_cred_id.insert(
+                    cred_id.to_string(),
+                    (credential, timestamp, rev_state, vec![], vec![reft.to_string()]),
+                );
+            }
+        }
+
+        // add all accumulated requested attributes and requested predicates to the [PresentCredentials] object
+        let mut present_credentials: PresentCredentials<Credential> = PresentCredentials::default();
+
+        for (credential, timestamp, rev_state, req_attr_refts_revealed, req_preds_refts) in
+            proof_details_by_cred_id.values()
+        {
+            let mut add_cred = present_credentials.add_credential(credential, *timestamp, rev_state.as_ref());
+
+            for (referent, revealed) in req_attr_refts_revealed {
+                add_cred.add_requested_attribute(referent, *revealed);
+            }
+
+            for referent in req_preds_refts {
+                add_cred.add_requested_predicate(referent);
+            }
+        }
+
+        let self_attested = match self_attested_attributes {
+            Some(self_attested_value) => {
+                let mut self_attested_map: HashMap<String, String> = HashMap::new();
+                for (k, v) in self_attested_value.try_as_object()?.iter() {
+                    self_attested_map.insert(k.to_string(), v.try_as_str()?.to_string());
+                }
+                Some(self_attested_map).filter(|map| !map.is_empty())
+            }
+            None => None,
+        };
+
+        let link_secret = self.get_link_secret(link_secret_id).await?;
+
+        let presentation = anoncreds::prover::create_presentation(
+            &pres_req,
+            present_credentials,
+            self_attested,
+            &link_secret,
+            &schemas.iter().collect(),
+            &cred_defs.iter().collect(),
+        )?;
+
+        Ok(serde_json::to_string(&presentation)?)
+    }
+
+    async fn prover_get_credential(&self, cred_id: &str) -> VcxCoreResult<String> {
+        let cred = self._get_credential(cred_id).await?;
+
+        let cred_info = _make_cred_info(cred_id, &cred)?;
+
+        Ok(serde_json::to_string(&cred_info)?)
+    }
+
+    async fn prover_get_credentials(&self, filter_json: Option<&str>) -> VcxCoreResult<String> {
+        // filter_json should map to WQL query directly
+        let creds_wql = filter_json.unwrap_or("{}");
+        let creds = self._get_credentials(creds_wql).await?;
+
+        let cred_info_list = creds
+            .iter()
+            .map(|(credential_id, cred)| _make_cred_info(credential_id, cred))
+            .collect::<VcxCoreResult<Vec<Value>>>()?;
+
+        Ok(serde_json::to_string(&cred_info_list)?)
+    }
+
+    async fn prover_get_credentials_for_proof_req(&self, proof_req: &str) -> VcxCoreResult<String> {
+        let proof_req_v: Value = serde_json::from_str(proof_req)
+            .map_err(|e| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidProofRequest, e))?;
+
+        let requested_attributes = proof_req_v
+            .get("requested_attributes")
+            .map(|attrs| attrs.try_as_object().cloned())
+            .transpose()?;
+        let requested_predicates = proof_req_v
+            .get("requested_predicates")
+            .map(|preds| preds.try_as_object().cloned())
+            .transpose()?;
+
+        // handle special case of "empty because json is bad" vs "empty because no attributes sepected"
+        if requested_attributes.is_none() && requested_predicates.is_none() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidAttributesStructure,
+                "Invalid Json Parsing of Requested Attributes Retrieved From Libindy",
+            ));
+        }
+
+        let referents: HashSet<String> = requested_attributes
+            .iter()
+            .chain(requested_predicates.iter())
+            .flat_map(|requested| requested.keys().cloned())
+            .collect();
+
+        let mut cred_by_attr: Value = json!({});
+
+        for reft in referents {
+            let requested_val = requested_attributes
+                .as_ref()
+                .and_then(|req_attrs| req_attrs.get(&reft))
+                .or_else(|| requested_predicates.as_ref().and_then(|req_preds| req_preds.get(&reft)))
+                .ok_or(AriesVcxCoreError::from_msg(
+                    // should not happen
+                    AriesVcxCoreErrorKind::InvalidState,
+                    format!("Unknown referent: {}", reft),
+                ))?;
+
+            let attr_name = _normalize_attr_name(requested_val.try_get("name")?.try_as_str()?);
+
+            let non_revoked = requested_val.get("non_revoked");
+            let restrictions = requested_val.get("restrictions");
+
+            let creds = self
+                ._get_credentials_for_proof_req_for_attr_name(restrictions, &attr_name)
+                .await?;
+
+            let mut credentials_json = vec![];
+
+            for (cred_id, cred) in creds {
+                credentials_json.push(json!({
+                    "cred_info": _make_cred_info(&cred_id, &cred)?,
+                    "interval": non_revoked
+                }))
+            }
+
+            cred_by_attr[ATTRS][reft] = Value::Array(credentials_json);
+        }
+
+        Ok(serde_json::to_string(&cred_by_attr)?)
+    }
+
+    async fn prover_create_credential_req(
+        &self,
+        prover_did: &str,
+        credential_offer_json: &str,
+        credential_def_json: &str,
+        link_secret_id: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let cred_def: CredentialDefinition =
+            serde_json::from_value(with_issuer_id(serde_json::from_str(credential_def_json)?)?)?;
+        let credential_offer: CredentialOffer = serde_json::from_str(credential_offer_json)?;
+        let link_secret = self.get_link_secret(link_secret_id).await?;
+
+        let (cred_req, cred_req_metadata) = anoncreds::prover::create_credential_request(
+            None,
+            Some(prover_did),
+            &cred_def,
+            &link_secret,
+            link_secret_id,
+            &credential_offer,
+        )?;
+
+        Ok((
+            serde_json::to_string(&cred_req)?,
+            serde_json::to_string(&cred_req_metadata)?,
+        ))
+    }
+
+    /// The legacy `rev_reg_delta_json` (as read from the ledger) is turned into the status list
+    /// of the registry at `timestamp` the revocation state is created for.
+    async fn create_revocation_state(
+        &self,
+        tails_dir: &str,
+        rev_reg_def_json: &str,
+        rev_reg_delta_json: &str,
+        timestamp: u64,
+        cred_rev_id: &str,
+    ) -> VcxCoreResult<String> {
+        let legacy_rev_reg_def: Value = serde_json::from_str(rev_reg_def_json)?;
+        let rev_reg_id = (&legacy_rev_reg_def).try_get("id")?.try_as_str()?.to_owned();
+        let rev_reg_def: RevocationRegistryDefinition = serde_json::from_value(with_issuer_id(legacy_rev_reg_def)?)?;
+
+        let rev_reg_delta: Value = serde_json::from_str(rev_reg_delta_json)?;
+        let rev_reg_delta_value = (&rev_reg_delta).try_get("value")?;
+        let revoked: Vec<u32> = match rev_reg_delta_value.get("revoked") {
+            Some(revoked) => serde_json::from_value(revoked.clone())?,
+            None => vec![],
+        };
+
+        let status_list = status_list_from_legacy(
+            &rev_reg_id,
+            &rev_reg_def,
+            rev_reg_delta_value.try_get("accum")?,
+            &revoked,
+            Some(timestamp),
+        )?;
+
+        let tails_path = tails_file_path(tails_dir, &rev_reg_def.value.tails_hash)?;
+        let rev_reg_idx: u32 = cred_rev_id
+            .parse()
+            .map_err(|e| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::ParsingError, e))?;
+
+        let rev_state = anoncreds::prover::create_or_update_revocation_state(
+            &tails_path,
+            &rev_reg_def,
+            &status_list,
+            rev_reg_idx,
+            None,
+            None,
+        )?;
+
+        Ok(serde_json::to_string(&rev_state)?)
+    }
+
+    async fn prover_store_credential(
+        &self,
+        cred_id: Option<&str>,
+        cred_req_meta: &str,
+        cred_json: &str,
+        cred_def_json: &str,
+        rev_reg_def_json: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        let mut credential: Credential = serde_json::from_str(cred_json)?;
+        let cred_request_metadata: CredentialRequestMetadata = serde_json::from_str(cred_req_meta)?;
+        let link_secret = self.get_link_secret(&cred_request_metadata.link_secret_name).await?;
+        let cred_def: CredentialDefinition =
+            serde_json::from_value(with_issuer_id(serde_json::from_str(cred_def_json)?)?)?;
+        let rev_reg_def: Option<RevocationRegistryDefinition> = rev_reg_def_json
+            .map(|rev_reg_def_json| {
+                serde_json::from_value(with_issuer_id(serde_json::from_str(rev_reg_def_json)?)?).map_err(From::from)
+            })
+            .transpose()?;
+
+        anoncreds::prover::process_credential(
+            &mut credential,
+            &cred_request_metadata,
+            &link_secret,
+            &cred_def,
+            rev_reg_def.as_ref(),
+        )?;
+
+        let schema_id = &credential.schema_id.0;
+        let (schema_issuer_did, schema_name, schema_version) = match schema_id.split(':').collect::<Vec<_>>()[..] {
+            [schema_issuer_did, "2", schema_name, schema_version] => (schema_issuer_did, schema_name, schema_version),
+            _ => {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidSchema,
+                    "Could not process credential.schema_id as parts.",
+                ))
+            }
+        };
+
+        let cred_def_id = &credential.cred_def_id.0;
+        let issuer_did = legacy_issuer_did(cred_def_id)?;
+
+        let mut tags = json!({
+            "schema_id": schema_id,
+            "schema_issuer_did": schema_issuer_did,
+            "schema_name": schema_name,
+            "schema_version": schema_version,
+            "issuer_did": issuer_did,
+            "cred_def_id": cred_def_id
+        });
+
+        if let Some(rev_reg_id) = &credential.rev_reg_id {
+            tags["rev_reg_id"] = Value::String(rev_reg_id.0.to_string())
+        }
+
+        for (raw_attr_name, attr_value) in credential.values.0.iter() {
+            let attr_name = _normalize_attr_name(raw_attr_name);
+            // add attribute name and raw value pair
+            let value_tag_name = _format_attribute_as_value_tag_name(&attr_name);
+            tags[value_tag_name] = Value::String(attr_value.raw.to_string());
+
+            // add attribute name and marker (used for checking existent)
+            let marker_tag_name = _format_attribute_as_marker_tag_name(&attr_name);
+            tags[marker_tag_name] = Value::String("1".to_string());
+        }
+
+        let credential_id = cred_id.map_or(Uuid::new_v4().to_string(), String::from);
+
+        let record_value = serde_json::to_string(&credential)?;
+        let tags_json: HashMap<String, String> = serde_json::from_value(tags)?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_CREDENTIAL, &credential_id, &record_value, Some(tags_json))
+            .await?;
+
+        Ok(credential_id)
+    }
+
+    async fn prover_create_link_secret(&self, link_secret_id: &str) -> VcxCoreResult<String> {
+        let existing_record = self
+            .wallet
+            .get_wallet_record(CATEGORY_LINK_SECRET, link_secret_id, "{}")
+            .await
+            .ok(); // ignore error, as we only care about whether it exists or not
+
+        if existing_record.is_some() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationMasterSecret,
+                format!("Master secret id: {} already exists in wallet.", link_secret_id),
+            ));
+        }
+
+        let link_secret = anoncreds::prover::create_link_secret()?;
+        let ms_decimal: String = link_secret.try_into().map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Failed to convert link secret to decimal string: {err}"),
+            )
+        })?;
+
+        self.wallet
+            .add_wallet_record(CATEGORY_LINK_SECRET, link_secret_id, &ms_decimal, None)
+            .await?;
+
+        Ok(link_secret_id.to_string())
+    }
+
+    async fn prover_delete_credential(&self, cred_id: &str) -> VcxCoreResult<()> {
+        self.wallet.delete_wallet_record(CATEGORY_CREDENTIAL, cred_id).await
+    }
+
+    async fn issuer_create_schema(
+        &self,
+        issuer_did: &str,
+        name: &str,
+        version: &str,
+        attrs: &str,
+    ) -> VcxCoreResult<(String, String)> {
+        let attr_names: Vec<String> = serde_json::from_str(attrs)?;
+
+        let schema = anoncreds::issuer::create_schema(name, version, issuer_did, attr_names.into())?;
+
+        let schema_id = format!("{issuer_did}:2:{name}:{version}");
+        let schema_json = legacy_object(&schema_id, &schema)?.to_string();
+
+        Ok((schema_id, schema_json))
+    }
+
+    /// Revokes the credential in the stored status list and accumulates the change into the
+    /// legacy revocation registry delta to be published, see [BaseAnonCreds::get_rev_reg_delta].
+    async fn revoke_credential_local(
+        &self,
+        _tails_dir: &str,
+        rev_reg_id: &str,
+        cred_rev_id: &str,
+    ) -> VcxCoreResult<()> {
+        let cred_rev_id: u32 = cred_rev_id.parse().map_err(|e| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Invalid cred_rev_id {cred_rev_id} - {e}"),
+            )
+        })?;
+
+        let rev_reg_def: RevocationRegistryDefinition =
+            self.get_wallet_record_value(CATEGORY_REV_REG_DEF, rev_reg_id).await?;
+        let rev_reg_def_priv: RevocationRegistryDefinitionPrivate = self
+            .get_wallet_record_value(CATEGORY_REV_REG_DEF_PRIV, rev_reg_id)
+            .await?;
+        let status_list: RevocationStatusList = self
+            .get_wallet_record_value(CATEGORY_REV_STATUS_LIST, rev_reg_id)
+            .await?;
+        let cred_def: CredentialDefinition = self
+            .get_wallet_record_value(CATEGORY_CRED_DEF, &rev_reg_def.cred_def_id.0)
+            .await?;
+
+        let new_status_list = anoncreds::issuer::update_revocation_status_list(
+            &cred_def,
+            &rev_reg_def,
+            &rev_reg_def_priv,
+            &status_list,
+            None,
+            Some(BTreeSet::from([cred_rev_id])),
+            None,
+        )?;
+
+        let old_str_rev_reg_delta = self.get_rev_reg_delta(rev_reg_id).await?;
+
+        let accum = accumulator(&new_status_list)?;
+        let rev_reg_delta = match &old_str_rev_reg_delta {
+            Some(str_rev_reg_delta) => {
+                let mut rev_reg_delta: Value = serde_json::from_str(str_rev_reg_delta)?;
+                rev_reg_delta["value"]["accum"] = accum;
+                let mut revoked: BTreeSet<u32> = match rev_reg_delta["value"].get("revoked") {
+                    Some(revoked) => serde_json::from_value(revoked.clone())?,
+                    None => BTreeSet::new(),
+                };
+                revoked.insert(cred_rev_id);
+                rev_reg_delta["value"]["revoked"] = json!(revoked);
+                rev_reg_delta
+            }
+            None => json!({
+                "ver": "1.0",
+                "value": {
+                    "prevAccum": accumulator(&status_list)?,
+                    "accum": accum,
+                    "issued": [],
+                    "revoked": [cred_rev_id]
+                }
+            }),
+        };
+
+        let str_status_list = serde_json::to_string(&new_status_list)?;
+        let str_rev_reg_delta = serde_json::to_string(&rev_reg_delta)?;
+
+        self.wallet
+            .update_wallet_record_value(CATEGORY_REV_STATUS_LIST, rev_reg_id, &str_status_list)
+            .await?;
+
+        match old_str_rev_reg_delta {
+            Some(_) => {
+                self.wallet
+                    .update_wallet_record_value(CATEGORY_REV_REG_DELTA, rev_reg_id, &str_rev_reg_delta)
+                    .await?
+            }
+            None => {
+                self.wallet
+                    .add_wallet_record(CATEGORY_REV_REG_DELTA, rev_reg_id, &str_rev_reg_delta, None)
+                    .await?
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_rev_reg_delta(&self, rev_reg_id: &str) -> VcxCoreResult<Option<String>> {
+        let res_rev_reg_delta = self
+            .wallet
+            .get_wallet_record_value(CATEGORY_REV_REG_DELTA, rev_reg_id)
+            .await;
+
+        if let Err(err) = &res_rev_reg_delta {
+            warn!(
+                "get_rev_reg_delta >> Unable to get rev_reg_delta cache for rev_reg_id: {}, error: {}",
+                rev_reg_id, err
+            );
+        }
+
+        Ok(res_rev_reg_delta.ok())
+    }
+
+    async fn clear_rev_reg_delta(&self, rev_reg_id: &str) -> VcxCoreResult<()> {
+        if self.get_rev_reg_delta(rev_reg_id).await?.is_some() {
+            self.wallet
+                .delete_wallet_record(CATEGORY_REV_REG_DELTA, rev_reg_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn generate_nonce(&self) -> VcxCoreResult<String> {
+        let nonce = anoncreds::verifier::generate_nonce()?.to_string();
+        Ok(nonce)
+    }
+}
+
+fn get_rev_state(
+    cred_id: &str,
+    credential: &Credential,
+    detail: &Value,
+    rev_states: Option<&Value>,
+) -> VcxCoreResult<(Option<u64>, Option<CredentialRevocationState>)> {
+    let timestamp = detail.get("timestamp").and_then(|timestamp| timestamp.as_u64());
+    let cred_rev_reg_id = credential.rev_reg_id.as_ref().map(|id| id.0.to_string());
+    let rev_state = if let (Some(timestamp), Some(cred_rev_reg_id)) = (timestamp, cred_rev_reg_id) {
+        let rev_state = rev_states
+            .and_then(|rev_states| rev_states.get(&cred_rev_reg_id))
+            .ok_or(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidJson,
+                format!(
+                    "No revocation states provided for credential '{}' with rev_reg_id '{}'",
+                    cred_id, cred_rev_reg_id
+                ),
+            ))?;
+
+        let rev_state = rev_state.get(timestamp.to_string()).ok_or(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidJson,
+            format!(
+                "No revocation states provided for credential '{}' with rev_reg_id '{}' at timestamp '{}'",
+                cred_id, cred_rev_reg_id, timestamp
+            ),
+        ))?;
+
+        Some(serde_json::from_value(rev_state.clone())?)
+    } else {
+        None
+    };
+
+    Ok((timestamp, rev_state))
+}
+
+fn tails_file_path(tails_dir: &str, tails_hash: &str) -> VcxCoreResult<String> {
+    let mut tails_file_path = PathBuf::new();
+    tails_file_path.push(tails_dir);
+    tails_file_path.push(tails_hash);
+
+    tails_file_path.to_str().map(String::from).ok_or_else(|| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidOption,
+            "tails file is not an unicode string",
+        )
+    })
+}
+
+/// Legacy identifiers are prefixed with the DID of their issuer.
+fn legacy_issuer_did(id: &str) -> VcxCoreResult<&str> {
+    id.split(':').next().filter(|did| !did.is_empty()).ok_or_else(|| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            format!("Could not get the issuer DID of legacy identifier {id}"),
+        )
+    })
+}
+
+/// Adds the `issuerId` anoncreds-rs requires to a legacy schema, credential definition or
+/// revocation registry definition.
+fn with_issuer_id(mut legacy: Value) -> VcxCoreResult<Value> {
+    if legacy.get("issuerId").is_none() {
+        let issuer_did = legacy_issuer_did((&legacy).try_get("id")?.try_as_str()?)?.to_owned();
+        legacy["issuerId"] = Value::String(issuer_did);
+    }
+
+    Ok(legacy)
+}
+
+fn from_legacy_map<K, V>(legacy: Map<String, Value>) -> VcxCoreResult<HashMap<K, V>>
+where
+    K: DeserializeOwned + Eq + std::hash::Hash,
+    V: DeserializeOwned,
+{
+    let objects = legacy
+        .into_iter()
+        .map(|(id, value)| Ok((id, with_issuer_id(value)?)))
+        .collect::<VcxCoreResult<Map<String, Value>>>()?;
+
+    Ok(serde_json::from_value(Value::Object(objects))?)
+}
+
+fn legacy_object<T: Serialize>(id: &str, object: &T) -> VcxCoreResult<Value> {
+    let mut value = serde_json::to_value(object)?;
+    let fields = value.as_object_mut().ok_or_else(|| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidJson,
+            format!("Object {id} is not serialized as a JSON object"),
+        )
+    })?;
+
+    fields.remove("issuerId");
+    fields.insert("id".to_owned(), Value::String(id.to_owned()));
+    fields.insert("ver".to_owned(), Value::String("1.0".to_owned()));
+
+    Ok(value)
+}
+
+/// Legacy credential definitions reference their schema by its ledger sequence number,
+/// which is part of the credential definition ID.
+fn legacy_cred_def(cred_def_id: &str, cred_def: &CredentialDefinition) -> VcxCoreResult<Value> {
+    let schema_seq_no = cred_def_id.split(':').nth(3).ok_or_else(|| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            format!("Could not get the schema sequence number of {cred_def_id}"),
+        )
+    })?;
+
+    let mut value = legacy_object(cred_def_id, cred_def)?;
+    value["schemaId"] = Value::String(schema_seq_no.to_owned());
+
+    Ok(value)
+}
+
+fn legacy_rev_reg_def(rev_reg_id: &str, rev_reg_def: &RevocationRegistryDefinition) -> VcxCoreResult<Value> {
+    let mut value = legacy_object(rev_reg_id, rev_reg_def)?;
+    value["value"]["issuanceType"] = Value::String("ISSUANCE_BY_DEFAULT".to_owned());
+
+    Ok(value)
+}
+
+fn legacy_rev_reg(status_list: &RevocationStatusList) -> VcxCoreResult<Value> {
+    Ok(json!({
+        "ver": "1.0",
+        "value": {
+            "accum": accumulator(status_list)?
+        }
+    }))
+}
+
+fn accumulator(status_list: &RevocationStatusList) -> VcxCoreResult<Value> {
+    let status_list = serde_json::to_value(status_list)?;
+    Ok((&status_list).try_get("currentAccumulator")?.clone())
+}
+
+/// Builds the status list of a registry issued by default from the accumulator and the
+/// revoked indices found in legacy revocation registry entries and deltas.
+fn status_list_from_legacy(
+    rev_reg_id: &str,
+    rev_reg_def: &RevocationRegistryDefinition,
+    accum: &Value,
+    revoked: &[u32],
+    timestamp: Option<u64>,
+) -> VcxCoreResult<RevocationStatusList> {
+    let mut revocation_list = vec![0u8; rev_reg_def.value.max_cred_num as usize];
+    for idx in revoked {
+        if let Some(revoked) = revocation_list.get_mut(*idx as usize) {
+            *revoked = 1;
+        }
+    }
+
+    Ok(serde_json::from_value(json!({
+        "issuerId": rev_reg_def.issuer_id,
+        "revRegDefId": rev_reg_id,
+        "revocationList": revocation_list,
+        "currentAccumulator": accum,
+        "timestamp": timestamp,
+    }))?)
+}
+
+fn _normalize_attr_name(name: &str) -> String {
+    // "name": string, // attribute name, (case insensitive and ignore spaces)
+    name.replace(' ', "").to_lowercase()
+}
+
+fn _make_cred_info(credential_id: &str, cred: &Credential) -> VcxCoreResult<Value> {
+    let cred_sig = serde_json::to_value(&cred.signature)?;
+
+    let rev_info = cred_sig.get("r_credential");
+
+    let schema_id = &cred.schema_id.0;
+    let cred_def_id = &cred.cred_def_id.0;
+    let rev_reg_id = cred.rev_reg_id.as_ref().map(|x| x.0.to_string());
+    let cred_rev_id = rev_info.and_then(|x| x.get("i")).and_then(|i| {
+        i.as_str()
+            .map(|str_i| str_i.to_string())
+            .or(i.as_i64().map(|int_i| int_i.to_string()))
+    });
+
+    let mut attrs = json!({});
+    for (x, y) in cred.values.0.iter() {
+        attrs[x] = Value::String(y.raw.to_string());
+    }
+
+    let val = json!({
+        "referent": credential_id,
+        "schema_id": schema_id,
+        "cred_def_id": cred_def_id,
+        "rev_reg_id": rev_reg_id,
+        "cred_rev_id": cred_rev_id,
+        "attrs": attrs
+    });
+
+    Ok(val)
+}
+
+fn _format_attribute_as_value_tag_name(attribute_name: &str) -> String {
+    format!("attr::{attribute_name}::value")
+}
+
+fn _format_attribute_as_marker_tag_name(attribute_name: &str) -> String {
+    format!("attr::{attribute_name}::marker")
+}
+
+// The in-memory wallet holds the records, so the tests need the `in_memory_wallet` feature too.
+#[cfg(all(test, feature = "in_memory_wallet"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::wallet::in_memory_wallet::InMemoryWallet;
+
+    const ISSUER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const PROVER_DID: &str = "VsKV7grR1BUE29mG2Fm2kX";
+    const LINK_SECRET_ID: &str = "main";
+
+    struct CredDef {
+        schema_id: String,
+        schema_json: String,
+        cred_def_id: String,
+        cred_def_json: String,
+    }
+
+    fn _anoncreds() -> AnoncredsRsAnonCreds {
+        AnoncredsRsAnonCreds::new(Arc::new(InMemoryWallet::new()))
+    }
+
+    fn _tails_dir() -> String {
+        let tails_dir = std::env::temp_dir().join(format!("anoncreds_rs_tails_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&tails_dir).unwrap();
+        tails_dir.to_str().unwrap().to_owned()
+    }
+
+    fn _cred_values(age: u32) -> String {
+        json!({
+            "name": { "raw": "Alice", "encoded": "27034640024117331033063128044004318218486816931520886405535659934417438781507" },
+            "age": { "raw": age.to_string(), "encoded": age.to_string() }
+        })
+        .to_string()
+    }
+
+    async fn _create_cred_def(anoncreds: &AnoncredsRsAnonCreds, support_revocation: bool) -> CredDef {
+        let (schema_id, schema_json) = anoncreds
+            .issuer_create_schema(ISSUER_DID, "test_schema", "1.0", r#"["name", "age"]"#)
+            .await
+            .unwrap();
+
+        // Schemas get their sequence number once written to the ledger.
+        let mut schema: Value = serde_json::from_str(&schema_json).unwrap();
+        schema["seqNo"] = json!(42);
+        let schema_json = schema.to_string();
+
+        let (cred_def_id, cred_def_json) = anoncreds
+            .issuer_create_and_store_credential_def(
+                ISSUER_DID,
+                &schema_json,
+                "tag",
+                None,
+                &json!({ "support_revocation": support_revocation }).to_string(),
+            )
+            .await
+            .unwrap();
+
+        CredDef {
+            schema_id,
+            schema_json,
+            cred_def_id,
+            cred_def_json,
+        }
+    }
+
+    /// Issues a credential to the prover, storing it under `cred_id`, and returns its revocation ID.
+    async fn _issue_credential(
+        anoncreds: &AnoncredsRsAnonCreds,
+        cred_def: &CredDef,
+        cred_id: &str,
+        age: u32,
+        revocation: Option<(&str, &str)>,
+    ) -> Option<String> {
+        let offer = anoncreds
+            .issuer_create_credential_offer(&cred_def.cred_def_id)
+            .await
+            .unwrap();
+        let (request, metadata) = anoncreds
+            .prover_create_credential_req(PROVER_DID, &offer, &cred_def.cred_def_json, LINK_SECRET_ID)
+            .await
+            .unwrap();
+
+        let (credential, cred_rev_id, rev_reg_delta) = anoncreds
+            .issuer_create_credential(
+                &offer,
+                &request,
+                &_cred_values(age),
+                revocation.map(|(rev_reg_id, _)| rev_reg_id.to_owned()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(rev_reg_delta.is_none());
+
+        let stored_id = anoncreds
+            .prover_store_credential(
+                Some(cred_id),
+                &metadata,
+                &credential,
+                &cred_def.cred_def_json,
+                revocation.map(|(_, rev_reg_def_json)| rev_reg_def_json),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stored_id, cred_id);
+
+        cred_rev_id
+    }
+
+    async fn _revocation_list(anoncreds: &AnoncredsRsAnonCreds, rev_reg_id: &str) -> Value {
+        let status_list: RevocationStatusList = anoncreds
+            .get_wallet_record_value(CATEGORY_REV_STATUS_LIST, rev_reg_id)
+            .await
+            .unwrap();
+        serde_json::to_value(status_list).unwrap()["revocationList"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_create_link_secret() {
+        let anoncreds = _anoncreds();
+
+        let link_secret_id = anoncreds.prover_create_link_secret(LINK_SECRET_ID).await.unwrap();
+        assert_eq!(link_secret_id, LINK_SECRET_ID);
+        anoncreds.get_link_secret(LINK_SECRET_ID).await.unwrap();
+
+        let err = anoncreds.prover_create_link_secret(LINK_SECRET_ID).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxCoreErrorKind::DuplicationMasterSecret);
+
+        anoncreds.get_link_secret("unknown").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_create_cred_def_is_idempotent() {
+        let anoncreds = _anoncreds();
+        let cred_def = _create_cred_def(&anoncreds, false).await;
+
+        assert_eq!(cred_def.schema_id, format!("{ISSUER_DID}:2:test_schema:1.0"));
+        assert_eq!(cred_def.cred_def_id, format!("{ISSUER_DID}:3:CL:42:tag"));
+
+        let legacy_cred_def: Value = serde_json::from_str(&cred_def.cred_def_json).unwrap();
+        assert_eq!(legacy_cred_def["id"], json!(cred_def.cred_def_id));
+        assert_eq!(legacy_cred_def["schemaId"], json!("42"));
+        assert!(legacy_cred_def.get("issuerId").is_none());
+
+        let (cred_def_id, cred_def_json) = anoncreds
+            .issuer_create_and_store_credential_def(ISSUER_DID, &cred_def.schema_json, "tag", None, "{}")
+            .await
+            .unwrap();
+        assert_eq!(cred_def_id, cred_def.cred_def_id);
+        assert_eq!(cred_def_json, cred_def.cred_def_json);
+    }
+
+    #[tokio::test]
+    async fn test_issue_and_store_credential() {
+        let anoncreds = _anoncreds();
+        let cred_def = _create_cred_def(&anoncreds, false).await;
+        anoncreds.prover_create_link_secret(LINK_SECRET_ID).await.unwrap();
+
+        let cred_rev_id = _issue_credential(&anoncreds, &cred_def, "cred_1", 25, None).await;
+        assert!(cred_rev_id.is_none());
+
+        let cred_info: Value = serde_json::from_str(&anoncreds.prover_get_credential("cred_1").await.unwrap()).unwrap();
+        assert_eq!(cred_info["referent"], json!("cred_1"));
+        assert_eq!(cred_info["schema_id"], json!(cred_def.schema_id));
+        assert_eq!(cred_info["cred_def_id"], json!(cred_def.cred_def_id));
+        assert_eq!(cred_info["attrs"], json!({ "name": "Alice", "age": "25" }));
+        assert_eq!(cred_info["rev_reg_id"], Value::Null);
+
+        let filter = json!({ "schema_name": "test_schema", "attr::age::value": "25" }).to_string();
+        let creds: Vec<Value> =
+            serde_json::from_str(&anoncreds.prover_get_credentials(Some(&filter)).await.unwrap()).unwrap();
+        assert_eq!(creds.len(), 1);
+
+        let filter = json!({ "issuer_did": PROVER_DID }).to_string();
+        let creds: Vec<Value> =
+            serde_json::from_str(&anoncreds.prover_get_credentials(Some(&filter)).await.unwrap()).unwrap();
+        assert!(creds.is_empty());
+
+        anoncreds.prover_delete_credential("cred_1").await.unwrap();
+        anoncreds.prover_get_credential("cred_1").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_create_and_verify_proof() {
+        let anoncreds = _anoncreds();
+        let cred_def = _create_cred_def(&anoncreds, false).await;
+        anoncreds.prover_create_link_secret(LINK_SECRET_ID).await.unwrap();
+        _issue_credential(&anoncreds, &cred_def, "cred_1", 25, None).await;
+
+        let proof_request = |min_age: u32| {
+            json!({
+                "nonce": "123432421212",
+                "name": "proof_request",
+                "version": "1.0",
+                "requested_attributes": {
+                    "attr_age": { "name": "age", "restrictions": { "cred_def_id": cred_def.cred_def_id } },
+                    "attr_name": { "name": "Name" },
+                },
+                "requested_predicates": {
+                    "pred_age": { "name": "age", "p_type": ">=", "p_value": min_age },
+                },
+            })
+            .to_string()
+        };
+
+        let creds_for_proof_req: Value = serde_json::from_str(
+            &anoncreds
+                .prover_get_credentials_for_proof_req(&proof_request(18))
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        for referent in ["attr_age", "attr_name", "pred_age"] {
+            assert_eq!(
+                creds_for_proof_req[ATTRS][referent][0]["cred_info"]["referent"],
+                json!("cred_1")
+            );
+        }
+
+        let requested_credentials = json!({
+            "self_attested_attributes": {},
+            "requested_attributes": {
+                "attr_age": { "cred_id": "cred_1", "revealed": true },
+                "attr_name": { "cred_id": "cred_1", "revealed": false },
+            },
+            "requested_predicates": {
+                "pred_age": { "cred_id": "cred_1" },
+            },
+        })
+        .to_string();
+        let schemas =
+            json!({ cred_def.schema_id.clone(): serde_json::from_str::<Value>(&cred_def.schema_json).unwrap() })
+                .to_string();
+        let cred_defs =
+            json!({ cred_def.cred_def_id.clone(): serde_json::from_str::<Value>(&cred_def.cred_def_json).unwrap() })
+                .to_string();
+
+        let proof = anoncreds
+            .prover_create_proof(
+                &proof_request(18),
+                &requested_credentials,
+                LINK_SECRET_ID,
+                &schemas,
+                &cred_defs,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let revealed: Value = serde_json::from_str(&proof).unwrap();
+        assert_eq!(
+            revealed["requested_proof"]["revealed_attrs"]["attr_age"]["raw"],
+            json!("25")
+        );
+
+        assert!(anoncreds
+            .verifier_verify_proof(&proof_request(18), &proof, &schemas, &cred_defs, "{}", "{}")
+            .await
+            .unwrap());
+
+        // The proof does not hold for another request.
+        let other_request = anoncreds
+            .verifier_verify_proof(&proof_request(21), &proof, &schemas, &cred_defs, "{}", "{}")
+            .await;
+        assert!(!matches!(other_request, Ok(true)));
+
+        // The predicate cannot be proven.
+        anoncreds
+            .prover_create_proof(
+                &proof_request(30),
+                &requested_credentials,
+                LINK_SECRET_ID,
+                &schemas,
+                &cred_defs,
+                None,
+            )
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_revocation_status_list_updates() {
+        let anoncreds = _anoncreds();
+        let cred_def = _create_cred_def(&anoncreds, true).await;
+        anoncreds.prover_create_link_secret(LINK_SECRET_ID).await.unwrap();
+        let tails_dir = _tails_dir();
+
+        let (rev_reg_id, rev_reg_def_json, rev_reg_json) = anoncreds
+            .issuer_create_and_store_revoc_reg(ISSUER_DID, &cred_def.cred_def_id, &tails_dir, 10, "tag1")
+            .await
+            .unwrap();
+        assert_eq!(
+            rev_reg_id,
+            format!("{ISSUER_DID}:4:{}:CL_ACCUM:tag1", cred_def.cred_def_id)
+        );
+        let legacy_rev_reg_def: Value = serde_json::from_str(&rev_reg_def_json).unwrap();
+        assert_eq!(legacy_rev_reg_def["id"], json!(rev_reg_id));
+        assert_eq!(
+            legacy_rev_reg_def["value"]["issuanceType"],
+            json!("ISSUANCE_BY_DEFAULT")
+        );
+
+        // Creating the registry again returns the stored one.
+        let (_, stored_rev_reg_def_json, stored_rev_reg_json) = anoncreds
+            .issuer_create_and_store_revoc_reg(ISSUER_DID, &cred_def.cred_def_id, &tails_dir, 10, "tag1")
+            .await
+            .unwrap();
+        assert_eq!(stored_rev_reg_def_json, rev_reg_def_json);
+        assert_eq!(stored_rev_reg_json, rev_reg_json);
+
+        let revocation = Some((rev_reg_id.as_str(), rev_reg_def_json.as_str()));
+        let first_rev_id = _issue_credential(&anoncreds, &cred_def, "cred_1", 25, revocation)
+            .await
+            .unwrap();
+        let second_rev_id = _issue_credential(&anoncreds, &cred_def, "cred_2", 30, revocation)
+            .await
+            .unwrap();
+        assert_ne!(first_rev_id, second_rev_id);
+
+        let cred_info: Value = serde_json::from_str(&anoncreds.prover_get_credential("cred_1").await.unwrap()).unwrap();
+        assert_eq!(cred_info["rev_reg_id"], json!(rev_reg_id));
+        assert_eq!(cred_info["cred_rev_id"], json!(first_rev_id));
+
+        // Revocation states are created from the registry as published.
+        let timestamp = 1_700_000_000;
+        let rev_state: Value = serde_json::from_str(
+            &anoncreds
+                .create_revocation_state(&tails_dir, &rev_reg_def_json, &rev_reg_json, timestamp, &first_rev_id)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(rev_state["timestamp"], json!(timestamp));
+
+        assert!(anoncreds.get_rev_reg_delta(&rev_reg_id).await.unwrap().is_none());
+        let issued_list = _revocation_list(&anoncreds, &rev_reg_id).await;
+        assert!(issued_list.as_array().unwrap().iter().all(|status| status == &json!(0)));
+
+        anoncreds
+            .revoke_credential_local(&tails_dir, &rev_reg_id, &first_rev_id)
+            .await
+            .unwrap();
+
+        let first_idx: usize = first_rev_id.parse().unwrap();
+        let revoked_list = _revocation_list(&anoncreds, &rev_reg_id).await;
+        assert_eq!(revoked_list[first_idx], json!(1));
+
+        let rev_reg: Value = serde_json::from_str(&rev_reg_json).unwrap();
+        let delta: Value =
+            serde_json::from_str(&anoncreds.get_rev_reg_delta(&rev_reg_id).await.unwrap().unwrap()).unwrap();
+        assert_eq!(delta["value"]["prevAccum"], rev_reg["value"]["accum"]);
+        assert_ne!(delta["value"]["accum"], rev_reg["value"]["accum"]);
+        assert_eq!(delta["value"]["revoked"], json!([first_idx]));
+
+        anoncreds
+            .revoke_credential_local(&tails_dir, &rev_reg_id, &second_rev_id)
+            .await
+            .unwrap();
+
+        let second_idx: usize = second_rev_id.parse().unwrap();
+        let revoked_list = _revocation_list(&anoncreds, &rev_reg_id).await;
+        assert_eq!(revoked_list[first_idx], json!(1));
+        assert_eq!(revoked_list[second_idx], json!(1));
+
+        // Revocations accumulate into the delta until it is published.
+        let accumulated: Value =
+            serde_json::from_str(&anoncreds.get_rev_reg_delta(&rev_reg_id).await.unwrap().unwrap()).unwrap();
+        assert_eq!(accumulated["value"]["prevAccum"], rev_reg["value"]["accum"]);
+        assert_ne!(accumulated["value"]["accum"], delta["value"]["accum"]);
+        assert_eq!(accumulated["value"]["revoked"], json!([first_idx, second_idx]));
+
+        anoncreds.clear_rev_reg_delta(&rev_reg_id).await.unwrap();
+        assert!(anoncreds.get_rev_reg_delta(&rev_reg_id).await.unwrap().is_none());
+
+        anoncreds
+            .revoke_credential_local(&tails_dir, &rev_reg_id, "not_a_number")
+            .await
+            .unwrap_err();
+
+        std::fs::remove_dir_all(tails_dir).unwrap();
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "anoncreds_rs")]
+pub mod anoncreds_rs_anoncreds;
 pub mod base_anoncreds;
 #[cfg(any(feature = "modular_libs", feature = "vdr_proxy_ledger"))]
 pub mod credx_anoncreds;
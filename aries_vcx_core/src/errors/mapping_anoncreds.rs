@@ -0,0 +1,24 @@
+use anoncreds::{Error as AnoncredsError, ErrorKind as AnoncredsErrorKind};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind};
+
+impl From<AnoncredsError> for AriesVcxCoreError {
+    fn from(err: AnoncredsError) -> Self {
+        match err.kind() {
+            AnoncredsErrorKind::Input => AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err),
+            AnoncredsErrorKind::IOError => AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::IOError, err),
+            AnoncredsErrorKind::InvalidState => AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, err),
+            AnoncredsErrorKind::Unexpected => AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::UnknownError, err),
+            AnoncredsErrorKind::CredentialRevoked => {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, err)
+            }
+            AnoncredsErrorKind::InvalidUserRevocId => {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err)
+            }
+            AnoncredsErrorKind::ProofRejected => AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::ProofRejected, err),
+            AnoncredsErrorKind::RevocationRegistryFull => {
+                AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, err)
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use aries_askar::{Error as AskarError, ErrorKind as AskarErrorKind};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind};
+
+impl From<AskarError> for AriesVcxCoreError {
+    fn from(err: AskarError) -> Self {
+        let kind = match err.kind() {
+            AskarErrorKind::Duplicate => AriesVcxCoreErrorKind::DuplicationWalletRecord,
+            AskarErrorKind::NotFound => AriesVcxCoreErrorKind::WalletRecordNotFound,
+            AskarErrorKind::Input => AriesVcxCoreErrorKind::InvalidInput,
+            AskarErrorKind::Unsupported => AriesVcxCoreErrorKind::UnimplementedFeature,
+            AskarErrorKind::Backend | AskarErrorKind::Busy | AskarErrorKind::Encryption => {
+                AriesVcxCoreErrorKind::WalletAccessFailed
+            }
+            AskarErrorKind::Custom | AskarErrorKind::Unexpected => AriesVcxCoreErrorKind::UnknownError,
+        };
+
+        AriesVcxCoreError::from_msg(kind, err)
+    }
+}
@@ -1,5 +1,9 @@
 pub mod error;
 mod mapping_agency_client;
+#[cfg(feature = "anoncreds_rs")]
+mod mapping_anoncreds;
+#[cfg(feature = "askar_wallet")]
+mod mapping_askar;
 #[cfg(any(feature = "modular_libs", feature = "vdr_proxy_ledger"))]
 mod mapping_credx;
 #[cfg(any(feature = "modular_libs", feature = "vdr_proxy_ledger"))]
@@ -219,6 +219,26 @@ pub async fn get_nym(pool_handle: PoolHandle, did: &str) -> VcxCoreResult<String
     libindy_submit_request(pool_handle, &get_nym_req).await
 }
 
+pub async fn get_nym_version(
+    pool_handle: PoolHandle,
+    did: &str,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+) -> VcxCoreResult<String> {
+    let submitter_did = get_sample_did();
+
+    let get_nym_req = libindy_build_get_nym_request(Some(&submitter_did), did).await?;
+    let mut get_nym_req: serde_json::Value = serde_json::from_str(&get_nym_req)?;
+    if let Some(seq_no) = seq_no {
+        get_nym_req["operation"]["seqNo"] = seq_no.into();
+    }
+    if let Some(timestamp) = timestamp {
+        get_nym_req["operation"]["timestamp"] = timestamp.into();
+    }
+
+    libindy_submit_request(pool_handle, &get_nym_req.to_string()).await
+}
+
 fn parse_response(response: &str) -> VcxCoreResult<Response> {
     serde_json::from_str::<Response>(response).map_err(|err| {
         AriesVcxCoreError::from_msg(
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use vdrtools::{
     types::domain::wallet::{default_key_derivation_method, KeyDerivationMethod},
     types::errors::IndyErrorKind,
-    Locator,
+    DidValue, KeyInfo, Locator, MyDidInfo,
 };
 
 use crate::{
@@ -11,7 +11,7 @@ use crate::{
     indy::credentials::holder,
 };
 use crate::{global::settings, WalletHandle};
-use crate::{indy::keys, SearchHandle};
+use crate::{indy::keys, wallet::export_import::WalletArchive, SearchHandle};
 
 #[derive(Clone, Debug, Default, Builder, Serialize, Deserialize)]
 #[builder(setter(into, strip_option), default)]
@@ -269,6 +269,69 @@ pub async fn import(restore_config: &RestoreWalletConfigs) -> VcxCoreResult<()>
     Ok(())
 }
 
+/// Imports the DIDs, keys and records of a wallet archive into an opened wallet, while [import]
+/// restores a vdrtools export as a new wallet. Archives created by other [BaseWallet](crate::wallet::base_wallet::BaseWallet)
+/// implementations can be imported either way.
+pub async fn import_archive(wallet_handle: WalletHandle, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+    trace!(
+        "import_archive >>> wallet_handle: {:?}, path: {:?}",
+        wallet_handle,
+        path
+    );
+
+    let archive = WalletArchive::read(path, backup_key)?;
+
+    let mut did_verkeys = Vec::new();
+    for did in archive.dids.iter() {
+        Locator::instance()
+            .did_controller
+            .create_and_store_my_did(
+                wallet_handle,
+                MyDidInfo {
+                    did: Some(DidValue(did.did.clone())),
+                    seed: Some(archive.key(&did.verkey)?.hex_seed()),
+                    ..MyDidInfo::default()
+                },
+            )
+            .await?;
+        did_verkeys.push(did.verkey.as_str());
+
+        if let Some(temp_verkey) = &did.temp_verkey {
+            let key_info = KeyInfo {
+                seed: Some(archive.key(temp_verkey)?.hex_seed()),
+                ..KeyInfo::default()
+            };
+            Locator::instance()
+                .did_controller
+                .replace_keys_start(wallet_handle, key_info, DidValue(did.did.clone()))
+                .await?;
+            did_verkeys.push(temp_verkey.as_str());
+        }
+    }
+
+    for key in archive
+        .keys
+        .iter()
+        .filter(|key| !did_verkeys.contains(&key.verkey.as_str()))
+    {
+        let key_info = KeyInfo {
+            seed: Some(key.hex_seed()),
+            ..KeyInfo::default()
+        };
+        Locator::instance()
+            .crypto_controller
+            .create_key(wallet_handle, &key_info)
+            .await?;
+    }
+
+    for record in archive.records.iter() {
+        let tags = serde_json::to_string(&record.tags)?;
+        add_wallet_record(wallet_handle, &record.type_, &record.id, &record.value, Some(&tags)).await?;
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn add_wallet_record(
     wallet_handle: WalletHandle,
     xtype: &str,
@@ -8,6 +8,8 @@ use crate::errors::error::VcxCoreResult;
 pub trait IndyLedgerRead: Debug + Send + Sync {
     async fn get_attr(&self, target_did: &str, attr_name: &str) -> VcxCoreResult<String>;
     async fn get_nym(&self, did: &str) -> VcxCoreResult<String>;
+    /// Reads the NYM of `did` as of transaction `seq_no` or of time `timestamp`, rather than its latest state.
+    async fn get_nym_version(&self, did: &str, seq_no: Option<i32>, timestamp: Option<u64>) -> VcxCoreResult<String>;
     async fn get_txn_author_agreement(&self) -> VcxCoreResult<Option<String>>;
     async fn get_ledger_txn(&self, seq_no: i32, submitter_did: Option<&str>) -> VcxCoreResult<String>;
 }
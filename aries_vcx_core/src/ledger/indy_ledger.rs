@@ -46,6 +46,10 @@ impl IndyLedgerRead for IndySdkLedgerRead {
         indy::ledger::transactions::get_nym(self.indy_pool_handle, did).await
     }
 
+    async fn get_nym_version(&self, did: &str, seq_no: Option<i32>, timestamp: Option<u64>) -> VcxCoreResult<String> {
+        indy::ledger::transactions::get_nym_version(self.indy_pool_handle, did, seq_no, timestamp).await
+    }
+
     async fn get_txn_author_agreement(&self) -> VcxCoreResult<Option<String>> {
         map_error_not_found_to_none(
             indy::ledger::transactions::libindy_get_txn_author_agreement(self.indy_pool_handle).await,
@@ -14,7 +14,7 @@ use serde_json::Value;
 use vdr::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
 use vdr::ledger::requests::cred_def::CredentialDefinition;
 use vdr::ledger::RequestBuilder;
-use vdr::pool::{LedgerType, PreparedRequest, ProtocolVersion as VdrProtocolVersion};
+use vdr::pool::{LedgerType, PreparedRequest, ProtocolVersion as VdrProtocolVersion, RequestMethod};
 use vdr::utils::did::DidValue;
 use vdr::utils::Qualifiable;
 
@@ -92,6 +92,32 @@ where
         Ok(RequestBuilder::new(self.protocol_version.0))
     }
 
+    // The request is prepared again from its JSON once the version is set, for the request
+    // metadata to be derived from the versioned request.
+    fn build_get_nym_version_request(
+        &self,
+        did: &str,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> VcxCoreResult<PreparedRequest> {
+        let dest = DidValue::from_str(did)?;
+        let mut req_json = self.request_builder()?.build_get_nym_request(None, &dest)?.req_json;
+        if let Some(seq_no) = seq_no {
+            req_json["operation"]["seqNo"] = seq_no.into();
+        }
+        if let Some(timestamp) = timestamp {
+            req_json["operation"]["timestamp"] = timestamp.into();
+        }
+
+        let mut request = PreparedRequest::from_request_json(serde_json::to_vec(&req_json)?)?;
+        if let RequestMethod::BuiltinStateProof { sp_timestamps, .. } = &mut request.method {
+            // A past state is proven by the multi-signature of its time, which cannot be checked
+            // for freshness against the current time, as done for GET_TXN.
+            *sp_timestamps = (None, Some(0));
+        }
+        Ok(request)
+    }
+
     async fn submit_request_cached(&self, id: &str, request: PreparedRequest) -> VcxCoreResult<String> {
         match self.response_cacher.get(id, None).await? {
             Some(response) => Ok(response),
@@ -182,6 +208,12 @@ where
         self.submit_request_cached(did, request).await
     }
 
+    async fn get_nym_version(&self, did: &str, seq_no: Option<i32>, timestamp: Option<u64>) -> VcxCoreResult<String> {
+        let request = self.build_get_nym_version_request(did, seq_no, timestamp)?;
+        // Historical states are not cached, as the cache is keyed by the DID only
+        self.request_submitter.submit(request).await
+    }
+
     async fn get_txn_author_agreement(&self) -> VcxCoreResult<Option<String>> {
         let request = self
             .request_builder()?
@@ -456,3 +488,61 @@ impl ProtocolVersion {
         ProtocolVersion(VdrProtocolVersion::Node1_4)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::ledger::response_cacher::noop::NoopResponseCacher;
+
+    struct DummySubmitter;
+
+    #[async_trait]
+    impl RequestSubmitter for DummySubmitter {
+        async fn submit(&self, _request: PreparedRequest) -> VcxCoreResult<String> {
+            unimplemented!()
+        }
+    }
+
+    fn ledger() -> IndyVdrLedgerRead<DummySubmitter, NoopResponseCacher> {
+        IndyVdrLedgerRead::new(IndyVdrLedgerReadConfig {
+            request_submitter: Arc::new(DummySubmitter),
+            response_parser: Arc::new(ResponseParser::new()),
+            response_cacher: Arc::new(NoopResponseCacher::new()),
+            protocol_version: ProtocolVersion::default(),
+        })
+    }
+
+    fn state_proof_params(request: &PreparedRequest) -> (Vec<u8>, (Option<u64>, Option<u64>)) {
+        match &request.method {
+            RequestMethod::BuiltinStateProof { sp_key, sp_timestamps } => (sp_key.clone(), *sp_timestamps),
+            method => panic!("Expected a request checked against a state proof, got {:?}", method),
+        }
+    }
+
+    #[test]
+    fn test_get_nym_version_request() {
+        let ledger = ledger();
+        let did = "V4SGRU86Z58d6TV7PBUe6f";
+        let latest = ledger
+            .request_builder()
+            .unwrap()
+            .build_get_nym_request(None, &DidValue::from_str(did).unwrap())
+            .unwrap();
+        let (sp_key, sp_timestamps) = state_proof_params(&latest);
+        assert_eq!(sp_timestamps, (None, None));
+
+        let request = ledger.build_get_nym_version_request(did, Some(42), None).unwrap();
+        assert_eq!(request.req_json["operation"]["seqNo"], 42);
+        assert!(request.req_json["operation"]["timestamp"].is_null());
+        assert_eq!(request.req_id, request.req_json["reqId"].to_string());
+        assert_eq!(state_proof_params(&request), (sp_key.clone(), (None, Some(0))));
+
+        let request = ledger
+            .build_get_nym_version_request(did, None, Some(1_600_000_000))
+            .unwrap();
+        assert!(request.req_json["operation"]["seqNo"].is_null());
+        assert_eq!(request.req_json["operation"]["timestamp"], 1_600_000_000u64);
+        assert_eq!(state_proof_params(&request), (sp_key, (None, Some(0))));
+    }
+}
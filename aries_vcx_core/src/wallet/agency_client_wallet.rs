@@ -105,6 +105,14 @@ impl BaseWallet for AgencyClientWallet {
     async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
         Ok(self.inner.unpack_message(msg).await?)
     }
+
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        Err(unimplemented_agency_client_wallet_method("export_wallet"))
+    }
+
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        Err(unimplemented_agency_client_wallet_method("import_wallet"))
+    }
 }
 
 pub trait ToBaseWallet {
@@ -0,0 +1,748 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use aries_askar::{
+    any::{AnySession, AnyStore},
+    generate_raw_store_key,
+    kms::{KeyAlg, LocalKey},
+    Entry, EntryTag, ManageBackend, PassKey, StoreKeyMethod, TagFilter,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+use crate::utils::async_fn_iterator::AsyncFnIterator;
+
+use super::base_wallet::BaseWallet;
+use super::common::crypto::{self, SigningKey};
+use super::common::record_options::RecordOptions;
+use super::export_import::{ArchivedDid, ArchivedKey, ArchivedRecord, WalletArchive};
+
+// Categories used by the wallet itself are prefixed so that they can't clash with record types.
+const RESERVED_CATEGORY_PREFIX: &str = "AriesVcx::";
+const DID_CATEGORY: &str = "AriesVcx::Did";
+// Askar cannot list the categories of a profile, so the record types in use are kept track of
+// in order to export the records.
+const RECORD_TYPE_CATEGORY: &str = "AriesVcx::RecordType";
+
+/// How the store key is obtained from the pass key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AskarKeyMethod {
+    /// The pass key is a password, from which the store key is derived with Argon2i.
+    DeriveKey,
+    /// The pass key is a raw key, as generated by [`AskarWallet::generate_raw_key`].
+    RawKey,
+}
+
+impl AskarKeyMethod {
+    fn to_store_key_method(self) -> StoreKeyMethod {
+        match self {
+            // Argon2i with the moderate difficulty level
+            AskarKeyMethod::DeriveKey => StoreKeyMethod::default(),
+            AskarKeyMethod::RawKey => StoreKeyMethod::RawKey,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AskarWalletConfig {
+    db_url: String,
+    pass_key: String,
+    key_method: AskarKeyMethod,
+    profile: Option<String>,
+}
+
+impl AskarWalletConfig {
+    /// Creates the config of a store at `db_url`, such as `sqlite://wallet.db` or
+    /// `sqlite://:memory:`, protected by the password `pass_key`.
+    pub fn new(db_url: &str, pass_key: &str) -> Self {
+        Self {
+            db_url: db_url.to_owned(),
+            pass_key: pass_key.to_owned(),
+            key_method: AskarKeyMethod::DeriveKey,
+            profile: None,
+        }
+    }
+
+    pub fn key_method(mut self, key_method: AskarKeyMethod) -> Self {
+        self.key_method = key_method;
+        self
+    }
+
+    /// Selects the profile to use, the store's default profile being used otherwise.
+    pub fn profile(mut self, profile: &str) -> Self {
+        self.profile = Some(profile.to_owned());
+        self
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DidValue {
+    verkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temp_verkey: Option<String>,
+}
+
+/// [`BaseWallet`] implementation on top of an [Aries Askar](https://github.com/hyperledger/aries-askar) store.
+///
+/// Keys never leave the store other than for packing messages. Records are kept in categories
+/// named after their type, tags prefixed with `~` being stored unencrypted, as in the indy wallet.
+#[derive(Debug, Clone)]
+pub struct AskarWallet {
+    store: Arc<AnyStore>,
+    profile: Option<String>,
+}
+
+impl AskarWallet {
+    /// Provisions a new store, replacing any existing one at the same location if `recreate` is set.
+    pub async fn create(config: &AskarWalletConfig, recreate: bool) -> VcxCoreResult<Self> {
+        let store = config
+            .db_url
+            .as_str()
+            .provision_backend(
+                config.key_method.to_store_key_method(),
+                PassKey::from(config.pass_key.as_str()),
+                config.profile.as_deref(),
+                recreate,
+            )
+            .await?;
+
+        Ok(Self {
+            store: Arc::new(store),
+            profile: config.profile.clone(),
+        })
+    }
+
+    pub async fn open(config: &AskarWalletConfig) -> VcxCoreResult<Self> {
+        let store = config
+            .db_url
+            .as_str()
+            .open_backend(
+                Some(config.key_method.to_store_key_method()),
+                PassKey::from(config.pass_key.as_str()),
+                config.profile.as_deref(),
+            )
+            .await?;
+
+        Ok(Self {
+            store: Arc::new(store),
+            profile: config.profile.clone(),
+        })
+    }
+
+    /// Generates a random key to use with [`AskarKeyMethod::RawKey`].
+    pub fn generate_raw_key() -> VcxCoreResult<String> {
+        Ok(generate_raw_store_key(None)?.to_string())
+    }
+
+    /// Creates a new profile in the store, which can be opened as a separate wallet
+    /// through [`AskarWallet::with_profile`].
+    pub async fn create_profile(&self, profile: &str) -> VcxCoreResult<String> {
+        Ok(self.store.create_profile(Some(profile.to_owned())).await?)
+    }
+
+    /// Returns a wallet sharing the same store but working within another profile.
+    pub fn with_profile(&self, profile: &str) -> Self {
+        Self {
+            store: self.store.clone(),
+            profile: Some(profile.to_owned()),
+        }
+    }
+
+    /// Closes the store, unless it is still shared with wallets of other profiles, in which
+    /// case it is closed when the last of them is dropped.
+    pub async fn close(self) -> VcxCoreResult<()> {
+        match Arc::try_unwrap(self.store) {
+            Ok(store) => Ok(store.close().await?),
+            Err(_) => Ok(()),
+        }
+    }
+
+    async fn session(&self) -> VcxCoreResult<AnySession> {
+        Ok(self.store.session(self.profile.clone()).await?)
+    }
+
+    async fn transaction(&self) -> VcxCoreResult<AnySession> {
+        Ok(self.store.transaction(self.profile.clone()).await?)
+    }
+
+    async fn insert_key(&self, session: &mut AnySession, key: &SigningKey) -> VcxCoreResult<String> {
+        let verkey = crypto::verkey(key);
+        let local_key = LocalKey::from_secret_bytes(KeyAlg::Ed25519, &key.to_bytes())?;
+        session.insert_key(&verkey, &local_key, None, None, None).await?;
+        Ok(verkey)
+    }
+
+    async fn fetch_local_key(&self, session: &mut AnySession, verkey: &str) -> VcxCoreResult<Option<LocalKey>> {
+        match session.fetch_key(verkey, false).await? {
+            Some(entry) => Ok(Some(entry.load_local_key()?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch_signing_key(&self, session: &mut AnySession, verkey: &str) -> VcxCoreResult<Option<SigningKey>> {
+        let Some(local_key) = self.fetch_local_key(session, verkey).await? else {
+            return Ok(None);
+        };
+
+        let secret: [u8; 32] = local_key.to_secret_bytes()?.as_ref().try_into().map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Key {} is not an Ed25519 key", verkey),
+            )
+        })?;
+
+        Ok(Some(SigningKey::from_bytes(&secret)))
+    }
+
+    async fn fetch_did(&self, session: &mut AnySession, did: &str, for_update: bool) -> VcxCoreResult<DidValue> {
+        let entry = session.fetch(DID_CATEGORY, did, for_update).await?.ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("DID {} not found in wallet", did),
+            )
+        })?;
+
+        Ok(serde_json::from_slice(&entry.value)?)
+    }
+
+    async fn fetch_record(
+        &self,
+        session: &mut AnySession,
+        xtype: &str,
+        id: &str,
+        for_update: bool,
+    ) -> VcxCoreResult<Entry> {
+        check_record_type(xtype)?;
+        session.fetch(xtype, id, for_update).await?.ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("Wallet record of type {} with id {} not found", xtype, id),
+            )
+        })
+    }
+
+    /// Inserts a record, keeping track of its type.
+    async fn insert_record(
+        &self,
+        transaction: &mut AnySession,
+        xtype: &str,
+        id: &str,
+        value: &str,
+        tags: Option<HashMap<String, String>>,
+    ) -> VcxCoreResult<()> {
+        check_record_type(xtype)?;
+        let tags = tags.map(to_entry_tags);
+        transaction
+            .insert(xtype, id, value.as_bytes(), tags.as_deref(), None)
+            .await?;
+
+        if transaction.fetch(RECORD_TYPE_CATEGORY, xtype, true).await?.is_none() {
+            transaction.insert(RECORD_TYPE_CATEGORY, xtype, &[], None, None).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the value and/or tags of a record within a transaction.
+    async fn update_record<F>(&self, xtype: &str, id: &str, update: F) -> VcxCoreResult<()>
+    where
+        F: FnOnce(String, HashMap<String, String>) -> (String, HashMap<String, String>) + Send,
+    {
+        let mut transaction = self.transaction().await?;
+        let entry = self.fetch_record(&mut transaction, xtype, id, true).await?;
+
+        let (value, tags) = update(entry_value(&entry)?, from_entry_tags(&entry.tags));
+        transaction
+            .replace(xtype, id, value.as_bytes(), Some(&to_entry_tags(tags)), None)
+            .await?;
+
+        Ok(transaction.commit().await?)
+    }
+}
+
+fn check_record_type(xtype: &str) -> VcxCoreResult<()> {
+    if xtype.starts_with(RESERVED_CATEGORY_PREFIX) {
+        return Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidInput,
+            format!(
+                "Wallet record type {} uses the prefix {}, which is reserved to the wallet",
+                xtype, RESERVED_CATEGORY_PREFIX
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn to_entry_tags(tags: HashMap<String, String>) -> Vec<EntryTag> {
+    tags.into_iter()
+        .map(|(name, value)| match name.strip_prefix('~') {
+            Some(name) => EntryTag::Plaintext(name.to_owned(), value),
+            None => EntryTag::Encrypted(name, value),
+        })
+        .collect()
+}
+
+fn from_entry_tags(tags: &[EntryTag]) -> HashMap<String, String> {
+    tags.iter()
+        .map(|tag| match tag {
+            EntryTag::Plaintext(name, value) => (format!("~{}", name), value.clone()),
+            EntryTag::Encrypted(name, value) => (name.clone(), value.clone()),
+        })
+        .collect()
+}
+
+fn entry_value(entry: &Entry) -> VcxCoreResult<String> {
+    String::from_utf8(entry.value.to_vec()).map_err(|err| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidState,
+            format!("Value of wallet record {} is not valid UTF-8: {}", entry.name, err),
+        )
+    })
+}
+
+fn key_not_found(verkey: &str) -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::WalletRecordNotFound,
+        format!("Key {} not found in wallet", verkey),
+    )
+}
+
+#[async_trait]
+impl BaseWallet for AskarWallet {
+    async fn create_and_store_my_did(
+        &self,
+        seed: Option<&str>,
+        method_name: Option<&str>,
+    ) -> VcxCoreResult<(String, String)> {
+        let key = crypto::create_key(seed)?;
+        let unqualified_did = bs58::encode(&key.verifying_key().as_bytes()[..16]).into_string();
+        let did = match method_name {
+            Some(method_name) => format!("did:{}:{}", method_name, unqualified_did),
+            None => unqualified_did,
+        };
+
+        let mut transaction = self.transaction().await?;
+        if transaction.fetch(DID_CATEGORY, &did, false).await?.is_some() {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationDid,
+                format!("DID {} already exists in wallet", did),
+            ));
+        }
+
+        let verkey = self.insert_key(&mut transaction, &key).await?;
+        let value = DidValue {
+            verkey: verkey.clone(),
+            temp_verkey: None,
+        };
+        transaction
+            .insert(DID_CATEGORY, &did, &serde_json::to_vec(&value)?, None, None)
+            .await?;
+        transaction.commit().await?;
+
+        Ok((did, verkey))
+    }
+
+    async fn key_for_local_did(&self, did: &str) -> VcxCoreResult<String> {
+        let mut session = self.session().await?;
+        Ok(self.fetch_did(&mut session, did, false).await?.verkey)
+    }
+
+    async fn replace_did_keys_start(&self, target_did: &str) -> VcxCoreResult<String> {
+        let mut transaction = self.transaction().await?;
+        let mut value = self.fetch_did(&mut transaction, target_did, true).await?;
+
+        let verkey = self.insert_key(&mut transaction, &crypto::create_key(None)?).await?;
+        value.temp_verkey = Some(verkey.clone());
+        transaction
+            .replace(DID_CATEGORY, target_did, &serde_json::to_vec(&value)?, None, None)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(verkey)
+    }
+
+    async fn replace_did_keys_apply(&self, target_did: &str) -> VcxCoreResult<()> {
+        let mut transaction = self.transaction().await?;
+        let mut value = self.fetch_did(&mut transaction, target_did, true).await?;
+
+        value.verkey = value.temp_verkey.take().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Key replacement of DID {} was not started", target_did),
+            )
+        })?;
+        transaction
+            .replace(DID_CATEGORY, target_did, &serde_json::to_vec(&value)?, None, None)
+            .await?;
+
+        Ok(transaction.commit().await?)
+    }
+
+    async fn add_wallet_record(
+        &self,
+        xtype: &str,
+        id: &str,
+        value: &str,
+        tags: Option<HashMap<String, String>>,
+    ) -> VcxCoreResult<()> {
+        let mut transaction = self.transaction().await?;
+        self.insert_record(&mut transaction, xtype, id, value, tags).await?;
+        Ok(transaction.commit().await?)
+    }
+
+    async fn get_wallet_record(&self, xtype: &str, id: &str, options: &str) -> VcxCoreResult<String> {
+        let options = RecordOptions::parse(options)?;
+
+        let mut session = self.session().await?;
+        let entry = self.fetch_record(&mut session, xtype, id, false).await?;
+
+        Ok(options
+            .render(xtype, id, &entry_value(&entry)?, &from_entry_tags(&entry.tags))
+            .to_string())
+    }
+
+    async fn get_wallet_record_value(&self, xtype: &str, id: &str) -> VcxCoreResult<String> {
+        let mut session = self.session().await?;
+        entry_value(&self.fetch_record(&mut session, xtype, id, false).await?)
+    }
+
+    async fn delete_wallet_record(&self, xtype: &str, id: &str) -> VcxCoreResult<()> {
+        check_record_type(xtype)?;
+        let mut session = self.session().await?;
+        Ok(session.remove(xtype, id).await?)
+    }
+
+    async fn update_wallet_record_value(&self, xtype: &str, id: &str, value: &str) -> VcxCoreResult<()> {
+        let value = value.to_owned();
+        self.update_record(xtype, id, |_, tags| (value, tags)).await
+    }
+
+    async fn add_wallet_record_tags(&self, xtype: &str, id: &str, tags: HashMap<String, String>) -> VcxCoreResult<()> {
+        self.update_record(xtype, id, |value, mut current_tags| {
+            current_tags.extend(tags);
+            (value, current_tags)
+        })
+        .await
+    }
+
+    async fn update_wallet_record_tags(
+        &self,
+        xtype: &str,
+        id: &str,
+        tags: HashMap<String, String>,
+    ) -> VcxCoreResult<()> {
+        self.update_record(xtype, id, |value, _| (value, tags)).await
+    }
+
+    async fn delete_wallet_record_tags(&self, xtype: &str, id: &str, tag_names: &str) -> VcxCoreResult<()> {
+        let tag_names: Vec<String> = serde_json::from_str(tag_names)?;
+
+        self.update_record(xtype, id, |value, mut tags| {
+            for tag_name in tag_names.iter() {
+                tags.remove(tag_name);
+            }
+            (value, tags)
+        })
+        .await
+    }
+
+    async fn iterate_wallet_records(
+        &self,
+        xtype: &str,
+        query: &str,
+        options: &str,
+    ) -> VcxCoreResult<Box<dyn AsyncFnIterator<Item = VcxCoreResult<String>>>> {
+        check_record_type(xtype)?;
+        let options = RecordOptions::parse(options)?;
+        let tag_filter = match query.trim() {
+            "" | "{}" => None,
+            query => Some(TagFilter::from_str(query)?),
+        };
+
+        let mut records = Vec::new();
+        if options.retrieve_records {
+            let mut session = self.session().await?;
+            for entry in session.fetch_all(xtype, tag_filter, None, false).await? {
+                let record = options.render(xtype, &entry.name, &entry_value(&entry)?, &from_entry_tags(&entry.tags));
+                records.push(record.to_string());
+            }
+        }
+
+        Ok(Box::new(AskarWalletRecordIterator {
+            records: records.into_iter(),
+        }))
+    }
+
+    async fn sign(&self, my_vk: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let mut session = self.session().await?;
+        let key = self
+            .fetch_signing_key(&mut session, my_vk)
+            .await?
+            .ok_or_else(|| key_not_found(my_vk))?;
+
+        Ok(crypto::sign(&key, msg))
+    }
+
+    async fn verify(&self, vk: &str, msg: &[u8], signature: &[u8]) -> VcxCoreResult<bool> {
+        crypto::verify(vk, msg, signature)
+    }
+
+    async fn pack_message(&self, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let receiver_keys: Vec<String> = serde_json::from_str(receiver_keys)?;
+
+        let sender = match sender_vk {
+            Some(sender_vk) => {
+                let mut session = self.session().await?;
+                let key = self
+                    .fetch_signing_key(&mut session, sender_vk)
+                    .await?
+                    .ok_or_else(|| key_not_found(sender_vk))?;
+                Some((sender_vk, key))
+            }
+            None => None,
+        };
+
+        crypto::pack(sender.as_ref().map(|(verkey, key)| (*verkey, key)), &receiver_keys, msg)
+    }
+
+    async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let mut session = self.session().await?;
+
+        for verkey in crypto::recipient_verkeys(msg)? {
+            if let Some(key) = self.fetch_signing_key(&mut session, &verkey).await? {
+                return crypto::unpack(msg, &verkey, &key);
+            }
+        }
+
+        Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::WalletRecordNotFound,
+            "Message is not packed for any key of the wallet",
+        ))
+    }
+
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        let mut session = self.session().await?;
+
+        let mut dids = Vec::new();
+        for entry in session.fetch_all(DID_CATEGORY, None, None, false).await? {
+            let value: DidValue = serde_json::from_slice(&entry.value)?;
+            dids.push(ArchivedDid {
+                did: entry.name,
+                verkey: value.verkey,
+                temp_verkey: value.temp_verkey,
+            });
+        }
+
+        let mut keys = Vec::new();
+        for entry in session.fetch_all_keys(None, None, None, None, false).await? {
+            let verkey = entry.name().to_owned();
+            if let Some(key) = self.fetch_signing_key(&mut session, &verkey).await? {
+                keys.push(ArchivedKey {
+                    verkey,
+                    seed: key.to_bytes(),
+                });
+            }
+        }
+
+        let mut records = Vec::new();
+        for record_type in session.fetch_all(RECORD_TYPE_CATEGORY, None, None, false).await? {
+            for entry in session.fetch_all(&record_type.name, None, None, false).await? {
+                records.push(ArchivedRecord {
+                    type_: record_type.name.clone(),
+                    value: entry_value(&entry)?,
+                    tags: from_entry_tags(&entry.tags),
+                    id: entry.name,
+                });
+            }
+        }
+
+        WalletArchive { dids, keys, records }.write(path, backup_key)
+    }
+
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        let archive = WalletArchive::read(path, backup_key)?;
+
+        let mut transaction = self.transaction().await?;
+        for did in archive.dids.iter() {
+            if transaction.fetch(DID_CATEGORY, &did.did, false).await?.is_some() {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::DuplicationDid,
+                    format!("DID {} already exists in wallet", did.did),
+                ));
+            }
+
+            let value = DidValue {
+                verkey: did.verkey.clone(),
+                temp_verkey: did.temp_verkey.clone(),
+            };
+            transaction
+                .insert(DID_CATEGORY, &did.did, &serde_json::to_vec(&value)?, None, None)
+                .await?;
+        }
+
+        for key in archive.keys.iter() {
+            let key = SigningKey::from_bytes(&key.seed);
+            if self
+                .fetch_local_key(&mut transaction, &crypto::verkey(&key))
+                .await?
+                .is_none()
+            {
+                self.insert_key(&mut transaction, &key).await?;
+            }
+        }
+
+        for record in archive.records {
+            self.insert_record(
+                &mut transaction,
+                &record.type_,
+                &record.id,
+                &record.value,
+                Some(record.tags),
+            )
+            .await?;
+        }
+
+        // Nothing is imported unless everything is.
+        Ok(transaction.commit().await?)
+    }
+}
+
+/// [AsyncFnIterator] over the records matched by a search, fetched when the search was opened.
+struct AskarWalletRecordIterator {
+    records: std::vec::IntoIter<String>,
+}
+
+#[async_trait]
+impl AsyncFnIterator for AskarWalletRecordIterator {
+    type Item = VcxCoreResult<String>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.records.next().map(Ok)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::wallet::base_wallet::AsyncFnIteratorCollect;
+
+    async fn create_wallet() -> AskarWallet {
+        let config = AskarWalletConfig::new("sqlite://:memory:", &AskarWallet::generate_raw_key().unwrap())
+            .key_method(AskarKeyMethod::RawKey);
+        AskarWallet::create(&config, true).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_askar_did_and_signing() {
+        let wallet = create_wallet().await;
+
+        let (did, verkey) = wallet
+            .create_and_store_my_did(Some("000000000000000000000000Trustee1"), None)
+            .await
+            .unwrap();
+        assert_eq!(did, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(verkey, "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL");
+
+        let signature = wallet.sign(&verkey, b"message").await.unwrap();
+        assert!(wallet.verify(&verkey, b"message", &signature).await.unwrap());
+
+        let new_verkey = wallet.replace_did_keys_start(&did).await.unwrap();
+        wallet.replace_did_keys_apply(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), new_verkey);
+    }
+
+    #[tokio::test]
+    async fn test_askar_pack_unpack() {
+        let wallet = create_wallet().await;
+        let (_, sender_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let (_, receiver_vk) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let packed = wallet
+            .pack_message(Some(&sender_vk), &json!([receiver_vk]).to_string(), b"hello")
+            .await
+            .unwrap();
+        let unpacked: serde_json::Value =
+            serde_json::from_slice(&wallet.unpack_message(&packed).await.unwrap()).unwrap();
+
+        assert_eq!(unpacked["message"], "hello");
+        assert_eq!(unpacked["sender_verkey"], sender_vk);
+    }
+
+    #[tokio::test]
+    async fn test_askar_records() {
+        let wallet = create_wallet().await;
+        let tags = HashMap::from([
+            ("tag".to_owned(), "a".to_owned()),
+            ("~plain".to_owned(), "b".to_owned()),
+        ]);
+
+        wallet
+            .add_wallet_record("type", "id", "value", Some(tags))
+            .await
+            .unwrap();
+        wallet.add_wallet_record("type", "id", "value", None).await.unwrap_err();
+        wallet
+            .add_wallet_record(DID_CATEGORY, "id", "value", None)
+            .await
+            .unwrap_err();
+
+        let records = wallet
+            .iterate_wallet_records("type", r#"{"~plain": "b"}"#, r#"{"retrieveTags": true}"#)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let record: serde_json::Value = serde_json::from_str(&records[0]).unwrap();
+        assert_eq!(record["tags"], json!({"tag": "a", "~plain": "b"}));
+
+        wallet
+            .update_wallet_record_value("type", "id", "updated")
+            .await
+            .unwrap();
+        wallet
+            .delete_wallet_record_tags("type", "id", r#"["tag"]"#)
+            .await
+            .unwrap();
+        let record: serde_json::Value = serde_json::from_str(
+            &wallet
+                .get_wallet_record("type", "id", r#"{"retrieveTags": true}"#)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(record["value"], "updated");
+        assert_eq!(record["tags"], json!({"~plain": "b"}));
+
+        wallet.delete_wallet_record("type", "id").await.unwrap();
+        wallet.get_wallet_record_value("type", "id").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_askar_import_is_atomic() {
+        let wallet = create_wallet().await;
+        let (did, _) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        wallet.add_wallet_record("type", "id", "value", None).await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("askar_wallet_{}", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        wallet.export_wallet(path, "backup_key").await.unwrap();
+
+        let imported = create_wallet().await;
+        imported
+            .add_wallet_record("type", "id", "other value", None)
+            .await
+            .unwrap();
+        imported.import_wallet(path, "backup_key").await.unwrap_err();
+        std::fs::remove_file(path).unwrap();
+
+        imported.key_for_local_did(&did).await.unwrap_err();
+        assert_eq!(
+            imported.get_wallet_record_value("type", "id").await.unwrap(),
+            "other value"
+        );
+    }
+}
@@ -71,6 +71,15 @@ pub trait BaseWallet: std::fmt::Debug + Send + Sync {
     async fn pack_message(&self, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>>;
 
     async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>>;
+
+    // ---- backup
+
+    /// Exports the DIDs, keys and records of the wallet to an archive at `path`, encrypted with `backup_key`.
+    /// The archive has the format of the indy wallet export, so it can be imported by any implementation.
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()>;
+
+    /// Imports the DIDs, keys and records of an archive created by [BaseWallet::export_wallet] into the wallet.
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()>;
 }
 
 #[async_trait]
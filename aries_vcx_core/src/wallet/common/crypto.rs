@@ -0,0 +1,385 @@
+//! Ed25519 keys and the legacy DIDComm (indy pack) envelope format,
+//! as defined in [RFC 0019](https://github.com/hyperledger/aries-rfcs/blob/main/features/0019-encryption-envelope/README.md).
+
+use base64::{
+    alphabet,
+    engine::{general_purpose::URL_SAFE, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2bVar,
+};
+use chacha20poly1305::{
+    aead::{AeadInPlace, NewAead},
+    Key, Tag, XChaCha20Poly1305, XNonce,
+};
+use crypto_box::{
+    aead::{generic_array::GenericArray, Aead},
+    PublicKey, SalsaBox, SecretKey,
+};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{Keypair, PublicKey as VerifyingKey, SecretKey as Ed25519SecretKey, Signature, Signer};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+// Indy encodes the envelope with padding, other agents without.
+const URL_SAFE_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    protected: String,
+    iv: String,
+    ciphertext: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Protected {
+    enc: String,
+    typ: String,
+    alg: String,
+    recipients: Vec<Recipient>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Recipient {
+    encrypted_key: String,
+    header: RecipientHeader,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecipientHeader {
+    kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iv: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Unpacked {
+    message: String,
+    recipient_verkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender_verkey: Option<String>,
+}
+
+/// Ed25519 signing key, stored as the 32 bytes seed it is derived from like indy does.
+pub(crate) struct SigningKey(Keypair);
+
+impl SigningKey {
+    pub(crate) fn from_bytes(seed: &[u8; 32]) -> Self {
+        let secret = Ed25519SecretKey::from_bytes(seed).expect("Any 32 bytes make an ed25519 secret key");
+        let public = VerifyingKey::from(&secret);
+        Self(Keypair { secret, public })
+    }
+
+    pub(crate) fn to_bytes(&self) -> [u8; 32] {
+        self.0.secret.to_bytes()
+    }
+
+    pub(crate) fn verifying_key(&self) -> VerifyingKey {
+        self.0.public
+    }
+}
+
+impl Clone for SigningKey {
+    fn clone(&self) -> Self {
+        Self::from_bytes(&self.to_bytes())
+    }
+}
+
+/// Creates a signing key from a seed following the indy conventions: either 32 characters
+/// or a base64 encoded 32 bytes value ending with `=`. A random key is created without a seed.
+pub(crate) fn create_key(seed: Option<&str>) -> VcxCoreResult<SigningKey> {
+    let mut secret = [0u8; 32];
+
+    match seed {
+        None => rand::thread_rng().fill_bytes(&mut secret),
+        Some(seed) if seed.len() == 32 => secret.copy_from_slice(seed.as_bytes()),
+        Some(seed) if seed.ends_with('=') => {
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(seed)
+                .map_err(|err| invalid_input(format!("Cannot decode base64 seed: {}", err)))?;
+            secret = decoded
+                .try_into()
+                .map_err(|_| invalid_input("Decoded seed must be 32 bytes long"))?;
+        }
+        Some(_) => {
+            return Err(invalid_input(
+                "Seed must be 32 characters or a base64 encoded 32 bytes value",
+            ))
+        }
+    }
+
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+pub(crate) fn verkey(key: &SigningKey) -> String {
+    bs58::encode(key.verifying_key().as_bytes()).into_string()
+}
+
+pub(crate) fn sign(key: &SigningKey, msg: &[u8]) -> Vec<u8> {
+    key.0.sign(msg).to_bytes().to_vec()
+}
+
+pub(crate) fn verify(verkey: &str, msg: &[u8], signature: &[u8]) -> VcxCoreResult<bool> {
+    let key = decode_verkey(verkey)?;
+    let Ok(signature) = Signature::try_from(signature) else {
+        return Ok(false);
+    };
+
+    Ok(key.verify_strict(msg, &signature).is_ok())
+}
+
+/// Packs `msg` for `receiver_keys`, authcrypted by `sender` if provided and anoncrypted otherwise.
+pub(crate) fn pack(
+    sender: Option<(&str, &SigningKey)>,
+    receiver_keys: &[String],
+    msg: &[u8],
+) -> VcxCoreResult<Vec<u8>> {
+    if receiver_keys.is_empty() {
+        return Err(invalid_input("Cannot pack message without receiver keys"));
+    }
+
+    let cek = Key::from(random_bytes::<32>());
+
+    let recipients = receiver_keys
+        .iter()
+        .map(|receiver_key| {
+            let receiver_public = to_x25519_public(&decode_verkey(receiver_key)?)?;
+
+            let (encrypted_key, sender, iv) = match sender {
+                Some((sender_verkey, sender_key)) => {
+                    let nonce = random_bytes::<24>();
+                    let encrypted_key = SalsaBox::new(&receiver_public, &to_x25519_secret(sender_key))
+                        .encrypt(GenericArray::from_slice(&nonce), cek.as_slice())
+                        .map_err(|_| invalid_input("Cannot encrypt content encryption key"))?;
+                    let sender = seal(&receiver_public, sender_verkey.as_bytes())
+                        .map_err(|_| invalid_input("Cannot encrypt sender verkey"))?;
+                    (
+                        encrypted_key,
+                        Some(URL_SAFE.encode(sender)),
+                        Some(URL_SAFE.encode(nonce)),
+                    )
+                }
+                None => {
+                    let encrypted_key = seal(&receiver_public, cek.as_slice())
+                        .map_err(|_| invalid_input("Cannot encrypt content encryption key"))?;
+                    (encrypted_key, None, None)
+                }
+            };
+
+            Ok(Recipient {
+                encrypted_key: URL_SAFE.encode(encrypted_key),
+                header: RecipientHeader {
+                    kid: receiver_key.clone(),
+                    sender,
+                    iv,
+                },
+            })
+        })
+        .collect::<VcxCoreResult<Vec<_>>>()?;
+
+    let protected = Protected {
+        enc: "xchacha20poly1305_ietf".to_owned(),
+        typ: "JWM/1.0".to_owned(),
+        alg: if sender.is_some() { "Authcrypt" } else { "Anoncrypt" }.to_owned(),
+        recipients,
+    };
+    let protected = URL_SAFE.encode(serde_json::to_vec(&protected)?);
+
+    let nonce = XNonce::from(random_bytes::<24>());
+    let mut ciphertext = msg.to_vec();
+    let tag = XChaCha20Poly1305::new(&cek)
+        .encrypt_in_place_detached(&nonce, protected.as_bytes(), &mut ciphertext)
+        .map_err(|_| invalid_input("Cannot encrypt message"))?;
+
+    let envelope = Envelope {
+        protected,
+        iv: URL_SAFE.encode(nonce),
+        ciphertext: URL_SAFE.encode(ciphertext),
+        tag: URL_SAFE.encode(tag),
+    };
+
+    Ok(serde_json::to_vec(&envelope)?)
+}
+
+/// Verkeys of the recipients an envelope is packed for.
+pub(crate) fn recipient_verkeys(msg: &[u8]) -> VcxCoreResult<Vec<String>> {
+    let envelope: Envelope = serde_json::from_slice(msg)?;
+    let protected: Protected = serde_json::from_slice(&decode(&envelope.protected)?)?;
+
+    Ok(protected
+        .recipients
+        .into_iter()
+        .map(|recipient| recipient.header.kid)
+        .collect())
+}
+
+/// Unpacks an envelope as the recipient `recipient_verkey`, whose key is `recipient_key`.
+pub(crate) fn unpack(msg: &[u8], recipient_verkey: &str, recipient_key: &SigningKey) -> VcxCoreResult<Vec<u8>> {
+    let envelope: Envelope = serde_json::from_slice(msg)?;
+    let protected: Protected = serde_json::from_slice(&decode(&envelope.protected)?)?;
+
+    let recipient = protected
+        .recipients
+        .iter()
+        .find(|recipient| recipient.header.kid == recipient_verkey)
+        .ok_or_else(|| invalid_input(format!("Message is not packed for {}", recipient_verkey)))?;
+
+    let recipient_secret = to_x25519_secret(recipient_key);
+    let encrypted_key = decode(&recipient.encrypted_key)?;
+
+    let (cek, sender_verkey) = match (&recipient.header.sender, &recipient.header.iv) {
+        (Some(sender), Some(iv)) => {
+            let sender_verkey = unseal(&recipient_secret, &decode(sender)?)
+                .map_err(|_| invalid_input("Cannot decrypt sender verkey"))?;
+            let sender_verkey =
+                String::from_utf8(sender_verkey).map_err(|_| invalid_input("Sender verkey is not valid UTF-8"))?;
+            let sender_public = to_x25519_public(&decode_verkey(&sender_verkey)?)?;
+
+            let nonce = decode(iv)?;
+            if nonce.len() != 24 {
+                return Err(invalid_input("Invalid content encryption key nonce"));
+            }
+            let cek = SalsaBox::new(&sender_public, &recipient_secret)
+                .decrypt(GenericArray::from_slice(&nonce), encrypted_key.as_slice())
+                .map_err(|_| invalid_input("Cannot decrypt content encryption key"))?;
+            (cek, Some(sender_verkey))
+        }
+        (None, None) => {
+            let cek = unseal(&recipient_secret, &encrypted_key)
+                .map_err(|_| invalid_input("Cannot decrypt content encryption key"))?;
+            (cek, None)
+        }
+        _ => {
+            return Err(invalid_input(
+                "Recipient header must contain both sender and iv, or neither",
+            ))
+        }
+    };
+
+    let nonce = decode(&envelope.iv)?;
+    let tag = decode(&envelope.tag)?;
+    if cek.len() != 32 || nonce.len() != 24 || tag.len() != 16 {
+        return Err(invalid_input("Invalid envelope encryption parameters"));
+    }
+
+    let mut message = decode(&envelope.ciphertext)?;
+    XChaCha20Poly1305::new(Key::from_slice(&cek))
+        .decrypt_in_place_detached(
+            XNonce::from_slice(&nonce),
+            envelope.protected.as_bytes(),
+            &mut message,
+            Tag::from_slice(&tag),
+        )
+        .map_err(|_| invalid_input("Cannot decrypt message"))?;
+
+    let unpacked = Unpacked {
+        message: String::from_utf8(message).map_err(|_| invalid_input("Message is not valid UTF-8"))?,
+        recipient_verkey: recipient.header.kid.clone(),
+        sender_verkey,
+    };
+
+    Ok(serde_json::to_vec(&unpacked)?)
+}
+
+fn decode_verkey(verkey: &str) -> VcxCoreResult<VerifyingKey> {
+    let bytes: [u8; 32] = bs58::decode(verkey)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidVerkey,
+                format!("Invalid verkey {}", verkey),
+            )
+        })?;
+
+    VerifyingKey::from_bytes(&bytes).map_err(|err| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidVerkey,
+            format!("Invalid verkey {}: {}", verkey, err),
+        )
+    })
+}
+
+fn to_x25519_public(key: &VerifyingKey) -> VcxCoreResult<PublicKey> {
+    let point = CompressedEdwardsY(key.to_bytes()).decompress().ok_or_else(|| {
+        AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::InvalidVerkey,
+            "Verkey is not a point of the ed25519 curve",
+        )
+    })?;
+
+    Ok(PublicKey::from(point.to_montgomery().to_bytes()))
+}
+
+fn to_x25519_secret(key: &SigningKey) -> SecretKey {
+    let mut scalar = [0u8; 32];
+    scalar.copy_from_slice(&Sha512::digest(key.to_bytes())[..32]);
+    SecretKey::from(scalar)
+}
+
+/// Encrypts `msg` for `public_key` with an ephemeral key, as libsodium's `crypto_box_seal` does.
+fn seal(public_key: &PublicKey, msg: &[u8]) -> Result<Vec<u8>, crypto_box::aead::Error> {
+    let ephemeral_secret = SecretKey::from(random_bytes::<32>());
+    let ephemeral_public = ephemeral_secret.public_key();
+    let nonce = seal_nonce(&ephemeral_public, public_key);
+
+    let ciphertext = SalsaBox::new(public_key, &ephemeral_secret).encrypt(GenericArray::from_slice(&nonce), msg)?;
+
+    Ok([ephemeral_public.as_bytes().as_slice(), &ciphertext].concat())
+}
+
+/// Decrypts a message encrypted by [`seal`], as libsodium's `crypto_box_seal_open` does.
+fn unseal(secret_key: &SecretKey, sealed: &[u8]) -> Result<Vec<u8>, crypto_box::aead::Error> {
+    if sealed.len() < 32 {
+        return Err(crypto_box::aead::Error);
+    }
+    let (ephemeral_public, ciphertext) = sealed.split_at(32);
+    let mut ephemeral_public_bytes = [0u8; 32];
+    ephemeral_public_bytes.copy_from_slice(ephemeral_public);
+    let ephemeral_public = PublicKey::from(ephemeral_public_bytes);
+    let nonce = seal_nonce(&ephemeral_public, &secret_key.public_key());
+
+    SalsaBox::new(&ephemeral_public, secret_key).decrypt(GenericArray::from_slice(&nonce), ciphertext)
+}
+
+fn seal_nonce(ephemeral_public: &PublicKey, public_key: &PublicKey) -> [u8; 24] {
+    let mut nonce = [0u8; 24];
+    let mut hasher = Blake2bVar::new(nonce.len()).expect("Nonce size is a valid blake2b output size");
+    hasher.update(ephemeral_public.as_bytes());
+    hasher.update(public_key.as_bytes());
+    hasher
+        .finalize_variable(&mut nonce)
+        .expect("Nonce size is the blake2b output size");
+    nonce
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn decode(value: &str) -> VcxCoreResult<Vec<u8>> {
+    URL_SAFE_INDIFFERENT
+        .decode(value)
+        .map_err(|err| invalid_input(format!("Cannot decode base64url value: {}", err)))
+}
+
+fn invalid_input<D>(msg: D) -> AriesVcxCoreError
+where
+    D: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, msg)
+}
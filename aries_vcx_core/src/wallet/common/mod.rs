@@ -0,0 +1,4 @@
+//! Building blocks shared by the pure Rust wallet implementations.
+
+pub(crate) mod crypto;
+pub(crate) mod record_options;
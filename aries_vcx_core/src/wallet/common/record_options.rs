@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::errors::error::VcxCoreResult;
+
+/// Record retrieval and search options, in the format of the indy wallet.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordOptions {
+    #[serde(default, alias = "retrieve_type")]
+    pub retrieve_type: bool,
+    #[serde(default = "default_true", alias = "retrieve_value")]
+    pub retrieve_value: bool,
+    #[serde(default, alias = "retrieve_tags")]
+    pub retrieve_tags: bool,
+    #[serde(default = "default_true", alias = "retrieve_records")]
+    pub retrieve_records: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl RecordOptions {
+    pub fn parse(options: &str) -> VcxCoreResult<Self> {
+        let options = if options.trim().is_empty() { "{}" } else { options };
+        Ok(serde_json::from_str(options)?)
+    }
+
+    /// Renders a record the way the indy wallet does, leaving out what was not requested.
+    pub fn render(&self, xtype: &str, id: &str, value: &str, tags: &HashMap<String, String>) -> Value {
+        json!({
+            "id": id,
+            "type": self.retrieve_type.then_some(xtype),
+            "value": self.retrieve_value.then_some(value),
+            "tags": self.retrieve_tags.then_some(tags),
+        })
+    }
+}
@@ -0,0 +1,478 @@
+//! Encrypted wallet archive shared by the [`BaseWallet`](super::base_wallet::BaseWallet) implementations.
+//!
+//! The archive has the format of the libvdrtools wallet export (see `indy-wallet/src/export_import.rs`),
+//! so that wallets exported by the vdrtools wallet can be imported by any other implementation and
+//! vice versa:
+//!
+//! * the length of the header as a little endian u32, followed by the msgpack encoded [`Header`],
+//! * a ChaCha20-Poly1305-IETF encrypted stream, in chunks of `chunk_size` bytes encrypted with an
+//!   incremented nonce, holding the SHA-256 hash of the header, then every record as a little endian
+//!   u32 length followed by the msgpack encoded [`ArchivedRecord`], and finally a zero length.
+//!
+//! DIDs and keys are archived as the `Indy::Did`, `Indy::TemporaryDid` and `Indy::Key` records of the
+//! vdrtools wallet. Other `Indy::` records (such as the vdrtools anoncreds state) are only meaningful to
+//! the vdrtools wallet and are skipped when reading an archive.
+
+use std::collections::HashMap;
+use std::fs;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+const CHUNK_SIZE: usize = 1024;
+const TAG_SIZE: usize = 16;
+const HEADER_VERSION: u32 = 0;
+
+const INDY_PREFIX: &str = "Indy::";
+const DID_TYPE: &str = "Indy::Did";
+const TEMPORARY_DID_TYPE: &str = "Indy::TemporaryDid";
+const KEY_TYPE: &str = "Indy::Key";
+
+// vdrtools stores 32 bytes salts (the size of sodiumoxide's default scrypt salts), of which
+// libsodium's argon2i only uses the first 16 bytes.
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+const SALT_SIZE: usize = 32;
+const ARGON2I_SALT_SIZE: usize = 16;
+
+// Same parameters as libsodium's argon2i MODERATE and INTERACTIVE limits
+const ARGON2I_MOD_PARAMS: (u32, u32) = (131072, 6);
+const ARGON2I_INT_PARAMS: (u32, u32) = (32768, 4);
+
+#[derive(Debug, Serialize, Deserialize)]
+enum EncryptionMethod {
+    ChaCha20Poly1305IETF {
+        salt: Vec<u8>,
+        nonce: Vec<u8>,
+        chunk_size: usize,
+    },
+    ChaCha20Poly1305IETFInteractive {
+        salt: Vec<u8>,
+        nonce: Vec<u8>,
+        chunk_size: usize,
+    },
+    ChaCha20Poly1305IETFRaw {
+        nonce: Vec<u8>,
+        chunk_size: usize,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    encryption_method: EncryptionMethod,
+    time: u64,
+    version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ArchivedRecord {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub id: String,
+    pub value: String,
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ArchivedDid {
+    pub did: String,
+    pub verkey: String,
+    pub temp_verkey: Option<String>,
+}
+
+#[derive(Clone)]
+pub(crate) struct ArchivedKey {
+    pub verkey: String,
+    pub seed: [u8; 32],
+}
+
+impl ArchivedKey {
+    /// Seed in the hex form accepted by vdrtools.
+    #[cfg(feature = "vdrtools")]
+    pub fn hex_seed(&self) -> String {
+        self.seed.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DidValue {
+    did: String,
+    verkey: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyValue {
+    verkey: String,
+    signkey: String,
+}
+
+/// Content of a wallet archive.
+#[derive(Default)]
+pub(crate) struct WalletArchive {
+    pub dids: Vec<ArchivedDid>,
+    pub keys: Vec<ArchivedKey>,
+    pub records: Vec<ArchivedRecord>,
+}
+
+impl WalletArchive {
+    #[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+    /// Writes the archive to `path`, encrypted with a key derived from `backup_key`.
+    pub fn write(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        let salt: [u8; SALT_SIZE] = rand::random();
+        let nonce: [u8; 12] = rand::random();
+        let key = derive_key(backup_key, &salt, ARGON2I_MOD_PARAMS)?;
+
+        let header = Header {
+            encryption_method: EncryptionMethod::ChaCha20Poly1305IETF {
+                salt: salt.to_vec(),
+                nonce: nonce.to_vec(),
+                chunk_size: CHUNK_SIZE,
+            },
+            time: time::OffsetDateTime::now_utc().unix_timestamp() as u64,
+            version: HEADER_VERSION,
+        };
+        let header = rmp_serde::to_vec(&header).map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Can't serialize wallet archive header: {}", err),
+            )
+        })?;
+
+        let mut plaintext = Sha256::digest(&header).to_vec();
+        for record in self.to_records()? {
+            let record = rmp_serde::to_vec(&record).map_err(|err| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::InvalidState,
+                    format!("Can't serialize wallet archive record: {}", err),
+                )
+            })?;
+            plaintext.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            plaintext.extend_from_slice(&record);
+        }
+        plaintext.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut archive = (header.len() as u32).to_le_bytes().to_vec();
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(&encrypt_chunks(&key, nonce, CHUNK_SIZE, &plaintext)?);
+
+        fs::write(path, archive).map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::IOError,
+                format!("Can't write wallet archive {}: {}", path, err),
+            )
+        })
+    }
+
+    /// Reads the archive at `path`, written by [`WalletArchive::write`] or exported by the vdrtools wallet.
+    pub fn read(path: &str, backup_key: &str) -> VcxCoreResult<Self> {
+        let archive = fs::read(path).map_err(|err| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::IOError,
+                format!("Can't read wallet archive {}: {}", path, err),
+            )
+        })?;
+
+        let header_len = read_u32(&archive, 0)? as usize;
+        let header_bytes = archive
+            .get(4..4 + header_len)
+            .filter(|_| header_len > 0)
+            .ok_or_else(invalid_archive)?;
+        let header: Header = rmp_serde::from_slice(header_bytes).map_err(|_| invalid_archive())?;
+
+        if header.version != HEADER_VERSION {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Unsupported wallet archive version {}", header.version),
+            ));
+        }
+
+        let (key, nonce, chunk_size) = match header.encryption_method {
+            EncryptionMethod::ChaCha20Poly1305IETF {
+                salt,
+                nonce,
+                chunk_size,
+            } => (derive_key(backup_key, &salt, ARGON2I_MOD_PARAMS)?, nonce, chunk_size),
+            EncryptionMethod::ChaCha20Poly1305IETFInteractive {
+                salt,
+                nonce,
+                chunk_size,
+            } => (derive_key(backup_key, &salt, ARGON2I_INT_PARAMS)?, nonce, chunk_size),
+            EncryptionMethod::ChaCha20Poly1305IETFRaw { nonce, chunk_size } => {
+                let key = bs58::decode(backup_key)
+                    .into_vec()
+                    .ok()
+                    .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                    .ok_or_else(|| {
+                        AriesVcxCoreError::from_msg(
+                            AriesVcxCoreErrorKind::InvalidInput,
+                            "Raw backup key must be a base58 encoded 32 bytes key",
+                        )
+                    })?;
+                (key, nonce, chunk_size)
+            }
+        };
+        let nonce: [u8; 12] = nonce.try_into().map_err(|_| invalid_archive())?;
+
+        let plaintext = decrypt_chunks(&key, nonce, chunk_size, &archive[4 + header_len..])?;
+
+        if plaintext.get(..32) != Some(&Sha256::digest(header_bytes)[..]) {
+            return Err(invalid_archive());
+        }
+
+        let mut records = Vec::new();
+        let mut position = 32;
+        loop {
+            let record_len = read_u32(&plaintext, position)? as usize;
+            position += 4;
+            if record_len == 0 {
+                break;
+            }
+
+            let record = plaintext
+                .get(position..position + record_len)
+                .ok_or_else(invalid_archive)?;
+            records.push(rmp_serde::from_slice(record).map_err(|_| invalid_archive())?);
+            position += record_len;
+        }
+
+        Self::from_records(records)
+    }
+
+    #[cfg(feature = "vdrtools")]
+    pub fn key(&self, verkey: &str) -> VcxCoreResult<&ArchivedKey> {
+        self.keys.iter().find(|key| key.verkey == verkey).ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                format!("Wallet archive has no key {}", verkey),
+            )
+        })
+    }
+
+    #[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+    fn to_records(&self) -> VcxCoreResult<Vec<ArchivedRecord>> {
+        let mut records = Vec::new();
+
+        for did in self.dids.iter() {
+            records.push(indy_record(
+                DID_TYPE,
+                &did.did,
+                &DidValue {
+                    did: did.did.clone(),
+                    verkey: did.verkey.clone(),
+                },
+            )?);
+
+            if let Some(temp_verkey) = &did.temp_verkey {
+                records.push(indy_record(
+                    TEMPORARY_DID_TYPE,
+                    &did.did,
+                    &DidValue {
+                        did: did.did.clone(),
+                        verkey: temp_verkey.clone(),
+                    },
+                )?);
+            }
+        }
+
+        for key in self.keys.iter() {
+            let mut signkey = key.seed.to_vec();
+            signkey.extend(
+                bs58::decode(&key.verkey)
+                    .into_vec()
+                    .map_err(|err| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidVerkey, err))?,
+            );
+
+            records.push(indy_record(
+                KEY_TYPE,
+                &key.verkey,
+                &KeyValue {
+                    verkey: key.verkey.clone(),
+                    signkey: bs58::encode(signkey).into_string(),
+                },
+            )?);
+        }
+
+        records.extend(self.records.iter().cloned());
+
+        Ok(records)
+    }
+
+    fn from_records(records: Vec<ArchivedRecord>) -> VcxCoreResult<Self> {
+        let mut archive = Self::default();
+        let mut temp_verkeys = HashMap::new();
+
+        for record in records {
+            match record.type_.as_str() {
+                DID_TYPE => {
+                    let value: DidValue = serde_json::from_str(&record.value)?;
+                    archive.dids.push(ArchivedDid {
+                        did: value.did,
+                        verkey: value.verkey,
+                        temp_verkey: None,
+                    });
+                }
+                TEMPORARY_DID_TYPE => {
+                    let value: DidValue = serde_json::from_str(&record.value)?;
+                    temp_verkeys.insert(value.did, value.verkey);
+                }
+                KEY_TYPE => {
+                    let value: KeyValue = serde_json::from_str(&record.value)?;
+                    let signkey = bs58::decode(&value.signkey).into_vec().map_err(|_| invalid_archive())?;
+                    let seed = signkey
+                        .get(..32)
+                        .and_then(|seed| <[u8; 32]>::try_from(seed).ok())
+                        .ok_or_else(invalid_archive)?;
+                    archive.keys.push(ArchivedKey {
+                        verkey: value.verkey,
+                        seed,
+                    });
+                }
+                type_ if type_.starts_with(INDY_PREFIX) => {
+                    warn!(
+                        "Skipping vdrtools specific record of type {} from wallet archive",
+                        type_
+                    );
+                }
+                _ => archive.records.push(record),
+            }
+        }
+
+        for did in archive.dids.iter_mut() {
+            did.temp_verkey = temp_verkeys.remove(&did.did);
+        }
+
+        Ok(archive)
+    }
+}
+
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+fn indy_record<T: Serialize>(type_: &str, id: &str, value: &T) -> VcxCoreResult<ArchivedRecord> {
+    Ok(ArchivedRecord {
+        type_: type_.to_owned(),
+        id: id.to_owned(),
+        value: serde_json::to_string(value)?,
+        tags: HashMap::new(),
+    })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], (memory_cost, time_cost): (u32, u32)) -> VcxCoreResult<[u8; 32]> {
+    let salt = salt.get(..ARGON2I_SALT_SIZE).ok_or_else(invalid_archive)?;
+    let params = Params::new(memory_cost, time_cost, 1, Some(32))
+        .map_err(|err| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, err))?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2i, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, err))?;
+
+    Ok(key)
+}
+
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+fn encrypt_chunks(key: &[u8; 32], mut nonce: [u8; 12], chunk_size: usize, plaintext: &[u8]) -> VcxCoreResult<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+
+    let mut ciphertext = Vec::new();
+    for chunk in plaintext.chunks(chunk_size) {
+        let encrypted = cipher.encrypt(Nonce::from_slice(&nonce), chunk).map_err(|_| {
+            AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidState, "Can't encrypt wallet archive")
+        })?;
+        ciphertext.extend(encrypted);
+        increment_nonce(&mut nonce);
+    }
+
+    Ok(ciphertext)
+}
+
+fn decrypt_chunks(key: &[u8; 32], mut nonce: [u8; 12], chunk_size: usize, ciphertext: &[u8]) -> VcxCoreResult<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+
+    let mut plaintext = Vec::new();
+    for chunk in ciphertext.chunks(chunk_size + TAG_SIZE) {
+        let decrypted = cipher.decrypt(Nonce::from_slice(&nonce), chunk).map_err(|_| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidInput,
+                "Can't decrypt wallet archive, the backup key may be wrong",
+            )
+        })?;
+        plaintext.extend(decrypted);
+        increment_nonce(&mut nonce);
+    }
+
+    Ok(plaintext)
+}
+
+// Little endian increment, as done by libsodium's `sodium_increment`
+fn increment_nonce(nonce: &mut [u8; 12]) {
+    for byte in nonce.iter_mut() {
+        let (incremented, overflow) = byte.overflowing_add(1);
+        *byte = incremented;
+        if !overflow {
+            break;
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], position: usize) -> VcxCoreResult<u32> {
+    bytes
+        .get(position..position + 4)
+        .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(invalid_archive)
+}
+
+fn invalid_archive() -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(AriesVcxCoreErrorKind::InvalidInput, "Invalid wallet archive format")
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_archive_round_trip() {
+        let path = std::env::temp_dir().join(format!("wallet_archive_{}", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+
+        let archive = WalletArchive {
+            dids: vec![ArchivedDid {
+                did: "V4SGRU86Z58d6TV7PBUe6f".to_owned(),
+                verkey: "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL".to_owned(),
+                temp_verkey: None,
+            }],
+            keys: vec![ArchivedKey {
+                verkey: "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL".to_owned(),
+                seed: *b"000000000000000000000000Trustee1",
+            }],
+            records: (0..100)
+                .map(|i| ArchivedRecord {
+                    type_: "type".to_owned(),
+                    id: i.to_string(),
+                    value: "value".repeat(i),
+                    tags: HashMap::from([("~tag".to_owned(), i.to_string())]),
+                })
+                .collect(),
+        };
+
+        archive.write(path, "backup_key").unwrap();
+        assert!(WalletArchive::read(path, "wrong_key").is_err());
+        let read = WalletArchive::read(path, "backup_key").unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(read.dids[0].did, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(read.keys[0].verkey, archive.keys[0].verkey);
+        assert_eq!(read.keys[0].seed, archive.keys[0].seed);
+        assert_eq!(read.records.len(), 100);
+        assert_eq!(read.records[99].value, archive.records[99].value);
+        assert_eq!(read.records[99].tags, archive.records[99].tags);
+    }
+}
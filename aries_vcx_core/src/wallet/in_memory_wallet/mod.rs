@@ -0,0 +1,639 @@
+mod wql;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+use crate::utils::async_fn_iterator::AsyncFnIterator;
+
+use super::base_wallet::BaseWallet;
+use super::common::crypto::{self, SigningKey};
+use super::common::record_options::RecordOptions;
+use super::export_import::{ArchivedDid, ArchivedKey, ArchivedRecord, WalletArchive};
+
+#[derive(Clone, Debug)]
+struct DidEntry {
+    verkey: String,
+    temp_verkey: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Record {
+    value: String,
+    tags: HashMap<String, String>,
+}
+
+/// Pure Rust [`BaseWallet`] keeping DIDs, keys and records in memory, for tests and
+/// short-lived agents. Everything is lost once the wallet is dropped.
+///
+/// Records are returned in the same JSON format as the indy wallet and searched with WQL,
+/// and messages are packed in the indy pack format, so the wallet is interchangeable with
+/// [`IndySdkWallet`](super::indy_wallet::IndySdkWallet).
+#[derive(Default)]
+pub struct InMemoryWallet {
+    dids: RwLock<HashMap<String, DidEntry>>,
+    keys: RwLock<HashMap<String, SigningKey>>,
+    records: RwLock<HashMap<String, BTreeMap<String, Record>>>,
+}
+
+impl InMemoryWallet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn store_key(&self, key: SigningKey) -> String {
+        let verkey = crypto::verkey(&key);
+        self.keys.write().await.insert(verkey.clone(), key);
+        verkey
+    }
+
+    async fn get_key(&self, verkey: &str) -> VcxCoreResult<SigningKey> {
+        self.keys.read().await.get(verkey).cloned().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("Key {} not found in wallet", verkey),
+            )
+        })
+    }
+
+    async fn with_record<T, F>(&self, xtype: &str, id: &str, f: F) -> VcxCoreResult<T>
+    where
+        F: FnOnce(&mut Record) -> VcxCoreResult<T>,
+    {
+        let mut records = self.records.write().await;
+        let record = records
+            .get_mut(xtype)
+            .and_then(|records| records.get_mut(id))
+            .ok_or_else(|| record_not_found(xtype, id))?;
+        f(record)
+    }
+}
+
+impl fmt::Debug for InMemoryWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keys are deliberately left out.
+        f.debug_struct("InMemoryWallet").finish_non_exhaustive()
+    }
+}
+
+fn record_not_found(xtype: &str, id: &str) -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::WalletRecordNotFound,
+        format!("Wallet record of type {} with id {} not found", xtype, id),
+    )
+}
+
+#[async_trait]
+impl BaseWallet for InMemoryWallet {
+    async fn create_and_store_my_did(
+        &self,
+        seed: Option<&str>,
+        method_name: Option<&str>,
+    ) -> VcxCoreResult<(String, String)> {
+        let key = crypto::create_key(seed)?;
+        let unqualified_did = bs58::encode(&key.verifying_key().as_bytes()[..16]).into_string();
+        let did = match method_name {
+            Some(method_name) => format!("did:{}:{}", method_name, unqualified_did),
+            None => unqualified_did,
+        };
+
+        let mut dids = self.dids.write().await;
+        if dids.contains_key(&did) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationDid,
+                format!("DID {} already exists in wallet", did),
+            ));
+        }
+
+        let verkey = self.store_key(key).await;
+        dids.insert(
+            did.clone(),
+            DidEntry {
+                verkey: verkey.clone(),
+                temp_verkey: None,
+            },
+        );
+
+        Ok((did, verkey))
+    }
+
+    async fn key_for_local_did(&self, did: &str) -> VcxCoreResult<String> {
+        self.dids
+            .read()
+            .await
+            .get(did)
+            .map(|entry| entry.verkey.clone())
+            .ok_or_else(|| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::WalletRecordNotFound,
+                    format!("DID {} not found in wallet", did),
+                )
+            })
+    }
+
+    async fn replace_did_keys_start(&self, target_did: &str) -> VcxCoreResult<String> {
+        let mut dids = self.dids.write().await;
+        let entry = dids.get_mut(target_did).ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("DID {} not found in wallet", target_did),
+            )
+        })?;
+
+        let verkey = self.store_key(crypto::create_key(None)?).await;
+        entry.temp_verkey = Some(verkey.clone());
+
+        Ok(verkey)
+    }
+
+    async fn replace_did_keys_apply(&self, target_did: &str) -> VcxCoreResult<()> {
+        let mut dids = self.dids.write().await;
+        let entry = dids.get_mut(target_did).ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::WalletRecordNotFound,
+                format!("DID {} not found in wallet", target_did),
+            )
+        })?;
+
+        entry.verkey = entry.temp_verkey.take().ok_or_else(|| {
+            AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::InvalidState,
+                format!("Key replacement of DID {} was not started", target_did),
+            )
+        })?;
+
+        Ok(())
+    }
+
+    async fn add_wallet_record(
+        &self,
+        xtype: &str,
+        id: &str,
+        value: &str,
+        tags: Option<HashMap<String, String>>,
+    ) -> VcxCoreResult<()> {
+        let mut records = self.records.write().await;
+        let records = records.entry(xtype.to_owned()).or_default();
+
+        if records.contains_key(id) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationWalletRecord,
+                format!("Wallet record of type {} with id {} already exists", xtype, id),
+            ));
+        }
+
+        records.insert(
+            id.to_owned(),
+            Record {
+                value: value.to_owned(),
+                tags: tags.unwrap_or_default(),
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn get_wallet_record(&self, xtype: &str, id: &str, options: &str) -> VcxCoreResult<String> {
+        let options = RecordOptions::parse(options)?;
+
+        let records = self.records.read().await;
+        let record = records
+            .get(xtype)
+            .and_then(|records| records.get(id))
+            .ok_or_else(|| record_not_found(xtype, id))?;
+
+        Ok(options.render(xtype, id, &record.value, &record.tags).to_string())
+    }
+
+    async fn get_wallet_record_value(&self, xtype: &str, id: &str) -> VcxCoreResult<String> {
+        let records = self.records.read().await;
+        records
+            .get(xtype)
+            .and_then(|records| records.get(id))
+            .map(|record| record.value.clone())
+            .ok_or_else(|| record_not_found(xtype, id))
+    }
+
+    async fn delete_wallet_record(&self, xtype: &str, id: &str) -> VcxCoreResult<()> {
+        let mut records = self.records.write().await;
+        records
+            .get_mut(xtype)
+            .and_then(|records| records.remove(id))
+            .map(|_| ())
+            .ok_or_else(|| record_not_found(xtype, id))
+    }
+
+    async fn update_wallet_record_value(&self, xtype: &str, id: &str, value: &str) -> VcxCoreResult<()> {
+        self.with_record(xtype, id, |record| {
+            record.value = value.to_owned();
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_wallet_record_tags(&self, xtype: &str, id: &str, tags: HashMap<String, String>) -> VcxCoreResult<()> {
+        self.with_record(xtype, id, |record| {
+            record.tags.extend(tags);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn update_wallet_record_tags(
+        &self,
+        xtype: &str,
+        id: &str,
+        tags: HashMap<String, String>,
+    ) -> VcxCoreResult<()> {
+        self.with_record(xtype, id, |record| {
+            record.tags = tags;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_wallet_record_tags(&self, xtype: &str, id: &str, tag_names: &str) -> VcxCoreResult<()> {
+        let tag_names: Vec<String> = serde_json::from_str(tag_names)?;
+
+        self.with_record(xtype, id, |record| {
+            for tag_name in tag_names.iter() {
+                record.tags.remove(tag_name);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn iterate_wallet_records(
+        &self,
+        xtype: &str,
+        query: &str,
+        options: &str,
+    ) -> VcxCoreResult<Box<dyn AsyncFnIterator<Item = VcxCoreResult<String>>>> {
+        let options = RecordOptions::parse(options)?;
+        let query: Value = if query.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(query)?
+        };
+
+        let mut matched = Vec::new();
+        if options.retrieve_records {
+            let records = self.records.read().await;
+            for (id, record) in records.get(xtype).into_iter().flatten() {
+                if wql::matches(&query, &record.tags)? {
+                    matched.push(options.render(xtype, id, &record.value, &record.tags).to_string());
+                }
+            }
+        }
+
+        Ok(Box::new(InMemoryWalletRecordIterator {
+            records: matched.into_iter(),
+        }))
+    }
+
+    async fn sign(&self, my_vk: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        Ok(crypto::sign(&self.get_key(my_vk).await?, msg))
+    }
+
+    async fn verify(&self, vk: &str, msg: &[u8], signature: &[u8]) -> VcxCoreResult<bool> {
+        crypto::verify(vk, msg, signature)
+    }
+
+    async fn pack_message(&self, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let receiver_keys: Vec<String> = serde_json::from_str(receiver_keys)?;
+
+        let sender = match sender_vk {
+            Some(sender_vk) => Some((sender_vk, self.get_key(sender_vk).await?)),
+            None => None,
+        };
+
+        crypto::pack(sender.as_ref().map(|(verkey, key)| (*verkey, key)), &receiver_keys, msg)
+    }
+
+    async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
+        let keys = self.keys.read().await;
+        let (verkey, key) = crypto::recipient_verkeys(msg)?
+            .into_iter()
+            .find_map(|verkey| keys.get(&verkey).map(|key| (verkey, key)))
+            .ok_or_else(|| {
+                AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::WalletRecordNotFound,
+                    "Message is not packed for any key of the wallet",
+                )
+            })?;
+
+        crypto::unpack(msg, &verkey, key)
+    }
+
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        let dids = self
+            .dids
+            .read()
+            .await
+            .iter()
+            .map(|(did, entry)| ArchivedDid {
+                did: did.clone(),
+                verkey: entry.verkey.clone(),
+                temp_verkey: entry.temp_verkey.clone(),
+            })
+            .collect();
+
+        let keys = self
+            .keys
+            .read()
+            .await
+            .iter()
+            .map(|(verkey, key)| ArchivedKey {
+                verkey: verkey.clone(),
+                seed: key.to_bytes(),
+            })
+            .collect();
+
+        let records = self
+            .records
+            .read()
+            .await
+            .iter()
+            .flat_map(|(xtype, records)| {
+                records.iter().map(move |(id, record)| ArchivedRecord {
+                    type_: xtype.clone(),
+                    id: id.clone(),
+                    value: record.value.clone(),
+                    tags: record.tags.clone(),
+                })
+            })
+            .collect();
+
+        WalletArchive { dids, keys, records }.write(path, backup_key)
+    }
+
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        let archive = WalletArchive::read(path, backup_key)?;
+
+        // Everything is checked before anything is imported, so that a failed import leaves the
+        // wallet untouched.
+        let mut dids = self.dids.write().await;
+        let mut keys = self.keys.write().await;
+        let mut records = self.records.write().await;
+
+        if let Some(did) = archive.dids.iter().find(|did| dids.contains_key(&did.did)) {
+            return Err(AriesVcxCoreError::from_msg(
+                AriesVcxCoreErrorKind::DuplicationDid,
+                format!("DID {} already exists in wallet", did.did),
+            ));
+        }
+
+        let mut archived_ids = HashSet::new();
+        for record in archive.records.iter() {
+            let exists = records
+                .get(&record.type_)
+                .map_or(false, |records| records.contains_key(&record.id));
+            if exists || !archived_ids.insert((&record.type_, &record.id)) {
+                return Err(AriesVcxCoreError::from_msg(
+                    AriesVcxCoreErrorKind::DuplicationWalletRecord,
+                    format!(
+                        "Wallet record of type {} with id {} already exists",
+                        record.type_, record.id
+                    ),
+                ));
+            }
+        }
+
+        for did in archive.dids {
+            dids.insert(
+                did.did,
+                DidEntry {
+                    verkey: did.verkey,
+                    temp_verkey: did.temp_verkey,
+                },
+            );
+        }
+
+        for key in archive.keys {
+            let key = SigningKey::from_bytes(&key.seed);
+            keys.insert(crypto::verkey(&key), key);
+        }
+
+        for record in archive.records {
+            records.entry(record.type_).or_default().insert(
+                record.id,
+                Record {
+                    value: record.value,
+                    tags: record.tags,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// [AsyncFnIterator] over the records matched by a search, collected when the search was opened.
+struct InMemoryWalletRecordIterator {
+    records: std::vec::IntoIter<String>,
+}
+
+#[async_trait]
+impl AsyncFnIterator for InMemoryWalletRecordIterator {
+    type Item = VcxCoreResult<String>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.records.next().map(Ok)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::wallet::base_wallet::AsyncFnIteratorCollect;
+
+    #[tokio::test]
+    async fn test_did_creation_is_deterministic_with_seed() {
+        let wallet = InMemoryWallet::new();
+        let seed = "000000000000000000000000Trustee1";
+
+        let (did, verkey) = wallet.create_and_store_my_did(Some(seed), None).await.unwrap();
+        // Well known trustee DID of the indy test networks.
+        assert_eq!(did, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(verkey, "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL");
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+
+        wallet.create_and_store_my_did(Some(seed), None).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        let wallet = InMemoryWallet::new();
+        let (_, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let signature = wallet.sign(&verkey, b"message").await.unwrap();
+        assert!(wallet.verify(&verkey, b"message", &signature).await.unwrap());
+        assert!(!wallet.verify(&verkey, b"other message", &signature).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_replace_did_keys() {
+        let wallet = InMemoryWallet::new();
+        let (did, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let new_verkey = wallet.replace_did_keys_start(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), verkey);
+
+        wallet.replace_did_keys_apply(&did).await.unwrap();
+        assert_eq!(wallet.key_for_local_did(&did).await.unwrap(), new_verkey);
+
+        // No key is left behind for unknown DIDs.
+        wallet.replace_did_keys_start("unknown").await.unwrap_err();
+        assert_eq!(wallet.keys.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pack_unpack() {
+        let sender_wallet = InMemoryWallet::new();
+        let receiver_wallet = InMemoryWallet::new();
+        let (_, sender_vk) = sender_wallet.create_and_store_my_did(None, None).await.unwrap();
+        let (_, receiver_vk) = receiver_wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver_keys = json!([receiver_vk]).to_string();
+
+        let packed = sender_wallet
+            .pack_message(Some(&sender_vk), &receiver_keys, b"authcrypted")
+            .await
+            .unwrap();
+        let unpacked: Value = serde_json::from_slice(&receiver_wallet.unpack_message(&packed).await.unwrap()).unwrap();
+        assert_eq!(unpacked["message"], "authcrypted");
+        assert_eq!(unpacked["sender_verkey"], sender_vk);
+        assert_eq!(unpacked["recipient_verkey"], receiver_vk);
+
+        let packed = sender_wallet
+            .pack_message(None, &receiver_keys, b"anoncrypted")
+            .await
+            .unwrap();
+        let unpacked: Value = serde_json::from_slice(&receiver_wallet.unpack_message(&packed).await.unwrap()).unwrap();
+        assert_eq!(unpacked["message"], "anoncrypted");
+        assert_eq!(unpacked.get("sender_verkey"), None);
+
+        sender_wallet.unpack_message(&packed).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_records() {
+        let wallet = InMemoryWallet::new();
+        let tags = HashMap::from([("tag".to_owned(), "a".to_owned())]);
+
+        wallet
+            .add_wallet_record("type", "id1", "value1", Some(tags))
+            .await
+            .unwrap();
+        wallet.add_wallet_record("type", "id2", "value2", None).await.unwrap();
+        wallet
+            .add_wallet_record("type", "id1", "value1", None)
+            .await
+            .unwrap_err();
+
+        wallet
+            .update_wallet_record_value("type", "id2", "updated")
+            .await
+            .unwrap();
+        assert_eq!(wallet.get_wallet_record_value("type", "id2").await.unwrap(), "updated");
+
+        let record: Value = serde_json::from_str(
+            &wallet
+                .get_wallet_record("type", "id1", r#"{"retrieveTags": true}"#)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            record,
+            json!({"id": "id1", "type": null, "value": "value1", "tags": {"tag": "a"}})
+        );
+
+        let records = wallet
+            .iterate_wallet_records("type", r#"{"tag": "a"}"#, "{}")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+
+        wallet
+            .delete_wallet_record_tags("type", "id1", r#"["tag"]"#)
+            .await
+            .unwrap();
+        let records = wallet
+            .iterate_wallet_records("type", r#"{"tag": "a"}"#, "{}")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+
+        wallet.delete_wallet_record("type", "id1").await.unwrap();
+        wallet.get_wallet_record_value("type", "id1").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_export_import() {
+        let wallet = InMemoryWallet::new();
+        let (did, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let tags = HashMap::from([("~tag".to_owned(), "value".to_owned())]);
+        wallet
+            .add_wallet_record("type", "id", "value", Some(tags))
+            .await
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("in_memory_wallet_{}", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        wallet.export_wallet(path, "backup_key").await.unwrap();
+
+        let imported = InMemoryWallet::new();
+        imported.import_wallet(path, "backup_key").await.unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(imported.key_for_local_did(&did).await.unwrap(), verkey);
+        let signature = imported.sign(&verkey, b"message").await.unwrap();
+        assert!(wallet.verify(&verkey, b"message", &signature).await.unwrap());
+        let record: Value = serde_json::from_str(
+            &imported
+                .get_wallet_record("type", "id", r#"{"retrieveTags": true}"#)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(record["tags"], json!({"~tag": "value"}));
+    }
+
+    #[tokio::test]
+    async fn test_import_is_atomic() {
+        let wallet = InMemoryWallet::new();
+        let (did, _) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        wallet.add_wallet_record("type", "id", "value", None).await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("in_memory_wallet_{}", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        wallet.export_wallet(path, "backup_key").await.unwrap();
+
+        let imported = InMemoryWallet::new();
+        imported
+            .add_wallet_record("type", "id", "other value", None)
+            .await
+            .unwrap();
+        imported.import_wallet(path, "backup_key").await.unwrap_err();
+        std::fs::remove_file(path).unwrap();
+
+        imported.key_for_local_did(&did).await.unwrap_err();
+        assert!(imported.keys.read().await.is_empty());
+        assert_eq!(
+            imported.get_wallet_record_value("type", "id").await.unwrap(),
+            "other value"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Evaluation of [WQL](https://github.com/hyperledger/indy-sdk/tree/main/docs/design/011-wallet-query-language)
+//! queries against the tags of a record.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult};
+
+pub(super) fn matches(query: &Value, tags: &HashMap<String, String>) -> VcxCoreResult<bool> {
+    match query {
+        Value::Object(query) => matches_all(query, tags),
+        _ => Err(invalid_query(query)),
+    }
+}
+
+fn matches_all(query: &Map<String, Value>, tags: &HashMap<String, String>) -> VcxCoreResult<bool> {
+    for (key, value) in query {
+        let matched = match key.as_str() {
+            "$and" => all_subqueries(value)?
+                .iter()
+                .map(|subquery| matches(subquery, tags))
+                .collect::<VcxCoreResult<Vec<_>>>()?
+                .into_iter()
+                .all(|matched| matched),
+            "$or" => all_subqueries(value)?
+                .iter()
+                .map(|subquery| matches(subquery, tags))
+                .collect::<VcxCoreResult<Vec<_>>>()?
+                .into_iter()
+                .any(|matched| matched),
+            "$not" => !matches(value, tags)?,
+            tag_name => matches_tag(tag_name, value, tags.get(tag_name))?,
+        };
+
+        if !matched {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn all_subqueries(value: &Value) -> VcxCoreResult<&Vec<Value>> {
+    value.as_array().ok_or_else(|| invalid_query(value))
+}
+
+fn matches_tag(tag_name: &str, condition: &Value, tag_value: Option<&String>) -> VcxCoreResult<bool> {
+    let (operator, operand) = match condition {
+        Value::String(_) => ("$eq", condition),
+        Value::Object(object) if object.len() == 1 => object
+            .iter()
+            .next()
+            .map(|(operator, operand)| (operator.as_str(), operand))
+            .ok_or_else(|| invalid_query(condition))?,
+        _ => return Err(invalid_query(format!("{}: {}", tag_name, condition))),
+    };
+
+    if operator == "$in" {
+        let values = operand.as_array().ok_or_else(|| invalid_query(operand))?;
+        return Ok(tag_value.map_or(false, |tag_value| {
+            values.iter().any(|value| value.as_str() == Some(tag_value))
+        }));
+    }
+
+    let operand = operand.as_str().ok_or_else(|| invalid_query(operand))?;
+    let Some(tag_value) = tag_value else {
+        return Ok(false);
+    };
+
+    let matched = match operator {
+        "$eq" => tag_value == operand,
+        "$neq" => tag_value != operand,
+        "$gt" => tag_value.as_str() > operand,
+        "$gte" => tag_value.as_str() >= operand,
+        "$lt" => tag_value.as_str() < operand,
+        "$lte" => tag_value.as_str() <= operand,
+        "$like" => matches_like(tag_value.as_bytes(), operand.as_bytes()),
+        _ => return Err(invalid_query(format!("Unknown operator {}", operator))),
+    };
+
+    Ok(matched)
+}
+
+/// SQL `LIKE` matching, `%` matching any sequence of characters and `_` any single one.
+fn matches_like(value: &[u8], pattern: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => value.is_empty(),
+        Some((b'%', rest)) => (0..=value.len()).any(|skip| matches_like(&value[skip..], rest)),
+        Some((b'_', rest)) => !value.is_empty() && matches_like(&value[1..], rest),
+        Some((c, rest)) => value.first() == Some(c) && matches_like(&value[1..], rest),
+    }
+}
+
+fn invalid_query<D: std::fmt::Display>(query: D) -> AriesVcxCoreError {
+    AriesVcxCoreError::from_msg(
+        AriesVcxCoreErrorKind::InvalidInput,
+        format!("Invalid WQL query: {}", query),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn tags() -> HashMap<String, String> {
+        HashMap::from([
+            ("name".to_owned(), "alice".to_owned()),
+            ("~age".to_owned(), "30".to_owned()),
+        ])
+    }
+
+    #[test]
+    fn test_wql_equality_and_operators() {
+        assert!(matches(&json!({}), &tags()).unwrap());
+        assert!(matches(&json!({"name": "alice"}), &tags()).unwrap());
+        assert!(!matches(&json!({"name": "bob"}), &tags()).unwrap());
+        assert!(!matches(&json!({"missing": "alice"}), &tags()).unwrap());
+        assert!(matches(&json!({"name": {"$neq": "bob"}}), &tags()).unwrap());
+        assert!(matches(&json!({"~age": {"$gte": "30"}}), &tags()).unwrap());
+        assert!(!matches(&json!({"~age": {"$lt": "30"}}), &tags()).unwrap());
+        assert!(matches(&json!({"name": {"$in": ["bob", "alice"]}}), &tags()).unwrap());
+        assert!(matches(&json!({"name": {"$like": "a%c_"}}), &tags()).unwrap());
+        assert!(!matches(&json!({"name": {"$like": "b%"}}), &tags()).unwrap());
+    }
+
+    #[test]
+    fn test_wql_combinators() {
+        let query = json!({"$or": [{"name": "bob"}, {"$and": [{"name": "alice"}, {"~age": "30"}]}]});
+        assert!(matches(&query, &tags()).unwrap());
+        assert!(!matches(&json!({"$not": {"name": "alice"}}), &tags()).unwrap());
+    }
+
+    #[test]
+    fn test_wql_invalid_query() {
+        matches(&json!({"name": {"$unknown": "alice"}}), &tags()).unwrap_err();
+        matches(&json!({"$and": {"name": "alice"}}), &tags()).unwrap_err();
+        matches(&json!("name"), &tags()).unwrap_err();
+    }
+}
@@ -136,6 +136,14 @@ impl BaseWallet for IndySdkWallet {
     async fn unpack_message(&self, msg: &[u8]) -> VcxCoreResult<Vec<u8>> {
         indy::signing::unpack_message(self.wallet_handle, msg).await
     }
+
+    async fn export_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        indy::wallet::export_wallet(self.wallet_handle, path, backup_key).await
+    }
+
+    async fn import_wallet(&self, path: &str, backup_key: &str) -> VcxCoreResult<()> {
+        indy::wallet::import_archive(self.wallet_handle, path, backup_key).await
+    }
 }
 
 struct IndyWalletRecordIterator {
@@ -190,3 +198,158 @@ impl Drop for IndyWalletRecordIterator {
         });
     }
 }
+
+#[cfg(test)]
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::indy::utils::test_setup::{generate_random_name, with_wallet};
+    use crate::indy::wallet::{RestoreWalletConfigs, WalletConfig};
+
+    const BACKUP_KEY: &str = "backup_key";
+    const WALLET_KEY: &str = "8dvfYSt5d1taSd6yJdpjq4emkwsPDDLYxkNFysFD2cZY";
+    const WALLET_KEY_DERIVATION: &str = "RAW";
+
+    struct Content {
+        did: String,
+        verkey: String,
+        temp_verkey: String,
+    }
+
+    async fn populate(wallet: &dyn BaseWallet) -> Content {
+        let (did, verkey) = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let temp_verkey = wallet.replace_did_keys_start(&did).await.unwrap();
+        let tags = HashMap::from([
+            ("tag".to_owned(), "a".to_owned()),
+            ("~plain".to_owned(), "b".to_owned()),
+        ]);
+        wallet
+            .add_wallet_record("type", "id", "value", Some(tags))
+            .await
+            .unwrap();
+
+        Content {
+            did,
+            verkey,
+            temp_verkey,
+        }
+    }
+
+    async fn assert_imported(wallet: &dyn BaseWallet, content: &Content) {
+        assert_eq!(wallet.key_for_local_did(&content.did).await.unwrap(), content.verkey);
+        for verkey in [&content.verkey, &content.temp_verkey] {
+            let signature = wallet.sign(verkey, b"message").await.unwrap();
+            assert!(wallet.verify(verkey, b"message", &signature).await.unwrap());
+        }
+
+        let record: Value = serde_json::from_str(
+            &wallet
+                .get_wallet_record("type", "id", r#"{"retrieveTags": true}"#)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(record["value"], "value");
+        assert_eq!(record["tags"], json!({"tag": "a", "~plain": "b"}));
+
+        // The key replacement started before the export can be completed after the import.
+        wallet.replace_did_keys_apply(&content.did).await.unwrap();
+        assert_eq!(
+            wallet.key_for_local_did(&content.did).await.unwrap(),
+            content.temp_verkey
+        );
+    }
+
+    fn archive_path() -> String {
+        let path = std::env::temp_dir().join(format!("wallet_archive_{}", uuid::Uuid::new_v4()));
+        path.to_str().unwrap().to_owned()
+    }
+
+    async fn export_to(other: &dyn BaseWallet) {
+        with_wallet(|wallet_handle| async move {
+            let wallet = IndySdkWallet::new(wallet_handle);
+            let content = populate(&wallet).await;
+
+            let path = archive_path();
+            wallet.export_wallet(&path, BACKUP_KEY).await.unwrap();
+            other.import_wallet(&path, BACKUP_KEY).await.unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_imported(other, &content).await;
+        })
+        .await;
+    }
+
+    async fn import_from(other: &dyn BaseWallet) {
+        let content = populate(other).await;
+
+        let path = archive_path();
+        other.export_wallet(&path, BACKUP_KEY).await.unwrap();
+
+        let (archive, expected) = (&path, &content);
+        with_wallet(|wallet_handle| async move {
+            let wallet = IndySdkWallet::new(wallet_handle);
+            wallet.import_wallet(archive, BACKUP_KEY).await.unwrap();
+
+            assert_imported(&wallet, expected).await;
+        })
+        .await;
+
+        // The archive can be restored by vdrtools itself as well.
+        let wallet_config = WalletConfig {
+            wallet_name: generate_random_name(),
+            wallet_key: WALLET_KEY.to_owned(),
+            wallet_key_derivation: WALLET_KEY_DERIVATION.to_owned(),
+            ..Default::default()
+        };
+        indy::wallet::import(&RestoreWalletConfigs {
+            wallet_name: wallet_config.wallet_name.clone(),
+            wallet_key: WALLET_KEY.to_owned(),
+            exported_wallet_path: path.clone(),
+            backup_key: BACKUP_KEY.to_owned(),
+            wallet_key_derivation: Some(WALLET_KEY_DERIVATION.to_owned()),
+        })
+        .await
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let wallet_handle = indy::wallet::open_wallet(&wallet_config).await.unwrap();
+        assert_imported(&IndySdkWallet::new(wallet_handle), &content).await;
+        indy::wallet::close_wallet(wallet_handle).await.unwrap();
+        indy::wallet::delete_wallet(&wallet_config).await.unwrap();
+    }
+
+    #[cfg(feature = "in_memory_wallet")]
+    #[tokio::test]
+    async fn test_indy_wallet_to_in_memory_wallet() {
+        export_to(&crate::wallet::in_memory_wallet::InMemoryWallet::new()).await;
+    }
+
+    #[cfg(feature = "in_memory_wallet")]
+    #[tokio::test]
+    async fn test_in_memory_wallet_to_indy_wallet() {
+        import_from(&crate::wallet::in_memory_wallet::InMemoryWallet::new()).await;
+    }
+
+    #[cfg(feature = "askar_wallet")]
+    async fn create_askar_wallet() -> crate::wallet::askar_wallet::AskarWallet {
+        use crate::wallet::askar_wallet::{AskarKeyMethod, AskarWallet, AskarWalletConfig};
+
+        let config = AskarWalletConfig::new("sqlite://:memory:", &AskarWallet::generate_raw_key().unwrap())
+            .key_method(AskarKeyMethod::RawKey);
+        AskarWallet::create(&config, true).await.unwrap()
+    }
+
+    #[cfg(feature = "askar_wallet")]
+    #[tokio::test]
+    async fn test_indy_wallet_to_askar_wallet() {
+        export_to(&create_askar_wallet().await).await;
+    }
+
+    #[cfg(feature = "askar_wallet")]
+    #[tokio::test]
+    async fn test_askar_wallet_to_indy_wallet() {
+        import_from(&create_askar_wallet().await).await;
+    }
+}
@@ -1,4 +1,12 @@
 pub mod agency_client_wallet;
+#[cfg(feature = "askar_wallet")]
+pub mod askar_wallet;
 pub mod base_wallet;
+#[cfg(any(feature = "in_memory_wallet", feature = "askar_wallet"))]
+mod common;
+#[cfg(any(feature = "vdrtools", feature = "in_memory_wallet", feature = "askar_wallet"))]
+pub(crate) mod export_import;
+#[cfg(feature = "in_memory_wallet")]
+pub mod in_memory_wallet;
 #[cfg(feature = "vdrtools")]
 pub mod indy_wallet;
@@ -7,9 +7,13 @@ use serde_json::Value;
 use crate::error::DidDocumentBuilderError;
 
 use super::{
-    service::Service,
+    service::{
+        didcomm::{select_service, DidCommService, DidCommServiceType, ServiceTransport},
+        Service,
+    },
     types::uri::Uri,
     utils::OneOrList,
+    validation::{self, Representation, ValidationReport},
     verification_method::{VerificationMethod, VerificationMethodKind},
 };
 
@@ -92,8 +96,51 @@ impl<E> DidDocument<E> {
         self.extra.get(key)
     }
 
+    /// Validates the document against DID Core, rejecting it for the errors of its
+    /// [`ValidationReport`].
     pub fn validate(&self) -> Result<(), DidDocumentBuilderError> {
-        Ok(())
+        let report = self.validation_report(Representation::Json);
+        if report.is_valid() {
+            return Ok(());
+        }
+        let errors: Vec<String> = report.errors().map(|issue| issue.to_string()).collect();
+        Err(DidDocumentBuilderError::InvalidInput(errors.join("; ")))
+    }
+
+    /// Validates the document against DID Core, including the rules specific to `representation`.
+    pub fn validation_report(&self, representation: Representation) -> ValidationReport {
+        validation::validate(self, representation)
+    }
+}
+
+impl<E: Serialize> DidDocument<E> {
+    /// DIDComm services of the document, skipping services of other types.
+    pub fn didcomm_services(&self) -> Result<Vec<DidCommService>, DidDocumentBuilderError> {
+        self.service
+            .iter()
+            .filter(|service| is_didcomm_service(service))
+            .map(DidCommService::try_from)
+            .collect()
+    }
+
+    /// The DIDComm service to deliver messages of `media_type` to over `transport`,
+    /// see [`select_service`].
+    pub fn best_didcomm_service(
+        &self,
+        transport: Option<ServiceTransport>,
+        media_type: Option<&str>,
+    ) -> Result<Option<DidCommService>, DidDocumentBuilderError> {
+        let services = self.didcomm_services()?;
+        Ok(select_service(&services, transport, media_type).cloned())
+    }
+}
+
+fn is_didcomm_service<E>(service: &Service<E>) -> bool {
+    match service.service_type() {
+        OneOrList::One(service_type) => service_type.parse::<DidCommServiceType>().is_ok(),
+        OneOrList::List(service_types) => service_types
+            .iter()
+            .any(|service_type| service_type.parse::<DidCommServiceType>().is_ok()),
     }
 }
 
@@ -1,5 +1,7 @@
 pub mod did_doc;
+pub mod references;
 pub mod service;
 pub mod types;
 pub(crate) mod utils;
+pub mod validation;
 pub mod verification_method;
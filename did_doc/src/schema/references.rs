@@ -0,0 +1,103 @@
+use serde_json::{Map, Value};
+
+/// Properties of a DID document holding verification relationships, see
+/// <https://www.w3.org/TR/did-core/#verification-relationships>.
+pub const VERIFICATION_RELATIONSHIPS: [&str; 5] = [
+    "authentication",
+    "assertionMethod",
+    "keyAgreement",
+    "capabilityInvocation",
+    "capabilityDelegation",
+];
+
+/// Replaces the ids of verification methods and services, and the references to verification
+/// methods, of a DID document in its JSON representation with the result of `map` when it
+/// returns some.
+///
+/// Meant for DID methods which encode documents with relative references, such as `#key-1`, and
+/// have to turn them into absolute DID URLs of the resolved DID, or the other way around.
+pub fn map_references<F>(document: &mut Map<String, Value>, map: F)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let map_value = |value: &mut Value| {
+        if let Some(mapped) = value.as_str().and_then(&map) {
+            *value = mapped.into();
+        }
+    };
+
+    for (key, values) in document.iter_mut() {
+        if key != "service"
+            && key != "verificationMethod"
+            && !VERIFICATION_RELATIONSHIPS.contains(&key.as_str())
+        {
+            continue;
+        }
+        for value in values.as_array_mut().into_iter().flatten() {
+            match value {
+                Value::Object(object) => object.get_mut("id").into_iter().for_each(map_value),
+                value => map_value(value),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const DID: &str = "did:example:123";
+
+    fn contextualize(reference: &str) -> Option<String> {
+        reference
+            .starts_with('#')
+            .then(|| format!("{}{}", DID, reference))
+    }
+
+    #[test]
+    fn test_map_references() {
+        let Value::Object(mut document) = json!({
+            "verificationMethod": [{ "id": "#key-1", "controller": "#not-a-reference" }],
+            "authentication": ["#key-1", { "id": "#key-2" }],
+            "keyAgreement": ["did:example:other#key-1"],
+            "service": [{ "id": "#didcomm", "serviceEndpoint": "#not-a-reference" }],
+            "alsoKnownAs": ["#not-a-reference"]
+        }) else {
+            unreachable!()
+        };
+
+        map_references(&mut document, contextualize);
+
+        assert_eq!(
+            Value::Object(document),
+            json!({
+                "verificationMethod": [
+                    { "id": "did:example:123#key-1", "controller": "#not-a-reference" }
+                ],
+                "authentication": ["did:example:123#key-1", { "id": "did:example:123#key-2" }],
+                "keyAgreement": ["did:example:other#key-1"],
+                "service": [
+                    { "id": "did:example:123#didcomm", "serviceEndpoint": "#not-a-reference" }
+                ],
+                "alsoKnownAs": ["#not-a-reference"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_map_references_ignores_malformed_properties() {
+        let Value::Object(mut document) = json!({
+            "authentication": "#key-1",
+            "service": [42, null]
+        }) else {
+            unreachable!()
+        };
+        let expected = Value::Object(document.clone());
+
+        map_references(&mut document, contextualize);
+
+        assert_eq!(Value::Object(document), expected);
+    }
+}
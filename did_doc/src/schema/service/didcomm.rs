@@ -0,0 +1,492 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use did_parser::DidUrl;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::DidDocumentBuilderError,
+    schema::{
+        types::{uri::Uri, url::Url},
+        utils::OneOrList,
+    },
+};
+
+use super::Service;
+
+pub const SERVICE_TYPE_DIDCOMM_MESSAGING: &str = "DIDCommMessaging";
+pub const SERVICE_TYPE_DID_COMMUNICATION: &str = "did-communication";
+pub const SERVICE_TYPE_INDY_AGENT: &str = "IndyAgent";
+
+// Services used to reach DIDComm agents:
+// https://identity.foundation/didcomm-messaging/spec/#did-document-service-endpoint
+// https://github.com/hyperledger/aries-rfcs/blob/main/features/0067-didcomm-diddoc-conventions/README.md
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DidCommServiceType {
+    DidCommMessaging,
+    DidCommunication,
+    IndyAgent,
+}
+
+impl DidCommServiceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DidCommServiceType::DidCommMessaging => SERVICE_TYPE_DIDCOMM_MESSAGING,
+            DidCommServiceType::DidCommunication => SERVICE_TYPE_DID_COMMUNICATION,
+            DidCommServiceType::IndyAgent => SERVICE_TYPE_INDY_AGENT,
+        }
+    }
+
+    /// Media types accepted by services of this type which don't list any.
+    pub fn default_accept(&self) -> &'static [&'static str] {
+        match self {
+            DidCommServiceType::DidCommMessaging => &["didcomm/v2"],
+            DidCommServiceType::DidCommunication | DidCommServiceType::IndyAgent => {
+                &["didcomm/aip1", "didcomm/aip2;env=rfc19"]
+            }
+        }
+    }
+}
+
+impl FromStr for DidCommServiceType {
+    type Err = DidDocumentBuilderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            SERVICE_TYPE_DIDCOMM_MESSAGING => Ok(DidCommServiceType::DidCommMessaging),
+            SERVICE_TYPE_DID_COMMUNICATION => Ok(DidCommServiceType::DidCommunication),
+            SERVICE_TYPE_INDY_AGENT => Ok(DidCommServiceType::IndyAgent),
+            _ => Err(DidDocumentBuilderError::InvalidInput(format!(
+                "Unsupported DIDComm service type: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for DidCommServiceType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Transport through which a service endpoint is reached, given by the endpoint's URL scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ServiceTransport {
+    Http,
+    WebSocket,
+}
+
+impl ServiceTransport {
+    pub fn from_scheme(scheme: &str) -> Option<Self> {
+        match scheme.to_ascii_lowercase().as_str() {
+            "http" | "https" => Some(ServiceTransport::Http),
+            "ws" | "wss" => Some(ServiceTransport::WebSocket),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+struct DidCommExtraFields {
+    #[serde(default)]
+    priority: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recipient_keys: Vec<String>,
+    #[serde(default)]
+    routing_keys: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    accept: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DidCommService {
+    id: Uri,
+    service_type: DidCommServiceType,
+    service_endpoint: Url,
+    priority: u32,
+    recipient_keys: Vec<String>,
+    routing_keys: Vec<String>,
+    accept: Vec<String>,
+}
+
+impl DidCommService {
+    pub fn builder(
+        id: Uri,
+        service_type: DidCommServiceType,
+        service_endpoint: Url,
+    ) -> DidCommServiceBuilder {
+        DidCommServiceBuilder::new(id, service_type, service_endpoint)
+    }
+
+    pub fn id(&self) -> &Uri {
+        &self.id
+    }
+
+    pub fn service_type(&self) -> DidCommServiceType {
+        self.service_type
+    }
+
+    pub fn service_endpoint(&self) -> &Url {
+        &self.service_endpoint
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn recipient_keys(&self) -> &[String] {
+        self.recipient_keys.as_ref()
+    }
+
+    pub fn routing_keys(&self) -> &[String] {
+        self.routing_keys.as_ref()
+    }
+
+    pub fn accept(&self) -> &[String] {
+        self.accept.as_ref()
+    }
+
+    pub fn transport(&self) -> Option<ServiceTransport> {
+        let endpoint: &str = self.service_endpoint.as_ref();
+        endpoint
+            .split_once(':')
+            .and_then(|(scheme, _)| ServiceTransport::from_scheme(scheme))
+    }
+
+    /// Whether the service accepts messages of `media_type`, such as `didcomm/v2` or
+    /// `didcomm/aip2;env=rfc19`. Media types without parameters match any parameters.
+    pub fn accepts(&self, media_type: &str) -> bool {
+        let matches = |accepted: &str| {
+            accepted == media_type || accepted.split(';').next() == Some(media_type)
+        };
+        if self.accept.is_empty() {
+            self.service_type
+                .default_accept()
+                .iter()
+                .any(|accepted| matches(accepted))
+        } else {
+            self.accept.iter().any(|accepted| matches(accepted))
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), DidDocumentBuilderError> {
+        match self.service_type {
+            DidCommServiceType::DidCommMessaging => {
+                // DIDComm v2 encrypts for the keyAgreement keys of the DID document.
+                if !self.recipient_keys.is_empty() {
+                    return Err(DidDocumentBuilderError::InvalidInput(format!(
+                        "Service {} of type {} must not have recipientKeys",
+                        self.id, self.service_type
+                    )));
+                }
+            }
+            DidCommServiceType::DidCommunication | DidCommServiceType::IndyAgent => {
+                if self.recipient_keys.is_empty() {
+                    return Err(DidDocumentBuilderError::MissingField("recipientKeys"));
+                }
+            }
+        }
+
+        for key in self.recipient_keys.iter().chain(self.routing_keys.iter()) {
+            // Keys are DID URLs, though legacy services also list the base58 keys themselves.
+            if !is_base58(key) && DidUrl::parse(key.to_owned()).is_err() {
+                return Err(DidDocumentBuilderError::InvalidInput(format!(
+                    "Invalid key {} in service {}",
+                    key, self.id
+                )));
+            }
+        }
+
+        if self.accept.iter().any(|accept| accept.trim().is_empty()) {
+            return Err(DidDocumentBuilderError::InvalidInput(format!(
+                "Empty accept entry in service {}",
+                self.id
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_service<E: DeserializeOwned>(&self) -> Result<Service<E>, DidDocumentBuilderError> {
+        let mut service = serde_json::to_value(DidCommExtraFields {
+            priority: self.priority,
+            recipient_keys: self.recipient_keys.clone(),
+            routing_keys: self.routing_keys.clone(),
+            accept: self.accept.clone(),
+        })?;
+        if let Value::Object(fields) = &mut service {
+            fields.insert("id".to_owned(), serde_json::to_value(&self.id)?);
+            fields.insert("type".to_owned(), self.service_type.as_str().into());
+            fields.insert(
+                "serviceEndpoint".to_owned(),
+                serde_json::to_value(&self.service_endpoint)?,
+            );
+        }
+        Ok(serde_json::from_value(service)?)
+    }
+}
+
+impl<E: Serialize> TryFrom<&Service<E>> for DidCommService {
+    type Error = DidDocumentBuilderError;
+
+    fn try_from(service: &Service<E>) -> Result<Self, Self::Error> {
+        let service_type = match service.service_type() {
+            OneOrList::One(service_type) => service_type.parse().ok(),
+            OneOrList::List(service_types) => service_types
+                .iter()
+                .find_map(|service_type| service_type.parse().ok()),
+        }
+        .ok_or_else(|| {
+            DidDocumentBuilderError::InvalidInput(format!(
+                "Service {} is not a DIDComm service",
+                service.id()
+            ))
+        })?;
+
+        let extra: DidCommExtraFields = serde_json::from_value(serde_json::to_value(service)?)?;
+
+        let service = DidCommService {
+            id: service.id().clone(),
+            service_type,
+            service_endpoint: service.service_endpoint().clone(),
+            priority: extra.priority,
+            recipient_keys: extra.recipient_keys,
+            routing_keys: extra.routing_keys,
+            accept: extra.accept,
+        };
+        service.validate()?;
+        Ok(service)
+    }
+}
+
+fn is_base58(key: &str) -> bool {
+    let base58_chars = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    !key.is_empty() && key.chars().all(|c| base58_chars.contains(c))
+}
+
+/// Picks the service to deliver a message to among `services`: the one with the lowest priority
+/// value, reachable over `transport` and accepting `media_type`, when given. Services listed
+/// first win among services of the same priority.
+pub fn select_service<'a>(
+    services: &'a [DidCommService],
+    transport: Option<ServiceTransport>,
+    media_type: Option<&str>,
+) -> Option<&'a DidCommService> {
+    services
+        .iter()
+        .filter(|service| {
+            transport.map_or(true, |transport| service.transport() == Some(transport))
+        })
+        .filter(|service| media_type.map_or(true, |media_type| service.accepts(media_type)))
+        .min_by_key(|service| service.priority)
+}
+
+#[derive(Debug)]
+pub struct DidCommServiceBuilder {
+    id: Uri,
+    service_type: DidCommServiceType,
+    service_endpoint: Url,
+    priority: u32,
+    recipient_keys: Vec<String>,
+    routing_keys: Vec<String>,
+    accept: Vec<String>,
+}
+
+impl DidCommServiceBuilder {
+    pub fn new(id: Uri, service_type: DidCommServiceType, service_endpoint: Url) -> Self {
+        Self {
+            id,
+            service_type,
+            service_endpoint,
+            priority: 0,
+            recipient_keys: Vec::new(),
+            routing_keys: Vec::new(),
+            accept: Vec::new(),
+        }
+    }
+
+    pub fn set_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn add_recipient_key(mut self, recipient_key: String) -> Self {
+        self.recipient_keys.push(recipient_key);
+        self
+    }
+
+    pub fn add_routing_key(mut self, routing_key: String) -> Self {
+        self.routing_keys.push(routing_key);
+        self
+    }
+
+    pub fn add_accept(mut self, accept: String) -> Self {
+        self.accept.push(accept);
+        self
+    }
+
+    pub fn build(self) -> Result<DidCommService, DidDocumentBuilderError> {
+        let service = DidCommService {
+            id: self.id,
+            service_type: self.service_type,
+            service_endpoint: self.service_endpoint,
+            priority: self.priority,
+            recipient_keys: self.recipient_keys,
+            routing_keys: self.routing_keys,
+            accept: self.accept,
+        };
+        service.validate()?;
+        Ok(service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    type ExtraFields = HashMap<String, Value>;
+
+    fn didcomm_v2_service(id: &str, endpoint: &str, priority: u32) -> DidCommService {
+        DidCommService::builder(
+            Uri::new(id).unwrap(),
+            DidCommServiceType::DidCommMessaging,
+            endpoint.try_into().unwrap(),
+        )
+        .set_priority(priority)
+        .add_routing_key("did:key:z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc".to_string())
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_didcomm_service_from_service() {
+        let service: Service<ExtraFields> = serde_json::from_str(
+            r##"{
+              "id": "did:sov:HR6vs6GEZ8rHaVgjg2WodM#did-communication",
+              "type": "did-communication",
+              "priority": 1,
+              "recipientKeys": ["did:sov:HR6vs6GEZ8rHaVgjg2WodM#key-agreement-1"],
+              "routingKeys": [],
+              "accept": ["didcomm/aip2;env=rfc19"],
+              "serviceEndpoint": "https://example.com/endpoint"
+            }"##,
+        )
+        .unwrap();
+
+        let didcomm_service = DidCommService::try_from(&service).unwrap();
+        assert_eq!(
+            didcomm_service.service_type(),
+            DidCommServiceType::DidCommunication
+        );
+        assert_eq!(didcomm_service.priority(), 1);
+        assert_eq!(
+            didcomm_service.recipient_keys(),
+            ["did:sov:HR6vs6GEZ8rHaVgjg2WodM#key-agreement-1".to_string()]
+        );
+        assert_eq!(didcomm_service.transport(), Some(ServiceTransport::Http));
+        assert!(didcomm_service.accepts("didcomm/aip2"));
+        assert!(!didcomm_service.accepts("didcomm/v2"));
+
+        assert_eq!(
+            didcomm_service.to_service::<ExtraFields>().unwrap(),
+            service
+        );
+    }
+
+    #[test]
+    fn test_indy_agent_service_accepts_raw_keys() {
+        let service: Service<ExtraFields> = serde_json::from_str(
+            r#"{
+              "id": "did:example:123456789abcdefghi;indy",
+              "type": "IndyAgent",
+              "recipientKeys": ["GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"],
+              "routingKeys": ["Hezce2UWMZ3wUhVkh2LfKSs8nDzWwzs2Win7EzNN3YaR"],
+              "serviceEndpoint": "https://example.com/endpoint"
+            }"#,
+        )
+        .unwrap();
+
+        let didcomm_service = DidCommService::try_from(&service).unwrap();
+        assert_eq!(
+            didcomm_service.service_type(),
+            DidCommServiceType::IndyAgent
+        );
+        assert!(didcomm_service.accepts("didcomm/aip1"));
+    }
+
+    #[test]
+    fn test_didcomm_service_validation() {
+        let id = Uri::new("did:example:123#didcomm").unwrap();
+        let endpoint: Url = "https://example.com/endpoint".try_into().unwrap();
+
+        let missing_recipient_keys = DidCommService::builder(
+            id.clone(),
+            DidCommServiceType::DidCommunication,
+            endpoint.clone(),
+        )
+        .build();
+        assert!(matches!(
+            missing_recipient_keys,
+            Err(DidDocumentBuilderError::MissingField("recipientKeys"))
+        ));
+
+        let invalid_key = DidCommService::builder(
+            id.clone(),
+            DidCommServiceType::DidCommunication,
+            endpoint.clone(),
+        )
+        .add_recipient_key("not a key".to_string())
+        .build();
+        assert!(invalid_key.is_err());
+
+        let v2_recipient_keys =
+            DidCommService::builder(id, DidCommServiceType::DidCommMessaging, endpoint)
+                .add_recipient_key("did:example:123#key-1".to_string())
+                .build();
+        assert!(v2_recipient_keys.is_err());
+    }
+
+    #[test]
+    fn test_not_didcomm_service() {
+        let service: Service<ExtraFields> = serde_json::from_str(
+            r#"{
+              "id": "did:example:123#linked-domain",
+              "type": "LinkedDomains",
+              "serviceEndpoint": "https://example.com"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(DidCommService::try_from(&service).is_err());
+    }
+
+    #[test]
+    fn test_select_service() {
+        let services = vec![
+            didcomm_v2_service("did:example:123#ws", "wss://example.com/ws", 1),
+            didcomm_v2_service(
+                "did:example:123#http-backup",
+                "https://backup.example.com",
+                2,
+            ),
+            didcomm_v2_service("did:example:123#http", "https://example.com", 1),
+        ];
+
+        let selected = select_service(&services, Some(ServiceTransport::Http), None).unwrap();
+        assert_eq!(selected.id().to_string(), "did:example:123#http");
+
+        let selected = select_service(&services, None, Some("didcomm/v2")).unwrap();
+        assert_eq!(selected.id().to_string(), "did:example:123#ws");
+
+        assert!(select_service(&services, None, Some("didcomm/aip2")).is_none());
+    }
+}
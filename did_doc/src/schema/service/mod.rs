@@ -1,3 +1,5 @@
+pub mod didcomm;
+
 use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
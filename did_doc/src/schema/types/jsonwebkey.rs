@@ -27,6 +27,22 @@ impl JsonWebKey {
     pub fn new(jwk: &str) -> Result<Self, DidDocumentBuilderError> {
         Ok(serde_json::from_str(jwk)?)
     }
+
+    pub fn kty(&self) -> &str {
+        self.kty.as_ref()
+    }
+
+    pub fn crv(&self) -> &str {
+        self.crv.as_ref()
+    }
+
+    pub fn x(&self) -> &str {
+        self.x.as_ref()
+    }
+
+    pub fn extra_field(&self, key: &str) -> Option<&Value> {
+        self.extra.get(key)
+    }
 }
 
 impl FromStr for JsonWebKey {
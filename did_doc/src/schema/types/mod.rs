@@ -1,4 +1,5 @@
 pub mod jsonwebkey;
 pub mod multibase;
+pub mod public_key;
 pub mod uri;
 pub mod url;
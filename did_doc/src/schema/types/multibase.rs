@@ -37,7 +37,7 @@ impl Serialize for Multibase {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.base.encode(&self.bytes))
+        serializer.serialize_str(&multibase::encode(self.base, &self.bytes))
     }
 }
 
@@ -103,6 +103,21 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_multibase_serialize_round_trip() {
+        let multibase =
+            Multibase::new("zQmWvQxTqbG2Z9HPJgG57jjwR154cKhbtJenbyYTWkjgF3e".to_string()).unwrap();
+        let serialized = serde_json::to_string(&multibase).unwrap();
+        assert_eq!(
+            serialized,
+            "\"zQmWvQxTqbG2Z9HPJgG57jjwR154cKhbtJenbyYTWkjgF3e\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Multibase>(&serialized).unwrap(),
+            multibase
+        );
+    }
+
     #[test]
     fn test_multibase_deserialize_invalid() {
         let multibase: Result<Multibase, _> = serde_json::from_str("\"invalidmultibasekey\"");
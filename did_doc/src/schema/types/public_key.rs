@@ -0,0 +1,392 @@
+use std::fmt::{self, Display, Formatter};
+
+use multibase::Base;
+use serde_json::json;
+
+use crate::error::DidDocumentBuilderError;
+
+use super::{jsonwebkey::JsonWebKey, multibase::Multibase};
+
+// Multicodec codes of public keys, prefixed to keys as unsigned varints:
+// https://github.com/multiformats/multicodec/blob/master/table.csv
+const ED25519_PUB: u64 = 0xed;
+const X25519_PUB: u64 = 0xec;
+const P256_PUB: u64 = 0x1200;
+const SECP256K1_PUB: u64 = 0xe7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    Ed25519,
+    X25519,
+    P256,
+    Secp256k1,
+}
+
+impl KeyType {
+    /// Key type implied by a verification method type. Types such as `JsonWebKey2020` or
+    /// `Multikey` don't imply any, as the key material itself tells its type.
+    pub fn from_verification_method_type(verification_method_type: &str) -> Option<Self> {
+        match verification_method_type {
+            "Ed25519VerificationKey2018" | "Ed25519VerificationKey2020" => Some(KeyType::Ed25519),
+            "X25519KeyAgreementKey2019" | "X25519KeyAgreementKey2020" => Some(KeyType::X25519),
+            "EcdsaSecp256r1VerificationKey2019" => Some(KeyType::P256),
+            "EcdsaSecp256k1VerificationKey2019" => Some(KeyType::Secp256k1),
+            _ => None,
+        }
+    }
+
+    pub fn from_multicodec(code: u64) -> Option<Self> {
+        match code {
+            ED25519_PUB => Some(KeyType::Ed25519),
+            X25519_PUB => Some(KeyType::X25519),
+            P256_PUB => Some(KeyType::P256),
+            SECP256K1_PUB => Some(KeyType::Secp256k1),
+            _ => None,
+        }
+    }
+
+    pub fn multicodec(&self) -> u64 {
+        match self {
+            KeyType::Ed25519 => ED25519_PUB,
+            KeyType::X25519 => X25519_PUB,
+            KeyType::P256 => P256_PUB,
+            KeyType::Secp256k1 => SECP256K1_PUB,
+        }
+    }
+
+    /// The multicodec code of the key type, varint encoded as prefixed to keys.
+    pub fn multicodec_prefix(&self) -> Vec<u8> {
+        encode_varint(self.multicodec())
+    }
+
+    /// The `kty` and `crv` parameters of JWKs holding keys of this type.
+    pub fn jwk_curve(&self) -> (&'static str, &'static str) {
+        match self {
+            KeyType::Ed25519 => ("OKP", "Ed25519"),
+            KeyType::X25519 => ("OKP", "X25519"),
+            KeyType::P256 => ("EC", "P-256"),
+            KeyType::Secp256k1 => ("EC", "secp256k1"),
+        }
+    }
+
+    fn is_elliptic_curve(&self) -> bool {
+        matches!(self, KeyType::P256 | KeyType::Secp256k1)
+    }
+}
+
+impl Display for KeyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (_, crv) = self.jwk_curve();
+        write!(f, "{}", crv)
+    }
+}
+
+/// A public key decoded from any of the representations used by verification methods.
+///
+/// Ed25519 and X25519 keys are 32 bytes long. P-256 and secp256k1 keys are SEC1 encoded points,
+/// either compressed (33 bytes) or uncompressed (65 bytes).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    key_type: KeyType,
+    key: Vec<u8>,
+}
+
+impl PublicKey {
+    pub fn new(key_type: KeyType, key: Vec<u8>) -> Result<Self, DidDocumentBuilderError> {
+        let valid = match key_type {
+            KeyType::Ed25519 | KeyType::X25519 => key.len() == 32,
+            KeyType::P256 | KeyType::Secp256k1 => match key.first() {
+                Some(0x02 | 0x03) => key.len() == 33,
+                Some(0x04) => key.len() == 65,
+                _ => false,
+            },
+        };
+        if !valid {
+            return Err(DidDocumentBuilderError::InvalidInput(format!(
+                "Invalid {} key of {} bytes",
+                key_type,
+                key.len()
+            )));
+        }
+        Ok(Self { key_type, key })
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    /// Decodes a key prefixed with the multicodec code of its type.
+    pub fn from_multicodec(bytes: &[u8]) -> Result<Self, DidDocumentBuilderError> {
+        let (code, key) = split_multicodec_prefix(bytes)?;
+        let key_type = KeyType::from_multicodec(code).ok_or_else(|| {
+            DidDocumentBuilderError::InvalidInput(format!(
+                "Unsupported multicodec key type {:#x}",
+                code
+            ))
+        })?;
+        Self::new(key_type, key.to_vec())
+    }
+
+    /// Decodes a multibase key, which is multicodec prefixed unless its type is known from
+    /// elsewhere, as for `Ed25519VerificationKey2018` verification methods. A multicodec prefix
+    /// contradicting the known type is rejected.
+    pub fn from_multibase(
+        multibase: &Multibase,
+        key_type: Option<KeyType>,
+    ) -> Result<Self, DidDocumentBuilderError> {
+        match (Self::from_multicodec(multibase.as_ref()), key_type) {
+            (Ok(key), Some(key_type)) if key.key_type != key_type => {
+                Err(DidDocumentBuilderError::InvalidInput(format!(
+                    "Expected {} key, found multicodec {} key",
+                    key_type, key.key_type
+                )))
+            }
+            (Ok(key), _) => Ok(key),
+            (Err(_), Some(key_type)) => Self::new(key_type, multibase.as_ref().to_vec()),
+            (Err(err), None) => Err(err),
+        }
+    }
+
+    pub fn from_base58(key_type: KeyType, base58: &str) -> Result<Self, DidDocumentBuilderError> {
+        let key = Base::Base58Btc.decode(base58).map_err(|err| {
+            DidDocumentBuilderError::InvalidInput(format!("Invalid base58 key: {}", err))
+        })?;
+        Self::new(key_type, key)
+    }
+
+    pub fn from_jwk(jwk: &JsonWebKey) -> Result<Self, DidDocumentBuilderError> {
+        let key_type = [
+            KeyType::Ed25519,
+            KeyType::X25519,
+            KeyType::P256,
+            KeyType::Secp256k1,
+        ]
+        .into_iter()
+        .find(|key_type| key_type.jwk_curve() == (jwk.kty(), jwk.crv()))
+        .ok_or_else(|| {
+            DidDocumentBuilderError::InvalidInput(format!(
+                "Unsupported JWK curve: {} {}",
+                jwk.kty(),
+                jwk.crv()
+            ))
+        })?;
+
+        let decode = |coordinate: &str| {
+            Base::Base64Url.decode(coordinate).map_err(|err| {
+                DidDocumentBuilderError::InvalidInput(format!("Invalid JWK coordinate: {}", err))
+            })
+        };
+
+        let mut key = decode(jwk.x())?;
+        if key_type.is_elliptic_curve() {
+            let y = jwk
+                .extra_field("y")
+                .and_then(|y| y.as_str())
+                .ok_or(DidDocumentBuilderError::MissingField("y"))?;
+            key.insert(0, 0x04);
+            key.extend(decode(y)?);
+        }
+        Self::new(key_type, key)
+    }
+
+    /// The key prefixed with the multicodec code of its type. Elliptic curve keys are compressed.
+    pub fn prefixed_multicodec(&self) -> Vec<u8> {
+        let mut bytes = self.key_type.multicodec_prefix();
+        bytes.extend(self.compressed());
+        bytes
+    }
+
+    /// The multibase, multicodec prefixed representation of the key, as used in `did:key`s.
+    pub fn to_multibase(&self) -> String {
+        multibase::encode(Base::Base58Btc, self.prefixed_multicodec())
+    }
+
+    pub fn to_base58(&self) -> String {
+        Base::Base58Btc.encode(&self.key)
+    }
+
+    /// The JWK representation of the key. Compressed elliptic curve keys are not supported,
+    /// decompressing them requires curve arithmetic.
+    pub fn to_jwk(&self) -> Result<JsonWebKey, DidDocumentBuilderError> {
+        let (kty, crv) = self.key_type.jwk_curve();
+        let jwk = if self.key_type.is_elliptic_curve() {
+            if self.key.len() != 65 {
+                return Err(DidDocumentBuilderError::InvalidInput(format!(
+                    "Cannot convert compressed {} key to JWK",
+                    self.key_type
+                )));
+            }
+            json!({
+                "kty": kty,
+                "crv": crv,
+                "x": Base::Base64Url.encode(&self.key[1..33]),
+                "y": Base::Base64Url.encode(&self.key[33..]),
+            })
+        } else {
+            json!({
+                "kty": kty,
+                "crv": crv,
+                "x": Base::Base64Url.encode(&self.key),
+            })
+        };
+        Ok(serde_json::from_value(jwk)?)
+    }
+
+    fn compressed(&self) -> Vec<u8> {
+        match (self.key_type.is_elliptic_curve(), self.key.len()) {
+            (true, 65) => {
+                let mut compressed = vec![0x02 | (self.key[64] & 1)];
+                compressed.extend(&self.key[1..33]);
+                compressed
+            }
+            _ => self.key.clone(),
+        }
+    }
+}
+
+/// Splits the varint encoded multicodec code off a multicodec prefixed key.
+pub fn split_multicodec_prefix(bytes: &[u8]) -> Result<(u64, &[u8]), DidDocumentBuilderError> {
+    let mut code = 0u64;
+    // Multicodec codes are at most 9 bytes long when varint encoded
+    for (i, byte) in bytes.iter().take(9).enumerate() {
+        code |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((code, &bytes[i + 1..]));
+        }
+    }
+    Err(DidDocumentBuilderError::InvalidInput(
+        "Invalid multicodec key prefix".to_string(),
+    ))
+}
+
+fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // did:key test vectors, https://w3c-ccg.github.io/did-method-key/#test-vectors
+    const ED25519_BASE58: &str = "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u";
+    const ED25519_MULTIBASE: &str = "z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH";
+    const ED25519_JWK: &str =
+        r#"{"kty":"OKP","crv":"Ed25519","x":"lJZrfAjkBXdfjebMHEUI9usidAPhAlssitLXR3OYxbI"}"#;
+    const P256_MULTIBASE: &str = "zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169";
+    const P256_JWK: &str = r#"{
+        "kty": "EC",
+        "crv": "P-256",
+        "x": "fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI",
+        "y": "hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"
+    }"#;
+
+    #[test]
+    fn test_multicodec_prefix_round_trip() {
+        for key_type in [
+            KeyType::Ed25519,
+            KeyType::X25519,
+            KeyType::P256,
+            KeyType::Secp256k1,
+        ] {
+            let mut bytes = key_type.multicodec_prefix();
+            bytes.push(0xff);
+            let (code, rest) = split_multicodec_prefix(&bytes).unwrap();
+            assert_eq!(KeyType::from_multicodec(code), Some(key_type));
+            assert_eq!(rest, &[0xff][..]);
+        }
+        assert_eq!(KeyType::Ed25519.multicodec_prefix(), vec![0xed, 0x01]);
+        assert_eq!(KeyType::P256.multicodec_prefix(), vec![0x80, 0x24]);
+    }
+
+    #[test]
+    fn test_key_type_from_verification_method_type() {
+        assert_eq!(
+            KeyType::from_verification_method_type("EcdsaSecp256r1VerificationKey2019"),
+            Some(KeyType::P256)
+        );
+        assert_eq!(
+            KeyType::from_verification_method_type("EcdsaSecp256k1VerificationKey2019"),
+            Some(KeyType::Secp256k1)
+        );
+        assert_eq!(
+            KeyType::from_verification_method_type("JsonWebKey2020"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ed25519_representations() {
+        let from_base58 = PublicKey::from_base58(KeyType::Ed25519, ED25519_BASE58).unwrap();
+        let from_multibase = PublicKey::from_multibase(
+            &Multibase::new(ED25519_MULTIBASE.to_string()).unwrap(),
+            None,
+        )
+        .unwrap();
+        let from_jwk = PublicKey::from_jwk(&JsonWebKey::new(ED25519_JWK).unwrap()).unwrap();
+
+        assert_eq!(from_base58, from_multibase);
+        assert_eq!(from_base58, from_jwk);
+        assert_eq!(from_base58.to_multibase(), ED25519_MULTIBASE);
+        assert_eq!(from_jwk.to_base58(), ED25519_BASE58);
+        assert_eq!(
+            from_multibase.to_jwk().unwrap(),
+            JsonWebKey::new(ED25519_JWK).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_p256_jwk_to_multibase() {
+        let key = PublicKey::from_jwk(&JsonWebKey::new(P256_JWK).unwrap()).unwrap();
+        assert_eq!(key.key_type(), KeyType::P256);
+        assert_eq!(key.key().len(), 65);
+        assert_eq!(key.to_jwk().unwrap(), JsonWebKey::new(P256_JWK).unwrap());
+        assert_eq!(key.to_multibase(), P256_MULTIBASE);
+
+        let compressed =
+            PublicKey::from_multibase(&Multibase::new(P256_MULTIBASE.to_string()).unwrap(), None)
+                .unwrap();
+        assert_eq!(compressed.key_type(), KeyType::P256);
+        assert_eq!(compressed.key().len(), 33);
+        assert!(compressed.to_jwk().is_err());
+    }
+
+    #[test]
+    fn test_untyped_multibase_key() {
+        let multibase = Multibase::new(format!("z{}", ED25519_BASE58)).unwrap();
+
+        assert!(PublicKey::from_multibase(&multibase, None).is_err());
+        assert_eq!(
+            PublicKey::from_multibase(&multibase, Some(KeyType::Ed25519))
+                .unwrap()
+                .to_base58(),
+            ED25519_BASE58
+        );
+    }
+
+    #[test]
+    fn test_mismatched_multicodec_key_type() {
+        let multibase = Multibase::new(ED25519_MULTIBASE.to_string()).unwrap();
+
+        assert!(PublicKey::from_multibase(&multibase, Some(KeyType::Ed25519)).is_ok());
+        assert!(PublicKey::from_multibase(&multibase, Some(KeyType::X25519)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert!(PublicKey::new(KeyType::X25519, vec![0; 31]).is_err());
+        assert!(PublicKey::new(KeyType::Secp256k1, vec![0x04; 33]).is_err());
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
+
+use did_parser::{Did, DidUrl};
+use serde_json::Value;
+
+use super::{
+    did_doc::DidDocument,
+    references::VERIFICATION_RELATIONSHIPS,
+    utils::OneOrList,
+    verification_method::{VerificationMethod, VerificationMethodKind},
+};
+
+pub const DID_V1_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+
+// https://www.w3.org/TR/did-core/#representations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Representation {
+    Json,
+    JsonLd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The document violates DID Core and should be rejected.
+    Error,
+    /// The document is valid, but likely not what its author intended.
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    DuplicateVerificationMethodId(String),
+    DuplicateServiceId(String),
+    DanglingReference {
+        relationship: &'static str,
+        reference: String,
+    },
+    ForeignController {
+        verification_method: String,
+        controller: String,
+    },
+    MissingContext,
+    InvalidContext(String),
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::ForeignController { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateVerificationMethodId(id) => {
+                write!(f, "Duplicate verification method id: {}", id)
+            }
+            ValidationIssue::DuplicateServiceId(id) => write!(f, "Duplicate service id: {}", id),
+            ValidationIssue::DanglingReference {
+                relationship,
+                reference,
+            } => write!(
+                f,
+                "Reference {} in {} does not match any verification method",
+                reference, relationship
+            ),
+            ValidationIssue::ForeignController {
+                verification_method,
+                controller,
+            } => write!(
+                f,
+                "Verification method {} is controlled by {}, which is neither the DID subject nor \
+                 one of its controllers",
+                verification_method, controller
+            ),
+            ValidationIssue::MissingContext => write!(f, "Missing @context"),
+            ValidationIssue::InvalidContext(reason) => write!(f, "Invalid @context: {}", reason),
+        }
+    }
+}
+
+/// Outcome of validating a [`DidDocument`] against the rules of
+/// [DID Core](https://www.w3.org/TR/did-core/).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn issues(&self) -> &[ValidationIssue] {
+        self.issues.as_ref()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Warning)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    fn push(&mut self, issue: ValidationIssue) {
+        self.issues.push(issue);
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let issues: Vec<String> = self.issues.iter().map(|issue| issue.to_string()).collect();
+        write!(f, "{}", issues.join("; "))
+    }
+}
+
+pub(crate) fn validate<E>(
+    did_doc: &DidDocument<E>,
+    representation: Representation,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let did = did_doc.id();
+
+    let relationships = [
+        did_doc.authentication(),
+        did_doc.assertion_method(),
+        did_doc.key_agreement(),
+        did_doc.capability_invocation(),
+        did_doc.capability_delegation(),
+    ];
+
+    // Verification methods may be embedded in verification relationships as well.
+    let embedded_methods = relationships.into_iter().flat_map(|methods| {
+        methods.iter().filter_map(|method| match method {
+            VerificationMethodKind::Resolved(method) => Some(method),
+            VerificationMethodKind::Resolvable(_) => None,
+        })
+    });
+    let methods: Vec<&VerificationMethod> = did_doc
+        .verification_method()
+        .iter()
+        .chain(embedded_methods)
+        .collect();
+
+    let mut method_ids = HashSet::new();
+    for method in methods.iter() {
+        let id = absolute_did_url(did, method.id());
+        if !method_ids.insert(id.clone()) {
+            report.push(ValidationIssue::DuplicateVerificationMethodId(id));
+        }
+    }
+
+    for (relationship, methods) in VERIFICATION_RELATIONSHIPS.into_iter().zip(relationships) {
+        for method in methods {
+            let VerificationMethodKind::Resolvable(reference) = method else {
+                continue;
+            };
+            // References to other DID subjects can only be checked by resolving them.
+            if reference
+                .did()
+                .map_or(false, |reference_did| reference_did != did.did())
+            {
+                continue;
+            }
+            if !method_ids.contains(&absolute_did_url(did, reference)) {
+                report.push(ValidationIssue::DanglingReference {
+                    relationship,
+                    reference: reference.to_string(),
+                });
+            }
+        }
+    }
+
+    let controllers: Vec<&Did> = match did_doc.controller() {
+        Some(OneOrList::One(controller)) => vec![controller],
+        Some(OneOrList::List(controllers)) => controllers.iter().collect(),
+        None => vec![],
+    };
+    for method in methods {
+        if method.controller() != did && !controllers.contains(&method.controller()) {
+            report.push(ValidationIssue::ForeignController {
+                verification_method: method.id().to_string(),
+                controller: method.controller().to_string(),
+            });
+        }
+    }
+
+    let mut service_ids = HashSet::new();
+    for service in did_doc.service() {
+        // Relative service ids are resolved against the DID subject like verification method ids.
+        let id = match DidUrl::parse(service.id().to_string()) {
+            Ok(did_url) => absolute_did_url(did, &did_url),
+            Err(_) => service.id().to_string(),
+        };
+        if !service_ids.insert(id.clone()) {
+            report.push(ValidationIssue::DuplicateServiceId(id));
+        }
+    }
+
+    if representation == Representation::JsonLd {
+        if let Some(issue) = validate_context(did_doc.extra_field("@context")) {
+            report.push(issue);
+        }
+    }
+
+    report
+}
+
+// https://www.w3.org/TR/did-core/#json-ld
+fn validate_context(context: Option<&Value>) -> Option<ValidationIssue> {
+    let first = match context {
+        None => return Some(ValidationIssue::MissingContext),
+        Some(Value::String(context)) => context.as_str(),
+        Some(Value::Array(contexts)) => {
+            if contexts
+                .iter()
+                .any(|context| !context.is_string() && !context.is_object())
+            {
+                return Some(ValidationIssue::InvalidContext(
+                    "entries must be strings or maps".to_string(),
+                ));
+            }
+            match contexts.first().and_then(Value::as_str) {
+                Some(first) => first,
+                None => return Some(ValidationIssue::MissingContext),
+            }
+        }
+        Some(_) => {
+            return Some(ValidationIssue::InvalidContext(
+                "must be a string or a list".to_string(),
+            ))
+        }
+    };
+
+    if first != DID_V1_CONTEXT {
+        return Some(ValidationIssue::InvalidContext(format!(
+            "first entry must be {}, found {}",
+            DID_V1_CONTEXT, first
+        )));
+    }
+    None
+}
+
+fn absolute_did_url(did: &Did, did_url: &DidUrl) -> String {
+    match did_url.did() {
+        Some(_) => did_url.did_url().to_string(),
+        None => format!("{}{}", did.did(), did_url.did_url()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn did_doc(value: Value) -> DidDocument<HashMap<String, Value>> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_valid_did_doc() {
+        let did_doc = did_doc(json!({
+            "@context": [DID_V1_CONTEXT, "https://w3id.org/security/suites/ed25519-2018/v1"],
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "controller": "did:example:123",
+                "publicKeyBase58": "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u"
+            }],
+            "authentication": ["did:example:123#key-1", "did:example:other#key-1"],
+            "keyAgreement": [{
+                "id": "did:example:123#key-2",
+                "type": "X25519KeyAgreementKey2019",
+                "controller": "did:example:123",
+                "publicKeyBase58": "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr"
+            }],
+            "assertionMethod": ["#key-2"]
+        }));
+
+        let report = validate(&did_doc, Representation::JsonLd);
+        assert!(report.is_valid(), "{}", report);
+        assert!(report.issues().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_did_doc() {
+        let did_doc = did_doc(json!({
+            "id": "did:example:123",
+            "controller": "did:example:controller",
+            "verificationMethod": [
+                {
+                    "id": "#key-1",
+                    "type": "Ed25519VerificationKey2018",
+                    "controller": "did:example:controller",
+                    "publicKeyBase58": "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u"
+                },
+                {
+                    "id": "did:example:123#key-1",
+                    "type": "Ed25519VerificationKey2018",
+                    "controller": "did:example:someone",
+                    "publicKeyBase58": "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u"
+                }
+            ],
+            "keyAgreement": ["#key-2"],
+            "service": [
+                {
+                    "id": "did:example:123#didcomm",
+                    "type": "DIDCommMessaging",
+                    "serviceEndpoint": "https://example.com"
+                },
+                {
+                    "id": "#didcomm",
+                    "type": "DIDCommMessaging",
+                    "serviceEndpoint": "https://example.org"
+                }
+            ]
+        }));
+
+        let report = validate(&did_doc, Representation::JsonLd);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors().cloned().collect::<Vec<_>>(),
+            vec![
+                ValidationIssue::DuplicateVerificationMethodId("did:example:123#key-1".to_string()),
+                ValidationIssue::DanglingReference {
+                    relationship: "keyAgreement",
+                    reference: "#key-2".to_string()
+                },
+                ValidationIssue::DuplicateServiceId("did:example:123#didcomm".to_string()),
+                ValidationIssue::MissingContext,
+            ]
+        );
+        assert_eq!(
+            report.warnings().cloned().collect::<Vec<_>>(),
+            vec![ValidationIssue::ForeignController {
+                verification_method: "did:example:123#key-1".to_string(),
+                controller: "did:example:someone".to_string()
+            }]
+        );
+
+        // Plain JSON documents need no @context.
+        assert!(!validate(&did_doc, Representation::Json)
+            .issues()
+            .contains(&ValidationIssue::MissingContext));
+    }
+
+    #[test]
+    fn test_invalid_context() {
+        assert_eq!(
+            validate_context(Some(&json!(["https://w3id.org/did/v1"]))),
+            Some(ValidationIssue::InvalidContext(
+                "first entry must be https://www.w3.org/ns/did/v1, found https://w3id.org/did/v1"
+                    .to_string()
+            ))
+        );
+        assert!(validate_context(Some(&json!(42))).is_some());
+        assert!(validate_context(Some(&json!(DID_V1_CONTEXT))).is_none());
+    }
+}
@@ -4,7 +4,13 @@ use did_parser::{Did, DidUrl};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::types::{jsonwebkey::JsonWebKey, multibase::Multibase};
+use crate::error::DidDocumentBuilderError;
+
+use super::types::{
+    jsonwebkey::JsonWebKey,
+    multibase::Multibase,
+    public_key::{KeyType, PublicKey},
+};
 
 // Either a set of verification methods maps or DID URLs
 // https://www.w3.org/TR/did-core/#did-document-properties
@@ -66,6 +72,29 @@ impl VerificationMethod {
     pub fn extra_field(&self, key: &str) -> Option<&Value> {
         self.extra.get(key)
     }
+
+    /// Decodes the key of the method from its `publicKeyMultibase`, `publicKeyJwk` or
+    /// `publicKeyBase58` property.
+    pub fn public_key(&self) -> Result<PublicKey, DidDocumentBuilderError> {
+        let key_type = KeyType::from_verification_method_type(&self.verification_method_type);
+
+        if let Some(multibase) = &self.public_key_multibase {
+            return PublicKey::from_multibase(multibase, key_type);
+        }
+        if let Some(jwk) = &self.public_key_jwk {
+            return PublicKey::from_jwk(jwk);
+        }
+        match (self.extra_field("publicKeyBase58"), key_type) {
+            (Some(Value::String(base58)), Some(key_type)) => {
+                PublicKey::from_base58(key_type, base58)
+            }
+            (Some(_), None) => Err(DidDocumentBuilderError::InvalidInput(format!(
+                "Unsupported verification method type {} for publicKeyBase58",
+                self.verification_method_type
+            ))),
+            _ => Err(DidDocumentBuilderError::MissingField("publicKey")),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -250,4 +279,33 @@ mod tests {
         assert_eq!(vm.public_key_multibase().unwrap(), &public_key_multibase);
         assert_eq!(vm.extra_field(&extra_key).unwrap(), &extra_value);
     }
+
+    #[test]
+    fn test_verification_method_public_key() {
+        let base58 = "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u";
+        let vm_base58 = VerificationMethod::builder(
+            create_valid_did_url(),
+            create_valid_did(),
+            create_valid_verification_key_type(),
+        )
+        .add_extra_field(
+            "publicKeyBase58".to_string(),
+            Value::String(base58.to_string()),
+        )
+        .build();
+        let vm_multibase = VerificationMethod::builder(
+            create_valid_did_url(),
+            create_valid_did(),
+            "Multikey".to_string(),
+        )
+        .add_public_key_multibase(
+            Multibase::new("z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH".to_string()).unwrap(),
+        )
+        .build();
+
+        let public_key = vm_base58.public_key().unwrap();
+        assert_eq!(public_key.key_type(), KeyType::Ed25519);
+        assert_eq!(public_key.to_base58(), base58);
+        assert_eq!(vm_multibase.public_key().unwrap(), public_key);
+    }
 }
@@ -0,0 +1,2 @@
+mod dereferencer;
+mod utils;
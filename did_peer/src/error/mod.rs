@@ -0,0 +1,28 @@
+mod resolution;
+
+use did_resolver::{did_doc::error::DidDocumentBuilderError, did_parser::ParseError};
+use did_resolver_key::error::DidKeyError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum DidPeerError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("DID method not supported: {0}")]
+    MethodNotSupported(String),
+    #[error("Representation not supported: {0}")]
+    RepresentationNotSupported(String),
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+    #[error("Unsupported numalgo: {0}")]
+    UnsupportedNumalgo(char),
+    #[error("Key error: {0}")]
+    KeyError(#[from] DidKeyError),
+    #[error("DID parsing error: {0}")]
+    DidParserError(#[from] ParseError),
+    #[error("DID Document Builder Error: {0}")]
+    DidDocumentBuilderError(#[from] DidDocumentBuilderError),
+    #[error("JSON parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
@@ -0,0 +1,7 @@
+pub extern crate did_resolver;
+
+pub mod dereferencing;
+pub mod error;
+mod numalgos;
+pub mod peer_did;
+pub mod resolution;
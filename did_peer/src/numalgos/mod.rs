@@ -0,0 +1,3 @@
+pub(crate) mod numalgo0;
+pub(crate) mod numalgo2;
+pub(crate) mod numalgo4;
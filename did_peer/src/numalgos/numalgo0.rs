@@ -0,0 +1,33 @@
+use did_resolver::{did_doc::schema::did_doc::DidDocument, did_parser::Did};
+use did_resolver_key::{key::MulticodecKey, resolution::did_document_from_key};
+
+use crate::error::DidPeerError;
+
+pub(crate) fn generate(key: &MulticodecKey) -> String {
+    format!("did:peer:0{}", key.to_multibase())
+}
+
+pub(crate) fn resolve<E>(did: &Did) -> Result<DidDocument<E>, DidPeerError> {
+    let key = MulticodecKey::from_multibase(&did.id()[1..])?;
+    Ok(did_document_from_key(did, &key)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_numalgo0() {
+        let did =
+            Did::parse("did:peer:0z6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V".to_string())
+                .unwrap();
+        let did_document: DidDocument<()> = resolve(&did).unwrap();
+
+        assert_eq!(did_document.id(), &did);
+        assert_eq!(
+            did_document.verification_method()[0].id().to_string(),
+            "did:peer:0z6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V#z6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V"
+        );
+        assert_eq!(did_document.key_agreement().len(), 1);
+    }
+}
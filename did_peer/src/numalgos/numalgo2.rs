@@ -0,0 +1,223 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use did_resolver::{
+    did_doc::schema::{did_doc::DidDocument, service::Service},
+    did_parser::{Did, DidUrl},
+};
+use did_resolver_key::{key::MulticodecKey, resolution::verification_method_from_key};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::DidPeerError,
+    peer_did::{KeyPurpose, PurposedKey},
+};
+
+const SERVICE_PREFIX: char = 'S';
+
+// https://identity.foundation/peer-did-method-spec/#generating-a-didpeer2
+const ABBREVIATIONS: [(&str, &str); 4] = [
+    ("type", "t"),
+    ("serviceEndpoint", "s"),
+    ("routingKeys", "r"),
+    ("accept", "a"),
+];
+const SERVICE_TYPE_ABBREVIATIONS: [(&str, &str); 1] = [("DIDCommMessaging", "dm")];
+
+pub(crate) fn generate<E: Serialize>(
+    keys: &[PurposedKey],
+    services: &[Service<E>],
+) -> Result<String, DidPeerError> {
+    let mut did = "did:peer:2".to_string();
+    for key in keys {
+        did.push('.');
+        did.push(key.purpose().to_char());
+        did.push_str(&key.key().to_multibase());
+    }
+    for service in services {
+        let service = abbreviate_service(serde_json::to_value(service)?);
+        did.push('.');
+        did.push(SERVICE_PREFIX);
+        did.push_str(&URL_SAFE_NO_PAD.encode(serde_json::to_vec(&service)?));
+    }
+    Ok(did)
+}
+
+pub(crate) fn resolve<E: Default + DeserializeOwned>(
+    did: &Did,
+) -> Result<DidDocument<E>, DidPeerError> {
+    let elements = did.id()[1..]
+        .strip_prefix('.')
+        .ok_or_else(|| DidPeerError::InvalidDid(did.did().to_string()))?;
+
+    let mut builder = DidDocument::builder(did.clone());
+    let mut key_count = 0;
+    let mut service_count = 0;
+    for element in elements.split('.') {
+        let mut chars = element.chars();
+        let prefix = chars
+            .next()
+            .ok_or_else(|| DidPeerError::InvalidDid(did.did().to_string()))?;
+        let value = chars.as_str();
+
+        if prefix == SERVICE_PREFIX {
+            let service = URL_SAFE_NO_PAD.decode(value).map_err(|err| {
+                DidPeerError::InvalidDid(format!("Invalid service encoding: {}", err))
+            })?;
+            let service = expand_service(serde_json::from_slice(&service)?, did, service_count)?;
+            builder = builder.add_service(serde_json::from_value(service)?);
+            service_count += 1;
+            continue;
+        }
+
+        key_count += 1;
+        let id = DidUrl::parse(format!("{}#key-{}", did, key_count))?;
+        let key = MulticodecKey::from_multibase(value)?;
+        builder =
+            builder.add_verification_method(verification_method_from_key(id.clone(), did, &key)?);
+        builder = match KeyPurpose::from_char(prefix)? {
+            KeyPurpose::Assertion => builder.add_assertion_method_reference(id),
+            KeyPurpose::Encryption => builder.add_key_agreement_refrence(id),
+            KeyPurpose::Verification => builder.add_authentication_reference(id),
+            KeyPurpose::CapabilityInvocation => builder.add_capability_invocation_refrence(id),
+            KeyPurpose::CapabilityDelegation => builder.add_capability_delegation_refrence(id),
+        };
+    }
+
+    Ok(builder.build())
+}
+
+fn abbreviate_service(service: Value) -> Value {
+    let Value::Object(service) = service else {
+        return service;
+    };
+
+    service
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match (key.as_str(), value) {
+                // A single service type is encoded as a string
+                ("type", Value::Array(mut types)) if types.len() == 1 => {
+                    abbreviate_service_type(types.remove(0))
+                }
+                ("type", service_type) => abbreviate_service_type(service_type),
+                ("serviceEndpoint", endpoint) => abbreviate_service(endpoint),
+                (_, value) => value,
+            };
+            (replace(&key, &ABBREVIATIONS, false), value)
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+fn abbreviate_service_type(service_type: Value) -> Value {
+    match service_type {
+        Value::String(service_type) => {
+            replace(&service_type, &SERVICE_TYPE_ABBREVIATIONS, false).into()
+        }
+        service_type => service_type,
+    }
+}
+
+fn expand_service(service: Value, did: &Did, index: usize) -> Result<Value, DidPeerError> {
+    let Value::Object(service) = service else {
+        return Err(DidPeerError::InvalidDid(format!(
+            "Service is not an object: {}",
+            service
+        )));
+    };
+
+    let mut expanded = Map::new();
+    for (key, value) in service {
+        let key = replace(&key, &ABBREVIATIONS, true);
+        let value = match (key.as_str(), value) {
+            ("type", Value::String(service_type)) => {
+                replace(&service_type, &SERVICE_TYPE_ABBREVIATIONS, true).into()
+            }
+            // DIDComm v2 endpoints are objects holding the uri along with accept and routing keys,
+            // which are lifted to the service itself
+            ("serviceEndpoint", Value::Object(endpoint)) => {
+                let mut uri = Value::Null;
+                for (key, value) in endpoint {
+                    match replace(&key, &ABBREVIATIONS, true) {
+                        key if key == "uri" => uri = value,
+                        key => {
+                            expanded.entry(key).or_insert(value);
+                        }
+                    }
+                }
+                uri
+            }
+            (_, value) => value,
+        };
+        expanded.insert(key, value);
+    }
+
+    let id = match expanded.get("id").and_then(Value::as_str) {
+        Some(id) if id.starts_with('#') => format!("{}{}", did, id),
+        Some(id) => id.to_string(),
+        None if index == 0 => format!("{}#service", did),
+        None => format!("{}#service-{}", did, index),
+    };
+    expanded.insert("id".to_string(), id.into());
+
+    Ok(expanded.into())
+}
+
+fn replace(value: &str, abbreviations: &[(&str, &str)], expand: bool) -> String {
+    abbreviations
+        .iter()
+        .find_map(|(full, short)| match expand {
+            true if value == *short => Some(full.to_string()),
+            false if value == *full => Some(short.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_abbreviate_service() {
+        let service = json!({
+            "id": "#didcomm",
+            "type": ["DIDCommMessaging"],
+            "serviceEndpoint": "https://example.com/endpoint",
+            "routingKeys": ["did:example:somemediator#somekey"],
+            "accept": ["didcomm/v2"]
+        });
+        let abbreviated = json!({
+            "id": "#didcomm",
+            "t": "dm",
+            "s": "https://example.com/endpoint",
+            "r": ["did:example:somemediator#somekey"],
+            "a": ["didcomm/v2"]
+        });
+        assert_eq!(abbreviate_service(service), abbreviated);
+    }
+
+    #[test]
+    fn test_expand_service() {
+        let did =
+            Did::parse("did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc".to_string())
+                .unwrap();
+        let service = json!({
+            "t": "dm",
+            "s": {
+                "uri": "https://example.com/endpoint",
+                "r": ["did:example:somemediator#somekey"],
+                "a": ["didcomm/v2"]
+            }
+        });
+        let expanded = json!({
+            "id": format!("{}#service-1", did),
+            "type": "DIDCommMessaging",
+            "serviceEndpoint": "https://example.com/endpoint",
+            "routingKeys": ["did:example:somemediator#somekey"],
+            "accept": ["didcomm/v2"]
+        });
+        assert_eq!(expand_service(service, &did, 1).unwrap(), expanded);
+    }
+}
@@ -0,0 +1,129 @@
+use did_resolver::{
+    did_doc::schema::{
+        did_doc::DidDocument,
+        references::{map_references, VERIFICATION_RELATIONSHIPS},
+    },
+    did_parser::Did,
+};
+use multibase::Base;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::DidPeerError;
+
+// Varint encoded multicodec of JSON and multihash prefix of a SHA2-256 digest
+const MULTICODEC_JSON: [u8; 2] = [0x80, 0x04];
+const MULTIHASH_SHA2_256: [u8; 2] = [0x12, 0x20];
+
+// https://identity.foundation/peer-did-method-spec/#method-4-short-form-and-long-form
+pub(crate) fn generate<E: Serialize>(document: &DidDocument<E>) -> Result<String, DidPeerError> {
+    let id = document.id().to_string();
+    let Value::Object(mut input_document) = serde_json::to_value(document)? else {
+        return Err(DidPeerError::InvalidDid(
+            "DID document is not an object".to_string(),
+        ));
+    };
+    input_document.remove("id");
+    map_references(&mut input_document, |reference| {
+        reference
+            .strip_prefix(&id)
+            .filter(|reference| reference.starts_with('#'))
+            .map(str::to_string)
+    });
+    for method in verification_methods(&mut input_document) {
+        if method.get("controller").and_then(Value::as_str) == Some(id.as_str()) {
+            method.remove("controller");
+        }
+    }
+
+    let mut encoded_document = MULTICODEC_JSON.to_vec();
+    encoded_document.extend(serde_json::to_vec(&input_document)?);
+    let encoded_document = multibase::encode(Base::Base58Btc, encoded_document);
+
+    Ok(format!(
+        "did:peer:4{}:{}",
+        hash(&encoded_document),
+        encoded_document
+    ))
+}
+
+pub(crate) fn short_form(did: &Did) -> String {
+    match did.id().split_once(':') {
+        Some((short_form, _)) => format!("did:peer:{}", short_form),
+        None => did.did().to_string(),
+    }
+}
+
+pub(crate) fn resolve<E: Default + DeserializeOwned>(
+    did: &Did,
+) -> Result<DidDocument<E>, DidPeerError> {
+    let (hash_value, encoded_document) = did.id()[1..].split_once(':').ok_or_else(|| {
+        DidPeerError::NotFound(format!(
+            "The short form {} can only be resolved from its long form",
+            did
+        ))
+    })?;
+    if hash_value != hash(encoded_document) {
+        return Err(DidPeerError::InvalidDid(format!(
+            "Hash does not match the encoded document of {}",
+            did
+        )));
+    }
+
+    let (base, decoded_document) = multibase::decode(encoded_document)
+        .map_err(|err| DidPeerError::InvalidDid(format!("Invalid document encoding: {}", err)))?;
+    let input_document = match decoded_document.strip_prefix(&MULTICODEC_JSON) {
+        Some(input_document) if base == Base::Base58Btc => input_document,
+        _ => {
+            return Err(DidPeerError::InvalidDid(
+                "Document is not base58btc encoded JSON".to_string(),
+            ))
+        }
+    };
+    let Value::Object(mut document) = serde_json::from_slice(input_document)? else {
+        return Err(DidPeerError::InvalidDid(
+            "Input document is not an object".to_string(),
+        ));
+    };
+
+    let did_string = did.to_string();
+    map_references(&mut document, |reference| {
+        reference
+            .starts_with('#')
+            .then(|| format!("{}{}", did_string, reference))
+    });
+    for method in verification_methods(&mut document) {
+        method
+            .entry("controller")
+            .or_insert_with(|| did_string.clone().into());
+    }
+
+    let mut also_known_as = match document.remove("alsoKnownAs") {
+        Some(Value::Array(also_known_as)) => also_known_as,
+        _ => Vec::new(),
+    };
+    also_known_as.push(short_form(did).into());
+    document.insert("alsoKnownAs".to_string(), also_known_as.into());
+    document.insert("id".to_string(), did_string.into());
+
+    Ok(serde_json::from_value(document.into())?)
+}
+
+fn hash(encoded_document: &str) -> String {
+    let mut multihash = MULTIHASH_SHA2_256.to_vec();
+    multihash.extend(Sha256::digest(encoded_document.as_bytes()));
+    multibase::encode(Base::Base58Btc, multihash)
+}
+
+fn verification_methods(document: &mut Map<String, Value>) -> Vec<&mut Map<String, Value>> {
+    document
+        .iter_mut()
+        .filter(|(key, _)| {
+            *key == "verificationMethod" || VERIFICATION_RELATIONSHIPS.contains(&key.as_str())
+        })
+        .filter_map(|(_, methods)| methods.as_array_mut())
+        .flatten()
+        .filter_map(Value::as_object_mut)
+        .collect()
+}
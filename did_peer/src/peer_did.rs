@@ -0,0 +1,174 @@
+use std::fmt::{self, Display, Formatter};
+
+use did_resolver::{
+    did_doc::schema::{did_doc::DidDocument, service::Service},
+    did_parser::Did,
+};
+use did_resolver_key::key::MulticodecKey;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::DidPeerError,
+    numalgos::{numalgo0, numalgo2, numalgo4},
+};
+
+// https://identity.foundation/peer-did-method-spec/#method-specific-identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numalgo {
+    InceptionKeyWithoutDoc,
+    MultipleInceptionKeys,
+    ShortFormAndLongForm,
+}
+
+impl Numalgo {
+    fn from_char(c: char) -> Result<Self, DidPeerError> {
+        match c {
+            '0' => Ok(Numalgo::InceptionKeyWithoutDoc),
+            '2' => Ok(Numalgo::MultipleInceptionKeys),
+            '4' => Ok(Numalgo::ShortFormAndLongForm),
+            c => Err(DidPeerError::UnsupportedNumalgo(c)),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Numalgo::InceptionKeyWithoutDoc => '0',
+            Numalgo::MultipleInceptionKeys => '2',
+            Numalgo::ShortFormAndLongForm => '4',
+        }
+    }
+}
+
+/// Verification relationship of a key encoded in a numalgo 2 peer DID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPurpose {
+    Assertion,
+    Encryption,
+    Verification,
+    CapabilityInvocation,
+    CapabilityDelegation,
+}
+
+impl KeyPurpose {
+    pub(crate) fn from_char(c: char) -> Result<Self, DidPeerError> {
+        match c {
+            'A' => Ok(KeyPurpose::Assertion),
+            'E' => Ok(KeyPurpose::Encryption),
+            'V' => Ok(KeyPurpose::Verification),
+            'I' => Ok(KeyPurpose::CapabilityInvocation),
+            'D' => Ok(KeyPurpose::CapabilityDelegation),
+            c => Err(DidPeerError::InvalidDid(format!(
+                "Unknown purpose code: {}",
+                c
+            ))),
+        }
+    }
+
+    pub(crate) fn to_char(self) -> char {
+        match self {
+            KeyPurpose::Assertion => 'A',
+            KeyPurpose::Encryption => 'E',
+            KeyPurpose::Verification => 'V',
+            KeyPurpose::CapabilityInvocation => 'I',
+            KeyPurpose::CapabilityDelegation => 'D',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurposedKey {
+    purpose: KeyPurpose,
+    key: MulticodecKey,
+}
+
+impl PurposedKey {
+    pub fn new(purpose: KeyPurpose, key: MulticodecKey) -> Self {
+        Self { purpose, key }
+    }
+
+    pub fn purpose(&self) -> KeyPurpose {
+        self.purpose
+    }
+
+    pub fn key(&self) -> &MulticodecKey {
+        &self.key
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerDid {
+    did: Did,
+    numalgo: Numalgo,
+}
+
+impl PeerDid {
+    pub fn parse(did: String) -> Result<Self, DidPeerError> {
+        let did = Did::parse(did)?;
+        if did.method() != "peer" {
+            return Err(DidPeerError::MethodNotSupported(did.method().to_string()));
+        }
+        let numalgo = did
+            .id()
+            .chars()
+            .next()
+            .ok_or_else(|| DidPeerError::InvalidDid(did.did().to_string()))?;
+        let numalgo = Numalgo::from_char(numalgo)?;
+        Ok(Self { did, numalgo })
+    }
+
+    /// Peer DID standing for a single inception key, resolving like the did:key of that key.
+    pub fn generate_numalgo0(key: &MulticodecKey) -> Result<Self, DidPeerError> {
+        Self::parse(numalgo0::generate(key))
+    }
+
+    /// Peer DID encoding every key, along with its purpose, and every service of the DID document.
+    pub fn generate_numalgo2<E: Serialize>(
+        keys: &[PurposedKey],
+        services: &[Service<E>],
+    ) -> Result<Self, DidPeerError> {
+        Self::parse(numalgo2::generate(keys, services)?)
+    }
+
+    /// Long form peer DID encoding the whole input document, whose id is ignored.
+    pub fn generate_numalgo4<E: Serialize>(
+        document: &DidDocument<E>,
+    ) -> Result<Self, DidPeerError> {
+        Self::parse(numalgo4::generate(document)?)
+    }
+
+    pub fn did(&self) -> &Did {
+        &self.did
+    }
+
+    pub fn numalgo(&self) -> Numalgo {
+        self.numalgo
+    }
+
+    /// Short form of a numalgo 4 peer DID, which the long form is known as once exchanged.
+    pub fn short_form(&self) -> Result<Self, DidPeerError> {
+        match self.numalgo {
+            Numalgo::ShortFormAndLongForm => Self::parse(numalgo4::short_form(&self.did)),
+            numalgo => Err(DidPeerError::InvalidDid(format!(
+                "Numalgo {} peer DIDs have no short form",
+                numalgo.to_char()
+            ))),
+        }
+    }
+
+    /// Expands the peer DID into its DID document, with service extra fields of type `E`.
+    pub fn to_did_document<E: Default + DeserializeOwned>(
+        &self,
+    ) -> Result<DidDocument<E>, DidPeerError> {
+        match self.numalgo {
+            Numalgo::InceptionKeyWithoutDoc => numalgo0::resolve(&self.did),
+            Numalgo::MultipleInceptionKeys => numalgo2::resolve(&self.did),
+            Numalgo::ShortFormAndLongForm => numalgo4::resolve(&self.did),
+        }
+    }
+}
+
+impl Display for PeerDid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.did)
+    }
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::Did,
+    error::GenericError,
+    shared_types::media_type::MediaType,
+    traits::resolvable::{
+        resolution_metadata::DidResolutionMetadata, resolution_options::DidResolutionOptions,
+        resolution_output::DidResolutionOutput, DidResolvable,
+    },
+};
+
+use crate::{error::DidPeerError, peer_did::PeerDid};
+
+/// Resolves numalgo 0, 2 and 4 peer DIDs, whose DID documents are encoded in the DIDs themselves.
+#[derive(Debug, Clone, Default)]
+pub struct PeerDidResolver;
+
+impl PeerDidResolver {
+    pub fn new() -> Self {
+        PeerDidResolver
+    }
+}
+
+#[async_trait]
+impl DidResolvable for PeerDidResolver {
+    type ExtraFields = ();
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions,
+    ) -> Result<DidResolutionOutput<Self::ExtraFields>, GenericError> {
+        if let Some(accept) = options.accept() {
+            if accept != &MediaType::DidJson {
+                return Err(Box::new(DidPeerError::RepresentationNotSupported(
+                    accept.to_string(),
+                )));
+            }
+        }
+
+        let did_document = PeerDid::parse(did.did().to_string())?.to_did_document()?;
+
+        let resolution_metadata = DidResolutionMetadata::builder()
+            .content_type(MediaType::DidJson.to_string())
+            .build();
+
+        Ok(DidResolutionOutput::builder(did_document)
+            .did_resolution_metadata(resolution_metadata)
+            .build())
+    }
+}
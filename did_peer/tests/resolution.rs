@@ -0,0 +1,193 @@
+use did_peer::error::DidPeerError;
+use did_peer::peer_did::{KeyPurpose, Numalgo, PeerDid, PurposedKey};
+use did_peer::resolution::PeerDidResolver;
+use did_resolver::did_doc::schema::{
+    did_doc::DidDocument, service::Service, verification_method::VerificationMethodKind,
+};
+use did_resolver::did_parser::{Did, DidUrl};
+use did_resolver::traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable};
+use did_resolver_key::key::MulticodecKey;
+use did_resolver_key::resolution::verification_method_from_key;
+use did_resolver_registry::ResolverRegistry;
+use serde_json::json;
+
+const DID_PEER_2: &str = "did:peer:2\
+    .Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc\
+    .Vz6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V\
+    .Vz6MkgoLTnTypo3tDRwCkZXSccTPHRLhF4ZnjhueYAFpEX6vg\
+    .SeyJ0IjoiZG0iLCJzIjoiaHR0cHM6Ly9leGFtcGxlLmNvbS9lbmRwb2ludCIsInIiOlsiZGlkOmV4YW1wbGU6c29tZW1lZGlhdG9yI3NvbWVrZXkiXSwiYSI6WyJkaWRjb21tL3YyIiwiZGlkY29tbS9haXAyO2Vudj1yZmM1ODciXX0";
+
+const ED25519_KEY: &str = "z6MkqRYqQiSgvZQdnBytw86Qbs2ZWUkGv22od935YF4s8M7V";
+const X25519_KEY: &str = "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc";
+
+fn reference(did_url: String) -> VerificationMethodKind {
+    VerificationMethodKind::Resolvable(DidUrl::parse(did_url).unwrap())
+}
+
+#[test]
+fn test_resolve_numalgo2() {
+    let peer_did = PeerDid::parse(DID_PEER_2.to_string()).unwrap();
+    assert_eq!(peer_did.numalgo(), Numalgo::MultipleInceptionKeys);
+
+    let did_document: DidDocument<()> = peer_did.to_did_document().unwrap();
+    let did_document = serde_json::to_value(did_document).unwrap();
+
+    assert_eq!(did_document["id"], DID_PEER_2);
+    assert_eq!(
+        did_document["verificationMethod"][0]["id"],
+        format!("{}#key-1", DID_PEER_2)
+    );
+    assert_eq!(
+        did_document["verificationMethod"][0]["type"],
+        "X25519KeyAgreementKey2020"
+    );
+    assert_eq!(
+        did_document["keyAgreement"],
+        json!([format!("{}#key-1", DID_PEER_2)])
+    );
+    assert_eq!(
+        did_document["authentication"],
+        json!([
+            format!("{}#key-2", DID_PEER_2),
+            format!("{}#key-3", DID_PEER_2)
+        ])
+    );
+    assert_eq!(
+        did_document["service"][0]["id"],
+        format!("{}#service", DID_PEER_2)
+    );
+    assert_eq!(did_document["service"][0]["type"], "DIDCommMessaging");
+    assert_eq!(
+        did_document["service"][0]["serviceEndpoint"],
+        "https://example.com/endpoint"
+    );
+}
+
+#[test]
+fn test_generate_numalgo2() {
+    let keys = vec![
+        PurposedKey::new(
+            KeyPurpose::Encryption,
+            MulticodecKey::from_multibase(X25519_KEY).unwrap(),
+        ),
+        PurposedKey::new(
+            KeyPurpose::Verification,
+            MulticodecKey::from_multibase(ED25519_KEY).unwrap(),
+        ),
+    ];
+    let service = Service::<()>::builder(
+        "#didcomm".parse().unwrap(),
+        "https://example.com/endpoint".try_into().unwrap(),
+        (),
+    )
+    .add_service_type("DIDCommMessaging".to_string())
+    .unwrap()
+    .build();
+
+    let peer_did = PeerDid::generate_numalgo2(&keys, &[service]).unwrap();
+    assert!(peer_did
+        .to_string()
+        .starts_with(&format!("did:peer:2.E{}.V{}.S", X25519_KEY, ED25519_KEY)));
+
+    let did_document: DidDocument<()> = peer_did.to_did_document().unwrap();
+    assert_eq!(did_document.verification_method().len(), 2);
+    assert_eq!(
+        did_document.key_agreement(),
+        &[reference(format!("{}#key-1", peer_did))]
+    );
+    assert_eq!(
+        did_document.authentication(),
+        &[reference(format!("{}#key-2", peer_did))]
+    );
+    assert_eq!(
+        did_document.service()[0].id().to_string(),
+        format!("{}#didcomm", peer_did)
+    );
+}
+
+#[test]
+fn test_generate_and_resolve_numalgo4() {
+    let did = Did::parse("did:example:123".to_string()).unwrap();
+    let key_id = DidUrl::parse("did:example:123#key-1".to_string()).unwrap();
+    let verification_method = verification_method_from_key(
+        key_id.clone(),
+        &did,
+        &MulticodecKey::from_multibase(ED25519_KEY).unwrap(),
+    )
+    .unwrap();
+    let input_document = DidDocument::<()>::builder(did)
+        .add_verification_method(verification_method)
+        .add_authentication_reference(key_id)
+        .build();
+
+    let peer_did = PeerDid::generate_numalgo4(&input_document).unwrap();
+    assert_eq!(peer_did.numalgo(), Numalgo::ShortFormAndLongForm);
+
+    let did_document: DidDocument<()> = peer_did.to_did_document().unwrap();
+    let short_form = peer_did.short_form().unwrap();
+    let key_id = format!("{}#key-1", peer_did);
+
+    assert_eq!(did_document.id(), peer_did.did());
+    assert_eq!(
+        did_document.also_known_as()[0].to_string(),
+        short_form.to_string()
+    );
+    assert_eq!(
+        did_document.verification_method()[0].id().to_string(),
+        key_id
+    );
+    assert_eq!(
+        did_document.verification_method()[0].controller(),
+        peer_did.did()
+    );
+    assert_eq!(did_document.authentication(), &[reference(key_id)]);
+
+    assert!(matches!(
+        short_form.to_did_document::<()>(),
+        Err(DidPeerError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_numalgo4_hash_mismatch() {
+    let did = Did::parse("did:example:123".to_string()).unwrap();
+    let peer_did = PeerDid::generate_numalgo4(&DidDocument::<()>::builder(did).build()).unwrap();
+    let tampered = peer_did
+        .to_string()
+        .replacen("did:peer:4z", "did:peer:4zz", 1);
+
+    assert!(matches!(
+        PeerDid::parse(tampered).unwrap().to_did_document::<()>(),
+        Err(DidPeerError::InvalidDid(_))
+    ));
+}
+
+#[test]
+fn test_unsupported_numalgo() {
+    assert!(matches!(
+        PeerDid::parse("did:peer:1zQmZMygzYqNwU6Uhmewx5Xepf2VLp5S4HLSwwgf2aiKZuwa".to_string()),
+        Err(DidPeerError::UnsupportedNumalgo('1'))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_through_registry() {
+    let mut registry = ResolverRegistry::new();
+    registry.register_resolver("peer".to_string(), Box::new(PeerDidResolver::new()));
+
+    let did = Did::parse(format!("did:peer:0{}", ED25519_KEY)).unwrap();
+    let output = registry
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().id(), &did);
+
+    let output = PeerDidResolver::new()
+        .resolve(
+            &Did::parse(DID_PEER_2.to_string()).unwrap(),
+            &DidResolutionOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().service().len(), 1);
+}
@@ -0,0 +1,31 @@
+use std::io::Cursor;
+
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::DidUrl,
+    error::GenericError,
+    traits::dereferenceable::{
+        dereferencing_options::DidDereferencingOptions,
+        dereferencing_output::DidDereferencingOutput, DidDereferenceable,
+    },
+};
+
+use crate::resolution::{DidIndyResolver, NymVersion};
+
+use super::utils::dereference_did_document;
+
+#[async_trait]
+impl DidDereferenceable for DidIndyResolver {
+    type Output = Cursor<Vec<u8>>;
+
+    async fn dereference(
+        &self,
+        did_url: &DidUrl,
+        _options: &DidDereferencingOptions,
+    ) -> Result<DidDereferencingOutput<Self::Output>, GenericError> {
+        let version = NymVersion::from_queries(&did_url.queries())?;
+        let resolution_output = self.resolve_version(&did_url.try_into()?, version).await?;
+
+        dereference_did_document(&resolution_output, did_url).map_err(|err| err.into())
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::Cursor;
+
+use did_resolver::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser::DidUrl,
+    shared_types::media_type::MediaType,
+    traits::{
+        dereferenceable::{
+            dereferencing_metadata::DidDereferencingMetadata,
+            dereferencing_output::DidDereferencingOutput,
+        },
+        resolvable::resolution_output::DidResolutionOutput,
+    },
+};
+
+use crate::error::DidIndyError;
+
+// Without a fragment, the DID URL dereferences to the whole document
+fn content_stream_from(
+    did_document: &DidDocument<()>,
+    did_url: &DidUrl,
+) -> Result<Cursor<Vec<u8>>, DidIndyError> {
+    let Some(fragment) = did_url.fragment() else {
+        return Ok(Cursor::new(serde_json::to_vec(did_document)?));
+    };
+
+    let fragment_string = format!("#{}", fragment);
+    let verification_method = did_document
+        .verification_method()
+        .iter()
+        .find(|vm| vm.id().did_url().ends_with(&fragment_string));
+    let service = did_document
+        .service()
+        .iter()
+        .find(|service| service.id().to_string().ends_with(&fragment_string));
+
+    let value = match (verification_method, service) {
+        (Some(verification_method), None) => serde_json::to_vec(verification_method)?,
+        (None, Some(service)) => serde_json::to_vec(service)?,
+        (None, None) => {
+            return Err(DidIndyError::NotFound(format!(
+                "Fragment '{}' not found in the DID document",
+                fragment
+            )));
+        }
+        (Some(_), Some(_)) => {
+            return Err(DidIndyError::InvalidDid(format!(
+                "Fragment '{}' is ambiguous",
+                fragment
+            )));
+        }
+    };
+    Ok(Cursor::new(value))
+}
+
+pub(crate) fn dereference_did_document(
+    resolution_output: &DidResolutionOutput<()>,
+    did_url: &DidUrl,
+) -> Result<DidDereferencingOutput<Cursor<Vec<u8>>>, DidIndyError> {
+    let content_stream = content_stream_from(resolution_output.did_document(), did_url)?;
+
+    let dereferencing_metadata = DidDereferencingMetadata::builder()
+        .content_type(MediaType::DidJson.to_string())
+        .build();
+
+    Ok(DidDereferencingOutput::builder(content_stream)
+        .content_metadata(resolution_output.did_document_metadata().clone())
+        .dereferencing_metadata(dereferencing_metadata)
+        .build())
+}
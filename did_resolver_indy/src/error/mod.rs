@@ -0,0 +1,37 @@
+mod resolution;
+
+use aries_vcx_core::errors::error::AriesVcxCoreError;
+use did_resolver::{did_doc::error::DidDocumentBuilderError, did_parser::ParseError};
+use did_resolver_key::error::DidKeyError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum DidIndyError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("DID method not supported: {0}")]
+    MethodNotSupported(String),
+    #[error("Namespace not supported: {0}")]
+    NamespaceNotSupported(String),
+    #[error("Representation not supported: {0}")]
+    RepresentationNotSupported(String),
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+    #[error("Invalid version query: {0}")]
+    InvalidVersion(String),
+    #[error("Version not supported: {0}")]
+    VersionNotSupported(String),
+    #[error("Ledger response parsing error: {0}")]
+    LedgerResponseParsingError(String),
+    #[error("AriesVCX Core error: {0}")]
+    AriesVcxCoreError(#[from] AriesVcxCoreError),
+    #[error("Key error: {0}")]
+    KeyError(#[from] DidKeyError),
+    #[error("DID parsing error: {0}")]
+    DidParserError(#[from] ParseError),
+    #[error("DID Document Builder Error: {0}")]
+    DidDocumentBuilderError(#[from] DidDocumentBuilderError),
+    #[error("JSON parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
@@ -0,0 +1,35 @@
+use aries_vcx_core::errors::error::AriesVcxCoreErrorKind;
+use did_resolver::traits::resolvable::{
+    resolution_error::DidResolutionError, resolution_metadata::DidResolutionMetadata,
+};
+
+use super::DidIndyError;
+
+impl From<&DidIndyError> for DidResolutionError {
+    fn from(err: &DidIndyError) -> Self {
+        match err {
+            DidIndyError::NotFound(_) => DidResolutionError::NotFound,
+            DidIndyError::AriesVcxCoreError(err)
+                if err.kind() == AriesVcxCoreErrorKind::LedgerItemNotFound =>
+            {
+                DidResolutionError::NotFound
+            }
+            DidIndyError::MethodNotSupported(_) | DidIndyError::NamespaceNotSupported(_) => {
+                DidResolutionError::MethodNotSupported
+            }
+            DidIndyError::RepresentationNotSupported(_) => {
+                DidResolutionError::RepresentationNotSupported
+            }
+            DidIndyError::InvalidDid(_)
+            | DidIndyError::InvalidVersion(_)
+            | DidIndyError::DidParserError(_) => DidResolutionError::InvalidDid,
+            _ => DidResolutionError::InternalError,
+        }
+    }
+}
+
+impl From<&DidIndyError> for DidResolutionMetadata {
+    fn from(err: &DidIndyError) -> Self {
+        DidResolutionMetadata::builder().error(err.into()).build()
+    }
+}
@@ -0,0 +1,5 @@
+pub extern crate did_resolver;
+
+pub mod dereferencing;
+pub mod error;
+pub mod resolution;
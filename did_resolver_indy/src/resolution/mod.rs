@@ -0,0 +1,6 @@
+mod resolver;
+mod utils;
+mod version;
+
+pub use resolver::DidIndyResolver;
+pub use version::NymVersion;
@@ -0,0 +1,108 @@
+use std::{collections::HashMap, sync::Arc};
+
+use aries_vcx_core::ledger::base_ledger::IndyLedgerRead;
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::Did,
+    error::GenericError,
+    shared_types::media_type::MediaType,
+    traits::resolvable::{
+        resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
+        DidResolvable,
+    },
+};
+
+use crate::error::DidIndyError;
+
+use super::{
+    utils::{endpoint_from_response, nym_from_response, nym_to_ddo, parse_did_indy},
+    NymVersion,
+};
+
+/// Resolves did:indy DIDs against the ledger of the namespace they are anchored to.
+#[derive(Debug, Default)]
+pub struct DidIndyResolver {
+    ledgers: HashMap<String, Arc<dyn IndyLedgerRead>>,
+}
+
+impl DidIndyResolver {
+    /// Creates a resolver reading each namespace, such as `sovrin` or `sovrin:staging`, from its
+    /// ledger.
+    pub fn new(ledgers: HashMap<String, Arc<dyn IndyLedgerRead>>) -> Self {
+        DidIndyResolver { ledgers }
+    }
+
+    pub fn register_ledger(&mut self, namespace: String, ledger: Arc<dyn IndyLedgerRead>) {
+        self.ledgers.insert(namespace, ledger);
+    }
+
+    pub fn unregister_ledger(&mut self, namespace: &str) {
+        self.ledgers.remove(namespace);
+    }
+
+    pub fn namespaces(&self) -> impl Iterator<Item = &String> {
+        self.ledgers.keys()
+    }
+
+    /// Resolves the DID document from the given version of its NYM. As the legacy `endpoint`
+    /// ATTRIB, read when the NYM has no `diddocContent`, can only be read at its latest state,
+    /// such NYMs are only resolved at their latest version.
+    pub async fn resolve_version(
+        &self,
+        did: &Did,
+        version: NymVersion,
+    ) -> Result<DidResolutionOutput<()>, DidIndyError> {
+        let (namespace, id) = parse_did_indy(did)?;
+        let ledger = self
+            .ledgers
+            .get(namespace)
+            .ok_or_else(|| DidIndyError::NamespaceNotSupported(namespace.to_string()))?;
+
+        let nym_response = match version {
+            NymVersion::Latest => ledger.get_nym(id).await?,
+            NymVersion::VersionId(seq_no) => ledger.get_nym_version(id, Some(seq_no), None).await?,
+            NymVersion::VersionTime(time) => {
+                let timestamp = u64::try_from(time.timestamp())
+                    .map_err(|_| DidIndyError::InvalidVersion(format!("versionTime={}", time)))?;
+                ledger.get_nym_version(id, None, Some(timestamp)).await?
+            }
+        };
+        let nym = nym_from_response(did, &nym_response)?;
+
+        let endpoint = match (nym.has_diddoc_content(), version) {
+            (true, _) => None,
+            (false, NymVersion::Latest) => {
+                endpoint_from_response(&ledger.get_attr(id, "endpoint").await?)?
+            }
+            (false, version) => {
+                return Err(DidIndyError::VersionNotSupported(format!(
+                    "{:?} of {}, whose endpoint is a legacy ATTRIB",
+                    version, did
+                )))
+            }
+        };
+
+        nym_to_ddo(did, nym, endpoint)
+    }
+}
+
+#[async_trait]
+impl DidResolvable for DidIndyResolver {
+    type ExtraFields = ();
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions,
+    ) -> Result<DidResolutionOutput<Self::ExtraFields>, GenericError> {
+        if let Some(accept) = options.accept() {
+            if accept != &MediaType::DidJson {
+                return Err(Box::new(DidIndyError::RepresentationNotSupported(
+                    accept.to_string(),
+                )));
+            }
+        }
+
+        Ok(self.resolve_version(did, NymVersion::Latest).await?)
+    }
+}
@@ -0,0 +1,419 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use did_resolver::{
+    did_doc::schema::{did_doc::DidDocument, references::map_references},
+    did_parser::Did,
+    shared_types::{did_document_metadata::DidDocumentMetadata, media_type::MediaType},
+    traits::resolvable::{
+        resolution_metadata::DidResolutionMetadata, resolution_output::DidResolutionOutput,
+    },
+};
+use did_resolver_key::key::{KeyType, MulticodecKey};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+use crate::error::DidIndyError;
+
+/// NYM of a DID as read from a GET_NYM ledger response.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Nym {
+    verkey: Option<String>,
+    diddoc_content: Option<Map<String, Value>>,
+    seq_no: Option<i64>,
+    txn_time: Option<i64>,
+}
+
+impl Nym {
+    pub(super) fn has_diddoc_content(&self) -> bool {
+        self.diddoc_content.is_some()
+    }
+}
+
+fn ledger_response_error(msg: &str) -> DidIndyError {
+    DidIndyError::LedgerResponseParsingError(msg.to_string())
+}
+
+/// Splits the method specific id of `did:indy:<namespace>[:<sub-namespace>]:<id>`.
+pub(super) fn parse_did_indy(did: &Did) -> Result<(&str, &str), DidIndyError> {
+    if did.method() != "indy" {
+        return Err(DidIndyError::MethodNotSupported(did.method().to_string()));
+    }
+    match did.id().rsplit_once(':') {
+        Some((namespace, id)) if !namespace.is_empty() && is_valid_indy_id(id) => {
+            Ok((namespace, id))
+        }
+        _ => Err(DidIndyError::InvalidDid(did.to_string())),
+    }
+}
+
+fn is_valid_indy_id(id: &str) -> bool {
+    matches!(bs58::decode(id).into_vec(), Ok(bytes) if bytes.len() == 16)
+}
+
+fn unix_to_datetime(posix_timestamp: i64) -> Option<DateTime<Utc>> {
+    NaiveDateTime::from_timestamp_opt(posix_timestamp, 0)
+        .map(|date_time| DateTime::<Utc>::from_utc(date_time, Utc))
+}
+
+/// Reads the `data` of a GET_ATTRIB or GET_NYM response, which is null when nothing was written.
+fn get_data_from_response(resp: &str) -> Result<Option<Value>, DidIndyError> {
+    let resp: Value = serde_json::from_str(resp)?;
+    match &resp["result"]["data"] {
+        Value::String(data) => Ok(Some(serde_json::from_str(data)?)),
+        Value::Null => Ok(None),
+        data => Err(DidIndyError::LedgerResponseParsingError(format!(
+            "Unexpected data format in ledger response: {data}"
+        ))),
+    }
+}
+
+pub(super) fn nym_from_response(did: &Did, resp: &str) -> Result<Nym, DidIndyError> {
+    let data = get_data_from_response(resp)?
+        .ok_or_else(|| DidIndyError::NotFound(format!("NYM of {} not found", did)))?;
+
+    let diddoc_content = match &data["diddocContent"] {
+        Value::Null => None,
+        Value::String(content) => Some(serde_json::from_str(content)?),
+        Value::Object(content) => Some(content.clone()),
+        _ => return Err(ledger_response_error("Unexpected diddocContent format")),
+    };
+
+    Ok(Nym {
+        verkey: data["verkey"].as_str().map(str::to_string),
+        diddoc_content,
+        seq_no: data["seqNo"].as_i64(),
+        txn_time: data["txnTime"].as_i64(),
+    })
+}
+
+pub(super) fn endpoint_from_response(resp: &str) -> Result<Option<Value>, DidIndyError> {
+    Ok(get_data_from_response(resp)?
+        .map(|mut data| data["endpoint"].take())
+        .filter(|endpoint| !endpoint.is_null()))
+}
+
+/// Abbreviated verkeys only hold the last 16 bytes of the key, the first ones being the DID.
+fn full_verkey(id: &str, verkey: &str) -> Result<String, DidIndyError> {
+    let Some(abbreviated) = verkey.strip_prefix('~') else {
+        return Ok(verkey.to_string());
+    };
+    let mut key = bs58::decode(id)
+        .into_vec()
+        .map_err(|_| ledger_response_error("DID is not base58 encoded"))?;
+    key.extend(
+        bs58::decode(abbreviated)
+            .into_vec()
+            .map_err(|_| ledger_response_error("Verkey is not base58 encoded"))?,
+    );
+    Ok(bs58::encode(key).into_string())
+}
+
+// https://hyperledger.github.io/indy-did-method/#diddoc
+fn base_did_document(did: &str, verkey: &str) -> Map<String, Value> {
+    let verkey_id = format!("{}#verkey", did);
+    let mut document = Map::new();
+    document.insert("id".to_string(), did.into());
+    document.insert(
+        "verificationMethod".to_string(),
+        json!([{
+            "id": verkey_id,
+            "type": "Ed25519VerificationKey2018",
+            "controller": did,
+            "publicKeyBase58": verkey,
+        }]),
+    );
+    document.insert("authentication".to_string(), json!([verkey_id]));
+    document
+}
+
+/// Adds the `diddocContent` of the NYM to the base document, extending its lists.
+fn merge_diddoc_content(
+    document: &mut Map<String, Value>,
+    did: &str,
+    mut content: Map<String, Value>,
+) -> Result<(), DidIndyError> {
+    if content.contains_key("id") {
+        return Err(ledger_response_error(
+            "diddocContent must not contain an id",
+        ));
+    }
+    map_references(&mut content, |reference| {
+        reference
+            .starts_with('#')
+            .then(|| format!("{}{}", did, reference))
+    });
+    for (key, value) in content {
+        match (document.get_mut(&key), value) {
+            (Some(Value::Array(items)), Value::Array(extra_items)) => items.extend(extra_items),
+            (Some(_), _) => {
+                return Err(DidIndyError::LedgerResponseParsingError(format!(
+                    "diddocContent overrides {} of the base DID document",
+                    key
+                )))
+            }
+            (None, value) => {
+                document.insert(key, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+// https://hyperledger.github.io/indy-did-method/#diddoc-content-from-legacy-endpoint-attribs
+fn add_legacy_services(
+    document: &mut Map<String, Value>,
+    did: &str,
+    verkey: &str,
+    endpoint: &Value,
+) -> Result<(), DidIndyError> {
+    let service_endpoint = endpoint["endpoint"]
+        .as_str()
+        .ok_or_else(|| ledger_response_error("Failed to parse endpoint ATTRIB"))?;
+    let routing_keys = endpoint
+        .get("routingKeys")
+        .cloned()
+        .unwrap_or_else(|| json!([]));
+    let types: Vec<&str> = endpoint["types"]
+        .as_array()
+        .map(|types| types.iter().filter_map(Value::as_str).collect())
+        .filter(|types: &Vec<&str>| !types.is_empty())
+        .unwrap_or_else(|| vec!["endpoint", "did-communication"]);
+
+    let mut services = Vec::new();
+    for service_type in &types {
+        match *service_type {
+            "endpoint" => services.push(json!({
+                "id": format!("{}#endpoint", did),
+                "type": "endpoint",
+                "serviceEndpoint": service_endpoint,
+            })),
+            "did-communication" => services.push(json!({
+                "id": format!("{}#did-communication", did),
+                "type": "did-communication",
+                "serviceEndpoint": service_endpoint,
+                "priority": 0,
+                "recipientKeys": [format!("{}#verkey", did)],
+                "routingKeys": routing_keys,
+                "accept": ["didcomm/aip2;env=rfc19"],
+            })),
+            "DIDComm" => services.push(json!({
+                "id": format!("{}#didcomm-1", did),
+                "type": "DIDComm",
+                "serviceEndpoint": service_endpoint,
+                "routingKeys": routing_keys,
+                "accept": ["didcomm/v2", "didcomm/aip2;env=rfc19"],
+            })),
+            _ => {}
+        }
+    }
+
+    // DIDComm services encrypt to the X25519 counterpart of the verkey
+    if types.contains(&"did-communication") || types.contains(&"DIDComm") {
+        let key_agreement_id = format!("{}#key-agreement-1", did);
+        let verkey = bs58::decode(verkey)
+            .into_vec()
+            .map_err(|_| ledger_response_error("Verkey is not base58 encoded"))?;
+        let x25519_key = MulticodecKey::new(KeyType::Ed25519, verkey)?.to_x25519()?;
+        if let Some(Value::Array(methods)) = document.get_mut("verificationMethod") {
+            methods.push(json!({
+                "id": key_agreement_id,
+                "type": "X25519KeyAgreementKey2019",
+                "controller": did,
+                "publicKeyBase58": bs58::encode(x25519_key.public_key()).into_string(),
+            }));
+        }
+        document.insert("keyAgreement".to_string(), json!([key_agreement_id]));
+    }
+    document.insert("service".to_string(), services.into());
+    Ok(())
+}
+
+/// Assembles the DID document of the NYM, falling back to the legacy `endpoint` ATTRIB for
+/// services when the NYM carries no `diddocContent`.
+pub(super) fn nym_to_ddo<E: Default + DeserializeOwned>(
+    did: &Did,
+    nym: Nym,
+    endpoint: Option<Value>,
+) -> Result<DidResolutionOutput<E>, DidIndyError> {
+    let (_, id) = parse_did_indy(did)?;
+    let did_string = did.to_string();
+
+    let mut metadata_builder = DidDocumentMetadata::builder();
+    if let Some(seq_no) = nym.seq_no {
+        metadata_builder = metadata_builder.version_id(seq_no.to_string());
+    }
+    if let Some(updated) = nym.txn_time.and_then(unix_to_datetime) {
+        metadata_builder = metadata_builder.updated(updated);
+    }
+
+    // A NYM whose verkey was removed is deactivated
+    let document = match nym.verkey {
+        Some(verkey) => {
+            let verkey = full_verkey(id, &verkey)?;
+            let mut document = base_did_document(&did_string, &verkey);
+            match (nym.diddoc_content, endpoint) {
+                (Some(content), _) => merge_diddoc_content(&mut document, &did_string, content)?,
+                (None, Some(endpoint)) => {
+                    add_legacy_services(&mut document, &did_string, &verkey, &endpoint)?
+                }
+                (None, None) => {}
+            }
+            metadata_builder = metadata_builder.deactivated(false);
+            serde_json::from_value(document.into())?
+        }
+        None => {
+            metadata_builder = metadata_builder.deactivated(true);
+            DidDocument::builder(did.clone()).build()
+        }
+    };
+
+    let resolution_metadata = DidResolutionMetadata::builder()
+        .content_type(MediaType::DidJson.to_string())
+        .build();
+
+    Ok(DidResolutionOutput::builder(document)
+        .did_document_metadata(metadata_builder.build())
+        .did_resolution_metadata(resolution_metadata)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:indy:sovrin:staging:WRfXPg8dantKVubE3HX8pw";
+    const VERKEY: &str = "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV";
+
+    fn nym_response(data: Value) -> String {
+        json!({
+            "result": {
+                "data": data.to_string(),
+                "seqNo": 12,
+                "txnTime": 1629272938
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_did_indy() {
+        let did = Did::parse(DID.to_string()).unwrap();
+        assert_eq!(
+            parse_did_indy(&did).unwrap(),
+            ("sovrin:staging", "WRfXPg8dantKVubE3HX8pw")
+        );
+
+        let did = Did::parse("did:indy:WRfXPg8dantKVubE3HX8pw".to_string()).unwrap();
+        assert!(matches!(
+            parse_did_indy(&did),
+            Err(DidIndyError::InvalidDid(_))
+        ));
+
+        let did = Did::parse("did:sov:WRfXPg8dantKVubE3HX8pw".to_string()).unwrap();
+        assert!(matches!(
+            parse_did_indy(&did),
+            Err(DidIndyError::MethodNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_full_verkey() {
+        assert_eq!(
+            full_verkey("WRfXPg8dantKVubE3HX8pw", VERKEY).unwrap(),
+            VERKEY
+        );
+        assert_eq!(
+            full_verkey("V4SGRU86Z58d6TV7PBUe6f", "~CoRER63DVYnWZtK8uAzNbx").unwrap(),
+            "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"
+        );
+    }
+
+    #[test]
+    fn test_nym_not_found() {
+        let did = Did::parse(DID.to_string()).unwrap();
+        let resp = json!({ "result": { "data": null } }).to_string();
+        assert!(matches!(
+            nym_from_response(&did, &resp),
+            Err(DidIndyError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_nym_to_ddo_with_diddoc_content() {
+        let did = Did::parse(DID.to_string()).unwrap();
+        let diddoc_content = json!({
+            "service": [{
+                "id": "#didcomm-1",
+                "type": "DIDComm",
+                "serviceEndpoint": "https://example.com/endpoint"
+            }]
+        });
+        let resp = nym_response(json!({
+            "verkey": VERKEY,
+            "diddocContent": diddoc_content.to_string(),
+            "seqNo": 12,
+            "txnTime": 1629272938
+        }));
+        let nym = nym_from_response(&did, &resp).unwrap();
+        let output = nym_to_ddo::<()>(&did, nym, None).unwrap();
+
+        let ddo = serde_json::to_value(output.did_document()).unwrap();
+        assert_eq!(ddo["id"], DID);
+        assert_eq!(
+            ddo["verificationMethod"][0]["id"],
+            format!("{}#verkey", DID)
+        );
+        assert_eq!(ddo["verificationMethod"][0]["publicKeyBase58"], VERKEY);
+        assert_eq!(ddo["authentication"], json!([format!("{}#verkey", DID)]));
+        assert_eq!(ddo["service"][0]["id"], format!("{}#didcomm-1", DID));
+
+        let metadata = output.did_document_metadata();
+        assert_eq!(metadata.version_id(), Some(&"12".to_string()));
+        assert_eq!(metadata.updated(), unix_to_datetime(1629272938));
+        assert_eq!(metadata.deactivated(), Some(false));
+    }
+
+    #[test]
+    fn test_nym_to_ddo_with_legacy_endpoint() {
+        let did = Did::parse(DID.to_string()).unwrap();
+        let nym = nym_from_response(&did, &nym_response(json!({ "verkey": VERKEY }))).unwrap();
+        let endpoint = endpoint_from_response(
+            &json!({
+                "result": {
+                    "data": json!({
+                        "endpoint": { "endpoint": "https://example.com/endpoint" }
+                    })
+                    .to_string()
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let output = nym_to_ddo::<()>(&did, nym, endpoint).unwrap();
+
+        let ddo = serde_json::to_value(output.did_document()).unwrap();
+        assert_eq!(ddo["service"][0]["id"], format!("{}#endpoint", DID));
+        assert_eq!(
+            ddo["service"][1]["id"],
+            format!("{}#did-communication", DID)
+        );
+        assert_eq!(ddo["service"][1]["type"], "did-communication");
+        assert_eq!(
+            ddo["keyAgreement"],
+            json!([format!("{}#key-agreement-1", DID)])
+        );
+        assert_eq!(
+            ddo["verificationMethod"][1]["type"],
+            "X25519KeyAgreementKey2019"
+        );
+    }
+
+    #[test]
+    fn test_nym_to_ddo_deactivated() {
+        let did = Did::parse(DID.to_string()).unwrap();
+        let nym = nym_from_response(&did, &nym_response(json!({ "verkey": null }))).unwrap();
+        let output = nym_to_ddo::<()>(&did, nym, None).unwrap();
+
+        assert!(output.did_document().verification_method().is_empty());
+        assert_eq!(output.did_document_metadata().deactivated(), Some(true));
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::DidIndyError;
+
+const VERSION_ID_QUERY: &str = "versionId";
+const VERSION_TIME_QUERY: &str = "versionTime";
+
+/// State of a NYM to resolve the DID document from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NymVersion {
+    #[default]
+    Latest,
+    /// The NYM as written by the transaction with this sequence number.
+    VersionId(i32),
+    /// The NYM as it was on the ledger at this time.
+    VersionTime(DateTime<Utc>),
+}
+
+impl NymVersion {
+    /// Reads the `versionId` or `versionTime` DID URL query, at most one of which may be present.
+    pub fn from_queries(queries: &HashMap<String, String>) -> Result<Self, DidIndyError> {
+        match (
+            queries.get(VERSION_ID_QUERY),
+            queries.get(VERSION_TIME_QUERY),
+        ) {
+            (None, None) => Ok(NymVersion::Latest),
+            (Some(version_id), None) => version_id
+                .parse()
+                .map(NymVersion::VersionId)
+                .map_err(|_| DidIndyError::InvalidVersion(format!("versionId={}", version_id))),
+            (None, Some(version_time)) => DateTime::parse_from_rfc3339(version_time)
+                .map(|version_time| NymVersion::VersionTime(version_time.with_timezone(&Utc)))
+                .map_err(|_| DidIndyError::InvalidVersion(format!("versionTime={}", version_time))),
+            (Some(_), Some(_)) => Err(DidIndyError::InvalidVersion(
+                "versionId and versionTime are mutually exclusive".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn queries(queries: &[(&str, &str)]) -> HashMap<String, String> {
+        queries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_version_from_queries() {
+        assert_eq!(
+            NymVersion::from_queries(&queries(&[])).unwrap(),
+            NymVersion::Latest
+        );
+        assert_eq!(
+            NymVersion::from_queries(&queries(&[("versionId", "12")])).unwrap(),
+            NymVersion::VersionId(12)
+        );
+        assert_eq!(
+            NymVersion::from_queries(&queries(&[("versionTime", "2021-05-01T00:00:00Z")])).unwrap(),
+            NymVersion::VersionTime(Utc.with_ymd_and_hms(2021, 5, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_version_from_invalid_queries() {
+        assert!(matches!(
+            NymVersion::from_queries(&queries(&[("versionId", "latest")])),
+            Err(DidIndyError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            NymVersion::from_queries(&queries(&[("versionTime", "yesterday")])),
+            Err(DidIndyError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            NymVersion::from_queries(&queries(&[
+                ("versionId", "12"),
+                ("versionTime", "2021-05-01T00:00:00Z")
+            ])),
+            Err(DidIndyError::InvalidVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+use std::{collections::HashMap, sync::Arc};
+
+use aries_vcx_core::{
+    errors::error::{AriesVcxCoreError, AriesVcxCoreErrorKind, VcxCoreResult},
+    ledger::base_ledger::IndyLedgerRead,
+};
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::{Did, DidUrl},
+    traits::{
+        dereferenceable::{dereferencing_options::DidDereferencingOptions, DidDereferenceable},
+        resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+    },
+};
+use did_resolver_indy::{
+    error::DidIndyError,
+    resolution::{DidIndyResolver, NymVersion},
+};
+use did_resolver_registry::ResolverRegistry;
+use serde_json::{json, Value};
+
+const ID: &str = "WRfXPg8dantKVubE3HX8pw";
+const VERKEY: &str = "H3C2AVvLMv6gmMNam3uVAjZpfkcJCwDwnZn6z3wXmqPV";
+const ROTATED_VERKEY: &str = "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL";
+
+/// Ledger holding a NYM written at seqNo 10 and rotated at seqNo 20, along with either its
+/// `diddocContent` or a legacy endpoint ATTRIB.
+#[derive(Debug)]
+struct TestLedger {
+    endpoint: &'static str,
+    diddoc_content: Option<Value>,
+}
+
+fn response(data: Option<Value>) -> String {
+    json!({ "result": { "data": data.map(|data| data.to_string()) } }).to_string()
+}
+
+fn nym(verkey: &str, seq_no: i32, txn_time: u64, diddoc_content: &Option<Value>) -> String {
+    let mut data = json!({
+        "dest": ID,
+        "verkey": verkey,
+        "seqNo": seq_no,
+        "txnTime": txn_time
+    });
+    if let Some(diddoc_content) = diddoc_content {
+        data["diddocContent"] = diddoc_content.clone();
+    }
+    response(Some(data))
+}
+
+#[async_trait]
+impl IndyLedgerRead for TestLedger {
+    async fn get_attr(&self, target_did: &str, attr_name: &str) -> VcxCoreResult<String> {
+        assert_eq!(target_did, ID);
+        assert_eq!(attr_name, "endpoint");
+        Ok(response(Some(
+            json!({ "endpoint": { "endpoint": self.endpoint } }),
+        )))
+    }
+
+    async fn get_nym(&self, did: &str) -> VcxCoreResult<String> {
+        self.get_nym_version(did, None, None).await
+    }
+
+    async fn get_nym_version(
+        &self,
+        did: &str,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> VcxCoreResult<String> {
+        if did != ID {
+            return Ok(response(None));
+        }
+        let content = &self.diddoc_content;
+        match (seq_no, timestamp) {
+            (Some(10), None) => Ok(nym(VERKEY, 10, 1600000000, content)),
+            (None, Some(timestamp)) if timestamp < 1700000000 => {
+                Ok(nym(VERKEY, 10, 1600000000, content))
+            }
+            _ => Ok(nym(ROTATED_VERKEY, 20, 1700000000, content)),
+        }
+    }
+
+    async fn get_txn_author_agreement(&self) -> VcxCoreResult<Option<String>> {
+        Ok(None)
+    }
+
+    async fn get_ledger_txn(
+        &self,
+        _seq_no: i32,
+        _submitter_did: Option<&str>,
+    ) -> VcxCoreResult<String> {
+        Err(AriesVcxCoreError::from_msg(
+            AriesVcxCoreErrorKind::UnimplementedFeature,
+            "unimplemented test method: get_ledger_txn",
+        ))
+    }
+}
+
+fn resolver() -> DidIndyResolver {
+    let mut ledgers: HashMap<String, Arc<dyn IndyLedgerRead>> = HashMap::new();
+    ledgers.insert(
+        "sovrin".to_string(),
+        Arc::new(TestLedger {
+            endpoint: "https://sovrin.example.com",
+            diddoc_content: None,
+        }),
+    );
+    ledgers.insert(
+        "sovrin:staging".to_string(),
+        Arc::new(TestLedger {
+            endpoint: "https://staging.example.com",
+            diddoc_content: None,
+        }),
+    );
+    ledgers.insert(
+        "sovrin:builder".to_string(),
+        Arc::new(TestLedger {
+            endpoint: "https://builder.example.com",
+            diddoc_content: Some(json!({})),
+        }),
+    );
+    DidIndyResolver::new(ledgers)
+}
+
+fn public_key(output: &Value) -> &Value {
+    &output["verificationMethod"][0]["publicKeyBase58"]
+}
+
+#[tokio::test]
+async fn test_resolve_by_namespace() {
+    let resolver = resolver();
+    for (namespace, endpoint) in [
+        ("sovrin", "https://sovrin.example.com/"),
+        ("sovrin:staging", "https://staging.example.com/"),
+    ] {
+        let did = Did::parse(format!("did:indy:{}:{}", namespace, ID)).unwrap();
+        let output = resolver
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(output.did_document().id(), &did);
+        assert_eq!(
+            output.did_document().service()[0]
+                .service_endpoint()
+                .as_ref(),
+            endpoint
+        );
+        assert_eq!(
+            output.did_document_metadata().version_id(),
+            Some(&"20".to_string())
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_unknown_namespace() {
+    let did = Did::parse(format!("did:indy:bcovrin:{}", ID)).unwrap();
+    let err = resolver()
+        .resolve_version(&did, Default::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DidIndyError::NamespaceNotSupported(_)));
+}
+
+#[tokio::test]
+async fn test_resolve_unknown_did() {
+    let did = Did::parse("did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f".to_string()).unwrap();
+    let err = resolver()
+        .resolve_version(&did, Default::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DidIndyError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_dereference_versions() {
+    let resolver = resolver();
+    for (query, verkey) in [
+        ("", ROTATED_VERKEY),
+        ("?versionId=10", VERKEY),
+        ("?versionTime=2021-01-01T00:00:00Z", VERKEY),
+        ("?versionTime=2024-01-01T00:00:00Z", ROTATED_VERKEY),
+    ] {
+        let did_url = DidUrl::parse(format!("did:indy:sovrin:builder:{}{}", ID, query)).unwrap();
+        let output = resolver
+            .dereference(&did_url, &DidDereferencingOptions::default())
+            .await
+            .unwrap();
+        let document: Value = serde_json::from_reader(output.content_stream().clone()).unwrap();
+        assert_eq!(public_key(&document), verkey, "{}", query);
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_version_with_legacy_endpoint() {
+    // The endpoint ATTRIB of the NYM can only be read at its latest state
+    let did = Did::parse(format!("did:indy:sovrin:{}", ID)).unwrap();
+    let err = resolver()
+        .resolve_version(&did, NymVersion::VersionId(10))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DidIndyError::VersionNotSupported(_)));
+}
+
+#[tokio::test]
+async fn test_dereference_verkey() {
+    let did_url = DidUrl::parse(format!("did:indy:sovrin:{}#verkey", ID)).unwrap();
+    let output = resolver()
+        .dereference(&did_url, &DidDereferencingOptions::default())
+        .await
+        .unwrap();
+    let verification_method: Value =
+        serde_json::from_reader(output.content_stream().clone()).unwrap();
+    assert_eq!(verification_method["type"], "Ed25519VerificationKey2018");
+    assert_eq!(verification_method["publicKeyBase58"], ROTATED_VERKEY);
+}
+
+#[tokio::test]
+async fn test_resolve_through_registry() {
+    let mut registry = ResolverRegistry::new();
+    registry.register_resolver("indy".to_string(), Box::new(resolver()));
+
+    let did = Did::parse(format!("did:indy:sovrin:staging:{}", ID)).unwrap();
+    let output = registry
+        .resolve(&did, &DidResolutionOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().id(), &did);
+}
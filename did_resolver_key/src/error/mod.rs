@@ -0,0 +1,29 @@
+mod resolution;
+
+use did_resolver::{did_doc::error::DidDocumentBuilderError, did_parser::ParseError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum DidKeyError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("DID method not supported: {0}")]
+    MethodNotSupported(String),
+    #[error("Representation not supported: {0}")]
+    RepresentationNotSupported(String),
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+    #[error("Unsupported public key type: multicodec {0:#x}")]
+    UnsupportedPublicKeyType(u64),
+    #[error("Invalid public key length: expected {expected} bytes, got {actual}")]
+    InvalidPublicKeyLength { expected: usize, actual: usize },
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("DID parsing error: {0}")]
+    DidParserError(#[from] ParseError),
+    #[error("DID Document Builder Error: {0}")]
+    DidDocumentBuilderError(#[from] DidDocumentBuilderError),
+    #[error("JSON parsing error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
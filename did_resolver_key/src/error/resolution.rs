@@ -0,0 +1,34 @@
+use did_resolver::traits::resolvable::{
+    resolution_error::DidResolutionError, resolution_metadata::DidResolutionMetadata,
+};
+
+use super::DidKeyError;
+
+impl From<&DidKeyError> for DidResolutionError {
+    fn from(err: &DidKeyError) -> Self {
+        match err {
+            DidKeyError::NotFound(_) => DidResolutionError::NotFound,
+            DidKeyError::MethodNotSupported(_) => DidResolutionError::MethodNotSupported,
+            DidKeyError::RepresentationNotSupported(_) => {
+                DidResolutionError::RepresentationNotSupported
+            }
+            DidKeyError::InvalidDid(_) | DidKeyError::DidParserError(_) => {
+                DidResolutionError::InvalidDid
+            }
+            DidKeyError::UnsupportedPublicKeyType(_) => {
+                DidResolutionError::UnsupportedPublicKeyType
+            }
+            DidKeyError::InvalidPublicKeyLength { .. } => {
+                DidResolutionError::InvalidPublicKeyLength
+            }
+            DidKeyError::InvalidPublicKey(_) => DidResolutionError::InvalidPublicKey,
+            _ => DidResolutionError::InternalError,
+        }
+    }
+}
+
+impl From<&DidKeyError> for DidResolutionMetadata {
+    fn from(err: &DidKeyError) -> Self {
+        DidResolutionMetadata::builder().error(err.into()).build()
+    }
+}
@@ -0,0 +1,183 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+pub use did_resolver::did_doc::schema::types::public_key::KeyType;
+use did_resolver::did_doc::schema::types::{
+    jsonwebkey::JsonWebKey,
+    public_key::{split_multicodec_prefix, PublicKey},
+};
+use multibase::Base;
+
+use crate::error::DidKeyError;
+
+// Elliptic curve keys are expected in the compressed SEC1 form
+fn key_length(key_type: KeyType) -> usize {
+    match key_type {
+        KeyType::Ed25519 | KeyType::X25519 => 32,
+        KeyType::P256 | KeyType::Secp256k1 => 33,
+    }
+}
+
+/// Public key prefixed with its multicodec type, as encoded in the method specific id of a did:key.
+/// Elliptic curve keys are always compressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MulticodecKey(PublicKey);
+
+impl MulticodecKey {
+    pub fn new(key_type: KeyType, public_key: Vec<u8>) -> Result<Self, DidKeyError> {
+        if public_key.len() != key_length(key_type) {
+            return Err(DidKeyError::InvalidPublicKeyLength {
+                expected: key_length(key_type),
+                actual: public_key.len(),
+            });
+        }
+        Ok(Self(PublicKey::new(key_type, public_key)?))
+    }
+
+    /// Decodes a base58btc multibase encoded multicodec key, such as `z6Mk...`.
+    pub fn from_multibase(multibase: &str) -> Result<Self, DidKeyError> {
+        let (base, bytes) = multibase::decode(multibase)
+            .map_err(|err| DidKeyError::InvalidDid(format!("Invalid multibase key: {}", err)))?;
+        if base != Base::Base58Btc {
+            return Err(DidKeyError::InvalidDid(format!(
+                "Expected base58btc multibase key, got {:?}",
+                base
+            )));
+        }
+
+        let (code, public_key) = split_multicodec_prefix(&bytes)
+            .map_err(|err| DidKeyError::InvalidDid(err.to_string()))?;
+        let key_type =
+            KeyType::from_multicodec(code).ok_or(DidKeyError::UnsupportedPublicKeyType(code))?;
+        Self::new(key_type, public_key.to_vec())
+    }
+
+    pub fn to_multibase(&self) -> String {
+        self.0.to_multibase()
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        self.0.key_type()
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.0.key()
+    }
+
+    /// Converts an Ed25519 key to the X25519 key of the birationally equivalent Montgomery curve.
+    pub fn to_x25519(&self) -> Result<Self, DidKeyError> {
+        if self.key_type() != KeyType::Ed25519 {
+            return Err(DidKeyError::InvalidPublicKey(format!(
+                "Cannot derive an X25519 key from a {} key",
+                self.key_type()
+            )));
+        }
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(self.public_key());
+        let point = CompressedEdwardsY(bytes).decompress().ok_or_else(|| {
+            DidKeyError::InvalidPublicKey("Ed25519 key is not a valid curve point".to_string())
+        })?;
+
+        Self::new(KeyType::X25519, point.to_montgomery().to_bytes().to_vec())
+    }
+
+    /// Public key as a JWK, elliptic curve keys being given by their uncompressed coordinates.
+    pub fn to_jwk(&self) -> Result<JsonWebKey, DidKeyError> {
+        match self.key_type() {
+            KeyType::Ed25519 | KeyType::X25519 => Ok(self.0.to_jwk()?),
+            KeyType::P256 | KeyType::Secp256k1 => {
+                Ok(PublicKey::new(self.key_type(), self.uncompressed()?)?.to_jwk()?)
+            }
+        }
+    }
+
+    /// The uncompressed SEC1 point of an elliptic curve key: 0x04 || x || y
+    fn uncompressed(&self) -> Result<Vec<u8>, DidKeyError> {
+        let invalid_point = || {
+            DidKeyError::InvalidPublicKey(format!(
+                "{} key is not a valid curve point",
+                self.key_type()
+            ))
+        };
+
+        let point = match self.key_type() {
+            KeyType::P256 => {
+                use p256::elliptic_curve::sec1::ToEncodedPoint;
+                p256::PublicKey::from_sec1_bytes(self.public_key())
+                    .map_err(|_| invalid_point())?
+                    .to_encoded_point(false)
+                    .as_bytes()
+                    .to_vec()
+            }
+            KeyType::Secp256k1 => {
+                use k256::elliptic_curve::sec1::ToEncodedPoint;
+                k256::PublicKey::from_sec1_bytes(self.public_key())
+                    .map_err(|_| invalid_point())?
+                    .to_encoded_point(false)
+                    .as_bytes()
+                    .to_vec()
+            }
+            KeyType::Ed25519 | KeyType::X25519 => return Err(invalid_point()),
+        };
+        Ok(point)
+    }
+}
+
+impl AsRef<PublicKey> for MulticodecKey {
+    fn as_ref(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+impl From<MulticodecKey> for PublicKey {
+    fn from(key: MulticodecKey) -> Self {
+        key.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519_MULTIBASE: &str = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+    const X25519_MULTIBASE: &str = "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p";
+
+    #[test]
+    fn test_multibase_round_trip() {
+        let key = MulticodecKey::from_multibase(ED25519_MULTIBASE).unwrap();
+        assert_eq!(key.key_type(), KeyType::Ed25519);
+        assert_eq!(key.public_key().len(), 32);
+        assert_eq!(key.to_multibase(), ED25519_MULTIBASE);
+    }
+
+    #[test]
+    fn test_to_x25519() {
+        let key = MulticodecKey::from_multibase(ED25519_MULTIBASE).unwrap();
+        assert_eq!(key.to_x25519().unwrap().to_multibase(), X25519_MULTIBASE);
+
+        let key = MulticodecKey::from_multibase(X25519_MULTIBASE).unwrap();
+        assert!(key.to_x25519().is_err());
+    }
+
+    #[test]
+    fn test_unsupported_key_type() {
+        // Varint encoded multicodec 0x1205, an RSA public key
+        let mut bytes = vec![0x85, 0x24];
+        bytes.extend_from_slice(&[0; 32]);
+        let multibase = multibase::encode(Base::Base58Btc, bytes);
+        assert!(matches!(
+            MulticodecKey::from_multibase(&multibase),
+            Err(DidKeyError::UnsupportedPublicKeyType(0x1205))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert!(matches!(
+            MulticodecKey::new(KeyType::P256, vec![0; 32]),
+            Err(DidKeyError::InvalidPublicKeyLength {
+                expected: 33,
+                actual: 32
+            })
+        ));
+    }
+}
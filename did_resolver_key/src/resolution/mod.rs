@@ -0,0 +1,5 @@
+mod resolver;
+mod utils;
+
+pub use resolver::DidKeyResolver;
+pub use utils::{did_document_from_key, verification_method_from_key};
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use did_resolver::{
+    did_parser::Did,
+    error::GenericError,
+    shared_types::media_type::MediaType,
+    traits::resolvable::{
+        resolution_metadata::DidResolutionMetadata, resolution_options::DidResolutionOptions,
+        resolution_output::DidResolutionOutput, DidResolvable,
+    },
+};
+
+use crate::{error::DidKeyError, key::MulticodecKey};
+
+use super::utils::did_document_from_key;
+
+/// Resolves did:key DIDs by expanding the key they encode, without any network access.
+#[derive(Debug, Clone, Default)]
+pub struct DidKeyResolver;
+
+impl DidKeyResolver {
+    pub fn new() -> Self {
+        DidKeyResolver
+    }
+}
+
+#[async_trait]
+impl DidResolvable for DidKeyResolver {
+    type ExtraFields = ();
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions,
+    ) -> Result<DidResolutionOutput<Self::ExtraFields>, GenericError> {
+        if did.method() != "key" {
+            return Err(Box::new(DidKeyError::MethodNotSupported(
+                did.method().to_string(),
+            )));
+        }
+
+        if let Some(accept) = options.accept() {
+            if accept != &MediaType::DidJson {
+                return Err(Box::new(DidKeyError::RepresentationNotSupported(
+                    accept.to_string(),
+                )));
+            }
+        }
+
+        let key = MulticodecKey::from_multibase(did.id())?;
+        let did_document = did_document_from_key(did, &key)?;
+
+        let resolution_metadata = DidResolutionMetadata::builder()
+            .content_type(MediaType::DidJson.to_string())
+            .build();
+
+        Ok(DidResolutionOutput::builder(did_document)
+            .did_resolution_metadata(resolution_metadata)
+            .build())
+    }
+}
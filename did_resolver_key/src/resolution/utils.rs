@@ -0,0 +1,83 @@
+use did_resolver::{
+    did_doc::schema::{
+        did_doc::DidDocument, types::multibase::Multibase, verification_method::VerificationMethod,
+    },
+    did_parser::{Did, DidUrl},
+};
+
+use crate::{
+    error::DidKeyError,
+    key::{KeyType, MulticodecKey},
+};
+
+/// Verification method of type matching `key`, as used by the did:key method.
+pub fn verification_method_from_key(
+    id: DidUrl,
+    controller: &Did,
+    key: &MulticodecKey,
+) -> Result<VerificationMethod, DidKeyError> {
+    let verification_method = match key.key_type() {
+        KeyType::Ed25519 => VerificationMethod::builder(
+            id,
+            controller.clone(),
+            "Ed25519VerificationKey2020".to_string(),
+        )
+        .add_public_key_multibase(Multibase::new(key.to_multibase())?)
+        .build(),
+        KeyType::X25519 => VerificationMethod::builder(
+            id,
+            controller.clone(),
+            "X25519KeyAgreementKey2020".to_string(),
+        )
+        .add_public_key_multibase(Multibase::new(key.to_multibase())?)
+        .build(),
+        KeyType::P256 | KeyType::Secp256k1 => {
+            VerificationMethod::builder(id, controller.clone(), "JsonWebKey2020".to_string())
+                .add_public_key_jwk(key.to_jwk()?)
+                .build()
+        }
+    };
+    Ok(verification_method)
+}
+
+fn verification_method(did: &Did, key: &MulticodecKey) -> Result<VerificationMethod, DidKeyError> {
+    let id = DidUrl::parse(format!("{}#{}", did, key.to_multibase()))?;
+    verification_method_from_key(id, did, key)
+}
+
+/// Expands a key into the DID document of `did`, as specified by the did:key method:
+/// signing keys are referenced by every verification relationship but key agreement, for which an
+/// X25519 key is derived from Ed25519 keys, while X25519 keys are only usable for key agreement.
+pub fn did_document_from_key<E>(
+    did: &Did,
+    key: &MulticodecKey,
+) -> Result<DidDocument<E>, DidKeyError> {
+    let signing_method = verification_method(did, key)?;
+    let signing_method_id = signing_method.id().clone();
+    let builder = DidDocument::builder(did.clone()).add_verification_method(signing_method);
+
+    let builder = match key.key_type() {
+        KeyType::X25519 => {
+            return Ok(builder
+                .add_key_agreement_refrence(signing_method_id)
+                .build())
+        }
+        KeyType::Ed25519 => {
+            let key_agreement_method = verification_method(did, &key.to_x25519()?)?;
+            let key_agreement_method_id = key_agreement_method.id().clone();
+            builder
+                .add_verification_method(key_agreement_method)
+                .add_key_agreement_refrence(key_agreement_method_id)
+        }
+        KeyType::P256 | KeyType::Secp256k1 => {
+            builder.add_key_agreement_refrence(signing_method_id.clone())
+        }
+    };
+
+    Ok(builder
+        .add_authentication_reference(signing_method_id.clone())
+        .add_assertion_method_reference(signing_method_id.clone())
+        .add_capability_invocation_refrence(signing_method_id.clone())
+        .add_capability_delegation_refrence(signing_method_id)
+        .build())
+}
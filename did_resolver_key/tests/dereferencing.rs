@@ -0,0 +1,53 @@
+use std::io::Read;
+
+use did_resolver::did_parser::DidUrl;
+use did_resolver::traits::dereferenceable::{
+    dereferencing_options::DidDereferencingOptions, DidDereferenceable,
+};
+use did_resolver_key::error::DidKeyError;
+use did_resolver_key::resolution::DidKeyResolver;
+use serde_json::Value;
+
+const DID_KEY_ED25519: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+async fn dereference(did_url: &str) -> Result<Value, DidKeyError> {
+    let output = DidKeyResolver::new()
+        .dereference(
+            &DidUrl::parse(did_url.to_string()).unwrap(),
+            &DidDereferencingOptions::default(),
+        )
+        .await
+        .map_err(|err| *err.downcast::<DidKeyError>().unwrap())?;
+
+    let mut content = String::new();
+    output
+        .content_stream()
+        .clone()
+        .read_to_string(&mut content)
+        .unwrap();
+    Ok(serde_json::from_str(&content).unwrap())
+}
+
+#[tokio::test]
+async fn test_dereference_verification_method() {
+    let did_url = format!("{}#{}", DID_KEY_ED25519, &DID_KEY_ED25519[8..]);
+    let content = dereference(&did_url).await.unwrap();
+
+    assert_eq!(content["id"], did_url);
+    assert_eq!(content["type"], "Ed25519VerificationKey2020");
+}
+
+#[tokio::test]
+async fn test_dereference_did() {
+    let content = dereference(DID_KEY_ED25519).await.unwrap();
+    assert_eq!(content["id"], DID_KEY_ED25519);
+}
+
+#[tokio::test]
+async fn test_dereference_unknown_fragment() {
+    let did_url = format!("{}#unknown", DID_KEY_ED25519);
+    assert!(matches!(
+        dereference(&did_url).await,
+        Err(DidKeyError::NotFound(_))
+    ));
+}
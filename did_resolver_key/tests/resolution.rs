@@ -0,0 +1,156 @@
+use did_resolver::did_doc::schema::{
+    types::multibase::Multibase, verification_method::VerificationMethodKind,
+};
+use did_resolver::did_parser::Did;
+use did_resolver::shared_types::media_type::MediaType;
+use did_resolver::traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable};
+use did_resolver_key::error::DidKeyError;
+use did_resolver_key::resolution::DidKeyResolver;
+use did_resolver_registry::ResolverRegistry;
+use serde_json::json;
+
+const DID_KEY_ED25519: &str = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+const DID_KEY_X25519: &str = "did:key:z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p";
+const DID_KEY_P256: &str = "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169";
+const DID_KEY_SECP256K1: &str = "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme";
+
+fn reference(did_url: &str) -> VerificationMethodKind {
+    VerificationMethodKind::Resolvable(did_url.to_string().try_into().unwrap())
+}
+
+async fn resolve(did: &str) -> Result<serde_json::Value, DidKeyError> {
+    let output = DidKeyResolver::new()
+        .resolve(
+            &Did::parse(did.to_string()).unwrap(),
+            &DidResolutionOptions::default(),
+        )
+        .await
+        .map_err(|err| *err.downcast::<DidKeyError>().unwrap())?;
+    Ok(serde_json::to_value(output.did_document()).unwrap())
+}
+
+#[tokio::test]
+async fn test_resolve_ed25519() {
+    let output = DidKeyResolver::new()
+        .resolve(
+            &Did::parse(DID_KEY_ED25519.to_string()).unwrap(),
+            &DidResolutionOptions::default(),
+        )
+        .await
+        .unwrap();
+    let did_document = output.did_document();
+
+    let signing_method_id = format!("{}#{}", DID_KEY_ED25519, &DID_KEY_ED25519[8..]);
+    let key_agreement_id = format!("{}#{}", DID_KEY_ED25519, &DID_KEY_X25519[8..]);
+
+    assert_eq!(did_document.id().to_string(), DID_KEY_ED25519);
+    assert_eq!(did_document.verification_method().len(), 2);
+
+    let signing_method = &did_document.verification_method()[0];
+    assert_eq!(signing_method.id().to_string(), signing_method_id);
+    assert_eq!(signing_method.controller().to_string(), DID_KEY_ED25519);
+    assert_eq!(
+        signing_method.verification_method_type(),
+        "Ed25519VerificationKey2020"
+    );
+    assert_eq!(
+        signing_method.public_key_multibase().unwrap(),
+        &Multibase::new(DID_KEY_ED25519[8..].to_string()).unwrap()
+    );
+
+    let key_agreement_method = &did_document.verification_method()[1];
+    assert_eq!(key_agreement_method.id().to_string(), key_agreement_id);
+    assert_eq!(
+        key_agreement_method.verification_method_type(),
+        "X25519KeyAgreementKey2020"
+    );
+
+    assert_eq!(
+        did_document.authentication(),
+        &[reference(&signing_method_id)]
+    );
+    assert_eq!(
+        did_document.assertion_method(),
+        &[reference(&signing_method_id)]
+    );
+    assert_eq!(
+        did_document.key_agreement(),
+        &[reference(&key_agreement_id)]
+    );
+    assert_eq!(
+        output.did_resolution_metadata().content_type(),
+        Some(&"application/did+json".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_x25519() {
+    let did_document = resolve(DID_KEY_X25519).await.unwrap();
+    let method_id = format!("{}#{}", DID_KEY_X25519, &DID_KEY_X25519[8..]);
+
+    assert_eq!(did_document["verificationMethod"][0]["id"], method_id);
+    assert_eq!(did_document["keyAgreement"], json!([method_id]));
+    assert!(did_document.get("authentication").is_none());
+}
+
+#[tokio::test]
+async fn test_resolve_p256() {
+    let did_document = resolve(DID_KEY_P256).await.unwrap();
+    let method = &did_document["verificationMethod"][0];
+
+    assert_eq!(method["type"], "JsonWebKey2020");
+    assert_eq!(method["publicKeyJwk"]["kty"], "EC");
+    assert_eq!(method["publicKeyJwk"]["crv"], "P-256");
+    assert_eq!(did_document["keyAgreement"], did_document["authentication"]);
+}
+
+#[tokio::test]
+async fn test_resolve_secp256k1() {
+    let did_document = resolve(DID_KEY_SECP256K1).await.unwrap();
+    let method = &did_document["verificationMethod"][0];
+
+    assert_eq!(method["type"], "JsonWebKey2020");
+    assert_eq!(method["publicKeyJwk"]["kty"], "EC");
+    assert_eq!(method["publicKeyJwk"]["crv"], "secp256k1");
+}
+
+#[tokio::test]
+async fn test_resolve_invalid_did() {
+    assert!(matches!(
+        resolve("did:sov:V4SGRU86Z58d6TV7PBUe6f").await,
+        Err(DidKeyError::MethodNotSupported(_))
+    ));
+    assert!(matches!(
+        resolve("did:key:6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").await,
+        Err(DidKeyError::InvalidDid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_unsupported_representation() {
+    let result = DidKeyResolver::new()
+        .resolve(
+            &Did::parse(DID_KEY_ED25519.to_string()).unwrap(),
+            &DidResolutionOptions::default().set_accept(MediaType::DidLdJson),
+        )
+        .await;
+    assert!(matches!(
+        result.unwrap_err().downcast_ref::<DidKeyError>(),
+        Some(DidKeyError::RepresentationNotSupported(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_through_registry() {
+    let mut registry = ResolverRegistry::new();
+    registry.register_resolver("key".to_string(), Box::new(DidKeyResolver::new()));
+
+    let output = registry
+        .resolve(
+            &Did::parse(DID_KEY_ED25519.to_string()).unwrap(),
+            &DidResolutionOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(output.did_document().id().to_string(), DID_KEY_ED25519);
+}
@@ -0,0 +1,92 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use did_resolver::error::GenericError;
+
+use crate::error::DidResolverRegistryError;
+
+/// Tells whether a resolution error means the DID does not exist, which varies across methods.
+pub type NotFoundPredicate = fn(&GenericError) -> bool;
+
+#[derive(Clone)]
+pub struct ResolutionCacheConfig {
+    ttl: Duration,
+    capacity: NonZeroUsize,
+    not_found: Option<(Duration, NotFoundPredicate)>,
+}
+
+impl ResolutionCacheConfig {
+    pub fn builder() -> ResolutionCacheConfigBuilder {
+        ResolutionCacheConfigBuilder::default()
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    pub fn not_found_ttl(&self) -> Option<Duration> {
+        self.not_found.map(|(ttl, _)| ttl)
+    }
+
+    pub(super) fn is_not_found(&self, err: &GenericError) -> bool {
+        self.not_found
+            .map_or(false, |(_, is_not_found)| is_not_found(err))
+    }
+}
+
+#[derive(Default)]
+pub struct ResolutionCacheConfigBuilder {}
+
+pub struct ResolutionCacheConfigBuilderTtlSet {
+    ttl: Duration,
+}
+
+pub struct ResolutionCacheConfigBuilderReady {
+    ttl: Duration,
+    capacity: NonZeroUsize,
+    not_found: Option<(Duration, NotFoundPredicate)>,
+}
+
+impl ResolutionCacheConfigBuilder {
+    pub fn ttl(self, ttl: Duration) -> ResolutionCacheConfigBuilderTtlSet {
+        ResolutionCacheConfigBuilderTtlSet { ttl }
+    }
+}
+
+impl ResolutionCacheConfigBuilderTtlSet {
+    pub fn capacity(
+        self,
+        capacity: usize,
+    ) -> Result<ResolutionCacheConfigBuilderReady, DidResolverRegistryError> {
+        let capacity = NonZeroUsize::new(capacity).ok_or_else(|| {
+            DidResolverRegistryError::InvalidConfiguration(
+                "Cache capacity must not be zero".to_string(),
+            )
+        })?;
+        Ok(ResolutionCacheConfigBuilderReady {
+            ttl: self.ttl,
+            capacity,
+            not_found: None,
+        })
+    }
+}
+
+impl ResolutionCacheConfigBuilderReady {
+    /// Caches the errors matching `is_not_found` for `ttl`, so that lookups of unknown DIDs
+    /// are not repeated against the ledger either.
+    pub fn cache_not_found(mut self, ttl: Duration, is_not_found: NotFoundPredicate) -> Self {
+        self.not_found = Some((ttl, is_not_found));
+        self
+    }
+
+    pub fn build(self) -> ResolutionCacheConfig {
+        ResolutionCacheConfig {
+            ttl: self.ttl,
+            capacity: self.capacity,
+            not_found: self.not_found,
+        }
+    }
+}
@@ -0,0 +1,356 @@
+mod config;
+
+pub use config::*;
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use did_resolver::{
+    did_parser::Did,
+    error::GenericError,
+    shared_types::did_document_metadata::DidDocumentMetadata,
+    traits::resolvable::{
+        resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
+        DidResolvable,
+    },
+};
+use lru::LruCache;
+use tokio::sync::Mutex;
+
+use crate::error::DidResolverRegistryError;
+
+type CacheKey = (String, Option<String>);
+
+enum CachedResolution<E: Default> {
+    Found(Box<DidResolutionOutput<E>>),
+    NotFound(String),
+}
+
+struct CacheEntry<E: Default> {
+    resolution: CachedResolution<E>,
+    expires_at: Instant,
+}
+
+impl<E: Default> CacheEntry<E> {
+    fn is_fresh(&self) -> bool {
+        Instant::now() < self.expires_at
+    }
+}
+
+/// Wraps a resolver, serving repeated resolutions of the same DID from memory.
+///
+/// Documents are cached for the configured TTL, shortened for documents which were updated
+/// recently or announce their next update, unless they are deactivated, deactivation being final.
+/// Errors are only cached when configured with `cache_not_found`, those errors being then returned
+/// as [`DidResolverRegistryError::NotFound`] whether they come from the cache or not.
+pub struct CachingResolver<R: DidResolvable> {
+    resolver: R,
+    cache: Mutex<LruCache<CacheKey, CacheEntry<R::ExtraFields>>>,
+    config: ResolutionCacheConfig,
+}
+
+impl<R: DidResolvable> CachingResolver<R> {
+    pub fn new(resolver: R, config: ResolutionCacheConfig) -> Self {
+        Self {
+            resolver,
+            cache: Mutex::new(LruCache::new(config.capacity())),
+            config,
+        }
+    }
+
+    pub fn resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// Drops the cached resolutions of `did`, e.g. after it was updated by this agent.
+    pub async fn invalidate(&self, did: &Did) {
+        let mut cache = self.cache.lock().await;
+        let keys: Vec<CacheKey> = cache
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|(cached_did, _)| *cached_did == did.did())
+            .cloned()
+            .collect();
+        for key in keys {
+            cache.pop(&key);
+        }
+    }
+
+    pub async fn clear(&self) {
+        self.cache.lock().await.clear();
+    }
+
+    fn cache_key(did: &Did, options: &DidResolutionOptions) -> CacheKey {
+        (
+            did.did().to_string(),
+            options.accept().map(|accept| accept.to_string()),
+        )
+    }
+
+    // Like HTTP heuristic freshness, a document is trusted for a tenth of the time since its
+    // last update, up to the configured TTL
+    fn lifetime(&self, metadata: &DidDocumentMetadata) -> Duration {
+        let mut lifetime = self.config.ttl();
+        if metadata.deactivated() == Some(true) {
+            return lifetime;
+        }
+        let now = Utc::now();
+        if let Some(updated) = metadata.updated() {
+            let age = (now - updated).to_std().unwrap_or_default();
+            lifetime = lifetime.min(age / 10);
+        }
+        if let Some(next_update) = metadata.next_update() {
+            lifetime = lifetime.min((next_update - now).to_std().unwrap_or_default());
+        }
+        lifetime
+    }
+
+    async fn put(
+        &self,
+        key: CacheKey,
+        resolution: CachedResolution<R::ExtraFields>,
+        lifetime: Duration,
+    ) {
+        if lifetime.is_zero() {
+            return;
+        }
+        let entry = CacheEntry {
+            resolution,
+            expires_at: Instant::now() + lifetime,
+        };
+        self.cache.lock().await.put(key, entry);
+    }
+}
+
+#[async_trait]
+impl<R> DidResolvable for CachingResolver<R>
+where
+    R: DidResolvable + Send + Sync,
+    R::ExtraFields: Clone + Send + Sync,
+{
+    type ExtraFields = R::ExtraFields;
+
+    async fn resolve(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions,
+    ) -> Result<DidResolutionOutput<Self::ExtraFields>, GenericError> {
+        let key = Self::cache_key(did, options);
+        {
+            let mut cache = self.cache.lock().await;
+            match cache.get(&key) {
+                Some(entry) if entry.is_fresh() => {
+                    return match &entry.resolution {
+                        CachedResolution::Found(output) => Ok(output.as_ref().clone()),
+                        CachedResolution::NotFound(msg) => {
+                            Err(Box::new(DidResolverRegistryError::NotFound(msg.clone())))
+                        }
+                    };
+                }
+                Some(_) => {
+                    cache.pop(&key);
+                }
+                None => {}
+            }
+        }
+
+        match self.resolver.resolve(did, options).await {
+            Ok(output) => {
+                let lifetime = self.lifetime(output.did_document_metadata());
+                self.put(
+                    key,
+                    CachedResolution::Found(Box::new(output.clone())),
+                    lifetime,
+                )
+                .await;
+                Ok(output)
+            }
+            Err(err) => match self.config.not_found_ttl() {
+                Some(ttl) if self.config.is_not_found(&err) => {
+                    let msg = err.to_string();
+                    self.put(key, CachedResolution::NotFound(msg.clone()), ttl)
+                        .await;
+                    Err(Box::new(DidResolverRegistryError::NotFound(msg)))
+                }
+                _ => Err(err),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use did_resolver::did_doc::schema::did_doc::DidDocumentBuilder;
+    use std::{
+        error::Error,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Debug)]
+    struct DummyNotFoundError;
+
+    impl std::fmt::Display for DummyNotFoundError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "Dummy DID not found")
+        }
+    }
+
+    impl Error for DummyNotFoundError {}
+
+    /// Resolves every DID to an empty document with the given metadata, or fails with
+    /// `DummyNotFoundError` when there is none.
+    struct CountingResolver {
+        metadata: Option<DidDocumentMetadata>,
+        calls: AtomicUsize,
+    }
+
+    impl CountingResolver {
+        fn new(metadata: Option<DidDocumentMetadata>) -> Self {
+            Self {
+                metadata,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl DidResolvable for CountingResolver {
+        type ExtraFields = ();
+
+        async fn resolve(
+            &self,
+            did: &Did,
+            _options: &DidResolutionOptions,
+        ) -> Result<DidResolutionOutput<()>, GenericError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match &self.metadata {
+                Some(metadata) => Ok(DidResolutionOutput::builder(
+                    DidDocumentBuilder::new(did.clone()).build(),
+                )
+                .did_document_metadata(metadata.clone())
+                .build()),
+                None => Err(Box::new(DummyNotFoundError)),
+            }
+        }
+    }
+
+    fn config(ttl: Duration) -> ResolutionCacheConfig {
+        ResolutionCacheConfig::builder()
+            .ttl(ttl)
+            .capacity(10)
+            .unwrap()
+            .cache_not_found(ttl, |err| err.is::<DummyNotFoundError>())
+            .build()
+    }
+
+    async fn resolve_twice(resolver: &CachingResolver<CountingResolver>) -> usize {
+        let did = Did::parse("did:example:1234".to_string()).unwrap();
+        for _ in 0..2 {
+            let _ = resolver
+                .resolve(&did, &DidResolutionOptions::default())
+                .await;
+        }
+        resolver.resolver().calls()
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit() {
+        let metadata = DidDocumentMetadata::builder().build();
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata)),
+            config(Duration::from_secs(60)),
+        );
+        assert_eq!(resolve_twice(&resolver).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_expired() {
+        let metadata = DidDocumentMetadata::builder().build();
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata)),
+            config(Duration::ZERO),
+        );
+        assert_eq!(resolve_twice(&resolver).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_recently_updated() {
+        // Updated a second ago, the document is only trusted for a tenth of a second
+        let metadata = DidDocumentMetadata::builder()
+            .updated(Utc::now() - ChronoDuration::seconds(1))
+            .build();
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata)),
+            config(Duration::from_secs(60)),
+        );
+        let did = Did::parse("did:example:1234".to_string()).unwrap();
+        for _ in 0..2 {
+            resolver
+                .resolve(&did, &DidResolutionOptions::default())
+                .await
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        assert_eq!(resolver.resolver().calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_deactivated() {
+        // Deactivated documents are cached for the whole TTL, however long ago they were updated
+        let metadata = DidDocumentMetadata::builder()
+            .deactivated(true)
+            .updated(Utc::now() - ChronoDuration::seconds(1))
+            .build();
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata.clone())),
+            config(Duration::from_secs(60)),
+        );
+        assert_eq!(resolve_twice(&resolver).await, 1);
+
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata)),
+            config(Duration::ZERO),
+        );
+        assert_eq!(resolve_twice(&resolver).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_not_found() {
+        let resolver =
+            CachingResolver::new(CountingResolver::new(None), config(Duration::from_secs(60)));
+        let did = Did::parse("did:example:1234".to_string()).unwrap();
+        for _ in 0..2 {
+            let err = resolver
+                .resolve(&did, &DidResolutionOptions::default())
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<DidResolverRegistryError>(),
+                Some(DidResolverRegistryError::NotFound(_))
+            ));
+        }
+        assert_eq!(resolver.resolver().calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate() {
+        let metadata = DidDocumentMetadata::builder().build();
+        let resolver = CachingResolver::new(
+            CountingResolver::new(Some(metadata)),
+            config(Duration::from_secs(60)),
+        );
+        assert_eq!(resolve_twice(&resolver).await, 1);
+
+        resolver
+            .invalidate(&Did::parse("did:example:1234".to_string()).unwrap())
+            .await;
+        assert_eq!(resolve_twice(&resolver).await, 2);
+    }
+}
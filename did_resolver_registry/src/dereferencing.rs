@@ -0,0 +1,120 @@
+use std::io::Cursor;
+
+use did_resolver::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser::DidUrl,
+    error::GenericError,
+    shared_types::media_type::MediaType,
+    traits::{
+        dereferenceable::{
+            dereferencing_error::DidDereferencingError,
+            dereferencing_metadata::DidDereferencingMetadata,
+            dereferencing_output::DidDereferencingOutput,
+        },
+        resolvable::resolution_output::DidResolutionOutput,
+    },
+};
+
+use crate::extra_fields::GenericExtraFields;
+
+const URI_LIST: &str = "text/uri-list";
+
+// https://www.w3.org/TR/did-core/#example-a-resource-external-to-a-did-document
+fn service_endpoint(
+    did_document: &DidDocument<GenericExtraFields>,
+    service: &str,
+    relative_ref: Option<&String>,
+) -> Result<Vec<u8>, GenericError> {
+    let fragment = format!("#{}", service);
+    let service = did_document
+        .service()
+        .iter()
+        .find(|candidate| candidate.id().as_ref().ends_with(&fragment))
+        .ok_or(DidDereferencingError::NotFound)?;
+
+    let endpoint = service.service_endpoint().as_ref();
+    Ok(match relative_ref {
+        Some(relative_ref) => format!(
+            "{}/{}",
+            endpoint.trim_end_matches('/'),
+            percent_decode(relative_ref)?.trim_start_matches('/')
+        ),
+        None => endpoint.to_string(),
+    }
+    .into_bytes())
+}
+
+// Query values are percent-encoded, as the DID URL syntax does not allow e.g. slashes in them
+fn percent_decode(value: &str) -> Result<String, GenericError> {
+    let mut bytes = value.bytes();
+    let mut decoded = Vec::with_capacity(value.len());
+    while let Some(byte) = bytes.next() {
+        if byte != b'%' {
+            decoded.push(byte);
+            continue;
+        }
+        let hex = [
+            bytes.next().ok_or(DidDereferencingError::InvalidDid)?,
+            bytes.next().ok_or(DidDereferencingError::InvalidDid)?,
+        ];
+        let hex = std::str::from_utf8(&hex).map_err(|_| DidDereferencingError::InvalidDid)?;
+        decoded.push(u8::from_str_radix(hex, 16).map_err(|_| DidDereferencingError::InvalidDid)?);
+    }
+    Ok(String::from_utf8(decoded).map_err(|_| DidDereferencingError::InvalidDid)?)
+}
+
+fn fragment(
+    did_document: &DidDocument<GenericExtraFields>,
+    fragment: &str,
+) -> Result<Vec<u8>, GenericError> {
+    let fragment = format!("#{}", fragment);
+    let verification_method = did_document
+        .verification_method()
+        .iter()
+        .find(|method| method.id().did_url().ends_with(&fragment));
+    let service = did_document
+        .service()
+        .iter()
+        .find(|service| service.id().as_ref().ends_with(&fragment));
+
+    match (verification_method, service) {
+        (Some(verification_method), None) => Ok(serde_json::to_vec(verification_method)?),
+        (None, Some(service)) => Ok(serde_json::to_vec(service)?),
+        (None, None) => Err(Box::new(DidDereferencingError::NotFound)),
+        (Some(_), Some(_)) => Err(Box::new(DidDereferencingError::InvalidDid)),
+    }
+}
+
+/// Dereferences a DID URL against the resolved document, for methods without a dereferencer of
+/// their own. Supports the `service` (and `relativeRef`) queries and fragments.
+pub(crate) fn dereference_did_document(
+    resolution_output: &DidResolutionOutput<GenericExtraFields>,
+    did_url: &DidUrl,
+) -> Result<DidDereferencingOutput<Cursor<Vec<u8>>>, GenericError> {
+    let did_document = resolution_output.did_document();
+    let queries = did_url.queries();
+
+    let (content, content_type) = match (queries.get("service"), did_url.fragment()) {
+        (Some(service), _) => (
+            service_endpoint(did_document, service, queries.get("relativeRef"))?,
+            URI_LIST.to_string(),
+        ),
+        (None, Some(fragment_id)) => (
+            fragment(did_document, fragment_id)?,
+            MediaType::DidJson.to_string(),
+        ),
+        (None, None) => (
+            serde_json::to_vec(did_document)?,
+            MediaType::DidJson.to_string(),
+        ),
+    };
+
+    let dereferencing_metadata = DidDereferencingMetadata::builder()
+        .content_type(content_type)
+        .build();
+
+    Ok(DidDereferencingOutput::builder(Cursor::new(content))
+        .content_metadata(resolution_output.did_document_metadata().clone())
+        .dereferencing_metadata(dereferencing_metadata)
+        .build())
+}
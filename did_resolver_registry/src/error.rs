@@ -3,12 +3,18 @@ use std::error::Error;
 #[derive(Debug)]
 pub enum DidResolverRegistryError {
     UnsupportedMethod,
+    InvalidConfiguration(String),
+    NotFound(String),
 }
 
 impl std::fmt::Display for DidResolverRegistryError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             DidResolverRegistryError::UnsupportedMethod => write!(f, "Unsupported DID method"),
+            DidResolverRegistryError::InvalidConfiguration(msg) => {
+                write!(f, "Invalid configuration: {}", msg)
+            }
+            DidResolverRegistryError::NotFound(msg) => write!(f, "Not found: {}", msg),
         }
     }
 }
@@ -0,0 +1,25 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Method-specific service fields of a document resolved through the registry, kept as JSON so
+/// that resolvers of any `ExtraFields` type can be registered side by side.
+///
+/// The typed fields are recovered with [`GenericExtraFields::to_typed`], e.g. as `ExtraFieldsSov`
+/// for services of did:sov documents.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct GenericExtraFields(Map<String, Value>);
+
+impl GenericExtraFields {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_typed<E: DeserializeOwned>(&self) -> Result<E, serde_json::Error> {
+        serde_json::from_value(Value::Object(self.0.clone()))
+    }
+}
@@ -1,51 +1,184 @@
+pub mod cache;
+mod dereferencing;
 pub mod error;
-
-use std::collections::HashMap;
+mod extra_fields;
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::{Cursor, Read},
+    pin::Pin,
+    sync::Arc,
+};
 
 use did_resolver::{
-    did_parser::Did,
+    did_parser::{Did, DidUrl},
     error::GenericError,
-    traits::resolvable::{
-        resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
-        DidResolvable,
+    traits::{
+        dereferenceable::{
+            dereferencing_options::DidDereferencingOptions,
+            dereferencing_output::DidDereferencingOutput, DidDereferenceable,
+        },
+        resolvable::{
+            resolution_options::DidResolutionOptions, resolution_output::DidResolutionOutput,
+            DidResolvable,
+        },
     },
 };
 use error::DidResolverRegistryError;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use extra_fields::GenericExtraFields;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Object safe view of a resolver, with its extra fields erased to `GenericExtraFields`.
+trait ErasedResolver {
+    fn resolve<'a>(
+        &'a self,
+        did: &'a Did,
+        options: &'a DidResolutionOptions,
+    ) -> BoxFuture<'a, Result<DidResolutionOutput<GenericExtraFields>, GenericError>>;
+}
+
+trait ErasedDereferencer {
+    fn dereference<'a>(
+        &'a self,
+        did_url: &'a DidUrl,
+        options: &'a DidDereferencingOptions,
+    ) -> BoxFuture<'a, Result<DidDereferencingOutput<Cursor<Vec<u8>>>, GenericError>>;
+}
+
+fn convert_output<E, F>(
+    output: DidResolutionOutput<E>,
+) -> Result<DidResolutionOutput<F>, GenericError>
+where
+    E: Default + Serialize,
+    F: Default + DeserializeOwned,
+{
+    Ok(serde_json::from_value(serde_json::to_value(output)?)?)
+}
+
+impl<R> ErasedResolver for Arc<R>
+where
+    R: DidResolvable + ?Sized,
+    R::ExtraFields: Serialize,
+{
+    fn resolve<'a>(
+        &'a self,
+        did: &'a Did,
+        options: &'a DidResolutionOptions,
+    ) -> BoxFuture<'a, Result<DidResolutionOutput<GenericExtraFields>, GenericError>> {
+        let resolution = self.as_ref().resolve(did, options);
+        Box::pin(async move { convert_output(resolution.await?) })
+    }
+}
+
+impl<D> ErasedDereferencer for Arc<D>
+where
+    D: DidDereferenceable + ?Sized,
+{
+    fn dereference<'a>(
+        &'a self,
+        did_url: &'a DidUrl,
+        options: &'a DidDereferencingOptions,
+    ) -> BoxFuture<'a, Result<DidDereferencingOutput<Cursor<Vec<u8>>>, GenericError>> {
+        let dereferencing = self.as_ref().dereference(did_url, options);
+        Box::pin(async move {
+            let output = dereferencing.await?;
+            let dereferencing_metadata = output.dereferencing_metadata().clone();
+            let content_metadata = output.content_metadata().clone();
+            let mut content = Vec::new();
+            output.into_content_stream().read_to_end(&mut content)?;
+            Ok(DidDereferencingOutput::builder(Cursor::new(content))
+                .dereferencing_metadata(dereferencing_metadata)
+                .content_metadata(content_metadata)
+                .build())
+        })
+    }
+}
 
 pub struct ResolverRegistry {
-    resolvers: HashMap<String, Box<dyn DidResolvable<ExtraFields = ()>>>, // TODO: Use e.g. hashmap
+    resolvers: HashMap<String, Box<dyn ErasedResolver>>,
+    dereferencers: HashMap<String, Box<dyn ErasedDereferencer>>,
 }
 
 impl ResolverRegistry {
     pub fn new() -> Self {
         ResolverRegistry {
             resolvers: HashMap::new(),
+            dereferencers: HashMap::new(),
         }
     }
 
-    pub fn register_resolver(
-        &mut self,
-        method: String,
-        resolver: Box<dyn DidResolvable<ExtraFields = ()>>,
-    ) {
-        self.resolvers.insert(method, resolver);
+    pub fn register_resolver<R>(&mut self, method: String, resolver: Box<R>)
+    where
+        R: DidResolvable + ?Sized + 'static,
+        R::ExtraFields: Serialize,
+    {
+        self.dereferencers.remove(&method);
+        self.resolvers
+            .insert(method, Box::new(Arc::<R>::from(resolver)));
+    }
+
+    /// Registers a resolver which also dereferences the DID URLs of its method, instead of
+    /// the generic dereferencing done by the registry on top of the resolved document.
+    pub fn register_dereferencer<D>(&mut self, method: String, dereferencer: Box<D>)
+    where
+        D: DidDereferenceable + ?Sized + 'static,
+        D::ExtraFields: Serialize,
+    {
+        let dereferencer = Arc::<D>::from(dereferencer);
+        self.resolvers
+            .insert(method.clone(), Box::new(dereferencer.clone()));
+        self.dereferencers.insert(method, Box::new(dereferencer));
     }
 
     pub fn unregister_resolver(&mut self, method: &str) {
         self.resolvers.remove(method);
+        self.dereferencers.remove(method);
     }
 
     pub async fn resolve(
         &self,
         did: &Did,
         options: &DidResolutionOptions,
-    ) -> Result<DidResolutionOutput<()>, GenericError> {
+    ) -> Result<DidResolutionOutput<GenericExtraFields>, GenericError> {
         let method = did.method();
         match self.resolvers.get(method) {
             Some(resolver) => resolver.resolve(did, options).await,
             None => Err(Box::new(DidResolverRegistryError::UnsupportedMethod)),
         }
     }
+
+    /// Resolves the DID, recovering the method-specific extra fields as `E`.
+    pub async fn resolve_typed<E>(
+        &self,
+        did: &Did,
+        options: &DidResolutionOptions,
+    ) -> Result<DidResolutionOutput<E>, GenericError>
+    where
+        E: Default + DeserializeOwned,
+    {
+        convert_output(self.resolve(did, options).await?)
+    }
+
+    pub async fn dereference(
+        &self,
+        did_url: &DidUrl,
+        options: &DidDereferencingOptions,
+    ) -> Result<DidDereferencingOutput<Cursor<Vec<u8>>>, GenericError> {
+        let method = did_url
+            .method()
+            .ok_or(DidResolverRegistryError::UnsupportedMethod)?;
+        if let Some(dereferencer) = self.dereferencers.get(method) {
+            return dereferencer.dereference(did_url, options).await;
+        }
+        let resolution_output = self
+            .resolve(&did_url.try_into()?, &DidResolutionOptions::default())
+            .await?;
+        dereferencing::dereference_did_document(&resolution_output, did_url)
+    }
 }
 
 #[cfg(test)]
@@ -53,6 +186,7 @@ mod tests {
     use super::*;
     use async_trait::async_trait;
     use did_resolver::did_doc::schema::did_doc::DidDocumentBuilder;
+    use did_resolver::traits::dereferenceable::dereferencing_error::DidDereferencingError;
     use mockall::{automock, predicate::eq};
     use std::{error::Error, pin::Pin};
 
@@ -229,4 +363,175 @@ mod tests {
             .await;
         assert!(result_after.is_ok());
     }
+
+    #[derive(Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+    #[serde(rename_all = "camelCase")]
+    struct TestExtraFields {
+        routing_keys: Vec<String>,
+    }
+
+    struct TypedDidResolver;
+
+    #[async_trait]
+    impl DidResolvable for TypedDidResolver {
+        type ExtraFields = TestExtraFields;
+
+        async fn resolve(
+            &self,
+            did: &Did,
+            _options: &DidResolutionOptions,
+        ) -> Result<DidResolutionOutput<TestExtraFields>, GenericError> {
+            let service = did_resolver::did_doc::schema::service::Service::builder(
+                format!("{}#didcomm", did).parse().unwrap(),
+                "https://example.com/endpoint".try_into().unwrap(),
+                TestExtraFields {
+                    routing_keys: vec!["did:example:mediator#key-1".to_string()],
+                },
+            )
+            .add_service_type("DIDCommMessaging".to_string())
+            .unwrap()
+            .build();
+            Ok(DidResolutionOutput::builder(
+                DidDocumentBuilder::new(did.clone())
+                    .add_service(service)
+                    .build(),
+            )
+            .build())
+        }
+    }
+
+    fn typed_registry() -> ResolverRegistry {
+        let mut registry = ResolverRegistry::new();
+        registry.register_resolver("example".to_string(), Box::new(TypedDidResolver));
+        registry
+    }
+
+    async fn dereference_to_string(registry: &ResolverRegistry, did_url: &str) -> String {
+        let output = registry
+            .dereference(
+                &DidUrl::parse(did_url.to_string()).unwrap(),
+                &DidDereferencingOptions::default(),
+            )
+            .await
+            .unwrap();
+        String::from_utf8(output.into_content_stream().into_inner()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resolve_extra_fields() {
+        let did = Did::parse("did:example:1234".to_string()).unwrap();
+        let registry = typed_registry();
+
+        let output = registry
+            .resolve(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+        let extra = output.did_document().service()[0].extra();
+        assert_eq!(
+            extra.get("routingKeys"),
+            Some(&serde_json::json!(["did:example:mediator#key-1"]))
+        );
+        assert_eq!(
+            extra.to_typed::<TestExtraFields>().unwrap().routing_keys,
+            vec!["did:example:mediator#key-1".to_string()]
+        );
+
+        let output = registry
+            .resolve_typed::<TestExtraFields>(&did, &DidResolutionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            output.did_document().service()[0].extra().routing_keys,
+            vec!["did:example:mediator#key-1".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dereference_fragment() {
+        let content = dereference_to_string(&typed_registry(), "did:example:1234#didcomm").await;
+        let service: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(service["id"], "did:example:1234#didcomm");
+        assert_eq!(
+            service["routingKeys"],
+            serde_json::json!(["did:example:mediator#key-1"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dereference_service_query() {
+        let registry = typed_registry();
+        assert_eq!(
+            dereference_to_string(&registry, "did:example:1234?service=didcomm").await,
+            "https://example.com/endpoint"
+        );
+        assert_eq!(
+            dereference_to_string(
+                &registry,
+                "did:example:1234?service=didcomm&relativeRef=%2Finbox"
+            )
+            .await,
+            "https://example.com/endpoint/inbox"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dereference_not_found() {
+        let result = typed_registry()
+            .dereference(
+                &DidUrl::parse("did:example:1234#unknown".to_string()).unwrap(),
+                &DidDereferencingOptions::default(),
+            )
+            .await;
+        assert!(matches!(
+            result
+                .err()
+                .unwrap()
+                .downcast_ref::<DidDereferencingError>(),
+            Some(DidDereferencingError::NotFound)
+        ));
+    }
+
+    struct DummyDidDereferencer;
+
+    #[async_trait]
+    impl DidResolvable for DummyDidDereferencer {
+        type ExtraFields = ();
+
+        async fn resolve(
+            &self,
+            did: &Did,
+            _options: &DidResolutionOptions,
+        ) -> Result<DidResolutionOutput<()>, GenericError> {
+            Ok(DidResolutionOutput::builder(DidDocumentBuilder::new(did.clone()).build()).build())
+        }
+    }
+
+    #[async_trait]
+    impl DidDereferenceable for DummyDidDereferencer {
+        type Output = Cursor<Vec<u8>>;
+
+        async fn dereference(
+            &self,
+            _did_url: &DidUrl,
+            _options: &DidDereferencingOptions,
+        ) -> Result<DidDereferencingOutput<Self::Output>, GenericError> {
+            Ok(DidDereferencingOutput::builder(Cursor::new(b"dereferenced".to_vec())).build())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_resolver_replaces_dereferencer() {
+        let mut registry = ResolverRegistry::new();
+        registry.register_dereferencer("example".to_string(), Box::new(DummyDidDereferencer));
+        assert_eq!(
+            dereference_to_string(&registry, "did:example:1234?service=didcomm").await,
+            "dereferenced"
+        );
+
+        registry.register_resolver("example".to_string(), Box::new(TypedDidResolver));
+        assert_eq!(
+            dereference_to_string(&registry, "did:example:1234?service=didcomm").await,
+            "https://example.com/endpoint"
+        );
+    }
 }
@@ -34,8 +34,7 @@ impl DidSovResolver {
 
 #[async_trait]
 impl DidResolvable for DidSovResolver {
-    // TODO: Change to ExtraFields from Sovrin-specific DDO wrapper
-    type ExtraFields = ();
+    type ExtraFields = ExtraFieldsSov;
 
     async fn resolve(
         &self,
@@ -17,6 +17,8 @@ use crate::{
     service::{DidSovServiceType, EndpointDidSov},
 };
 
+use super::ExtraFieldsSov;
+
 fn prepare_ids(did: &str) -> Result<(Uri, Did), DidSovError> {
     let service_id = Uri::new(did)?;
     let ddo_id = Did::parse(did.to_string())?;
@@ -59,11 +61,11 @@ pub(super) fn is_valid_sovrin_did_id(id: &str) -> bool {
     id.chars().all(|c| base58_chars.contains(c))
 }
 
-pub(super) async fn ledger_response_to_ddo<E: Default>(
+pub(super) async fn ledger_response_to_ddo(
     did: &str,
     resp: &str,
     verkey: String,
-) -> Result<DidResolutionOutput<E>, DidSovError> {
+) -> Result<DidResolutionOutput<ExtraFieldsSov>, DidSovError> {
     let (service_id, ddo_id) = prepare_ids(did)?;
 
     let service_data = get_data_from_response(resp)?;
@@ -73,19 +75,30 @@ pub(super) async fn ledger_response_to_ddo<E: Default>(
     let datetime = unix_to_datetime(txn_time);
 
     let service = {
+        let mut accept: Vec<String> = endpoint
+            .types
+            .iter()
+            .filter_map(|t| match t {
+                DidSovServiceType::DidCommunication => Some("didcomm/aip2;env=rfc19".to_string()),
+                DidSovServiceType::DIDComm => Some("didcomm/v2".to_string()),
+                _ => None,
+            })
+            .collect();
+        accept.sort();
+        let extra = ExtraFieldsSov::builder()
+            .set_recipient_keys(vec![verkey.clone()])
+            .set_routing_keys(endpoint.routing_keys)
+            .set_accept(accept)
+            .build();
         let service_types: Vec<String> = endpoint
             .types
             .into_iter()
             .filter(|t| *t != DidSovServiceType::Unknown)
             .map(|t| t.to_string())
             .collect();
-        Service::builder(
-            service_id,
-            endpoint.endpoint.as_str().try_into()?,
-            Default::default(),
-        )
-        .add_service_types(service_types)?
-        .build()
+        Service::builder(service_id, endpoint.endpoint.as_str().try_into()?, extra)
+            .add_service_types(service_types)?
+            .build()
     };
 
     // TODO: Use multibase instead of base58
@@ -181,9 +194,7 @@ mod tests {
             }
         }"#;
         let verkey = "9wvq2i4xUa5umXoThe83CDgx1e5bsjZKJL4DEWvTP9qe".to_string();
-        let resolution_output = ledger_response_to_ddo::<()>(did, resp, verkey)
-            .await
-            .unwrap();
+        let resolution_output = ledger_response_to_ddo(did, resp, verkey).await.unwrap();
         let ddo = resolution_output.did_document();
         assert_eq!(ddo.id().to_string(), "did:example:1234567890");
         assert_eq!(ddo.service()[0].id().to_string(), "did:example:1234567890");
@@ -191,6 +202,10 @@ mod tests {
             ddo.service()[0].service_endpoint().as_ref(),
             "https://example.com/"
         );
+        assert_eq!(
+            ddo.service()[0].extra().recipient_keys(),
+            &["9wvq2i4xUa5umXoThe83CDgx1e5bsjZKJL4DEWvTP9qe".to_string()]
+        );
         assert_eq!(
             ddo.verification_method()[0]
                 .extra_field("publicKeyBase58")
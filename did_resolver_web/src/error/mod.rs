@@ -1,5 +1,6 @@
 pub mod parsing;
 
+use did_resolver::did_doc::{error::DidDocumentBuilderError, schema::validation::ValidationReport};
 use hyper::StatusCode;
 use thiserror::Error;
 
@@ -14,12 +15,22 @@ pub enum DidWebError {
     RepresentationNotSupported(String),
     #[error("Invalid DID: {0}")]
     InvalidDid(String),
+    #[error("Invalid DID document: {0}")]
+    InvalidDidDocument(ValidationReport),
+    #[error("Served DID document belongs to another DID: {0}")]
+    DidDocumentIdMismatch(String),
+    #[error("DID document error: {0}")]
+    DidDocumentError(#[from] DidDocumentBuilderError),
+    #[error("Published DID document does not match: {0}")]
+    VerificationFailed(String),
     #[error("Parsing error: {0}")]
     ParsingError(#[from] ParsingErrorSource),
     #[error("URL parsing error: {0}")]
     HttpError(#[from] hyper::Error),
     #[error("Non-success server response: {0}")]
     NonSuccessResponse(StatusCode),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -0,0 +1,151 @@
+use did_resolver::{
+    did_doc::schema::{
+        did_doc::DidDocument,
+        service::didcomm::DidCommService,
+        types::public_key::{KeyType, PublicKey},
+        validation::{Representation, DID_V1_CONTEXT},
+        verification_method::VerificationMethod,
+    },
+    did_parser::{Did, DidUrl},
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::error::DidWebError;
+
+const JWS_2020_CONTEXT: &str = "https://w3id.org/security/suites/jws-2020/v1";
+const JSON_WEB_KEY_2020: &str = "JsonWebKey2020";
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Relationships {
+    authentication: bool,
+    assertion_method: bool,
+    key_agreement: bool,
+}
+
+/// Builds the DID document of a `did:web` DID out of wallet keys and service endpoints.
+///
+/// Every key is published as a `JsonWebKey2020` verification method with id `{did}#key-{n}`,
+/// referenced from the verification relationships it was added for.
+#[derive(Debug)]
+pub struct DidWebDocumentBuilder {
+    did: Did,
+    keys: Vec<(PublicKey, Relationships)>,
+    services: Vec<DidCommService>,
+}
+
+impl DidWebDocumentBuilder {
+    pub fn new(did: Did) -> Result<Self, DidWebError> {
+        if did.method() != "web" {
+            return Err(DidWebError::MethodNotSupported(did.method().to_string()));
+        }
+        Ok(Self {
+            did,
+            keys: vec![],
+            services: vec![],
+        })
+    }
+
+    /// Adds a base58 encoded Ed25519 verkey, as stored in the wallet, for authentication and
+    /// assertions.
+    pub fn add_verkey(self, verkey: &str) -> Result<Self, DidWebError> {
+        let key = PublicKey::from_base58(KeyType::Ed25519, verkey)?;
+        Ok(self.add_key(
+            key,
+            Relationships {
+                authentication: true,
+                assertion_method: true,
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn add_authentication_key(self, key: PublicKey) -> Self {
+        self.add_key(
+            key,
+            Relationships {
+                authentication: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn add_assertion_key(self, key: PublicKey) -> Self {
+        self.add_key(
+            key,
+            Relationships {
+                assertion_method: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn add_key_agreement_key(self, key: PublicKey) -> Self {
+        self.add_key(
+            key,
+            Relationships {
+                key_agreement: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn add_didcomm_service(mut self, service: DidCommService) -> Self {
+        self.services.push(service);
+        self
+    }
+
+    /// Builds the document, failing if it is not a valid JSON-LD DID document.
+    pub fn build<E: DeserializeOwned>(self) -> Result<DidDocument<E>, DidWebError> {
+        let mut builder = DidDocument::builder(self.did.clone())
+            .add_extra_field("@context".to_string(), json!([DID_V1_CONTEXT, JWS_2020_CONTEXT]));
+
+        // Keys added for several relationships are published once.
+        let mut keys: Vec<(PublicKey, Relationships)> = vec![];
+        for (key, relationships) in self.keys {
+            match keys.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, existing)) => {
+                    existing.authentication |= relationships.authentication;
+                    existing.assertion_method |= relationships.assertion_method;
+                    existing.key_agreement |= relationships.key_agreement;
+                }
+                None => keys.push((key, relationships)),
+            }
+        }
+
+        for (index, (key, relationships)) in keys.into_iter().enumerate() {
+            let id = DidUrl::parse(format!("{}#key-{}", self.did.did(), index))
+                .map_err(|err| DidWebError::InvalidDid(err.to_string()))?;
+            let method = VerificationMethod::builder(id.clone(), self.did.clone(), JSON_WEB_KEY_2020.to_string())
+                .add_public_key_jwk(key.to_jwk()?)
+                .build();
+            builder = builder.add_verification_method(method);
+            if relationships.authentication {
+                builder = builder.add_authentication_reference(id.clone());
+            }
+            if relationships.assertion_method {
+                builder = builder.add_assertion_method_reference(id.clone());
+            }
+            if relationships.key_agreement {
+                builder = builder.add_key_agreement_refrence(id);
+            }
+        }
+
+        for service in self.services {
+            service.validate()?;
+            builder = builder.add_service(service.to_service()?);
+        }
+
+        let did_doc = builder.build();
+        let report = did_doc.validation_report(Representation::JsonLd);
+        if !report.is_valid() {
+            return Err(DidWebError::InvalidDidDocument(report));
+        }
+        Ok(did_doc)
+    }
+
+    fn add_key(mut self, key: PublicKey, relationships: Relationships) -> Self {
+        self.keys.push((key, relationships));
+        self
+    }
+}
@@ -0,0 +1,101 @@
+mod builder;
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use did_resolver::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser::Did,
+    shared_types::media_type::MediaType,
+    traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable},
+};
+use hyper::client::connect::Connect;
+use serde::Serialize;
+
+use crate::{error::DidWebError, resolution::resolver::DidWebResolver, resolution::utils::did_document_location};
+
+pub use builder::DidWebDocumentBuilder;
+
+/// Location of the DID document of a `did:web` DID relative to the web root of its domain,
+/// i.e. `.well-known/did.json` or `{path}/did.json`.
+pub fn did_document_path(did: &Did) -> Result<PathBuf, DidWebError> {
+    if did.method() != "web" {
+        return Err(DidWebError::MethodNotSupported(did.method().to_string()));
+    }
+    let (_, path) = did_document_location(did)?;
+    let path = PathBuf::from(path.trim_start_matches('/'));
+
+    // Path segments come from the DID, don't let them escape the web root.
+    if !path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(DidWebError::InvalidDid(did.to_string()));
+    }
+    Ok(path)
+}
+
+/// Writes the DID document under the web root serving its domain and returns the path of the
+/// written file.
+pub fn publish<E: Serialize>(did_doc: &DidDocument<E>, web_root: &Path) -> Result<PathBuf, DidWebError> {
+    let path = web_root.join(did_document_path(did_doc.id())?);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(did_doc)?)?;
+    Ok(path)
+}
+
+/// Resolves the DID of the document and checks the served document publishes the same
+/// verification methods, relationships and services.
+pub async fn verify_published<C, E>(resolver: &DidWebResolver<C>, did_doc: &DidDocument<E>) -> Result<(), DidWebError>
+where
+    C: Connect + Send + Sync + Clone + 'static,
+{
+    let options = DidResolutionOptions::new().set_accept(MediaType::DidLdJson);
+    let resolved = resolver.resolve(did_doc.id(), &options).await?;
+    let resolved = resolved.did_document();
+
+    let mismatch = |property: &str| Err(DidWebError::VerificationFailed(property.to_string()));
+
+    if resolved.id() != did_doc.id() {
+        return mismatch("id");
+    }
+    if resolved.verification_method() != did_doc.verification_method() {
+        return mismatch("verificationMethod");
+    }
+    if resolved.authentication() != did_doc.authentication() {
+        return mismatch("authentication");
+    }
+    if resolved.assertion_method() != did_doc.assertion_method() {
+        return mismatch("assertionMethod");
+    }
+    if resolved.key_agreement() != did_doc.key_agreement() {
+        return mismatch("keyAgreement");
+    }
+    if resolved.capability_invocation() != did_doc.capability_invocation() {
+        return mismatch("capabilityInvocation");
+    }
+    if resolved.capability_delegation() != did_doc.capability_delegation() {
+        return mismatch("capabilityDelegation");
+    }
+
+    // The resolver drops the service properties it doesn't know.
+    let services_match = resolved.service().len() == did_doc.service().len()
+        && resolved
+            .service()
+            .iter()
+            .zip(did_doc.service())
+            .all(|(resolved, service)| {
+                resolved.id() == service.id()
+                    && resolved.service_type() == service.service_type()
+                    && resolved.service_endpoint() == service.service_endpoint()
+            });
+    if !services_match {
+        return mismatch("service");
+    }
+
+    Ok(())
+}
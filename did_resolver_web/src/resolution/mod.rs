@@ -1 +1,2 @@
 pub mod resolver;
+pub(crate) mod utils;
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use did_resolver::{
+    did_doc::schema::{did_doc::DidDocument, validation::Representation},
     did_parser::Did,
     error::GenericError,
     shared_types::{did_document_metadata::DidDocumentMetadata, media_type::MediaType},
@@ -15,6 +16,7 @@ use hyper::{
 };
 use hyper_tls::HttpsConnector;
 
+use super::utils::did_document_location;
 use crate::error::DidWebError;
 
 pub struct DidWebResolver<C>
@@ -76,37 +78,40 @@ where
             return Err(Box::new(DidWebError::MethodNotSupported(did.method().to_string())));
         }
 
-        if let Some(accept) = options.accept() {
-            if accept != &MediaType::DidJson {
-                return Err(Box::new(DidWebError::RepresentationNotSupported(accept.to_string())));
-            }
-        }
-
-        let did_parts: Vec<&str> = did.id().split(':').collect();
-
-        if did_parts.is_empty() {
-            return Err(Box::new(DidWebError::InvalidDid(did.id().to_string())));
-        }
+        let (media_type, representation) = match options.accept() {
+            None | Some(MediaType::DidJson) => (MediaType::DidJson, Representation::Json),
+            Some(MediaType::DidLdJson) => (MediaType::DidLdJson, Representation::JsonLd),
+            Some(accept) => return Err(Box::new(DidWebError::RepresentationNotSupported(accept.to_string()))),
+        };
 
-        let domain = did_parts[0].replace("%3A", ":");
+        let (domain, path_and_query) = did_document_location(did)?;
 
-        let path_parts = &did_parts[1..];
-        let path_and_query = if path_parts.is_empty() {
-            "/.well-known/did.json".to_string()
-        } else {
-            let path = path_parts.join("/");
-            format!("/{}/did.json", path)
-        };
         let url = uri::Builder::new()
             .scheme(self.scheme.clone())
             .authority(domain.as_str())
             .path_and_query(path_and_query.as_str())
             .build()?;
 
-        let did_document = serde_json::from_str(&self.fetch_did_document(url).await?)?;
+        let did_document: DidDocument<()> = serde_json::from_str(&self.fetch_did_document(url).await?)?;
+
+        // The server must not be able to serve the document of another DID.
+        if did_document.id().did() != did.did() {
+            return Err(Box::new(DidWebError::DidDocumentIdMismatch(
+                did_document.id().to_string(),
+            )));
+        }
+
+        let report = did_document.validation_report(representation);
+        if !report.is_valid() {
+            return Err(Box::new(DidWebError::InvalidDidDocument(report)));
+        }
 
         let did_resolution_output = DidResolutionOutput::builder(did_document)
-            .did_resolution_metadata(DidResolutionMetadata::default())
+            .did_resolution_metadata(
+                DidResolutionMetadata::builder()
+                    .content_type(media_type.to_string())
+                    .build(),
+            )
             .did_document_metadata(DidDocumentMetadata::default())
             .build();
 
@@ -0,0 +1,25 @@
+use did_resolver::did_parser::Did;
+
+use crate::error::DidWebError;
+
+/// Splits a did:web DID into the authority of its web server and the path of its DID document,
+/// as defined in the [method specification](https://w3c-ccg.github.io/did-method-web/#read-resolve).
+pub(crate) fn did_document_location(did: &Did) -> Result<(String, String), DidWebError> {
+    let did_parts: Vec<&str> = did.id().split(':').collect();
+
+    if did_parts.is_empty() || did_parts[0].is_empty() {
+        return Err(DidWebError::InvalidDid(did.id().to_string()));
+    }
+
+    let domain = did_parts[0].replace("%3A", ":");
+
+    let path_parts = &did_parts[1..];
+    let path = if path_parts.is_empty() {
+        "/.well-known/did.json".to_string()
+    } else {
+        let path = path_parts.join("/");
+        format!("/{}/did.json", path)
+    };
+
+    Ok((domain, path))
+}
@@ -0,0 +1,126 @@
+use did_resolver::did_doc::schema::did_doc::DidDocument;
+use did_resolver::did_doc::schema::service::didcomm::{DidCommService, DidCommServiceType};
+use did_resolver::did_doc::schema::types::public_key::{KeyType, PublicKey};
+use did_resolver::did_parser::Did;
+use did_resolver_web::error::DidWebError;
+use did_resolver_web::publishing::{did_document_path, publish, verify_published, DidWebDocumentBuilder};
+use did_resolver_web::resolution::resolver::DidWebResolver;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio_test::assert_ok;
+
+const VERKEY: &str = "B12NYF8RrR3h41TDCTJojY59usg3mbtbjnFs7Eud1Y6u";
+const X25519_BASE58: &str = "JhNWeSVLMYccCk7iopQW4guaSJTojqpMEELgSLhKwRr";
+
+async fn create_file_server(port: u16, web_root: PathBuf) {
+    let make_svc = make_service_fn(move |_conn| {
+        let web_root = web_root.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let path = web_root.join(req.uri().path().trim_start_matches('/'));
+                async move {
+                    let response = match std::fs::read(path) {
+                        Ok(content) => Response::new(Body::from(content)),
+                        Err(_) => Response::builder().status(404).body(Body::from("Not Found")).unwrap(),
+                    };
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = Server::bind(&addr).serve(make_svc);
+
+    tokio::spawn(async move {
+        server.await.unwrap();
+    });
+}
+
+fn build_did_doc(did: &str) -> DidDocument<HashMap<String, serde_json::Value>> {
+    let did = Did::parse(did.to_string()).unwrap();
+    let service = DidCommService::builder(
+        format!("{}#didcomm", did).parse().unwrap(),
+        DidCommServiceType::DidCommMessaging,
+        "https://example.com/didcomm".parse().unwrap(),
+    )
+    .add_routing_key("did:example:mediator#key-1".to_string())
+    .build()
+    .unwrap();
+
+    DidWebDocumentBuilder::new(did)
+        .unwrap()
+        .add_verkey(VERKEY)
+        .unwrap()
+        .add_key_agreement_key(PublicKey::from_base58(KeyType::X25519, X25519_BASE58).unwrap())
+        .add_didcomm_service(service)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_did_document_path() {
+    let path = |did: &str| did_document_path(&Did::parse(did.to_string()).unwrap());
+
+    assert_eq!(
+        path("did:web:example.com").unwrap(),
+        PathBuf::from(".well-known/did.json")
+    );
+    assert_eq!(
+        path("did:web:example.com%3A8080:user:alice").unwrap(),
+        PathBuf::from("user/alice/did.json")
+    );
+    assert!(matches!(
+        path("did:key:z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH"),
+        Err(DidWebError::MethodNotSupported(_))
+    ));
+}
+
+#[test]
+fn test_did_web_document_builder() {
+    let did_doc = build_did_doc("did:web:example.com");
+
+    assert_eq!(did_doc.verification_method().len(), 2);
+    assert_eq!(did_doc.authentication().len(), 1);
+    assert_eq!(did_doc.assertion_method().len(), 1);
+    assert_eq!(did_doc.key_agreement().len(), 1);
+    assert_eq!(
+        did_doc.verification_method()[0].public_key().unwrap().to_base58(),
+        VERKEY
+    );
+    assert_eq!(did_doc.didcomm_services().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_publish_and_verify_did_document() {
+    let port = 3002;
+    let web_root = std::env::temp_dir().join(format!("did_resolver_web_publishing_{}", std::process::id()));
+    create_file_server(port, web_root.clone()).await;
+
+    let did_doc = build_did_doc(&format!("did:web:localhost%3A{}:user:alice", port));
+    let path = assert_ok!(publish(&did_doc, &web_root));
+    assert_eq!(path, web_root.join("user/alice/did.json"));
+
+    let resolver = DidWebResolver::http();
+    assert_ok!(verify_published(&resolver, &did_doc).await);
+
+    // A document served for the DID must match the one we meant to publish.
+    let other_did_doc = build_did_doc(&format!("did:web:localhost%3A{}:user:bob", port));
+    assert!(matches!(
+        verify_published(&resolver, &other_did_doc).await,
+        Err(DidWebError::Other(_))
+    ));
+    std::fs::write(&path, serde_json::to_string(&other_did_doc).unwrap()).unwrap();
+    assert!(matches!(
+        verify_published(&resolver, &did_doc).await,
+        Err(DidWebError::VerificationFailed(_))
+    ));
+
+    std::fs::remove_dir_all(web_root).unwrap();
+}
@@ -1,5 +1,6 @@
 use did_resolver::did_doc::schema::did_doc::DidDocument;
 use did_resolver::did_parser::Did;
+use did_resolver::shared_types::media_type::MediaType;
 use did_resolver::traits::resolvable::{resolution_options::DidResolutionOptions, DidResolvable};
 use did_resolver_web::resolution::resolver::DidWebResolver;
 use hyper::{
@@ -64,9 +65,51 @@ const DID_DOCUMENT: &'static str = r##"
   ]
 }"##;
 
+const DID_DOCUMENT_DANGLING_REFERENCE: &str = r##"
+{
+  "id": "did:web:example.com",
+  "verificationMethod": [
+    {
+      "id": "did:web:example.com#key-0",
+      "type": "JsonWebKey2020",
+      "controller": "did:web:example.com",
+      "publicKeyJwk": {
+        "kty": "OKP",
+        "crv": "Ed25519",
+        "x": "0-e2i2_Ua1S5HbTYnVB0lj2Z2ytXu2-tYmDFf8f5NjU"
+      }
+    }
+  ],
+  "keyAgreement": [
+    "did:web:example.com#key-1"
+  ]
+}"##;
+
+const FIXTURE_DID: &str = "did:web:example.com";
+
+/// The did:web DID whose document is served at the location of the request.
+fn requested_did(req: &Request<Body>) -> String {
+    let host = req.headers()["host"].to_str().unwrap().replace(':', "%3A");
+    let path = req
+        .uri()
+        .path()
+        .trim_start_matches("/.well-known")
+        .trim_end_matches("/did.json")
+        .replace('/', ":");
+    format!("did:web:{}{}", host, path)
+}
+
 async fn mock_server_handler(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let did = requested_did(&req);
     let response = match req.uri().path() {
-        "/.well-known/did.json" | "/user/alice/did.json" => Response::new(Body::from(DID_DOCUMENT)),
+        "/.well-known/did.json" | "/user/alice/did.json" => {
+            Response::new(Body::from(DID_DOCUMENT.replace(FIXTURE_DID, &did)))
+        }
+        "/user/mallory/did.json" => {
+            Response::new(Body::from(DID_DOCUMENT_DANGLING_REFERENCE.replace(FIXTURE_DID, &did)))
+        }
+        // Document of another DID, served as is.
+        "/user/eve/did.json" => Response::new(Body::from(DID_DOCUMENT)),
         _ => Response::builder().status(404).body(Body::from("Not Found")).unwrap(),
     };
 
@@ -88,8 +131,8 @@ async fn create_mock_server(port: u16) -> String {
 
 #[tokio::test]
 async fn test_did_web_resolver() {
-    fn verify_did_document(did_document: &DidDocument<()>) {
-        assert_eq!(did_document.id().to_string(), "did:web:example.com".to_string());
+    fn verify_did_document(did_document: &DidDocument<()>, did: &Did) {
+        assert_eq!(did_document.id(), did);
         assert_eq!(did_document.verification_method().len(), 3);
         assert_eq!(did_document.authentication().len(), 2);
         assert_eq!(did_document.assertion_method().len(), 2);
@@ -109,12 +152,59 @@ async fn test_did_web_resolver() {
             .resolve(&did_example_1, &DidResolutionOptions::default())
             .await
     );
-    verify_did_document(result_1.did_document());
+    verify_did_document(result_1.did_document(), &did_example_1);
 
     let result_2 = assert_ok!(
         did_web_resolver
             .resolve(&did_example_2, &DidResolutionOptions::default())
             .await
     );
-    verify_did_document(result_2.did_document());
+    verify_did_document(result_2.did_document(), &did_example_2);
+}
+
+#[tokio::test]
+async fn test_did_web_resolver_validates_did_document() {
+    let port = 3001;
+    let host = create_mock_server(port).await;
+
+    let did_web_resolver = DidWebResolver::http();
+
+    let did_alice = Did::parse(format!("did:web:{}%3A{}:user:alice", host, port)).unwrap();
+    let did_mallory = Did::parse(format!("did:web:{}%3A{}:user:mallory", host, port)).unwrap();
+    let json_ld_options = DidResolutionOptions::new().set_accept(MediaType::DidLdJson);
+
+    let result = assert_ok!(did_web_resolver.resolve(&did_alice, &json_ld_options).await);
+    assert_eq!(
+        result.did_resolution_metadata().content_type(),
+        Some(&"application/did+ld+json".to_string())
+    );
+
+    let err = did_web_resolver
+        .resolve(&did_mallory, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains(&format!("{}#key-1", did_mallory)));
+
+    // The document lacks the @context required by its JSON-LD representation as well.
+    let err = did_web_resolver
+        .resolve(&did_mallory, &json_ld_options)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("@context"));
+}
+
+#[tokio::test]
+async fn test_did_web_resolver_rejects_document_of_other_did() {
+    let port = 3003;
+    let host = create_mock_server(port).await;
+
+    let did_web_resolver = DidWebResolver::http();
+
+    let did_eve = Did::parse(format!("did:web:{}%3A{}:user:eve", host, port)).unwrap();
+
+    let err = did_web_resolver
+        .resolve(&did_eve, &DidResolutionOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains(FIXTURE_DID));
 }
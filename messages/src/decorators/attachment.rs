@@ -42,9 +42,8 @@ impl Attachment {
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AttachmentData {
-    // There probably is a better type for this???
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub jws: Option<String>,
+    pub jws: Option<AttachmentJws>,
     // Better type for this as well?
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sha256: Option<String>,
@@ -62,6 +61,39 @@ impl AttachmentData {
     }
 }
 
+/// Detached JWS signing the content of an attachment, as described in the
+/// [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/concepts/0017-attachments/README.md#signing-attachments>).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentJws {
+    #[serde(default)]
+    pub header: AttachmentJwsHeader,
+    pub protected: String,
+    pub signature: String,
+}
+
+impl AttachmentJws {
+    pub fn new(header: AttachmentJwsHeader, protected: String, signature: String) -> Self {
+        Self {
+            header,
+            protected,
+            signature,
+        }
+    }
+}
+
+/// Unprotected header of an [`AttachmentJws`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentJwsHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+impl AttachmentJwsHeader {
+    pub fn new(kid: String) -> Self {
+        Self { kid: Some(kid) }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum AttachmentType {
@@ -163,9 +195,43 @@ pub mod tests {
         test_utils::test_serde(attach_data, expected);
     }
 
+    #[test]
+    fn test_attachment_jws() {
+        let jws = AttachmentJws::new(
+            AttachmentJwsHeader::new("test_kid".to_owned()),
+            "test_protected".to_owned(),
+            "test_signature".to_owned(),
+        );
+
+        let expected = json!({
+            "header": {
+                "kid": "test_kid"
+            },
+            "protected": "test_protected",
+            "signature": "test_signature"
+        });
+
+        test_utils::test_serde(jws, expected);
+    }
+
+    #[test]
+    fn test_attachment_jws_without_header() {
+        let jws: AttachmentJws = serde_json::from_value(json!({
+            "protected": "test_protected",
+            "signature": "test_signature"
+        }))
+        .unwrap();
+
+        assert_eq!(jws.header, AttachmentJwsHeader::default());
+    }
+
     #[test]
     fn test_extended_attach_data() {
-        let jws = "test_jws".to_owned();
+        let jws = AttachmentJws::new(
+            AttachmentJwsHeader::new("test_kid".to_owned()),
+            "test_protected".to_owned(),
+            "test_signature".to_owned(),
+        );
         let sha256 = "test_sha256".to_owned();
 
         let data = json!({
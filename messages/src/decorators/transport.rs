@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// Struct representing the `~transport` decorator from its [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0092-transport-return-route/README.md>).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transport {
+    pub return_route: ReturnRoute,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_route_thread: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued_message_count: Option<u32>,
+}
+
+impl Transport {
+    pub fn new(return_route: ReturnRoute) -> Self {
+        Self {
+            return_route,
+            return_route_thread: None,
+            queued_message_count: None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReturnRoute {
+    None,
+    All,
+    Thread,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+pub mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    pub fn make_minimal_transport() -> Transport {
+        Transport::new(ReturnRoute::All)
+    }
+
+    pub fn make_extended_transport() -> Transport {
+        let mut transport = Transport::new(ReturnRoute::Thread);
+        transport.return_route_thread = Some("test_thread_id".to_owned());
+        transport.queued_message_count = Some(1);
+        transport
+    }
+
+    #[test]
+    fn test_minimal_transport() {
+        let transport = make_minimal_transport();
+        let expected = json!({ "return_route": "all" });
+
+        test_utils::test_serde(transport, expected);
+    }
+
+    #[test]
+    fn test_extended_transport() {
+        let transport = make_extended_transport();
+        let expected = json!({
+            "return_route": "thread",
+            "return_route_thread": transport.return_route_thread,
+            "queued_message_count": transport.queued_message_count
+        });
+
+        test_utils::test_serde(transport, expected);
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use shared_vcx::maybe_known::MaybeKnown;
+
+/// Entry of the `formats` field used by format-agnostic protocols, such as
+/// [issue credential 2.0](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0453-issue-credential-v2/README.md>)
+/// and [present proof 2.0](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0454-present-proof-v2/README.md>).
+///
+/// It binds an attachment, through its `@id`, to the format of its contents.
+/// The generic type `F` is the enum of known formats for the attachment kind.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AttachmentFormatSpecifier<F> {
+    pub attach_id: String,
+    pub format: MaybeKnown<F>,
+}
+
+impl<F> AttachmentFormatSpecifier<F> {
+    pub fn new(attach_id: String, format: MaybeKnown<F>) -> Self {
+        Self { attach_id, format }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+    enum TestFormat {
+        #[serde(rename = "test/format@v1.0")]
+        Test1_0,
+    }
+
+    #[test]
+    fn test_known_format() {
+        let specifier = AttachmentFormatSpecifier::new("test_id".to_owned(), MaybeKnown::Known(TestFormat::Test1_0));
+
+        let expected = json!({
+            "attach_id": "test_id",
+            "format": "test/format@v1.0"
+        });
+
+        test_utils::test_serde(specifier, expected);
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        let specifier = AttachmentFormatSpecifier::<TestFormat>::new(
+            "test_id".to_owned(),
+            MaybeKnown::Unknown("other/format@v1.0".to_owned()),
+        );
+
+        let expected = json!({
+            "attach_id": "test_id",
+            "format": "other/format@v1.0"
+        });
+
+        test_utils::test_serde(specifier, expected);
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Keylist = MsgParts<KeylistContent, KeylistDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistContent {
+    pub keys: Vec<KeylistItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<KeylistPagination>,
+}
+
+impl KeylistContent {
+    pub fn new(keys: Vec<KeylistItem>) -> Self {
+        Self { keys, pagination: None }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistItem {
+    pub recipient_key: String,
+}
+
+impl KeylistItem {
+    pub fn new(recipient_key: String) -> Self {
+        Self { recipient_key }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistPagination {
+    pub count: u64,
+    pub offset: u64,
+    pub remaining: u64,
+}
+
+impl KeylistPagination {
+    pub fn new(count: u64, offset: u64, remaining: u64) -> Self {
+        Self {
+            count,
+            offset,
+            remaining,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl KeylistDecorators {
+    pub fn new(thread: Thread) -> Self {
+        Self { thread, timing: None }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_keylist() {
+        let content = KeylistContent::new(vec![KeylistItem::new("test_recipient_key".to_owned())]);
+
+        let decorators = KeylistDecorators::new(make_extended_thread());
+
+        let expected = json!({
+            "keys": [
+                {
+                    "recipient_key": "test_recipient_key"
+                }
+            ],
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::Keylist, expected);
+    }
+
+    #[test]
+    fn test_extended_keylist() {
+        let mut content = KeylistContent::new(vec![KeylistItem::new("test_recipient_key".to_owned())]);
+        content.pagination = Some(KeylistPagination::new(1, 0, 0));
+
+        let mut decorators = KeylistDecorators::new(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "keys": [
+                {
+                    "recipient_key": "test_recipient_key"
+                }
+            ],
+            "pagination": {
+                "count": 1,
+                "offset": 0,
+                "remaining": 0
+            },
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, CoordinateMediationTypeV1_0::Keylist, expected);
+    }
+}
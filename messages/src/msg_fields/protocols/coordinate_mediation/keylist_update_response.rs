@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use super::keylist_update::KeylistUpdateAction;
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type KeylistUpdateResponse = MsgParts<KeylistUpdateResponseContent, KeylistUpdateResponseDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateResponseContent {
+    pub updated: Vec<KeylistUpdateResponseItem>,
+}
+
+impl KeylistUpdateResponseContent {
+    pub fn new(updated: Vec<KeylistUpdateResponseItem>) -> Self {
+        Self { updated }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateResponseItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+    pub result: KeylistUpdateResult,
+}
+
+impl KeylistUpdateResponseItem {
+    pub fn new(recipient_key: String, action: KeylistUpdateAction, result: KeylistUpdateResult) -> Self {
+        Self {
+            recipient_key,
+            action,
+            result,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeylistUpdateResult {
+    ClientError,
+    ServerError,
+    NoChange,
+    Success,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct KeylistUpdateResponseDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl KeylistUpdateResponseDecorators {
+    pub fn new(thread: Thread) -> Self {
+        Self { thread, timing: None }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::coordinate_mediation::CoordinateMediationTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_keylist_update_response() {
+        let content = KeylistUpdateResponseContent::new(vec![KeylistUpdateResponseItem::new(
+            "test_recipient_key".to_owned(),
+            KeylistUpdateAction::Add,
+            KeylistUpdateResult::Success,
+        )]);
+
+        let decorators = KeylistUpdateResponseDecorators::new(make_extended_thread());
+
+        let expected = json!({
+            "updated": [
+                {
+                    "recipient_key": "test_recipient_key",
+                    "action": "add",
+                    "result": "success"
+                }
+            ],
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::KeylistUpdateResponse,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_keylist_update_response() {
+        let content = KeylistUpdateResponseContent::new(vec![KeylistUpdateResponseItem::new(
+            "test_recipient_key".to_owned(),
+            KeylistUpdateAction::Remove,
+            KeylistUpdateResult::NoChange,
+        )]);
+
+        let mut decorators = KeylistUpdateResponseDecorators::new(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "updated": [
+                {
+                    "recipient_key": "test_recipient_key",
+                    "action": "remove",
+                    "result": "no_change"
+                }
+            ],
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV1_0::KeylistUpdateResponse,
+            expected,
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod disclose;
 pub mod query;
+pub mod v2;
 
 use derive_more::From;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
@@ -15,9 +16,7 @@ use crate::{
     misc::utils::{into_msg_with_type, transit_to_aries_msg},
     msg_fields::traits::DelayedSerde,
     msg_types::{
-        protocols::discover_features::{
-            DiscoverFeaturesType as DiscoverFeaturesKind, DiscoverFeaturesTypeV1, DiscoverFeaturesTypeV1_0,
-        },
+        protocols::discover_features::{DiscoverFeaturesTypeV1, DiscoverFeaturesTypeV1_0},
         MsgWithType, Protocol, Role,
     },
 };
@@ -29,7 +28,7 @@ pub enum DiscoverFeatures {
 }
 
 impl DelayedSerde for DiscoverFeatures {
-    type MsgType<'a> = (DiscoverFeaturesKind, &'a str);
+    type MsgType<'a> = (DiscoverFeaturesTypeV1, &'a str);
 
     fn delayed_deserialize<'de, D>(msg_type: Self::MsgType<'de>, deserializer: D) -> Result<Self, D::Error>
     where
@@ -38,7 +37,7 @@ impl DelayedSerde for DiscoverFeatures {
         let (protocol, kind_str) = msg_type;
 
         let kind = match protocol {
-            DiscoverFeaturesKind::V1(DiscoverFeaturesTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
+            DiscoverFeaturesTypeV1::V1_0(kind) => kind.kind_from_str(kind_str),
         };
 
         match kind.map_err(D::Error::custom)? {
@@ -71,8 +70,56 @@ impl ProtocolDescriptor {
     }
 }
 
+/// Matches a discover features query against a feature identifier, such as a protocol URI or a
+/// goal code. A `*` in the query matches any sequence of characters.
+pub fn matches_query(query: &str, id: &str) -> bool {
+    let mut parts = query.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match id.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let mut parts: Vec<&str> = parts.collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        // No wildcards, the query must be the identifier itself.
+        None => return rest.is_empty(),
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
 transit_to_aries_msg!(QueryContent: QueryDecorators, DiscoverFeatures);
 transit_to_aries_msg!(DiscloseContent: DiscloseDecorators, DiscoverFeatures);
 
 into_msg_with_type!(Query, DiscoverFeaturesTypeV1_0, Query);
 into_msg_with_type!(Disclose, DiscoverFeaturesTypeV1_0, Disclose);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_query() {
+        let pid = "https://didcomm.org/discover-features/2.0";
+
+        assert!(matches_query("*", pid));
+        assert!(matches_query(pid, pid));
+        assert!(matches_query("https://didcomm.org/discover-features/*", pid));
+        assert!(matches_query("https://didcomm.org/*/2.*", pid));
+        assert!(matches_query("*features*", pid));
+        assert!(!matches_query("https://didcomm.org/discover-features/1.*", pid));
+        assert!(!matches_query("https://didcomm.org/discover-features", pid));
+        assert!(!matches_query("*2.0*2.0", pid));
+        assert!(matches_query("aries.*", "aries.vc.issue"));
+        assert!(!matches_query("aries.*", "org.aries.vc"));
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use shared_vcx::maybe_known::MaybeKnown;
 
-use super::ProtocolDescriptor;
+use super::{matches_query, ProtocolDescriptor};
 use crate::{decorators::timing::Timing, msg_parts::MsgParts, msg_types::registry::PROTOCOL_REGISTRY};
 
 pub type Query = MsgParts<QueryContent, QueryDecorators>;
@@ -19,17 +19,13 @@ impl QueryContent {
     }
 
     /// Looks up into the [`PROTOCOL_REGISTRY`] and returns a [`Vec<ProtocolDescriptor`] matching the inner query.
+    /// A `*` in the query matches any sequence of characters.
     pub fn lookup(&self) -> Vec<ProtocolDescriptor> {
         let mut protocols = Vec::new();
-        let query = self
-            .query
-            .split('*')
-            .next()
-            .expect("query must have at least an empty string before *");
 
         for entries in PROTOCOL_REGISTRY.values() {
             for entry in entries {
-                if entry.str_pid.starts_with(query) {
+                if matches_query(&self.query, &entry.str_pid) {
                     let pid = MaybeKnown::Known(entry.protocol);
                     let mut pd = ProtocolDescriptor::new(pid);
                     pd.roles = Some(entry.roles.clone());
@@ -141,6 +137,18 @@ mod tests {
         assert_eq!(protocols, matched_protocol);
     }
 
+    #[test]
+    fn test_lookup_match_infix_wildcard() {
+        let matched_protocol = QueryContent::new("https://didcomm.org/conn*/1.0".to_owned()).lookup();
+
+        let pid = ConnectionTypeV1::new_v1_0();
+        let roles = pid.roles();
+        let mut pd = ProtocolDescriptor::new(MaybeKnown::Known(pid.into()));
+        pd.roles = Some(roles);
+
+        assert_eq!(vec![pd], matched_protocol);
+    }
+
     #[test]
     fn test_lookup_match_none() {
         let matched_protocol = QueryContent::new("https://didcomm.org/non-existent/*".to_owned()).lookup();
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use super::FeatureDisclosure;
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::discover_features::ProtocolDescriptor,
+    msg_parts::MsgParts,
+};
+
+pub type Disclosures = MsgParts<DisclosuresContent, DisclosuresDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DisclosuresContent {
+    pub disclosures: Vec<FeatureDisclosure>,
+}
+
+impl DisclosuresContent {
+    pub fn new(disclosures: Vec<FeatureDisclosure>) -> Self {
+        Self { disclosures }
+    }
+
+    /// The disclosed protocols, leaving out other feature types.
+    pub fn protocols(&self) -> Vec<ProtocolDescriptor> {
+        self.disclosures
+            .iter()
+            .filter_map(FeatureDisclosure::protocol_descriptor)
+            .collect()
+    }
+}
+
+/// Disclosures can be sent without being queried, in which case they are not threaded.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct DisclosuresDecorators {
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared_vcx::maybe_known::MaybeKnown;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_fields::protocols::discover_features::v2::FeatureType,
+        msg_types::{discover_features::DiscoverFeaturesTypeV2_0, Role},
+    };
+
+    #[test]
+    fn test_minimal_disclosures() {
+        let content = DisclosuresContent::new(vec![FeatureDisclosure::new(
+            FeatureType::GoalCode,
+            "aries.vc.issue".to_owned(),
+        )]);
+
+        let decorators = DisclosuresDecorators::default();
+
+        let expected = json!({
+            "disclosures": [{
+                "feature-type": "goal-code",
+                "id": "aries.vc.issue"
+            }]
+        });
+
+        test_utils::test_msg(content, decorators, DiscoverFeaturesTypeV2_0::Disclosures, expected);
+    }
+
+    #[test]
+    fn test_extended_disclosures() {
+        let mut disclosure = FeatureDisclosure::new(
+            FeatureType::Protocol,
+            "https://didcomm.org/discover-features/2.0".to_owned(),
+        );
+        disclosure.roles = Some(vec![MaybeKnown::Known(Role::Responder)]);
+        let content = DisclosuresContent::new(vec![
+            disclosure,
+            FeatureDisclosure::new(FeatureType::GoalCode, "aries.vc.issue".to_owned()),
+        ]);
+
+        let mut decorators = DisclosuresDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "disclosures": [
+                {
+                    "feature-type": "protocol",
+                    "id": "https://didcomm.org/discover-features/2.0",
+                    "roles": ["responder"]
+                },
+                {
+                    "feature-type": "goal-code",
+                    "id": "aries.vc.issue"
+                }
+            ],
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, DiscoverFeaturesTypeV2_0::Disclosures, expected);
+    }
+}
@@ -0,0 +1,157 @@
+//! Module containing the `discover features` protocol 2.0 messages, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0557-discover-features-v2/README.md>).
+//!
+//! Unlike 1.0, a single message can query several feature types at once, such as protocols and goal
+//! codes.
+
+pub mod disclosures;
+pub mod queries;
+
+use std::str::FromStr;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use shared_vcx::maybe_known::MaybeKnown;
+
+use self::{
+    disclosures::{Disclosures, DisclosuresContent, DisclosuresDecorators},
+    queries::{Queries, QueriesContent, QueriesDecorators},
+};
+use super::ProtocolDescriptor;
+use crate::{
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::discover_features::{DiscoverFeaturesTypeV2, DiscoverFeaturesTypeV2_0},
+        MsgWithType, Protocol, Role,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum DiscoverFeaturesV2 {
+    Queries(Queries),
+    Disclosures(Disclosures),
+}
+
+impl DelayedSerde for DiscoverFeaturesV2 {
+    type MsgType<'a> = (DiscoverFeaturesTypeV2, &'a str);
+
+    fn delayed_deserialize<'de, D>(msg_type: Self::MsgType<'de>, deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+
+        let kind = match protocol {
+            DiscoverFeaturesTypeV2::V2_0(kind) => kind.kind_from_str(kind_str),
+        };
+
+        match kind.map_err(D::Error::custom)? {
+            DiscoverFeaturesTypeV2_0::Queries => Queries::deserialize(deserializer).map(From::from),
+            DiscoverFeaturesTypeV2_0::Disclosures => Disclosures::deserialize(deserializer).map(From::from),
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Queries(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Disclosures(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeatureType {
+    Protocol,
+    GoalCode,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FeatureQuery {
+    #[serde(rename = "feature-type")]
+    pub feature_type: MaybeKnown<FeatureType>,
+    /// Identifier of the features to match, where `*` matches any sequence of characters.
+    #[serde(rename = "match")]
+    pub match_: String,
+}
+
+impl FeatureQuery {
+    pub fn new(feature_type: FeatureType, match_: String) -> Self {
+        Self {
+            feature_type: MaybeKnown::Known(feature_type),
+            match_,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FeatureDisclosure {
+    #[serde(rename = "feature-type")]
+    pub feature_type: MaybeKnown<FeatureType>,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<MaybeKnown<Role>>>,
+}
+
+impl FeatureDisclosure {
+    pub fn new(feature_type: FeatureType, id: String) -> Self {
+        Self {
+            feature_type: MaybeKnown::Known(feature_type),
+            id,
+            roles: None,
+        }
+    }
+
+    /// The disclosed protocol, as disclosed by discover features 1.0, if this discloses one.
+    pub fn protocol_descriptor(&self) -> Option<ProtocolDescriptor> {
+        if self.feature_type != MaybeKnown::Known(FeatureType::Protocol) {
+            return None;
+        }
+
+        let pid = match Protocol::from_str(&self.id) {
+            Ok(protocol) => MaybeKnown::Known(protocol),
+            Err(_) => MaybeKnown::Unknown(self.id.clone()),
+        };
+        let mut descriptor = ProtocolDescriptor::new(pid);
+        descriptor.roles = self.roles.clone();
+        Some(descriptor)
+    }
+}
+
+transit_to_aries_msg!(QueriesContent: QueriesDecorators, DiscoverFeaturesV2);
+transit_to_aries_msg!(DisclosuresContent: DisclosuresDecorators, DiscoverFeaturesV2);
+
+into_msg_with_type!(Queries, DiscoverFeaturesTypeV2_0, Queries);
+into_msg_with_type!(Disclosures, DiscoverFeaturesTypeV2_0, Disclosures);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg_types::protocols::cred_issuance::CredentialIssuanceTypeV2;
+
+    #[test]
+    fn test_protocol_descriptor() {
+        let mut disclosure = FeatureDisclosure::new(
+            FeatureType::Protocol,
+            "https://didcomm.org/issue-credential/2.3".to_owned(),
+        );
+        disclosure.roles = Some(vec![MaybeKnown::Known(Role::Holder)]);
+
+        let pid = CredentialIssuanceTypeV2::new_v2_0();
+        let mut expected = ProtocolDescriptor::new(MaybeKnown::Known(pid.into()));
+        expected.roles = Some(vec![MaybeKnown::Known(Role::Holder)]);
+        assert_eq!(disclosure.protocol_descriptor(), Some(expected));
+
+        let unknown = FeatureDisclosure::new(FeatureType::Protocol, "https://didcomm.org/tictactoe/1.0".to_owned());
+        assert_eq!(
+            unknown.protocol_descriptor().map(|descriptor| descriptor.pid),
+            Some(MaybeKnown::Unknown("https://didcomm.org/tictactoe/1.0".to_owned()))
+        );
+
+        let goal_code = FeatureDisclosure::new(FeatureType::GoalCode, "aries.vc.issue".to_owned());
+        assert_eq!(goal_code.protocol_descriptor(), None);
+    }
+}
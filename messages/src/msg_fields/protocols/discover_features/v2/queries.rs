@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use super::FeatureQuery;
+use crate::{decorators::timing::Timing, msg_parts::MsgParts};
+
+pub type Queries = MsgParts<QueriesContent, QueriesDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct QueriesContent {
+    pub queries: Vec<FeatureQuery>,
+}
+
+impl QueriesContent {
+    pub fn new(queries: Vec<FeatureQuery>) -> Self {
+        Self { queries }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct QueriesDecorators {
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::timing::tests::make_extended_timing, misc::test_utils,
+        msg_fields::protocols::discover_features::v2::FeatureType,
+        msg_types::discover_features::DiscoverFeaturesTypeV2_0,
+    };
+
+    #[test]
+    fn test_minimal_queries() {
+        let content = QueriesContent::new(vec![FeatureQuery::new(
+            FeatureType::Protocol,
+            "https://didcomm.org/tictactoe/1.*".to_owned(),
+        )]);
+
+        let decorators = QueriesDecorators::default();
+
+        let expected = json!({
+            "queries": [{
+                "feature-type": "protocol",
+                "match": "https://didcomm.org/tictactoe/1.*"
+            }]
+        });
+
+        test_utils::test_msg(content, decorators, DiscoverFeaturesTypeV2_0::Queries, expected);
+    }
+
+    #[test]
+    fn test_extended_queries() {
+        let content = QueriesContent::new(vec![
+            FeatureQuery::new(FeatureType::Protocol, "*".to_owned()),
+            FeatureQuery::new(FeatureType::GoalCode, "aries.*".to_owned()),
+        ]);
+
+        let mut decorators = QueriesDecorators::default();
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "queries": content.queries,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, DiscoverFeaturesTypeV2_0::Queries, expected);
+    }
+}
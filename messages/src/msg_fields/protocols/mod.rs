@@ -1,9 +1,13 @@
 pub mod basic_message;
+pub mod common;
 pub mod connection;
+pub mod coordinate_mediation;
 pub mod cred_issuance;
+pub mod did_exchange;
 pub mod discover_features;
 pub mod notification;
 pub mod out_of_band;
+pub mod pickup;
 pub mod present_proof;
 pub mod report_problem;
 pub mod revocation;
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing, transport::Transport},
+    msg_parts::MsgParts,
+};
+
+pub type StatusRequest = MsgParts<StatusRequestContent, StatusRequestDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct StatusRequestContent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+pub struct StatusRequestDecorators {
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            thread::tests::make_extended_thread, timing::tests::make_extended_timing,
+            transport::tests::make_extended_transport,
+        },
+        misc::test_utils,
+        msg_types::pickup::PickupTypeV2_0,
+    };
+
+    #[test]
+    fn test_minimal_status_request() {
+        let content = StatusRequestContent::default();
+
+        let decorators = StatusRequestDecorators::default();
+
+        let expected = json!({});
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::StatusRequest, expected);
+    }
+
+    #[test]
+    fn test_extended_status_request() {
+        let mut content = StatusRequestContent::default();
+        content.recipient_key = Some("test_recipient_key".to_owned());
+
+        let mut decorators = StatusRequestDecorators::default();
+        decorators.thread = Some(make_extended_thread());
+        decorators.transport = Some(make_extended_transport());
+        decorators.timing = Some(make_extended_timing());
+
+        let expected = json!({
+            "recipient_key": content.recipient_key,
+            "~thread": decorators.thread,
+            "~transport": decorators.transport,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, PickupTypeV2_0::StatusRequest, expected);
+    }
+}
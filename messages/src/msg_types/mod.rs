@@ -14,8 +14,8 @@ use std::{marker::PhantomData, str::FromStr};
 use serde::Serialize;
 
 pub use protocols::{
-    basic_message, connection, cred_issuance, discover_features, notification, out_of_band, present_proof,
-    report_problem, revocation, routing, trust_ping, Protocol,
+    basic_message, connection, coordinate_mediation, cred_issuance, did_exchange, discover_features, notification,
+    out_of_band, pickup, present_proof, report_problem, revocation, routing, trust_ping, Protocol,
 };
 pub use role::Role;
 
@@ -0,0 +1,129 @@
+use derive_more::{From, TryInto};
+use messages_macros::MessageType;
+use strum_macros::{AsRefStr, EnumString};
+use transitive::TransitiveFrom;
+
+use super::Protocol;
+use crate::msg_types::{role::Role, MsgKindType};
+
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, MessageType)]
+#[msg_type(protocol = "coordinate-mediation")]
+pub enum CoordinateMediationType {
+    V1(CoordinateMediationTypeV1),
+}
+
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, TransitiveFrom, MessageType)]
+#[transitive(into(CoordinateMediationType, Protocol))]
+#[msg_type(major = 1)]
+pub enum CoordinateMediationTypeV1 {
+    #[msg_type(minor = 0, roles = "Role::Mediator, Role::Recipient")]
+    V1_0(MsgKindType<CoordinateMediationTypeV1_0>),
+}
+
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CoordinateMediationTypeV1_0 {
+    MediateRequest,
+    MediateGrant,
+    MediateDeny,
+    KeylistUpdate,
+    KeylistUpdateResponse,
+    KeylistQuery,
+    Keylist,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    #[test]
+    fn test_protocol_coordinate_mediation() {
+        test_utils::test_serde(
+            Protocol::from(CoordinateMediationTypeV1::new_v1_0()),
+            json!("https://didcomm.org/coordinate-mediation/1.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_coordinate_mediation() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/coordinate-mediation/1.255",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_version_coordinate_mediation() {
+        test_utils::test_serde(
+            Protocol::from(CoordinateMediationTypeV1::new_v1_0()),
+            json!("https://didcomm.org/coordinate-mediation/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_mediate_request() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "mediate-request",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_mediate_grant() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "mediate-grant",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_mediate_deny() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "mediate-deny",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_keylist_update() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "keylist-update",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_keylist_update_response() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "keylist-update-response",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_keylist_query() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "keylist-query",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_keylist() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/1.0",
+            "keylist",
+            CoordinateMediationTypeV1::new_v1_0(),
+        )
+    }
+}
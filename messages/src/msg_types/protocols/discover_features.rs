@@ -1,4 +1,4 @@
-use derive_more::From;
+use derive_more::{From, TryInto};
 use messages_macros::MessageType;
 use strum_macros::{AsRefStr, EnumString};
 use transitive::TransitiveFrom;
@@ -6,13 +6,14 @@ use transitive::TransitiveFrom;
 use super::Protocol;
 use crate::msg_types::{role::Role, MsgKindType};
 
-#[derive(Copy, Clone, Debug, From, PartialEq, MessageType)]
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, MessageType)]
 #[msg_type(protocol = "discover-features")]
 pub enum DiscoverFeaturesType {
     V1(DiscoverFeaturesTypeV1),
+    V2(DiscoverFeaturesTypeV2),
 }
 
-#[derive(Copy, Clone, Debug, From, PartialEq, TransitiveFrom, MessageType)]
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, TransitiveFrom, MessageType)]
 #[transitive(into(DiscoverFeaturesType, Protocol))]
 #[msg_type(major = 1)]
 pub enum DiscoverFeaturesTypeV1 {
@@ -27,6 +28,21 @@ pub enum DiscoverFeaturesTypeV1_0 {
     Disclose,
 }
 
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, TransitiveFrom, MessageType)]
+#[transitive(into(DiscoverFeaturesType, Protocol))]
+#[msg_type(major = 2)]
+pub enum DiscoverFeaturesTypeV2 {
+    #[msg_type(minor = 0, roles = "Role::Requester, Role::Responder")]
+    V2_0(MsgKindType<DiscoverFeaturesTypeV2_0>),
+}
+
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DiscoverFeaturesTypeV2_0 {
+    Queries,
+    Disclosures,
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -55,7 +71,7 @@ mod tests {
     fn test_unsupported_version_discover_features() {
         test_utils::test_serde(
             Protocol::from(DiscoverFeaturesTypeV1::new_v1_0()),
-            json!("https://didcomm.org/discover-features/2.0"),
+            json!("https://didcomm.org/discover-features/3.0"),
         )
     }
 
@@ -76,4 +92,30 @@ mod tests {
             DiscoverFeaturesTypeV1::new_v1_0(),
         )
     }
+
+    #[test]
+    fn test_protocol_discover_features_v2() {
+        test_utils::test_serde(
+            Protocol::from(DiscoverFeaturesTypeV2::new_v2_0()),
+            json!("https://didcomm.org/discover-features/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_queries() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/discover-features/2.0",
+            "queries",
+            DiscoverFeaturesTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_disclosures() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/discover-features/2.0",
+            "disclosures",
+            DiscoverFeaturesTypeV2::new_v2_0(),
+        )
+    }
 }